@@ -417,6 +417,14 @@ cli: {
 						"""
 					type: "string"
 				}
+				"format": {
+					description: "Format the validation results in an encoding schema"
+					default:     "text"
+					enum: {
+						json: "Output the validation results as a JSON object"
+						text: "Output the validation results as text"
+					}
+				}
 			}
 
 			args: {
@@ -460,6 +468,15 @@ cli: {
 						"""
 					type: "string"
 				}
+
+				"format": {
+					description: "Format compile-time diagnostics and runtime errors in an encoding schema"
+					default:     "text"
+					enum: {
+						json: "Output diagnostics as a JSON array, for use by editors and other tooling"
+						text: "Output diagnostics as text"
+					}
+				}
 			}
 
 			args: {
@@ -0,0 +1,151 @@
+package metadata
+
+components: _mqtt: {
+	features: {
+		collect: from: {
+			service: services.mqtt
+			interface: {
+				socket: {
+					api: {
+						title: "MQTT protocol"
+						url:   urls.mqtt
+					}
+					direction: "outgoing"
+					port:      1883
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+
+		send: to: {
+			service: services.mqtt
+			interface: {
+				socket: {
+					api: {
+						title: "MQTT protocol"
+						url:   urls.mqtt
+					}
+					direction: "outgoing"
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		clean_session: {
+			common:      false
+			description: """
+				Whether to start a clean session on every connection. When disabled the broker keeps
+				the session, including subscriptions and messages queued while disconnected, for the
+				configured `client_id`.
+				"""
+			required:    false
+			type: bool: default: true
+		}
+		client_id: {
+			common:      true
+			description: "The client identifier to connect with. Brokers disconnect existing connections sharing a client identifier, so it must be unique per component and Vector instance. When unset, a random `vector-<hex>` identifier is generated each time the component starts, so set one when `clean_session` is disabled to resume the same session."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector-edge-1"]
+				syntax: "literal"
+			}
+		}
+		host: {
+			description: "The hostname of the MQTT broker."
+			required:    true
+			type: string: {
+				examples: ["localhost", "mqtt.example.com"]
+				syntax: "literal"
+			}
+		}
+		keep_alive: {
+			common:      false
+			description: "The interval between keep alive pings sent to the broker."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		password: {
+			common:      false
+			description: "The password to authenticate with. Only used along with `user`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${MQTT_PASSWORD}"]
+				syntax: "literal"
+			}
+		}
+		port: {
+			common:      true
+			description: "The port of the MQTT broker."
+			required:    false
+			type: uint: {
+				default: 1883
+				examples: [1883, 8883]
+				unit: null
+			}
+		}
+		qos: {
+			common:      true
+			description: "The [quality of service](\(urls.mqtt)) level to use: `0` (at most once), `1` (at least once) or `2` (exactly once)."
+			required:    false
+			type: uint: {
+				default: 1
+				examples: [0, 1, 2]
+				unit: null
+			}
+		}
+		user: {
+			common:      false
+			description: "The username to authenticate with."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	how_it_works: {
+		rumqttc: {
+			title: "rumqttc"
+			body:  """
+				The `mqtt` source and sink use [`rumqttc`](\(urls.rumqttc)) under the hood and
+				support MQTT 3.1.1 brokers. Connections are re-established automatically when
+				they drop.
+				"""
+		}
+		tls: {
+			title: "Transport Layer Security (TLS)"
+			body:  """
+				When TLS is enabled, `tls.ca_file` must be set to the certificate authority used to
+				verify the broker. Client certificates set with `tls.crt_file` and `tls.key_file`
+				are presented to brokers requiring mutual TLS.
+				"""
+		}
+	}
+}
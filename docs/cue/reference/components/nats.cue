@@ -51,6 +51,91 @@ components: _nats: {
 	}
 
 	configuration: {
+		auth: {
+			common:      false
+			description: "Options for [authenticating](\(urls.nats_auth)) with the NATS server."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					nkey: {
+						description:   "The public NKey of the user."
+						relevant_when: "strategy = \"nkey\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["UDXU4RCSJNZOIQHZNWXHXORDPRTGNJAHAHFRGZNEEJCPQTT2M7NLCNF4"]
+							syntax: "literal"
+						}
+					}
+					password: {
+						description:   "The password to authenticate with."
+						relevant_when: "strategy = \"user_password\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["${NATS_PASSWORD}"]
+							syntax: "literal"
+						}
+					}
+					path: {
+						description:   "The path to a credentials file holding a user JWT and NKey seed."
+						relevant_when: "strategy = \"credentials_file\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["/etc/nats/vector.creds"]
+							syntax: "literal"
+						}
+					}
+					seed: {
+						description:   "The NKey seed used to sign the server's challenge."
+						relevant_when: "strategy = \"nkey\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["${NATS_NKEY_SEED}"]
+							syntax: "literal"
+						}
+					}
+					strategy: {
+						description: "The authentication strategy to use."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								credentials_file: "Authenticate with a decentralized JWT and NKey credentials file."
+								nkey:             "Authenticate with an NKey, signing the server's challenge with its seed."
+								token:            "Authenticate with a token."
+								user_password:    "Authenticate with a username and password."
+							}
+							syntax: "literal"
+						}
+					}
+					token: {
+						description:   "The token to authenticate with."
+						relevant_when: "strategy = \"token\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["${NATS_TOKEN}"]
+							syntax: "literal"
+						}
+					}
+					user: {
+						description:   "The username to authenticate with."
+						relevant_when: "strategy = \"user_password\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["vector"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
 		url: {
 			description: "The NATS URL to connect to. The url _must_ take the form of `nats://server:port`."
 			required:    true
@@ -88,5 +173,14 @@ components: _nats: {
 				The `nats` source/sink uses [`nats.rs`](\(urls.nats_rs)) under the hood.
 				"""
 		}
+		tls: {
+			title: "Transport Layer Security (TLS)"
+			body:  """
+				Setting any `tls` option requires the connection to use TLS. The server is verified
+				against the system's root certificates and `tls.ca_file`, and `tls.crt_file` and
+				`tls.key_file` are presented to servers requiring client certificates. Encrypted
+				keys aren't supported, so `tls.key_pass` can't be used.
+				"""
+		}
 	}
 }
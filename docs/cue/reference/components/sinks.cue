@@ -143,6 +143,9 @@ components: sinks: [Name=string]: {
 							if list.Contains(features.send.compression.algorithms, "gzip") {
 								gzip: "[Gzip](\(urls.gzip)) standard DEFLATE compression."
 							}
+							if list.Contains(features.send.compression.algorithms, "snappy") {
+								snappy: "[Snappy](\(urls.snappy)) compression."
+							}
 						}
 						syntax: "literal"
 					}
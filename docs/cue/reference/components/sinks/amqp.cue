@@ -0,0 +1,88 @@
+package metadata
+
+components: sinks: amqp: {
+	title: "AMQP"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			to: components._amqp.features.send.to
+		}
+	}
+
+	support: components._amqp.support
+
+	configuration: components._amqp.configuration & {
+		exchange: {
+			description: "The exchange to publish messages to. An empty string publishes to the default exchange."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["logs", "logs-{{ application }}"]
+				syntax: "template"
+			}
+		}
+		routing_key: {
+			common:      true
+			description: "The routing key to publish messages with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ host }}", "app.production"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: components._amqp.how_it_works & {
+		publisher_confirms: {
+			title: "Publisher confirms"
+			body:  """
+				The sink enables publisher confirms on its channel, so events are only
+				acknowledged once the broker has confirmed it took responsibility for the
+				message. Messages the broker rejects mark their events as failed.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:  components.sources.internal_metrics.output.metrics.events_discarded_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:  components.sources.internal_metrics.output.metrics.processed_events_total
+		send_errors_total:       components.sources.internal_metrics.output.metrics.send_errors_total
+	}
+}
@@ -60,7 +60,7 @@ components: sinks: clickhouse: {
 							url:   urls.clickhouse_http
 						}
 						direction: "outgoing"
-						protocols: ["http"]
+						protocols: ["http", "tcp"]
 						ssl: "optional"
 					}
 				}
@@ -90,6 +90,12 @@ components: sinks: clickhouse: {
 	}
 
 	configuration: {
+		coerce_types: {
+			common:      false
+			description: "Converts event fields to the types of the table's columns before inserting them over HTTP. The columns are looked up with `DESCRIBE TABLE` the first time a table is written to. Always enabled with the native protocol."
+			required:    false
+			type: bool: default: false
+		}
 		auth: configuration._http_auth & {_args: {
 			password_example: "${CLICKHOUSE_PASSWORD}"
 			username_example: "${CLICKHOUSE_USERNAME}"
@@ -106,20 +112,20 @@ components: sinks: clickhouse: {
 			}
 		}
 		endpoint: {
-			description: "The endpoint of the [Clickhouse](\(urls.clickhouse)) server."
+			description: "The endpoint of the [Clickhouse](\(urls.clickhouse)) server. A `tcp://` endpoint inserts over the [native protocol](\(urls.clickhouse_native)) instead of HTTP, defaulting to port `9000`."
 			required:    true
 			type: string: {
-				examples: ["http://localhost:8123"]
+				examples: ["http://localhost:8123", "tcp://localhost:9000"]
 				syntax: "literal"
 			}
 		}
 		table: {
-			description: "The table that data will be inserted into."
+			description: "The table that data will be inserted into. Rendered table names may only contain letters, digits, `_` and `.`; events rendering any other name are dropped."
 			required:    true
 			warnings: []
 			type: string: {
-				examples: ["mytable"]
-				syntax: "literal"
+				examples: ["mytable", "logs_{{ tenant }}"]
+				syntax: "template"
 			}
 		}
 		skip_unknown_fields: {
@@ -134,4 +140,28 @@ components: sinks: clickhouse: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		native_protocol: {
+			title: "Native protocol"
+			body:  """
+				With a `tcp://` endpoint, events are inserted over the [native protocol](\(urls.clickhouse_native)) as
+				blocks of typed columns. A block holds the columns that any of its events has a field for; events
+				missing one of them get `NULL` for nullable columns and the default of the type otherwise. Compression
+				other than `none` enables the protocol's LZ4 compression. TLS and bearer authentication aren't
+				supported with this protocol.
+				"""
+		}
+		type_coercion: {
+			title: "Type coercion"
+			body:  """
+				When `coerce_types` is enabled, or with the native protocol, each field is converted to the
+				[type](\(urls.clickhouse_types)) of its column: timestamps to `Date`, `DateTime` and `DateTime64`
+				values, numbers and strings to the numeric types, and single values to one element arrays.
+				`LowCardinality` and `Nullable` columns are converted as their inner type. Nested fields are matched to
+				columns by dotted names, such as `labels.app`. Fields that can't be converted are left out, and
+				counted in the `processing_errors_total` metric.
+				"""
+		}
+	}
 }
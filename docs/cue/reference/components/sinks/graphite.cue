@@ -0,0 +1,152 @@
+package metadata
+
+components: sinks: graphite: {
+	title: "Graphite"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer:      sinks.socket.features.buffer
+		healthcheck: sinks.socket.features.healthcheck
+		send: {
+			compression: sinks.socket.features.send.compression
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			send_buffer_bytes: {
+				enabled:       true
+				relevant_when: "mode = `tcp` or mode = `udp`"
+			}
+			keepalive: enabled: true
+			request: enabled:   false
+			tls: sinks.socket.features.send.tls
+			to: {
+				service: services.graphite
+
+				interface: {
+					socket: {
+						api: {
+							title: "Graphite plaintext protocol"
+							url:   urls.graphite_plaintext_protocol
+						}
+						direction: "outgoing"
+						protocols: ["tcp", "udp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: sinks.socket.support
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	configuration: {
+		address: {
+			description: "The address of the carbon daemon receiving the plaintext protocol. The address _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["127.0.0.1:2003"]
+				syntax: "literal"
+			}
+		}
+		default_namespace: {
+			common: true
+			description: """
+				Used as a namespace for metrics that don't have it.
+				A namespace will be prefixed to a metric's name, separated by a dot.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+				syntax: "literal"
+			}
+		}
+		format: {
+			common:      true
+			description: "How the tags of the metrics are written."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "dotted"
+				enum: {
+					dotted: "The tag values are added to the dotted path after the metric name, ordered by tag name. The tags with an empty value are left out."
+					tagged: "The tags are written as a [tagged series](\(urls.graphite_tags)), like `name;tag=value`, supported since Graphite 1.1."
+				}
+				syntax: "literal"
+			}
+		}
+		mode: {
+			description: "The type of socket to use."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					tcp: "TCP socket"
+					udp: "UDP socket"
+				}
+				syntax: "literal"
+			}
+		}
+		quantiles: {
+			common:      false
+			description: "The quantiles computed for the [distribution](\(urls.vector_data_model)/metric#distribution) metrics."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.5, 0.75, 0.9, 0.95, 0.99]
+				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
+			}
+		}
+	}
+
+	how_it_works: {
+		metric_paths: {
+			title: "Metric paths"
+			body:  """
+				Each value is sent as a `<path> <value> <timestamp>` line, with the current
+				time used for the metrics without timestamp. The metrics made of several
+				values are sent as one line per value, with a suffix added to the path:
+
+				* distributions are sent as their `min`, `max`, `median`, `avg`, `sum` and
+				  `count`, and their quantiles named after the percentile, like `p99` for
+				  `0.99` and `p99_9` for `0.999`;
+				* aggregated histograms are sent as their `bucket_le_<upper limit>`,
+				  `count` and `sum`, with the dots of the limit replaced by underscores;
+				* aggregated summaries are sent as their quantiles, `count` and `sum`;
+				* sets are sent as the number of values they hold.
+
+				The whitespace, which separates the path from the value, is replaced by
+				underscores, as are the dots of the tag values in the `dotted` format.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		connection_errors_total: components.sources.internal_metrics.output.metrics.connection_errors_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:  components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
@@ -23,7 +23,12 @@ components: sinks: loki: {
 				max_events:   100000
 				timeout_secs: 1
 			}
-			compression: enabled: false
+			compression: {
+				enabled: true
+				default: "snappy"
+				algorithms: ["none", "gzip", "snappy"]
+				levels: ["fast"]
+			}
 			encoding: {
 				enabled: true
 				codec: {
@@ -35,7 +40,8 @@ components: sinks: loki: {
 			proxy: enabled: true
 			request: {
 				enabled:                    true
-				concurrency:                1
+				adaptive_concurrency:       true
+				concurrency:                5
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -81,6 +87,54 @@ components: sinks: loki: {
 	}
 
 	configuration: {
+		cardinality_limit: {
+			common: false
+			description: """
+				Limits the number of distinct streams, that is distinct label sets, that are pushed to Loki within a
+				time window. Once the limit is reached, the label with the most distinct values is dropped or rolled
+				up for every new stream.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					action: {
+						common:      true
+						description: "What to do with the offending label of a new stream once the limit is reached."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "drop"
+							enum: {
+								drop:   "Remove the label from the stream's labels. A stream's last label is rolled up instead, as Loki rejects streams without labels."
+								rollup: "Replace the label's value with `__overflow__`."
+							}
+							syntax: "literal"
+						}
+					}
+					max_streams: {
+						description: "The maximum number of distinct streams within a window."
+						required:    true
+						warnings: []
+						type: uint: {
+							examples: [1000]
+							unit: null
+						}
+					}
+					window_secs: {
+						common:      false
+						description: "The length of the window the streams are counted in."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 60
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
 		endpoint: {
 			description: "The base URL of the Loki instance."
 			required:    true
@@ -195,12 +249,38 @@ components: sinks: loki: {
 				"""
 		}
 
+		cardinality: {
+			title: "Label Cardinality"
+			body: """
+				Every distinct set of labels is a separate stream in Loki,
+				and a label with many values, such as a request or pod id,
+				can create more streams than Loki can handle. The
+				`cardinality_limit` option caps the number of streams seen
+				within a time window. Once the cap is reached, new streams
+				have the label with the most distinct values in the window
+				dropped, or rolled up into the `__overflow__` value. The
+				limited streams fold into one another, so the cap is a soft
+				one.
+				"""
+		}
+
 		concurrency: {
 			title: "Concurrency"
 			body: """
-				To make sure logs arrive at Loki in a correct order,
-				the `loki` sink only sends one request at a time.
-				Setting `request.concurrency` will not have any effects.
+				Events are batched per stream, and the requests of a stream
+				are sent one at a time, in order, including their retries.
+				Requests for different streams are sent concurrently, as
+				configured by `request.concurrency`.
+				"""
+		}
+
+		push_formats: {
+			title: "Push Formats"
+			body: """
+				By default, batches are pushed as [snappy](\(urls.snappy))
+				compressed protobuf, the format Loki's own clients use. With
+				`compression` set to `none` or `gzip`, batches are pushed as
+				JSON instead, uncompressed or gzipped respectively.
 				"""
 		}
 
@@ -26,18 +26,14 @@ components: sinks: nats: {
 				}
 			}
 			request: enabled: false
-			tls: enabled:     false
-			to: {
-				service: services.nats
-
-				interface: {
-					socket: {
-						direction: "outgoing"
-						protocols: ["tcp"]
-						ssl: "disabled"
-					}
-				}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
 			}
+			to: components._nats.features.send.to
 		}
 	}
 
@@ -78,6 +78,24 @@ components: sinks: prometheus_exporter: {
 				syntax: "literal"
 			}
 		}
+		bucket_overrides: {
+			common:      false
+			description: "The buckets of the [distribution](\(urls.vector_data_model)/metric#distribution) metrics of given names, instead of `buckets`. The names don't include the namespace."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"http_request_duration_seconds": [0.01, 0.1, 1.0], "response_size_bytes": [1024.0, 65536.0, 1048576.0]}]
+				options: {
+					"*": {
+						common:      false
+						description: "The buckets of the distributions with this name."
+						required:    false
+						warnings: []
+						type: array: items: type: float: examples: [0.01, 0.1, 1.0]
+					}
+				}
+			}
+		}
 		buckets: {
 			common:      false
 			description: """
@@ -91,6 +109,17 @@ components: sinks: prometheus_exporter: {
 				items: type: float: examples: [0.005, 0.01]
 			}
 		}
+		expire_metrics_secs: {
+			common:      false
+			description: "The time after which a series that was not updated is no longer exposed, and forgotten at the next flush period. The series are never expired by default."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
 		flush_period_secs: {
 			common:      false
 			description: "Time interval between [set](\(urls.vector_data_model)/metric#set) values are reset."
@@ -117,6 +146,13 @@ components: sinks: prometheus_exporter: {
 				syntax: "literal"
 			}
 		}
+		suppress_timestamp: {
+			common:      false
+			description: "Don't expose the timestamps of the metrics, letting Prometheus use the scrape time instead."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		quantiles: {
 			common:      false
 			description: """
@@ -361,9 +397,37 @@ components: sinks: prometheus_exporter: {
 						buckets customized to your use case.
 						"""
 				},
+				{
+					title: "Bucket Overrides"
+					body: """
+						The `bucket_overrides` option defines the buckets of the histograms of given metric
+						names, so that metrics of different natures, such as latencies and sizes, each get
+						sensible boundaries.
+						"""
+				},
 			]
 		}
 
+		exposition_formats: {
+			title: "Exposition Formats"
+			body: """
+				The metrics are exposed in the [OpenMetrics](\(urls.openmetrics)) text format to clients
+				asking for it with the `Accept` header, such as Prometheus, and in the
+				[Prometheus text format](\(urls.prometheus_text_based_exposition_format)) otherwise.
+				OpenMetrics summaries don't include the `_min`, `_max` and `_avg` series of the summaries
+				aggregated from distributions.
+				"""
+		}
+
+		series_expiration: {
+			title: "Series Expiration"
+			body: """
+				Every series is exposed until Vector restarts, even once its source stopped reporting it.
+				With `expire_metrics_secs`, the series that were not updated for that long are no longer
+				exposed, and an incremental series expiring starts over from zero.
+				"""
+		}
+
 		memory_usage: {
 			title: "Memory Usage"
 			body: """
@@ -99,13 +99,14 @@ components: sinks: redis: {
 		}
 		data_type: {
 			common:      false
-			description: "The Redis data type (`list` or `channel`) to use."
+			description: "The Redis data type (`list`, `channel` or `stream`) to use."
 			required:    false
 			type: string: {
 				default: "list"
 				enum: {
 					list:    "Use the Redis `list` data type."
 					channel: "Use the Redis `channel` data type."
+					stream:  "Use the Redis `stream` data type, adding each message as a new entry."
 				}
 				syntax: "literal"
 			}
@@ -134,6 +135,43 @@ components: sinks: redis: {
 				}
 			}
 		}
+		stream: {
+			common:      false
+			description: "Options for the Redis `stream` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					exact: {
+						common:        false
+						description:   "Trim the stream to exactly `maxlen` entries. By default the stream is trimmed approximately, which Redis does far more efficiently."
+						required:      false
+						relevant_when: "maxlen is set"
+						type: bool: default: false
+					}
+					field: {
+						common:      false
+						description: "The entry field holding the encoded message."
+						required:    false
+						type: string: {
+							default: "message"
+							syntax:  "literal"
+						}
+					}
+					maxlen: {
+						common:      false
+						description: "Trim the stream to this many entries as messages are added to it. The stream is not trimmed when unset."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [10000]
+							unit: null
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
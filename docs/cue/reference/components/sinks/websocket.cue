@@ -0,0 +1,139 @@
+package metadata
+
+components: sinks: websocket: {
+	title: "WebSocket"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.websocket
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${WEBSOCKET_PASSWORD}"
+			username_example: "${WEBSOCKET_USERNAME}"
+		}}
+		message_type: {
+			common:      false
+			description: "The type of frame encoded events are sent in."
+			required:    false
+			type: string: {
+				default: "text"
+				enum: {
+					text:   "Send each event in a text frame. Encoded events that aren't valid UTF-8 have their invalid sequences replaced."
+					binary: "Send each event in a binary frame."
+				}
+				syntax: "literal"
+			}
+		}
+		ping_interval_secs: {
+			common:      false
+			description: "How often to send a ping to the server, keeping the connection alive. No pings are sent when unset."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [30]
+				unit: "seconds"
+			}
+		}
+		ping_timeout_secs: {
+			common:        false
+			description:   "How long to wait for the server to answer a ping before reconnecting. This is checked each time a ping is due."
+			relevant_when: "ping_interval_secs is set"
+			required:      false
+			type: uint: {
+				default: null
+				examples: [10]
+				unit: "seconds"
+			}
+		}
+		uri: {
+			description: "The WebSocket URI to connect to. The scheme must be `ws`, or `wss` for connections secured with TLS."
+			required:    true
+			type: string: {
+				examples: ["ws://127.0.0.1:9000/endpoint", "wss://dashboard.example.com/events"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		reconnections: {
+			title: "Reconnections"
+			body:  """
+				The connection is opened once there are events to send and is reopened, with an
+				exponential backoff, whenever it is closed by the server, fails, or the server stops
+				answering pings. An event being sent when the connection fails is lost.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		connection_errors_total:      components.sources.internal_metrics.output.metrics.connection_errors_total
+		connection_established_total: components.sources.internal_metrics.output.metrics.connection_established_total
+		connection_failed_total:      components.sources.internal_metrics.output.metrics.connection_failed_total
+		connection_shutdown_total:    components.sources.internal_metrics.output.metrics.connection_shutdown_total
+		processed_bytes_total:        components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:       components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
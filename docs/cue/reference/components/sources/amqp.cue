@@ -0,0 +1,141 @@
+package metadata
+
+components: sources: amqp: {
+	title: "AMQP"
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			from: components._amqp.features.collect.from
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: true
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: components._amqp.support
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: components._amqp.configuration & {
+		acknowledgements: configuration._acknowledgements
+		consumer: {
+			common:      false
+			description: "The consumer tag to identify this consumer to the broker."
+			required:    false
+			type: string: {
+				default: "vector"
+				examples: ["vector", "vector-{{ host }}"]
+				syntax: "literal"
+			}
+		}
+		exchange_key: {
+			common:      false
+			description: "The log field name to use for the exchange the message was published to."
+			required:    false
+			type: string: {
+				default: "exchange"
+				syntax:  "literal"
+			}
+		}
+		prefetch_count: {
+			common:      false
+			description: "The maximum number of unacknowledged messages the broker delivers to Vector at once."
+			required:    false
+			type: uint: {
+				default: 100
+				examples: [100, 1000]
+				unit: null
+			}
+		}
+		queue: {
+			common:      true
+			description: "The name of the queue to consume."
+			required:    false
+			type: string: {
+				default: "vector"
+				examples: ["logs"]
+				syntax: "literal"
+			}
+		}
+		routing_key: {
+			common:      false
+			description: "The log field name to use for the routing key the message was published with."
+			required:    false
+			type: string: {
+				default: "routing"
+				syntax:  "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual AMQP message."
+		fields: {
+			message: {
+				description: "The body of the AMQP message."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The timestamp of the message if it has one, otherwise the time it was received."
+			}
+			exchange: {
+				description: "The exchange the message was published to."
+				required:    true
+				type: string: {
+					examples: ["logs"]
+					syntax: "literal"
+				}
+			}
+			routing: {
+				description: "The routing key the message was published with."
+				required:    true
+				type: string: {
+					examples: ["app.production"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	how_it_works: components._amqp.how_it_works & {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				When `acknowledgements` are enabled, messages are only acknowledged to the broker
+				once their events have been delivered by every connected sink. Messages whose
+				events errored are requeued, while those rejected by a sink are negatively
+				acknowledged without being requeued, so the broker can dead-letter them if the
+				queue is configured to. Without acknowledgements, the broker considers messages
+				delivered as soon as it sends them.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		consumer_offset_updates_failed_total: components.sources.internal_metrics.output.metrics.consumer_offset_updates_failed_total
+		events_failed_total:                  components.sources.internal_metrics.output.metrics.events_failed_total
+		processed_bytes_total:                components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:               components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
@@ -0,0 +1,198 @@
+package metadata
+
+components: sources: graphite: {
+	_port: 2003
+
+	title: "Graphite"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.graphite
+				interface: socket: {
+					api: {
+						title: "Graphite plaintext protocol"
+						url:   urls.graphite_plaintext_protocol
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["tcp", "udp"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: {
+				enabled:       true
+				relevant_when: "mode = `tcp` or mode = `udp`"
+			}
+			keepalive: enabled: true
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for connections on, or `systemd#N` to use the Nth socket passed by systemd socket activation. If an address is used it _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:2004", "systemd", "systemd#3"]
+				syntax: "literal"
+			}
+		}
+		framing: {
+			common:        true
+			description:   "The protocol of the received data."
+			relevant_when: "mode = `tcp`"
+			required:      false
+			warnings: []
+			type: string: {
+				default: "plaintext"
+				enum: {
+					plaintext: "The [plaintext protocol](\(urls.graphite_plaintext_protocol)), one `<path> <value> <timestamp>` line per value, usually received on port 2003. Lines longer than 64KiB are discarded."
+					pickle:    "The [pickle protocol](\(urls.graphite_pickle_protocol)), frames made of a 4-byte big-endian length followed by a pickled list of `(path, (timestamp, value))` tuples, usually received on port 2004. Frames longer than 1MiB are refused."
+				}
+				syntax: "literal"
+			}
+		}
+		mode: {
+			description: "The type of socket to use. The pickle protocol is only received over TCP."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					tcp: "TCP socket."
+					udp: "UDP socket."
+				}
+				syntax: "literal"
+			}
+		}
+		separator: {
+			common:      false
+			description: "The separator joining the parts of the paths making the name of the metrics, when set by the templates."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "."
+				examples: ["_"]
+				syntax: "literal"
+			}
+		}
+		shutdown_timeout_secs: {
+			common:        false
+			description:   "The timeout before a connection is forcefully closed during shutdown."
+			relevant_when: "mode = `tcp`"
+			required:      false
+			warnings: []
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+		templates: {
+			common:      true
+			description: "The templates converting the dotted paths into a metric name and tags, as described in [Templates](#templates)."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["servers.* .host.measurement* datacenter=dc1", "stats.*.requests .service.measurement.status", "measurement.measurement.host"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	output: metrics: {
+		gauge: output._passthrough_gauge
+	}
+
+	how_it_works: {
+		templates: {
+			title: "Templates"
+			body:  """
+				Graphite names its series with dotted paths, like `servers.web01.cpu.load`,
+				where some parts are better stored as tags. The templates use the
+				[syntax](\(urls.telegraf_graphite_templates)) of the InfluxDB and Telegraf
+				Graphite parsers, `[filter] template [tag=value,...]`:
+
+				* The filter selects the paths the template applies to, part by part, where
+				  `*` matches any part. Paths longer than the filter match as well.
+				* Each part of the template names the matching part of the path:
+				  `measurement` adds it to the metric name, `measurement*` adds it and all
+				  the following parts, an empty part skips it, and any other part turns it
+				  into the value of the tag named after the template part.
+				* The tags are added to all the metrics the template applies to.
+
+				For instance, `servers.* .host.measurement*` converts
+				`servers.web01.cpu.load` into a `cpu.load` metric tagged with
+				`host = "web01"`.
+
+				The first template whose filter matches a path is applied, or the template
+				without filter when none does. The paths matching no template are used as
+				the metric name as is.
+				"""
+		}
+
+		tagged_series: {
+			title: "Tagged series"
+			body:  """
+				The [tags](\(urls.graphite_tags)) of the series named like
+				`disk.used;datacenter=dc1;server=web01` are added to the metrics, after
+				the templates are applied to the path before the first `;`.
+				"""
+		}
+
+		timestamps: {
+			title: "Timestamps"
+			body:  """
+				The timestamps are in seconds since the Unix epoch. The values sent without
+				timestamp, or with a timestamp of `-1`, are assigned a `null` timestamp
+				indicating a realtime metric.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:            components.sources.internal_metrics.output.metrics.events_in_total
+		connection_errors_total:    components.sources.internal_metrics.output.metrics.connection_errors_total
+		invalid_record_total:       components.sources.internal_metrics.output.metrics.invalid_record_total
+		invalid_record_bytes_total: components.sources.internal_metrics.output.metrics.invalid_record_bytes_total
+		processed_bytes_total:      components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
@@ -79,13 +79,14 @@ components: sources: host_metrics: {
 
 	configuration: {
 		collectors: {
-			description: "The list of host metric collector services to use. Defaults to all collectors."
+			description: "The list of host metric collector services to use. Defaults to all collectors but `process` and `cgroups`, which generate metrics for each process and control group."
 			common:      true
 			required:    false
 			type: array: {
 				default: ["cpu", "disk", "filesystem", "load", "host", "memory", "network"]
 				items: type: string: {
 					enum: {
+						cgroups:    "Metrics related to the CPU, memory and I/O utilization of control groups (Linux only)."
 						cpu:        "Metrics related to CPU utilization."
 						disk:       "Metrics related to disk I/O utilization."
 						filesystem: "Metrics related to filesystem space utilization."
@@ -93,6 +94,7 @@ components: sources: host_metrics: {
 						host:       "Metrics related to host"
 						memory:     "Metrics related to memory utilization."
 						network:    "Metrics related to network utilization."
+						process:    "Metrics related to the CPU, memory, threads and open files of each process (Linux only)."
 					}
 					syntax: "literal"
 				}
@@ -332,6 +334,157 @@ components: sources: host_metrics: {
 				}
 			}
 		}
+		process: {
+			common:      false
+			description: #"Options for the "process" metrics collector."#
+			required:    false
+			type: object: options: {
+				names: {
+					common:      false
+					required:    false
+					description: "Lists of process name patterns to include or exclude."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of process name patterns for which to gather metrics.
+								Defaults to including all process names.
+								The patterns are matched using [globbing](#globbing).
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["nginx", "postgres*"]
+									syntax: "literal"
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of process name patterns for which not to gather metrics.
+								Defaults to excluding no process names.
+								The patterns are matched using [globbing](#globbing).
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["nginx", "postgres*"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+				cmdlines: {
+					common:      false
+					required:    false
+					description: "Lists of command line patterns to include or exclude, matched against the arguments of the processes joined with spaces."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of command line patterns for which to gather metrics.
+								Defaults to including all command lines.
+								The patterns are matched using [globbing](#globbing).
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["*--config /etc/app*"]
+									syntax: "literal"
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of command line patterns for which not to gather metrics.
+								Defaults to excluding no command lines.
+								The patterns are matched using [globbing](#globbing).
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["*--config /etc/app*"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+		cgroups: {
+			common:      false
+			description: #"Options for the "cgroups" metrics collector."#
+			required:    false
+			type: object: options: {
+				base: {
+					common:      false
+					required:    false
+					description: "The mount point of the cgroup hierarchies. Defaults to `fs/cgroup` in the sysfs root, set by the `SYSFS_ROOT` environment variable."
+					type: string: {
+						default: "/sys/fs/cgroup"
+						examples: ["/host/sys/fs/cgroup"]
+						syntax: "literal"
+					}
+				}
+				levels: {
+					common:      false
+					required:    false
+					description: "The number of levels of the cgroup hierarchies to scan below their root."
+					type: uint: {
+						default: 100
+						examples: [1, 3]
+						unit: null
+					}
+				}
+				groups: {
+					common:      false
+					required:    false
+					description: "Lists of group name patterns to include or exclude. The names are the paths of the groups from the root of their hierarchy, like `/system.slice/nginx.service`. The groups below the excluded ones are still scanned."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of group name patterns for which to gather metrics.
+								Defaults to including all group names.
+								The patterns are matched using [globbing](#globbing).
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["/", "/system.slice/*"]
+									syntax: "literal"
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of group name patterns for which not to gather metrics.
+								Defaults to excluding no group names.
+								The patterns are matched using [globbing](#globbing).
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["/", "/system.slice/*"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+			}
+		}
 	}
 
 	output: metrics: {
@@ -417,6 +570,44 @@ components: sources: host_metrics: {
 		network_transmit_packets_drop_total: _host & _network_nomac & {description: "The number of packets dropped during transmits on this interface."}
 		network_transmit_packets_total:      _host & _network_nomac & {description: "The number of packets transmitted on this interface."}
 
+		// Host processes
+		process_cpu_seconds_total: _host & _process_metric & {
+			description: "The number of CPU seconds accumulated by the process in different operating modes."
+			type:        "counter"
+			tags:        _process_tags & {
+				mode: {
+					description: "Which mode the CPU was running in during the given time."
+					required:    true
+					examples: ["system", "user"]
+				}
+			}
+		}
+		process_memory_rss_bytes:     _host & _process_gauge & {description: "The number of bytes of main memory used by the process, left out for kernel threads."}
+		process_memory_virtual_bytes: _host & _process_gauge & {description: "The number of bytes of virtual memory of the process, left out for kernel threads."}
+		process_open_fds:             _host & _process_gauge & {description: "The number of files opened by the process, left out when not readable by Vector."}
+		process_threads:              _host & _process_gauge & {description: "The number of threads of the process."}
+
+		// Host cgroups
+		cgroup_cpu_seconds_total: _host & _cgroup_metric & {
+			description: "The number of CPU seconds accumulated by the tasks of the group in different operating modes."
+			type:        "counter"
+			tags:        _cgroup_tags & {
+				mode: {
+					description: "Which mode the CPU was running in during the given time."
+					required:    true
+					examples: ["system", "user"]
+				}
+			}
+		}
+		cgroup_cpu_usage_seconds_total:   _host & _cgroup_counter & {description: "The number of CPU seconds accumulated by the tasks of the group."}
+		cgroup_memory_anon_bytes:         _host & _cgroup_gauge & {description:   "The number of bytes of anonymous memory, like the heaps and stacks, used by the group."}
+		cgroup_memory_current_bytes:      _host & _cgroup_gauge & {description:   "The number of bytes of memory used by the group and its descendants, left out for the root group in cgroup v2."}
+		cgroup_memory_file_bytes:         _host & _cgroup_gauge & {description:   "The number of bytes of memory used by the group to cache files."}
+		cgroup_io_read_bytes_total:       _host & _cgroup_io & {description:      "The number of bytes read by the group from the device."}
+		cgroup_io_reads_completed_total:  _host & _cgroup_io & {description:      "The number of read operations of the group completed by the device."}
+		cgroup_io_writes_completed_total: _host & _cgroup_io & {description:      "The number of write operations of the group completed by the device."}
+		cgroup_io_written_bytes_total:    _host & _cgroup_io & {description:      "The number of bytes written by the group to the device."}
+
 		// Helpers
 		_host: {
 			default_namespace: "host"
@@ -486,6 +677,50 @@ components: sources: host_metrics: {
 			}
 		}
 		_network_nomac: _network_gauge & {relevant_when: "OS is not macOS"}
+		_process_tags: _host_metrics_tags & {
+			collector: examples: ["process"]
+			pid: {
+				description: "The identifier of the process."
+				required:    true
+				examples: ["1234"]
+			}
+			name: {
+				description: "The name of the process."
+				required:    true
+				examples: ["nginx"]
+			}
+		}
+		_process_metric: {relevant_when: "OS is Linux"}
+		_process_gauge: _process_metric & {
+			type: "gauge"
+			tags: _process_tags
+		}
+		_cgroup_tags: _host_metrics_tags & {
+			collector: examples: ["cgroups"]
+			cgroup: {
+				description: "The path of the control group from the root of its hierarchy."
+				required:    true
+				examples: ["/", "/system.slice/nginx.service"]
+			}
+		}
+		_cgroup_metric: {relevant_when: "OS is Linux"}
+		_cgroup_counter: _cgroup_metric & {
+			type: "counter"
+			tags: _cgroup_tags
+		}
+		_cgroup_gauge: _cgroup_metric & {
+			type: "gauge"
+			tags: _cgroup_tags
+		}
+		_cgroup_io: _cgroup_counter & {
+			tags: _cgroup_tags & {
+				device: {
+					description: "The `major:minor` number of the block device."
+					required:    true
+					examples: ["8:0"]
+				}
+			}
+		}
 	}
 
 	telemetry: metrics: {
@@ -0,0 +1,202 @@
+package metadata
+
+components: sources: influxdb: {
+	_port: 8086
+
+	title: "InfluxDB"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.influxdb
+				interface: socket: {
+					api: {
+						title: "InfluxDB line protocol"
+						url:   urls.influxdb_line_protocol
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["http", "tcp", "udp"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: {
+				enabled:       true
+				relevant_when: "mode = `tcp` or mode = `udp`"
+			}
+			keepalive: enabled: true
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for connections on, or `systemd#N` to use the Nth socket passed by systemd socket activation in `tcp` mode. If an address is used it _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:8094", "systemd", "systemd#3"]
+				syntax: "literal"
+			}
+		}
+		auth: configuration._http_basic_auth & {relevant_when: "mode = `http`"}
+		mode: {
+			description: "The type of server to use."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					http: "HTTP server, implementing the [`/write`](\(urls.influxdb_http_api_v1)) endpoint of InfluxDB 1.x and the [`/api/v2/write`](\(urls.influxdb_http_api_v2)) endpoint of InfluxDB 2.x."
+					tcp:  "TCP socket, receiving one point per line."
+					udp:  "UDP socket, receiving one or more lines per datagram."
+				}
+				syntax: "literal"
+			}
+		}
+		output: {
+			common:      true
+			description: "The events the points are converted into."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "metrics"
+				enum: {
+					metrics: "A gauge per numeric or boolean field of the points."
+					logs:    "A log event per point."
+				}
+				syntax: "literal"
+			}
+		}
+		precision: {
+			common:      false
+			description: "The unit of the timestamps of the points. On the HTTP endpoints, it is overridden by the `precision` query parameter of the requests."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "ns"
+				enum: {
+					ns: "Nanoseconds."
+					us: "Microseconds."
+					ms: "Milliseconds."
+					s:  "Seconds."
+					m:  "Minutes."
+					h:  "Hours."
+				}
+				syntax: "literal"
+			}
+		}
+		shutdown_timeout_secs: {
+			common:        false
+			description:   "The timeout before a connection is forcefully closed during shutdown."
+			relevant_when: "mode = `tcp`"
+			required:      false
+			warnings: []
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: {
+		metrics: gauge: output._passthrough_gauge
+		logs: point: {
+			description: "A point, when `output` is `logs`."
+			fields: {
+				"*": {
+					common:      false
+					description: "The tags and fields of the point, as flat fields. The fields take precedence over the tags of the same name."
+					required:    false
+					type: "*": {}
+				}
+				measurement: {
+					description: "The measurement of the point."
+					required:    true
+					type: string: {
+						examples: ["cpu"]
+						syntax: "literal"
+					}
+				}
+				source_type: {
+					description: "The name of the source type."
+					required:    true
+					type: string: {
+						examples: ["influxdb"]
+						syntax: "literal"
+					}
+				}
+				timestamp: fields._current_timestamp & {
+					description: "The timestamp of the point, or the time it was received when it has none."
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		metrics: {
+			title: "Metrics"
+			body:  """
+				Each numeric field of a point becomes a gauge named after the field, in the
+				namespace of the measurement, and tagged with the tags of the point. The
+				boolean fields are converted into `1` and `0`, and the string fields are
+				left out.
+
+				For instance, `cpu,host=web01 usage=12.5,cores=8i` results in the
+				`cpu.usage` and `cpu.cores` gauges tagged with `host = "web01"`.
+				"""
+		}
+
+		invalid_lines: {
+			title: "Invalid lines"
+			body:  """
+				Like InfluxDB, the HTTP endpoints reject the requests with an invalid line
+				with a `400` status, and none of their points are kept. Over TCP and UDP,
+				the invalid lines are discarded.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:            components.sources.internal_metrics.output.metrics.events_in_total
+		connection_errors_total:    components.sources.internal_metrics.output.metrics.connection_errors_total
+		http_bad_requests_total:    components.sources.internal_metrics.output.metrics.http_bad_requests_total
+		invalid_record_total:       components.sources.internal_metrics.output.metrics.invalid_record_total
+		invalid_record_bytes_total: components.sources.internal_metrics.output.metrics.invalid_record_bytes_total
+		processed_bytes_total:      components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
@@ -146,6 +146,14 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		component_errors_total: {
+			description:       "The total number of errors encountered by this component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				error_type: _error_type
+			}
+		}
 		connection_errors_total: {
 			description:       "The total number of connection errors for this Vector instance."
 			type:              "counter"
@@ -552,6 +560,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		file_discovery_errors_total: {
+			description:       "The total number of errors reading the target files of file based service discovery."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		file_delete_errors_total: {
 			description:       "The total number of failures to delete a file."
 			type:              "counter"
@@ -801,6 +815,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		slo_flushes_total: {
+			description:       "The number of flushes done by the slo transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		sqs_message_delete_failed_total: {
 			description:       "The total number of failures to delete SQS messages."
 			type:              "counter"
@@ -1006,8 +1026,10 @@ components: sources: internal_metrics: {
 			description: "The type of the error"
 			required:    true
 			enum: {
+				"acknowledgment_failed":       "The acknowledgement of a message to its source failed."
 				"field_missing":               "The event field was missing."
 				"invalid_metric":              "The metric was invalid."
+				"invalid_table_name":          "The rendered table name was not a valid identifier."
 				"mapping_failed":              "The mapping failed."
 				"match_failed":                "The match operation failed."
 				"parse_failed":                "The parsing operation failed."
@@ -0,0 +1,155 @@
+package metadata
+
+components: sources: loki: {
+	_port: 3100
+
+	title: "Loki"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.loki
+
+				interface: socket: {
+					api: {
+						title: "Loki push API"
+						url:   urls.loki_push_api
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		address: {
+			description: "The address to accept connections on. The address _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+				syntax: "literal"
+			}
+		}
+		auth: configuration._http_basic_auth
+	}
+
+	output: logs: line: {
+		description: "An individual entry of a pushed stream."
+		fields: {
+			"*": {
+				common:      false
+				description: "The labels of the entry's stream, as flat fields."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["varlogs"]
+					syntax: "literal"
+				}
+			}
+			message: {
+				description: "The line of the entry."
+				required:    true
+				type: string: {
+					examples: ["Hello world"]
+					syntax: "literal"
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["loki"]
+					syntax: "literal"
+				}
+			}
+			tenant_id: {
+				description: "The tenant ID of the request, from its `X-Scope-OrgID` header."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["fake"]
+					syntax: "literal"
+				}
+			}
+			timestamp: {
+				description: "The timestamp of the entry."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	how_it_works: {
+		push_formats: {
+			title: "Push Formats"
+			body: """
+				The source implements Loki's `/loki/api/v1/push` endpoint, so
+				Loki clients such as [Promtail](\(urls.promtail)) can push to
+				it by pointing their Loki URL at Vector. Requests with an
+				`application/json` content type are decoded as JSON push
+				requests, optionally compressed as set by their
+				`Content-Encoding` header. All other requests are decoded as
+				snappy compressed protobuf push requests, like Loki does.
+				"""
+		}
+
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				With `acknowledgements` enabled, the response to a push
+				request is only sent once its entries are delivered. Entries
+				that fail to be delivered are answered with an error status,
+				so that the client can retry them.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:         components.sources.internal_metrics.output.metrics.events_in_total
+		http_bad_requests_total: components.sources.internal_metrics.output.metrics.http_bad_requests_total
+		parse_errors_total:      components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
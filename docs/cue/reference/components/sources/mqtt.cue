@@ -0,0 +1,109 @@
+package metadata
+
+components: sources: mqtt: {
+	title: "MQTT"
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			from: components._mqtt.features.collect.from
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: components._mqtt.support
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: components._mqtt.configuration & {
+		acknowledgements: configuration._acknowledgements
+		topic_key: {
+			common:      false
+			description: "The log field name to use for the topic the message was published to."
+			required:    false
+			type: string: {
+				default: "topic"
+				syntax:  "literal"
+			}
+		}
+		topics: {
+			description: "The [topic filters](\(urls.mqtt_topics)) to subscribe to. Filters may use the `+` and `#` wildcards."
+			required:    true
+			type: array: items: type: string: {
+				examples: ["sensors/+/temperature", "factory/#"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual MQTT message."
+		fields: {
+			message: {
+				description: "The payload of the MQTT message."
+				required:    true
+				type: string: {
+					examples: ["{\"temperature\": 21.5}"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp
+			topic: {
+				description: "The topic the message was published to."
+				required:    true
+				type: string: {
+					examples: ["sensors/kitchen/temperature"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	how_it_works: components._mqtt.how_it_works & {
+		sessions: {
+			title: "Sessions"
+			body:  """
+				The source subscribes to its topics whenever the broker doesn't hold a session for
+				it. With `clean_session` disabled the broker remembers the subscriptions and queues
+				messages with a QoS of `1` or `2` while Vector is disconnected, delivering them on
+				reconnection.
+				"""
+		}
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				When `acknowledgements` are enabled, messages with a QoS of `1` or `2` are only
+				acknowledged to the broker once their events have been delivered by every connected
+				sink. MQTT has no negative acknowledgement, so messages whose events errored are left
+				unacknowledged for the broker to redeliver when the session is resumed, while those
+				rejected by a sink are acknowledged and dropped. Without acknowledgements, messages are
+				acknowledged as soon as they are received.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:  components.sources.internal_metrics.output.metrics.component_errors_total
+		connection_errors_total: components.sources.internal_metrics.output.metrics.connection_errors_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:  components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
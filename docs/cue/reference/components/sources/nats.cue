@@ -6,6 +6,13 @@ components: sources: nats: {
 	features: {
 		collect: {
 			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
 			from: components._nats.features.collect.from
 		}
 		multiline: enabled: false
@@ -14,7 +21,7 @@ components: sources: nats: {
 	classes: {
 		commonly_used: true
 		deployment_roles: ["aggregator"]
-		delivery:      "best_effort"
+		delivery:      "at_least_once"
 		development:   "beta"
 		egress_method: "stream"
 		stateful:      false
@@ -27,9 +34,59 @@ components: sources: nats: {
 	}
 
 	configuration: components._nats.configuration & {
+		acknowledgements: configuration._acknowledgements
+		jetstream: {
+			common:      false
+			description: "Consume from a [JetStream](\(urls.nats_jetstream)) stream through a durable consumer instead of a core NATS subscription. The `subject` is used to filter the stream's messages, and `queue` sets the consumer's deliver group, so the Vector instances sharing it also share the messages."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					ack_wait_secs: {
+						common:      false
+						description: "How long the server waits for a message to be acknowledged before redelivering it."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+					durable_name: {
+						description: "The name of the durable consumer, which tracks what has been acknowledged across restarts. It's created if it doesn't exist."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["vector"]
+							syntax: "literal"
+						}
+					}
+					max_ack_pending: {
+						common:      false
+						description: "The maximum number of messages delivered but not yet acknowledged."
+						required:    false
+						warnings: []
+						type: int: {
+							default: 1000
+							unit:    null
+						}
+					}
+					stream: {
+						description: "The name of the stream to consume."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["ORDERS"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
 		queue: {
 			common:      false
-			description: "NATS Queue Group to join"
+			description: "NATS Queue Group to join. With `jetstream`, the deliver group of the consumer, which load balances its messages between the subscribers of the group."
 			required:    false
 			type: string: {
 				default: "vector"
@@ -54,9 +111,22 @@ components: sources: nats: {
 	}
 
 	telemetry: metrics: {
+		component_errors_total: components.sources.internal_metrics.output.metrics.component_errors_total
 		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
 	}
 
-	how_it_works: components._nats.how_it_works
+	how_it_works: components._nats.how_it_works & {
+		jetstream: {
+			title: "JetStream"
+			body:  """
+				With `jetstream` set, every message is explicitly acknowledged. When
+				`acknowledgements` are enabled, that only happens once the message's event has been
+				delivered by every connected sink, making delivery at-least-once: messages whose
+				events errored are negatively acknowledged so the server redelivers them, while
+				those rejected by a sink are terminated. Without acknowledgements, messages are
+				acknowledged as soon as they are received.
+				"""
+		}
+	}
 }
@@ -63,16 +63,181 @@ components: sources: prometheus_scrape: {
 
 	configuration: {
 		endpoints: {
-			description: "Endpoints to scrape metrics from."
-			required:    true
+			common:      true
+			description: "Endpoints to scrape metrics from. At least one of `endpoints`, `file_sd_configs` or `kubernetes_sd_configs` must be set."
+			required:    false
 			warnings: ["You must explicitly add the path to your endpoints. Vector will _not_ automatically add `/metics`."]
 			type: array: {
+				default: []
 				items: type: string: {
 					examples: ["http://localhost:9090/metrics"]
 					syntax: "literal"
 				}
 			}
 		}
+		endpoint_tag: {
+			common:      false
+			description: "The tag name added to each metric holding the URL of the scraped target."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["endpoint"]
+				syntax: "literal"
+			}
+		}
+		file_sd_configs: {
+			common:      false
+			description: "Discover the targets to scrape from files, in the [Prometheus file based service discovery](\(urls.prometheus_file_sd_config)) format."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						files: {
+							description: "The JSON or YAML files listing the targets. Files with a `.yml` or `.yaml` extension are read as YAML, the others as JSON. Glob patterns are supported."
+							required:    true
+							warnings: []
+							type: array: items: type: string: {
+								examples: ["/etc/vector/targets/*.json"]
+								syntax: "literal"
+							}
+						}
+						refresh_interval_secs: {
+							common:      false
+							description: "The interval between checks for changes to the files."
+							required:    false
+							warnings: []
+							type: uint: {
+								default: 30
+								unit:    "seconds"
+							}
+						}
+					}
+				}
+			}
+		}
+		honor_labels: {
+			description: """
+				How to handle the scraped tags conflicting with the tags added by Vector, such as `instance_tag`,
+				`endpoint_tag` or the tags of discovered targets. If `true`, the scraped tags are kept and the
+				conflicting tags are not added. If `false`, the scraped tags are renamed to `exported_<name>`.
+				"""
+			common:   false
+			required: false
+			warnings: []
+			type: bool: default: false
+		}
+		instance_tag: {
+			common:      false
+			description: "The tag name added to each metric holding the `host:port` of the scraped target."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["instance"]
+				syntax: "literal"
+			}
+		}
+		kubernetes_sd_configs: {
+			common:      false
+			description: "Discover the targets to scrape through the Kubernetes API."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						field_selector: {
+							common:      false
+							description: "The [field selector](\(urls.kubernetes_field_selector)) of the watched resources."
+							required:    false
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["spec.nodeName=node-1"]
+								syntax: "literal"
+							}
+						}
+						kube_config_file: {
+							common:      false
+							description: "The path to a kubeconfig file, the in-cluster configuration is used by default."
+							required:    false
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["/path/to/.kube/config"]
+								syntax: "literal"
+							}
+						}
+						label_selector: {
+							common:      true
+							description: "The [label selector](\(urls.kubernetes_label_selector)) of the watched resources."
+							required:    false
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["app.kubernetes.io/name=api"]
+								syntax: "literal"
+							}
+						}
+						namespaces: {
+							common:      true
+							description: "The namespaces of the discovered targets, all namespaces by default."
+							required:    false
+							warnings: []
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["default"]
+									syntax: "literal"
+								}
+							}
+						}
+						role: {
+							description: "The Kubernetes resources the targets are discovered from."
+							required:    true
+							warnings: []
+							type: string: {
+								enum: {
+									pod:       "A target for each TCP port declared by the containers of running pods."
+									service:   "A target for each TCP port of services, at the DNS name of the service."
+									endpoints: "A target for each TCP port of the ready addresses of endpoints."
+								}
+								syntax: "literal"
+							}
+						}
+					}
+				}
+			}
+		}
+		metrics_path: {
+			common:      false
+			description: "The path to scrape the discovered targets at."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "/metrics"
+				syntax:  "literal"
+			}
+		}
+		scheme: {
+			common:      false
+			description: "The scheme used to scrape the discovered targets."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "http"
+				enum: {
+					http:  "HTTP"
+					https: "HTTPS"
+				}
+				syntax: "literal"
+			}
+		}
 		scrape_interval_secs: {
 			common:      true
 			description: "The interval between scrapes, in seconds."
@@ -83,6 +248,16 @@ components: sources: prometheus_scrape: {
 				unit:    "seconds"
 			}
 		}
+		scrape_timeout_secs: {
+			common:      false
+			description: "The timeout of a scrape, in seconds. It is capped at `scrape_interval_secs`."
+			required:    false
+			warnings: []
+			type: float: {
+				default: 5.0
+				unit:    "seconds"
+			}
+		}
 		auth: configuration._http_auth & {_args: {
 			password_example: "${PROMETHEUS_PASSWORD}"
 			username_example: "${PROMETHEUS_USERNAME}"
@@ -96,14 +271,54 @@ components: sources: prometheus_scrape: {
 		summary:   output._passthrough_summary
 	}
 
+	how_it_works: {
+		exposition_formats: {
+			title: "Exposition formats"
+			body:  """
+				The source asks the targets for the [OpenMetrics](\(urls.openmetrics)) text format, and falls back to
+				the [Prometheus text format](\(urls.prometheus_text_based_exposition_format)). OpenMetrics counters
+				are named after their `_total` samples, info metrics after their `_info` samples, and state sets
+				are gauges. The exemplars and the `_created` samples are not kept.
+				"""
+		}
+		service_discovery: {
+			title: "Service discovery"
+			body:  """
+				Besides the static `endpoints`, the targets to scrape can be discovered from files with
+				`file_sd_configs`, or through the Kubernetes API with `kubernetes_sd_configs`. The discovered targets
+				are scraped at the `scheme` and `metrics_path` of the source, unless overridden by the `__scheme__`
+				and `__metrics_path__` labels of a target file.
+
+				The metrics scraped from a discovered target are tagged with its address as `instance`, along with
+				the labels of the target file, or the metadata of the Kubernetes resource, such as
+				`kubernetes_namespace`, `kubernetes_pod_name` and `kubernetes_pod_label_<name>`. Like Prometheus, a
+				scraped tag conflicting with a target tag is kept as `exported_<name>`, unless `honor_labels` is
+				set.
+
+				Kubernetes discovery requires permissions to `list` and `watch` the resources of its `role`, see
+				[RBAC](\(urls.kubernetes_rbac)).
+				"""
+		}
+		scrape_health: {
+			title: "Scrape health"
+			body:  """
+				For each scrape, the source also emits the `up` gauge, set to `1` if the target was scraped
+				successfully and `0` otherwise, and the `scrape_duration_seconds` gauge. Both are tagged with the tags
+				of the target, and with its address as `instance`, or as `instance_tag` when set. A scrape taking
+				longer than `scrape_timeout_secs` fails. Up to 64 targets are scraped concurrently.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
-		events_in_total:           components.sources.internal_metrics.output.metrics.events_in_total
-		http_error_response_total: components.sources.internal_metrics.output.metrics.http_error_response_total
-		http_request_errors_total: components.sources.internal_metrics.output.metrics.http_request_errors_total
-		parse_errors_total:        components.sources.internal_metrics.output.metrics.parse_errors_total
-		processed_bytes_total:     components.sources.internal_metrics.output.metrics.processed_bytes_total
-		processed_events_total:    components.sources.internal_metrics.output.metrics.processed_events_total
-		requests_completed_total:  components.sources.internal_metrics.output.metrics.requests_completed_total
-		request_duration_seconds:  components.sources.internal_metrics.output.metrics.request_duration_seconds
+		events_in_total:             components.sources.internal_metrics.output.metrics.events_in_total
+		file_discovery_errors_total: components.sources.internal_metrics.output.metrics.file_discovery_errors_total
+		http_error_response_total:   components.sources.internal_metrics.output.metrics.http_error_response_total
+		http_request_errors_total:   components.sources.internal_metrics.output.metrics.http_request_errors_total
+		parse_errors_total:          components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:       components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:      components.sources.internal_metrics.output.metrics.processed_events_total
+		requests_completed_total:    components.sources.internal_metrics.output.metrics.requests_completed_total
+		request_duration_seconds:    components.sources.internal_metrics.output.metrics.request_duration_seconds
 	}
 }
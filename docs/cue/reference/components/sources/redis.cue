@@ -0,0 +1,237 @@
+package metadata
+
+components: sources: redis: {
+	title: "Redis"
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			from: {
+				service: services.redis
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		url: {
+			description: "The Redis URL to connect to. The url _must_ take the form of `protocol://server:port/db` where the protocol can either be `redis` or `rediss` for connections secured via TLS."
+			groups: ["tcp"]
+			required: true
+			warnings: []
+			type: string: {
+				examples: ["redis://127.0.0.1:6379/0"]
+				syntax: "literal"
+			}
+		}
+		key: {
+			description: "The Redis key to read messages from. When `channel.pattern` is set, this is a glob-style pattern of channels to subscribe to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector", "logs.*"]
+				syntax: "literal"
+			}
+		}
+		redis_key: {
+			common:      false
+			description: "The log field name to use for the Redis key the message was read from."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["redis_key"]
+				syntax: "literal"
+			}
+		}
+		data_type: {
+			common:      false
+			description: "The Redis data type (`list`, `channel` or `stream`) to read from."
+			required:    false
+			type: string: {
+				default: "list"
+				enum: {
+					list:    "Pop messages from a Redis `list`."
+					channel: "Subscribe to a Redis [`channel`](\(urls.redis_pubsub))."
+					stream:  "Read entries from a Redis `stream` as part of a [consumer group](\(urls.redis_consumer_groups))."
+				}
+				syntax: "literal"
+			}
+		}
+		list: {
+			common:      false
+			description: "Options for the Redis `list` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					method: {
+						common:      false
+						description: "The method (`lpop` or `rpop`) to pop messages with when `data_type` is list."
+						required:    false
+						type: string: {
+							default: "lpop"
+							enum: {
+								lpop: "Use the blocking `blpop` method to pop messages."
+								rpop: "Use the blocking `brpop` method to pop messages."
+							}
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		channel: {
+			common:      false
+			description: "Options for the Redis `channel` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					pattern: {
+						common:      false
+						description: "Treat `key` as a glob-style pattern and subscribe to all matching channels."
+						required:    false
+						type: bool: default: false
+					}
+				}
+			}
+		}
+		stream: {
+			common:      false
+			description: "Options for the Redis `stream` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					batch_size: {
+						common:      false
+						description: "The maximum number of entries to read at once."
+						required:    false
+						type: uint: {
+							default: 100
+							unit:    null
+						}
+					}
+					consumer: {
+						common:      false
+						description: "The name of this consumer within the consumer group."
+						required:    false
+						type: string: {
+							default: "vector"
+							syntax:  "literal"
+						}
+					}
+					group: {
+						description:   "The consumer group to read entries as. The group is created if it doesn't exist."
+						relevant_when: "data_type = \"stream\""
+						required:      true
+						type: string: {
+							examples: ["vector"]
+							syntax: "literal"
+						}
+					}
+					start_id: {
+						common:      false
+						description: "The id of the last entry a newly created consumer group has seen. Use `$` to only read entries added from then on, or `0` to read the whole stream."
+						required:    false
+						type: string: {
+							default: "$"
+							examples: ["$", "0"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Redis message or stream entry."
+		fields: {
+			message: {
+				description: "The message read from a list or channel. Stream entries have their fields added to the event instead."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		redis_rs: {
+			title: "redis-rs"
+			body:  """
+				The `redis` source uses [`redis-rs`](\(urls.redis_rs)) under the hood, which is a high level Redis library
+				for Rust. It provides convenient access to all Redis functionality through a very flexible but low-level
+				API.
+				"""
+		}
+		stream_acknowledgements: {
+			title: "Stream acknowledgements"
+			body:  """
+				Stream entries are acknowledged with `XACK` once their events have been delivered, or rejected, by
+				the connected sinks when `acknowledgements` is enabled, and as soon as they are read otherwise. Entries
+				that couldn't be delivered stay pending in the consumer group and are read again every 30 seconds, and
+				when Vector restarts.
+				Channels have no such guarantees: messages published while Vector is not subscribed are lost.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		consumer_offset_updates_failed_total: components.sources.internal_metrics.output.metrics.consumer_offset_updates_failed_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:                components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
@@ -104,7 +104,16 @@ components: sources: statsd: {
 				unit:    "seconds"
 			}
 		}
-
+		sketch_relative_accuracy: {
+			common:      false
+			description: "When set, timers and histograms are emitted as sketches with this relative accuracy instead of distributions. Sketches can be merged downstream while staying small. Valid values are greater than 0 and less than 1."
+			required:    false
+			warnings: []
+			type: float: {
+				default: null
+				examples: [0.01]
+			}
+		}
 	}
 
 	output: metrics: {
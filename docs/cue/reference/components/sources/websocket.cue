@@ -0,0 +1,144 @@
+package metadata
+
+components: sources: websocket: {
+	_port: 8080
+
+	title: "WebSocket"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator", "sidecar"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.websocket
+
+				interface: {
+					socket: {
+						direction: "incoming"
+						port:      _port
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to accept connections on. The address _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "localhost:\(_port)"]
+				syntax: "literal"
+			}
+		}
+		auth: configuration._http_basic_auth
+		encoding: {
+			common:      true
+			description: "How the payload of each text or binary frame is decoded."
+			required:    false
+			type: string: {
+				default: "text"
+				enum: {
+					text: "The whole frame forms a single message."
+					json: "A JSON object, or an array of JSON objects, each of which forms an event with the object's fields."
+				}
+				syntax: "literal"
+			}
+		}
+		path: {
+			common:      false
+			description: "The URL path clients must open their connection on."
+			required:    false
+			type: string: {
+				default: "/"
+				examples: ["/events"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: {
+		text: {
+			description: "An individual frame, when `encoding` is `text`."
+			fields: {
+				message: {
+					description: "The payload of the frame."
+					required:    true
+					type: string: {
+						examples: ["Hello world"]
+						syntax: "literal"
+					}
+				}
+				timestamp: fields._current_timestamp
+			}
+		}
+		structured: {
+			description: "An individual JSON object from a frame, when `encoding` is `json`."
+			fields: {
+				"*": {
+					common:      false
+					description: "Any field contained in the JSON object."
+					required:    false
+					type: "*": {}
+				}
+				timestamp: fields._current_timestamp
+			}
+		}
+	}
+
+	how_it_works: {
+		frames: {
+			title: "Frames"
+			body:  """
+				Every text or binary frame received on a connection is decoded into events. Pings are
+				answered automatically and other control frames are ignored. Frames that can't be
+				decoded are dropped without closing the connection. Connections are closed when
+				Vector shuts down.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		connection_errors_total: components.sources.internal_metrics.output.metrics.connection_errors_total
+		events_in_total:         components.sources.internal_metrics.output.metrics.events_in_total
+		http_bad_requests_total: components.sources.internal_metrics.output.metrics.http_bad_requests_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
@@ -37,6 +37,79 @@ components: transforms: aggregate: {
 	}
 
 	configuration: {
+		aggregations: {
+			common:      false
+			description: "How the values of counters and gauges that are aggregated into the same series are combined."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{counter: "sum", gauge: "max"}]
+				options: {
+					counter: {
+						common:      false
+						description: "The function combining the values of counters."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "sum"
+							enum: {
+								sum:   "The sum of the values."
+								max:   "The largest value."
+								min:   "The smallest value."
+								mean:  "The mean of the values."
+								last:  "The most recently received value."
+								count: "The number of values."
+							}
+							syntax: "literal"
+						}
+					}
+					gauge: {
+						common:      false
+						description: "The function combining the values of gauges."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "sum"
+							enum: {
+								sum:   "The sum of the values."
+								max:   "The largest value."
+								min:   "The smallest value."
+								mean:  "The mean of the values."
+								last:  "The most recently received value."
+								count: "The number of values."
+							}
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		drop_tags: {
+			common:      true
+			description: "Tags removed from the aggregated metrics. Series that only differ in these tags are aggregated together. Can't be used with `group_by`."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["host", "pod_name"]
+					syntax: "literal"
+				}
+			}
+		}
+		group_by: {
+			common:      true
+			description: "The only tags kept on the aggregated metrics. Series that only differ in other tags are aggregated together. Can't be used with `drop_tags`."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["region", "service"]
+					syntax: "literal"
+				}
+			}
+		}
 		interval_ms: {
 			common: true
 			description: """
@@ -50,6 +123,21 @@ components: transforms: aggregate: {
 				unit:    "milliseconds"
 			}
 		}
+		sketch_relative_accuracy: {
+			common: false
+			description: """
+				When set, distributions are converted to sketches with this relative accuracy before being
+				aggregated, so the memory used by a series no longer grows with the number of samples. Quantiles
+				estimated from the sketch are within this fraction of the exact value. Valid values are greater than 0
+				and less than 1.
+				"""
+			required: false
+			warnings: []
+			type: float: {
+				default: null
+				examples: [0.01]
+			}
+		}
 	}
 
 	input: {
@@ -196,6 +284,20 @@ components: transforms: aggregate: {
 				"""
 		}
 
+		grouping: {
+			title: "Grouping"
+			body: """
+				With `group_by` or `drop_tags`, series that only differ in the removed tags are aggregated into a
+				single series. Their `incremental` metrics are combined as above, and the latest `absolute` metric of
+				each series is combined with the latest of the others, so two hosts reporting `absolute` gauges of 3 and
+				5 result in a single gauge of 8. For counters and gauges, `aggregations` chooses how values are
+				combined: for `incremental` metrics the function applies to all the values received during the
+				interval, for `absolute` metrics to the latest value of each series. Other types are always merged.
+				When the series of an `absolute` group differ in value type, only those of the same type as the latest
+				metric are combined.
+				"""
+		}
+
 		advantages: {
 			title: "Advantages of Use"
 			body: """
@@ -0,0 +1,59 @@
+package metadata
+
+components: transforms: metric_relabel: {
+	title: "Metric Relabel"
+
+	description: """
+		Rewrites the names and tags of metrics, and filters metrics, with
+		[Prometheus style relabeling rules](\(urls.prometheus_relabel_config)).
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		relabel_configs: {
+			description: "The relabeling rules, applied in order to each metric."
+			required:    true
+			warnings: []
+			type: array: items: type: object: {
+				how_it_works: {
+		relabeling: {
+			title: "Relabeling"
+			body: """
+				The rules follow the [Prometheus relabeling](\(urls.prometheus_relabel_config)) semantics, with the
+				metric tags as labels and the metric name as the `__name__` label. The metric namespace is left
+				untouched. Rules are applied in order, and a metric dropped by a `keep` or `drop` rule is not
+				processed by the following rules.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -0,0 +1,193 @@
+package metadata
+
+components: transforms: slo: {
+	title: "SLO"
+
+	description: """
+		Tracks service level objectives from log events, emitting the ratio
+		of good events and the error budget burn rate over multiple windows.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		interval_secs: {
+			common:      true
+			description: "The interval between emissions of the gauges, and the granularity of the windows, in seconds."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		namespace: {
+			common:      false
+			description: "The namespace of the emitted metrics."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+				syntax: "literal"
+			}
+		}
+		objectives: {
+			description: "The objectives to track."
+			required:    true
+			warnings: []
+			type: array: items: type: object: {
+				examples: []
+				options: {
+					good: {
+						description: "The condition matching the good events, amongst the counted ones."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: [#"(to_int(.status) ?? 500) < 500"#]
+							syntax: "remap_boolean_expression"
+						}
+					}
+					name: {
+						description: "The name of the objective, used as the `slo` tag of its metrics."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["availability"]
+							syntax: "literal"
+						}
+					}
+					tags: {
+						common:      false
+						description: "Tags added to the metrics of the objective."
+						required:    false
+						warnings: []
+						type: object: {
+							examples: [{service: "checkout"}]
+							options: {}
+						}
+					}
+					target: {
+						description: "The fraction of the counted events expected to be good. Must be greater than 0 and less than 1."
+						required:    true
+						warnings: []
+						type: float: examples: [0.99, 0.999]
+					}
+					total: {
+						common:      false
+						description: "The condition matching the events counted by the objective. All the events are counted when unset."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: [#".path == "/checkout""#]
+							syntax: "remap_boolean_expression"
+						}
+					}
+				}
+			}
+		}
+		windows_secs: {
+			common:      true
+			description: "The windows the ratios and burn rates are computed over, in seconds. They are rounded up to a whole number of intervals."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [300, 3600, 21600]
+				items: type: uint: {
+					examples: [300, 3600]
+					unit: "seconds"
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	output: metrics: {
+		_slo_tags: {
+			slo: {
+				description: "The name of the objective."
+				required:    true
+				examples: ["availability"]
+			}
+			window: {
+				description: "The window the value is computed over."
+				required:    true
+				examples: ["5m", "1h", "6h"]
+			}
+			"*": {
+				description: "The `tags` of the objective."
+				required:    false
+				examples: ["checkout"]
+			}
+		}
+		slo_burn_rate: {
+			description:       "The rate at which the error budget is consumed over the window. A burn rate of 1 spends exactly the budget allowed by the target."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _slo_tags
+		}
+		slo_ratio: {
+			description:       "The fraction of the counted events that are good over the window."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _slo_tags
+		}
+	}
+
+	how_it_works: {
+		windows: {
+			title: "Windows"
+			body: """
+				The events are counted per interval, and every `interval_secs` the counts of the
+				intervals covered by each window are summed to compute the gauges. A window without
+				any counted event is left out, as its ratio is undefined. The gauges are also emitted,
+				for the partial interval, when Vector shuts down.
+				"""
+		}
+
+		burn_rate: {
+			title: "Burn Rate"
+			body: """
+				The burn rate is the fraction of bad events divided by the fraction allowed by the
+				target: `(1 - slo_ratio) / (1 - target)`. With a target of `0.999`, a ratio of `0.99`
+				gives a burn rate of 10, spending a 30 days error budget in 3 days. Alerting when both
+				a short and a long window exceed the same burn rate catches fast and slow burns,
+				while quickly recovering once the errors stop.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		slo_flushes_total: components.sources.internal_metrics.output.metrics.slo_flushes_total
+	}
+}
@@ -284,6 +284,67 @@ data_model: schema: {
 					}
 				}
 
+				sketch: {
+					common: false
+					description: """
+						A sketch summarizes a distribution of values
+						in a fixed number of logarithmic bins, so that
+						quantiles can be estimated within a relative
+						accuracy. Unlike distributions, sketches stay
+						small and can be merged.
+						"""
+					required: false
+					warnings: []
+					type: object: {
+						examples: []
+						options: {
+							bins: {
+								description: "The non-empty bins of the sketch, sorted by key."
+								required:    true
+								warnings: []
+								type: array: items: type: object: {
+									examples: []
+									options: {
+										count: {
+											description: "The number of values counted in this bin."
+											required:    true
+											warnings: []
+											type: uint: {
+												examples: [12, 43, 25]
+												unit: null
+											}
+										}
+										key: {
+											description: "The index of the logarithmic bin the values fall into."
+											required:    true
+											warnings: []
+											type: int: examples: [-17, 0, 254]
+										}
+									}
+								}
+							}
+							relative_accuracy: {
+								description: "The relative accuracy of the quantiles estimated from the sketch."
+								required:    true
+								warnings: []
+								type: float: examples: [0.01]
+							}
+							statistic: {
+								description: "The statistic to be calculated from the values."
+								required:    true
+								warnings: []
+								type: string: {
+									enum: {
+										histogram: "Counts values in buckets."
+										summary:   "Calculates quantiles of values."
+									}
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+
 				summary: {
 					common: true
 					description: """
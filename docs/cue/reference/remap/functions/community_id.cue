@@ -0,0 +1,70 @@
+package metadata
+
+remap: functions: community_id: {
+	category:    "IP"
+	description: """
+		Generates the [Community ID](\(urls.community_id)) hash of a network flow, as used by Zeek, Suricata and others to
+		correlate flows across tools. Both directions of a flow produce the same ID.
+		"""
+
+	arguments: [
+		{
+			name:        "source_ip"
+			description: "The source IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "destination_ip"
+			description: "The destination IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "protocol"
+			description: "The IANA protocol number of the flow, for example `6` for TCP or `17` for UDP."
+			required:    true
+			type: ["integer"]
+		},
+		{
+			name: "source_port"
+			description: """
+				The source port of the flow. For ICMP and ICMPv6, this is the message type.
+				"""
+			required: false
+			type: ["integer"]
+		},
+		{
+			name: "destination_port"
+			description: """
+				The destination port of the flow. For ICMP and ICMPv6, this is the message code.
+				"""
+			required: false
+			type: ["integer"]
+		},
+		{
+			name:        "seed"
+			description: "The seed to hash the flow with, to distinguish flows observed at different sites."
+			required:    false
+			default:     0
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`source_ip` or `destination_ip` isn't a valid IP address",
+		"`source_ip` and `destination_ip` aren't of the same IP version",
+		"only one of `source_port` and `destination_port` is set",
+		"`protocol`, a port or the `seed` is out of range",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "TCP flow"
+			source: #"""
+				community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", source_port: 1122, destination_port: 3344, protocol: 6)
+				"""#
+			return: "1:wCb3OG7yAFWelaUydu0D+125CLM="
+		},
+	]
+}
@@ -0,0 +1,32 @@
+package metadata
+
+remap: functions: decode_gzip: {
+	category:    "Codec"
+	description: """
+		Decompresses the [Gzip](\(urls.gzip)) compressed `value`. Concatenated Gzip members are decompressed into a
+		single string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Gzip](\(urls.gzip)) data to decompress."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't valid Gzip compressed data.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decompress Gzip data"
+			source: """
+				decode_gzip!(decode_base64!("H4sIAAAAAAACAyvISU0sTlVIzUvOT0lVyE0FAI4R4vcQAAAA"))
+				"""
+			return: "please encode me"
+		},
+	]
+}
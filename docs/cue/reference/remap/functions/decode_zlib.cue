@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: decode_zlib: {
+	category:    "Codec"
+	description: """
+		Decompresses the [Zlib](\(urls.zlib)) compressed `value`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Zlib](\(urls.zlib)) data to decompress."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't valid Zlib compressed data.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decompress Zlib data"
+			source: """
+				decode_zlib!(decode_base64!("eJwryElNLE5VSM1Lzk9JVchNBQA0RQX7"))
+				"""
+			return: "please encode me"
+		},
+	]
+}
@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: encode_csv: {
+	category: "Codec"
+	description: #"""
+		Encodes the `value` array as a single CSV formatted row, without a trailing newline. Fields are quoted when
+		required. This is the inverse of `parse_csv`.
+		"""#
+	notices: [
+		"""
+			Integers, floats, booleans and timestamps are encoded as their string representation, and `null` is encoded
+			as an empty field.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The fields of the row to encode."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "delimiter"
+			description: "The field delimiter to use when encoding. Must be a single-byte utf8 character."
+			required:    false
+			default:     ","
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"delimiter must be a single-byte utf8 character",
+		"`value` contains an object or array",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode a single CSV formatted row"
+			source: #"""
+				encode_csv!(["foo", "bar", "foo \", bar"])
+				"""#
+			return: #"foo,bar,"foo "", bar""#
+		},
+		{
+			title: "Encode a single CSV formatted row with custom delimiter"
+			source: #"""
+				encode_csv!(["foo", "bar"], delimiter: " ")
+				"""#
+			return: "foo bar"
+		},
+	]
+}
@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: encode_gzip: {
+	category:    "Codec"
+	description: """
+		Compresses the `value` with [Gzip](\(urls.gzip)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to compress."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "compression_level"
+			description: "The compression level, from `0` (no compression) to `9` (best compression)."
+			required:    false
+			type: ["integer"]
+			default: 6
+		},
+	]
+	internal_failure_reasons: [
+		"`compression_level` isn't between `0` and `9`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Compress with Gzip"
+			source: """
+				encode_base64(encode_gzip("please encode me"))
+				"""
+			return: "H4sIAAAAAAAA/yvISU0sTlVIzUvOT0lVyE0FAI4R4vcQAAAA"
+		},
+	]
+}
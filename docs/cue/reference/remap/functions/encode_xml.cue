@@ -0,0 +1,62 @@
+package metadata
+
+remap: functions: encode_xml: {
+	category: "Codec"
+	description: """
+		Encodes the `value` object as an XML document. This is the inverse of `parse_xml`: keys prefixed with
+		`attr_prefix` become attributes, the `text_key` holds the text of an element and arrays are encoded as repeated
+		elements.
+		"""
+	notices: [
+		"""
+			Valid XML must contain exactly one root node, so `value` must have exactly one key. Elements are encoded in
+			the order of their keys.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to encode."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "include_attr"
+			description: "Encode keys prefixed with `attr_prefix` as XML tag attributes."
+			required:    false
+			default:     true
+			type: ["boolean"]
+		},
+		{
+			name:        "attr_prefix"
+			description: "String prefix of keys to encode as XML tag attributes."
+			required:    false
+			default:     "@"
+			type: ["string"]
+		},
+		{
+			name:        "text_key"
+			description: "Key name holding the text of an element."
+			required:    false
+			default:     "text"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` doesn't have exactly one key",
+		"a key isn't a valid XML tag or attribute name",
+		"an attribute or text node is an object or array",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode XML"
+			source: #"""
+				encode_xml!({ "book": { "@category": "CHILDREN", "title": { "@lang": "en", "text": "Harry Potter" }, "year": 2005 } })
+				"""#
+			return: #"<book category="CHILDREN"><title lang="en">Harry Potter</title><year>2005</year></book>"#
+		},
+	]
+}
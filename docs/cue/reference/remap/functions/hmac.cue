@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: hmac: {
+	category:    "Hash"
+	description: """
+		Calculates a [HMAC](\(urls.hmac)) of the `value` using the given `key`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the HMAC for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key to use."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The hashing algorithm to use."
+			enum: {
+				"SHA-256": "SHA-256 algorithm"
+				"SHA-512": "SHA-512 algorithm"
+			}
+			required: false
+			default:  "SHA-256"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Calculate HMAC"
+			source: #"""
+				hmac("foobar", "secret")
+				"""#
+			return: "4fcc06915b43d8a49aff193441e9e18654e6a27c2c428b02e8fcc41ccc2299f9"
+		},
+		{
+			title: "Calculate HMAC with SHA-512"
+			source: #"""
+				hmac("foobar", "secret", algorithm: "SHA-512")
+				"""#
+			return: "ac76d1f21ab3affcab713dcec165cc517a1d9b79b1ac21fe99619fda7dfbee98b926080dc90117a8aa600875f4dbe7d50b0f13712bbfc9db8b57d7eddb91bc0c"
+		},
+	]
+}
@@ -3,15 +3,16 @@ package metadata
 remap: functions: ip_cidr_contains: {
 	category: "IP"
 	description: """
-		Determines whether the `ip` is contained in the block referenced by the `cidr`.
+		Determines whether the `ip` is contained in the block referenced by the `cidr`. When `cidr` is an array, determines
+		whether the `ip` is contained in any of the referenced blocks.
 		"""
 
 	arguments: [
 		{
 			name:        "cidr"
-			description: "The CIDR mask (v4 or v6)."
+			description: "The CIDR mask (v4 or v6), or an array of CIDR masks."
 			required:    true
-			type: ["string"]
+			type: ["string", "array"]
 		},
 		{
 			name:        "ip"
@@ -21,7 +22,7 @@ remap: functions: ip_cidr_contains: {
 		},
 	]
 	internal_failure_reasons: [
-		"`cidr` isn't a valid CIDR",
+		"`cidr`, or any of its elements, isn't a valid CIDR",
 		"`ip` isn't a valid IP address",
 	]
 	return: types: ["boolean"]
@@ -41,5 +42,12 @@ remap: functions: ip_cidr_contains: {
 				"""#
 			return: true
 		},
+		{
+			title: "IP contained in any of multiple CIDRs"
+			source: #"""
+				ip_cidr_contains!(["10.0.0.0/8", "192.168.0.0/16"], "192.168.10.32")
+				"""#
+			return: true
+		},
 	]
 }
@@ -0,0 +1,34 @@
+package metadata
+
+remap: functions: ip_ntop: {
+	category:    "IP"
+	description: """
+		Converts IPv4 and IPv6 addresses from binary to text form. The binary form is expected in network byte order,
+		4 bytes long for IPv4 addresses and 16 bytes long for IPv6 addresses.
+
+		This behavior mimics [inet_ntop](\(urls.ip_ntop)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The binary data to convert from."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't 4 or 16 bytes long",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Convert IPv4 address from bytes after decoding from Base64"
+			source: #"""
+				ip_ntop!(decode_base64!("wKgAAQ=="))
+				"""#
+			return: "192.168.0.1"
+		},
+	]
+}
@@ -0,0 +1,34 @@
+package metadata
+
+remap: functions: ip_pton: {
+	category:    "IP"
+	description: """
+		Converts IPv4 and IPv6 addresses from text to binary form, in network byte order. The result is 4 bytes long for
+		IPv4 addresses and 16 bytes long for IPv6 addresses.
+
+		This behavior mimics [inet_pton](\(urls.ip_pton)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address (v4 or v6) to convert to binary form."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Convert IPv4 address to bytes and encode to Base64"
+			source: #"""
+				encode_base64(ip_pton!("192.168.0.1"))
+				"""#
+			return: "wKgAAQ=="
+		},
+	]
+}
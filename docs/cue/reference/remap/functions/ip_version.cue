@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: ip_version: {
+	category:    "IP"
+	description: """
+		Determines the version of the IP address `value`, either `IPv4` or `IPv6`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "IPv6 address"
+			source: #"""
+				ip_version!("2001:4f8:3:ba:2e0:81ff:fe22:d1f1")
+				"""#
+			return: "IPv6"
+		},
+	]
+}
@@ -0,0 +1,41 @@
+package metadata
+
+remap: functions: is_private_ip: {
+	category: "IP"
+	description: """
+		Determines whether the IP address `value` belongs to a private range. These are the IPv4 ranges
+		`10.0.0.0/8`, `172.16.0.0/12` and `192.168.0.0/16` of [RFC 1918](\(urls.ietf_rfc_1918)), and the IPv6 unique
+		local range `fc00::/7` of [RFC 4193](\(urls.ietf_rfc_4193)). IPv4-mapped IPv6 addresses are checked against the
+		IPv4 ranges.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: types: ["boolean"]
+
+	examples: [
+		{
+			title: "Private IPv4 address"
+			source: #"""
+				is_private_ip!("192.168.10.32")
+				"""#
+			return: true
+		},
+		{
+			title: "Public IPv6 address"
+			source: #"""
+				is_private_ip!("2001:4f8:3:ba:2e0:81ff:fe22:d1f1")
+				"""#
+			return: false
+		},
+	]
+}
@@ -0,0 +1,76 @@
+package metadata
+
+remap: functions: parse_cef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in the [Common Event Format (CEF)](\(urls.cef)).
+
+		* The seven header fields are returned as `cefVersion`, `deviceVendor`, `deviceProduct`, `deviceVersion`,
+		  `deviceEventClassId`, `name` and `severity`.
+		* Extension keys are added alongside the header fields.
+		* Any text preceding the `CEF:` prefix, such as a syslog header, is ignored.
+		"""
+	notices: [
+		"""
+			All values are returned as strings. We recommend manually coercing values to desired types as you see fit.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "translate_keys"
+			description: "Translate the short extension keys, such as `src`, into their full names, such as `sourceAddress`. Unknown keys are kept as is."
+			required:    false
+			default:     false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a properly formatted CEF string",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse CEF message"
+			source: #"""
+				parse_cef!(s'CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232')
+				"""#
+			return: {
+				cefVersion:         "0"
+				deviceVendor:       "Security"
+				deviceProduct:      "threatmanager"
+				deviceVersion:      "1.0"
+				deviceEventClassId: "100"
+				name:               "worm successfully stopped"
+				severity:           "10"
+				src:                "10.0.0.1"
+				dst:                "2.1.2.2"
+				spt:                "1232"
+			}
+		},
+		{
+			title: "Parse CEF message with full key names"
+			source: #"""
+				parse_cef!(s'CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 spt=1232', translate_keys: true)
+				"""#
+			return: {
+				cefVersion:         "0"
+				deviceVendor:       "Security"
+				deviceProduct:      "threatmanager"
+				deviceVersion:      "1.0"
+				deviceEventClassId: "100"
+				name:               "worm successfully stopped"
+				severity:           "10"
+				sourceAddress:      "10.0.0.1"
+				sourcePort:         "1232"
+			}
+		},
+	]
+}
@@ -0,0 +1,72 @@
+package metadata
+
+remap: functions: parse_leef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in the [Log Event Extended Format (LEEF)](\(urls.leef)).
+
+		* The header fields are returned as `leefVersion`, `vendor`, `productName`, `productVersion` and `eventId`.
+		* Attributes are added alongside the header fields.
+		* LEEF 2.0 messages use the delimiter declared in their header, LEEF 1.0 messages use a tab.
+		* Any text preceding the `LEEF:` prefix, such as a syslog header, is ignored.
+		"""
+	notices: [
+		"""
+			All values are returned as strings. We recommend manually coercing values to desired types as you see fit.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "delimiter"
+			description: "The character that separates the attributes, overriding the one from the header. Can be given as a hexadecimal code point, such as `x09` or `0x5E`."
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a properly formatted LEEF string",
+		"`delimiter` isn't a single character or hexadecimal code point",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse LEEF 1.0 message"
+			source: #"""
+				parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5")
+				"""#
+			return: {
+				leefVersion:    "1.0"
+				vendor:         "Microsoft"
+				productName:    "MSExchange"
+				productVersion: "4.0 SP1"
+				eventId:        "15345"
+				src:            "192.0.2.0"
+				dst:            "172.50.123.1"
+				sev:            "5"
+			}
+		},
+		{
+			title: "Parse LEEF 2.0 message"
+			source: #"""
+				parse_leef!("LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5")
+				"""#
+			return: {
+				leefVersion:    "2.0"
+				vendor:         "Lancope"
+				productName:    "StealthWatch"
+				productVersion: "1.0"
+				eventId:        "41"
+				src:            "10.0.1.8"
+				dst:            "10.0.0.5"
+			}
+		},
+	]
+}
@@ -0,0 +1,46 @@
+package metadata
+
+remap: functions: xxhash: {
+	category:    "Hash"
+	description: """
+		Calculates a non-cryptographic [xxHash](\(urls.xxhash)) hash of the `value`.
+		"""
+	notices: [
+		"""
+			VRL integers are signed, so `XXH64` hashes that don't fit into a signed 64-bit integer are returned as
+			negative numbers.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the hash for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "variant"
+			description: "The variant of the algorithm to use."
+			enum: {
+				"XXH32": "32-bit xxHash algorithm"
+				"XXH64": "64-bit xxHash algorithm"
+			}
+			required: false
+			default:  "XXH64"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Calculate xxHash hash"
+			source: #"""
+				xxhash("foobar", variant: "XXH32")
+				"""#
+			return: 3986901679
+		},
+	]
+}
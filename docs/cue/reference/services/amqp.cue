@@ -0,0 +1,10 @@
+package metadata
+
+services: amqp: {
+	name:     "AMQP"
+	thing:    "an \(name) 0.9.1 broker"
+	url:      urls.amqp
+	versions: null
+
+	description: "[AMQP](\(urls.amqp)) 0.9.1 is an open standard messaging protocol implemented by brokers such as [RabbitMQ](\(urls.rabbitmq)). Producers publish messages to exchanges, which route them to the queues consumers read from based on routing keys and bindings."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: mqtt: {
+	name:     "MQTT"
+	thing:    "an \(name) broker"
+	url:      urls.mqtt
+	versions: "3.1.1"
+
+	description: "[MQTT](\(urls.mqtt)) is a lightweight publish/subscribe messaging protocol designed for constrained devices and unreliable networks, commonly used for IoT telemetry. Clients publish messages to hierarchical topics on a broker, which forwards them to every client subscribed to a matching topic filter."
+}
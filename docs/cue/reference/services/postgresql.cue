@@ -0,0 +1,10 @@
+package metadata
+
+services: postgresql: {
+	name:     "PostgreSQL"
+	thing:    "a \(name) database"
+	url:      urls.postgresql
+	versions: ">= 9.6"
+
+	description: "[PostgreSQL](\(urls.postgresql)) is a powerful, open source object-relational database system with over 30 years of active development that has earned it a strong reputation for reliability, feature robustness, and performance."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: websocket: {
+	name:     "WebSocket"
+	thing:    "a \(name) peer"
+	url:      urls.websocket
+	versions: null
+
+	description: "[WebSocket](\(urls.websocket)) is a protocol providing full-duplex communication over a single, long-lived TCP connection, opened with an HTTP upgrade request. It is commonly used to stream data to and from browsers and between services."
+}
@@ -5,6 +5,8 @@ urls: {
 	affine_type_system:                                       "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
+	amqp:                                                     "https://www.amqp.org/specification/0-9-1/amqp-org-download"
+	amqp_connection_uri:                                      "https://www.rabbitmq.com/uri-spec.html"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
 	apache_common:                                            "\(apache)/docs/current/logs.html#common"
@@ -89,23 +91,29 @@ urls: {
 	base64_padding:                                           "\(wikipedia)/wiki/Base64#Output_padding"
 	base64_standard:                                          "https://tools.ietf.org/html/rfc4648#section-4"
 	base64_url_safe:                                          "\(wikipedia)/wiki/Base64#URL_applications"
+	base16:                                                   "https://tools.ietf.org/html/rfc4648#section-8"
 	basic_auth:                                               "\(wikipedia)/wiki/Basic_access_authentication"
 	big_query_streaming:                                      "https://cloud.google.com/bigquery/streaming-data-into-bigquery"
 	bind_dnstap:                                              "https://kb.isc.org/docs/aa-01342"
 	b_tree_map:                                               "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
 	cargo_audit:                                              "\(github)/RustSec/cargo-audit"
 	centos:                                                   "https://www.centos.org/"
+	cef:                                                      "https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf"
 	chrono_time_formats:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
 	clickhouse:                                               "https://clickhouse.yandex/"
 	clickhouse_http:                                          "https://clickhouse.yandex/docs/en/interfaces/http/"
+	clickhouse_native:                                        "https://clickhouse.tech/docs/en/interfaces/tcp/"
+	clickhouse_types:                                         "https://clickhouse.tech/docs/en/sql-reference/data-types/"
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
 	console:                                                  "\(wikipedia)/wiki/System_console"
+	community_id:                                             "https://github.com/corelight/community-id-spec"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
 	crc:                                                      "\(wikipedia)/wiki/Cyclic_redundancy_check"
+	crc32:                                                    "\(wikipedia)/wiki/Cyclic_redundancy_check#CRC-32_algorithm"
 	ctime:                                                    "https://www.cplusplus.com/reference/ctime"
 	cue:                                                      "https://cuelang.org/"
 	dag:                                                      "\(wikipedia)/wiki/Directed_acyclic_graph"
@@ -163,6 +171,9 @@ urls: {
 	elasticsearch_id_field:                                   "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-id-field.html"
 	elasticsearch_id_performance:                             "https://www.elastic.co/guide/en/elasticsearch/reference/master/tune-for-indexing-speed.html#_use_auto_generated_ids"
 	elasticsearch_ignore_malformed:                           "https://www.elastic.co/guide/en/elasticsearch/reference/current/ignore-malformed.html"
+	elasticsearch_point_in_time:                              "https://www.elastic.co/guide/en/elasticsearch/reference/current/point-in-time-api.html"
+	elasticsearch_query_dsl:                                  "https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl.html"
+	elasticsearch_search_after:                               "https://www.elastic.co/guide/en/elasticsearch/reference/current/paginate-search-results.html#search-after"
 	encoding_charset_labels:                                  "https://encoding.spec.whatwg.org/#concept-encoding-get"
 	encoding_standard:                                        "https://encoding.spec.whatwg.org/"
 	endler_dev:                                               "https://endler.dev/"
@@ -208,6 +219,10 @@ urls: {
 	github_sign_commits:                                      "https://help.github.com/en/github/authenticating-to-github/signing-commits"
 	globbing:                                                 "\(wikipedia)/wiki/Glob_(programming)"
 	glog:                                                     "\(github)/google/glog"
+	graphite:                                                 "https://graphiteapp.org/"
+	graphite_pickle_protocol:                                 "https://graphite.readthedocs.io/en/latest/feeding-carbon.html#the-pickle-protocol"
+	graphite_plaintext_protocol:                              "https://graphite.readthedocs.io/en/latest/feeding-carbon.html#the-plaintext-protocol"
+	graphite_tags:                                            "https://graphite.readthedocs.io/en/latest/tags.html"
 	graphql:                                                  "https://graphql.org"
 	graphql_playground:                                       "\(github)/graphql/graphql-playground"
 	graphviz:                                                 "https://graphviz.org/"
@@ -217,6 +232,7 @@ urls: {
 	gzip:                                                     "https://www.gzip.org/"
 	haproxy:                                                  "https://www.haproxy.org/"
 	helm:                                                     "https://helm.sh/"
+	hmac:                                                     "\(wikipedia)/wiki/HMAC"
 	heroku:                                                   "https://www.heroku.com"
 	heroku_http_log_drain:                                    "https://devcenter.heroku.com/articles/log-drains#https-drains"
 	heroku_start:                                             "https://devcenter.heroku.com/start"
@@ -235,6 +251,8 @@ urls: {
 	iana_time_zone_format:                                    "\(wikipedia)/wiki/Tz_database#Names_of_time_zones"
 	iana_time_zones:                                          "\(wikipedia)/wiki/List_of_tz_database_time_zones"
 	ieee_754:                                                 "\(wikipedia)/wiki/IEEE_754"
+	ietf_rfc_1918:                                            "https://tools.ietf.org/html/rfc1918"
+	ietf_rfc_4193:                                            "https://tools.ietf.org/html/rfc4193"
 	ietf_rfc_6750:                                            "https://tools.ietf.org/html/rfc6750"
 	initd:                                                    "https://bash.cyberciti.biz/guide//etc/init.d"
 	influxdb:                                                 "https://www.influxdata.com/products/influxdb-overview/"
@@ -245,6 +263,8 @@ urls: {
 	inode:                                                    "\(wikipedia)/wiki/Inode"
 	ip_aton:                                                  "https://linux.die.net/man/3/inet_aton"
 	ip_ntoa:                                                  "https://linux.die.net/man/3/inet_ntoa"
+	ip_ntop:                                                  "https://linux.die.net/man/3/inet_ntop"
+	ip_pton:                                                  "https://linux.die.net/man/3/inet_pton"
 	iso_8601:                                                 "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                                "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                               "\(vector_repo)/issues/1694"
@@ -266,14 +286,18 @@ urls: {
 	kubernetes_authorization:                                 "\(kubernetes)/docs/reference/access-authn-authz/authorization/"
 	kubernetes_daemonset:                                     "\(kubernetes)/docs/concepts/workloads/controllers/daemonset/"
 	kubernetes_example_daemonset:                             "\(vector_repo)/blob/master/config/kubernetes/vector-daemonset.yaml"
+	kubernetes_field_selector:                                "\(kubernetes)/docs/concepts/overview/working-with-objects/field-selectors/"
+	kubernetes_label_selector:                                "\(kubernetes)/docs/concepts/overview/working-with-objects/labels/#label-selectors"
 	kubernetes_limit_resources:                               "\(kubernetes)/docs/tasks/configure-pod-container/assign-cpu-resource/"
 	kubernetes_logging_architecture:                          "\(kubernetes)/docs/concepts/cluster-administration/logging/"
 	kubernetes_rbac:                                          "\(kubernetes)/docs/reference/access-authn-authz/rbac/"
 	kubernetes_request_verbs:                                 "\(kubernetes)/docs/reference/access-authn-authz/authorization/#determine-the-request-verb"
 	kubernetes_watch_api:                                     "\(kubernetes)/docs/reference/generated/kubernetes-api/v1.10/#watch-30"
 	leveldb:                                                  "\(github)/google/leveldb"
+	lapin:                                                    "\(github)/amqp-rs/lapin"
 	leveldb_sys_2:                                            "https://crates.io/crates/leveldb-sys"
 	leveldb_sys_3:                                            "\(github)/timberio/leveldb-sys/tree/v3.0.0"
+	leef:                                                     "https://www.ibm.com/docs/en/dsm?topic=overview-leef-event-components"
 	librdkafka:                                               "\(github)/edenhill/librdkafka"
 	librdkafka_config:                                        "\(github)/edenhill/librdkafka/blob/master/CONFIGURATION.md"
 	linux_capability:                                         "https://man7.org/linux/man-pages/man7/capabilities.7.html"
@@ -284,6 +308,7 @@ urls: {
 	logstash_protocol:                                        "https://github.com/elastic/logstash-forwarder/blob/master/PROTOCOL.md"
 	loki:                                                     "https://grafana.com/oss/loki/"
 	loki_multi_tenancy:                                       "\(github)/grafana/loki/blob/master/docs/operations/multi-tenancy.md"
+	loki_push_api:                                            "\(github)/grafana/loki/blob/master/docs/api.md#post-lokiapiv1push"
 	log_event_source:                                         "\(vector_repo)/blob/master/src/event/"
 	logplex:                                                  "https://devcenter.heroku.com/articles/logplex"
 	logplex_protocol:                                         "\(github)/heroku/logplex/blob/master/doc/README.http_drains.md"
@@ -324,8 +349,12 @@ urls: {
 	mongodb:                                                  "https://www.mongodb.com"
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
+	mqtt:                                                     "https://mqtt.org/"
+	mqtt_topics:                                              "https://www.hivemq.com/blog/mqtt-essentials-part-5-mqtt-topics-best-practices/"
 	musl_builder_docker_image:                                "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	nats:                                                     "https://nats.io/"
+	nats_auth:                                                "https://docs.nats.io/nats-server/configuration/securing_nats/auth_intro"
+	nats_jetstream:                                           "https://docs.nats.io/jetstream/jetstream"
 	nats_rs:                                                  "\(github)/nats-io/nats.rs"
 	new_bug_report:                                           "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "\(vector_repo)/issues/new?labels=type%3A+new+feature"
@@ -343,6 +372,7 @@ urls: {
 	nix:                                                      "https://nixos.org/nix/"
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "\(github)/NixOS/nixpkgs/issues/9682"
+	openmetrics:                                              "https://openmetrics.io/"
 	openssl:                                                  "https://www.openssl.org/"
 	order_of_ops:                                             "\(wikipedia)/wiki/Order_of_operations"
 	papertrail:                                               "https://www.papertrail.com/"
@@ -359,12 +389,15 @@ urls: {
 	percent_encoding_www_form_urlencoded:                     "https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set"
 	posix_acls:                                               "https://www.usenix.org/legacy/publications/library/proceedings/usenix03/tech/freenix03/full_papers/gruenbacher/gruenbacher_html/main.html"
 	postgresql:                                               "https://www.postgresql.org/"
+	postgresql_connection_string:                             "https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNSTRING"
+	postgresql_copy:                                          "https://www.postgresql.org/docs/current/sql-copy.html"
 	postgresql_csvlog:                                        "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
 	postgresql_matching:                                      "https://www.postgresql.org/docs/current/functions-matching.html#FUNCTIONS-POSIX-REGEXP"
 	prometheus:                                               "https://prometheus.io/"
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_counter:                                       "https://prometheus.io/docs/concepts/metric_types/#counter"
+	prometheus_file_sd_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#file_sd_config"
 	prometheus_gauge:                                         "https://prometheus.io/docs/concepts/metric_types/#gauge"
 	prometheus_high_cardinality:                              "https://prometheus.io/docs/practices/naming/#labels"
 	prometheus_histogram:                                     "https://prometheus.io/docs/concepts/metric_types/#histogram"
@@ -373,12 +406,15 @@ urls: {
 	prometheus_text_based_exposition_format:                  "\(github)/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
+	prometheus_relabel_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config"
 	prometheus_remote_write:                                  "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#remote_write"
 	prometheus_remote_write_protocol:                         "https://docs.google.com/document/d/1LPhVRSFkGNSuU1fBd81ulhsCPR4hkSZyyBj1SZ8fWOM/edit#heading=h.n0d0vphea3fe"
+	promtail:                                                 "https://grafana.com/docs/loki/latest/clients/promtail/"
 	protobuf:                                                 "https://developers.google.com/protocol-buffers"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
 	raspbian:                                                 "https://www.raspbian.org/"
+	rabbitmq:                                                 "https://www.rabbitmq.com/"
 	rdkafka:                                                  "\(github)/edenhill/librdkafka"
 	regex:                                                    "\(wikipedia)/wiki/Regular_expression"
 	regex_grouping_and_flags:                                 "https://docs.rs/regex/latest/regex/#grouping-and-flags"
@@ -397,6 +433,7 @@ urls: {
 	rfc_6891:                                                 "https://tools.ietf.org/html/rfc6891"
 	rhel:                                                     "https://www.redhat.com/en/technologies/linux-platforms/enterprise-linux"
 	rpm:                                                      "https://rpm.org/"
+	rumqttc:                                                  "\(github)/bytebeamio/rumqtt"
 	rust:                                                     "https://www.rust-lang.org/"
 	rust_date_time:                                           "https://docs.rs/chrono/latest/chrono/struct.DateTime.html"
 	rust_grok_library:                                        "\(github)/daschl/grok"
@@ -410,6 +447,8 @@ urls: {
 	rust_tokio:                                               "\(github)/tokio-rs/tokio"
 	rustup:                                                   "https://rustup.rs"
 	redis:                                                    "https://redis.io"
+	redis_consumer_groups:                                    "https://redis.io/topics/streams-intro#consumer-groups"
+	redis_pubsub:                                             "https://redis.io/topics/pubsub"
 	redis_rs:                                                 "https://github.com/mitsuhiko/redis-rs"
 	sematext:                                                 "https://sematext.com"
 	sematext_create_logs_app:                                 "https://apps.sematext.com/ui/integrations"
@@ -454,6 +493,7 @@ urls: {
 	systemd_resolved:                                         "https://wiki.archlinux.org/index.php/Systemd-resolved"
 	tcp:                                                      "\(wikipedia)/wiki/Transmission_Control_Protocol"
 	team:                                                     "/community#team"
+	telegraf_graphite_templates:                              "\(github)/influxdata/telegraf/blob/master/docs/TEMPLATE_PATTERN.md"
 	timber:                                                   "https://timber.io"
 	toml:                                                     "\(github)/toml-lang/toml"
 	toml_array:                                               "\(github)/toml-lang/toml#array"
@@ -549,6 +589,7 @@ urls: {
 	vector_datadog_events_sink:                               "/docs/reference/configuration/sinks/datadog_events"
 	vector_azure_blob_sink:                                   "/docs/reference/configuration/sinks/azure_blob"
 	vector_redis_sink:                                        "/docs/reference/configuration/sinks/redis"
+	vector_redis_source:                                      "/docs/reference/configuration/sources/redis"
 	vector_dnstap_source:                                     "/docs/reference/configuration/sources/dnstap"
 	vector_eventstoredb_metrics_source:                       "/docs/reference/configuration/sources/eventstoredb_metrics"
 	vector_fluent_source:                                     "/docs/reference/configuration/sources/fluent"
@@ -566,6 +607,8 @@ urls: {
 	vector_twitter:                                           "https://twitter.com/vectordotdev"
 	vector_unit_testing:                                      "/guides/level-up/unit-testing/"
 	vector_version_branches:                                  "\(vector_repo)/branches/all?query=v"
+	vector_websocket_sink:                                    "/docs/reference/configuration/sinks/websocket"
+	vector_websocket_source:                                  "/docs/reference/configuration/sources/websocket"
 	vrl_announcement:                                         "/blog/vector-remap-language"
 	vrl_error_handling:                                       "\(vrl_errors_reference)#handling"
 	vrl_error_handling_assigning:                             "\(vrl_errors_reference)#assigning"
@@ -588,11 +631,13 @@ urls: {
 	vote_feature:                                             "\(vector_repo)/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
 	wasm:                                                     "https://webassembly.org/"
 	wasm_languages:                                           "\(github)/appcypher/awesome-wasm-langs"
+	websocket:                                                "https://datatracker.ietf.org/doc/html/rfc6455"
 	wikipedia:                                                "https://en.wikipedia.org"
 	windows:                                                  "https://www.microsoft.com/en-us/windows"
 	windows_installer:                                        "\(wikipedia)/wiki/Windows_Installer"
 	windows_service:                                          "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	woothee:                                                  "https://github.com/woothee/woothee"
+	xxhash:                                                   "https://cyan4973.github.io/xxHash/"
 	yaml:                                                     "https://yaml.org/"
 	yum:                                                      "\(wikipedia)/wiki/Yum_(software)"
 	zlib:                                                     "https://www.zlib.net"
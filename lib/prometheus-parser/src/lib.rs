@@ -4,8 +4,8 @@ use std::{collections::BTreeMap, convert::TryFrom};
 
 mod line;
 
-pub use line::ErrorKind;
-use line::{Line, Metric, MetricKind};
+pub use line::{ErrorKind, Exemplar};
+use line::{Format, Line, Metric, MetricKind};
 
 pub const METRIC_NAME_LABEL: &str = "__name__";
 
@@ -77,12 +77,15 @@ pub struct SummaryMetric {
     pub quantiles: Vec<SummaryQuantile>,
     pub sum: f64,
     pub count: u32,
+    /// The creation time in seconds, from the OpenMetrics `_created` sample.
+    pub created: Option<f64>,
 }
 
 #[derive(Debug, Default, PartialEq, PartialOrd)]
 pub struct HistogramBucket {
     pub bucket: f64,
     pub count: u32,
+    pub exemplar: Option<Exemplar>,
 }
 
 #[derive(Debug, Default, PartialEq)]
@@ -90,11 +93,17 @@ pub struct HistogramMetric {
     pub buckets: Vec<HistogramBucket>,
     pub sum: f64,
     pub count: u32,
+    /// The creation time in seconds, from the OpenMetrics `_created` sample.
+    pub created: Option<f64>,
 }
 
 #[derive(Debug, Default, PartialEq)]
 pub struct SimpleMetric {
     pub value: f64,
+    /// The creation time in seconds of a counter, from the OpenMetrics
+    /// `_created` sample.
+    pub created: Option<f64>,
+    pub exemplar: Option<Exemplar>,
 }
 
 type MetricMap<T> = IndexMap<GroupKey, T>;
@@ -111,25 +120,40 @@ pub enum GroupKind {
 impl GroupKind {
     fn new(kind: MetricKind) -> Self {
         match kind {
-            MetricKind::Histogram => Self::Histogram(IndexMap::default()),
+            MetricKind::Histogram | MetricKind::GaugeHistogram => {
+                Self::Histogram(IndexMap::default())
+            }
             MetricKind::Summary => Self::Summary(IndexMap::default()),
             MetricKind::Counter => Self::Counter(IndexMap::default()),
-            MetricKind::Gauge => Self::Gauge(IndexMap::default()),
+            MetricKind::Gauge | MetricKind::Info | MetricKind::StateSet => {
+                Self::Gauge(IndexMap::default())
+            }
             MetricKind::Untyped => Self::Untyped(IndexMap::default()),
         }
     }
 
     fn new_untyped(key: GroupKey, value: f64) -> Self {
         let mut metrics = IndexMap::default();
-        metrics.insert(key, SimpleMetric { value });
+        metrics.insert(
+            key,
+            SimpleMetric {
+                value,
+                ..Default::default()
+            },
+        );
         Self::Untyped(metrics)
     }
 
     fn matches_kind(&self, kind: MetricKind) -> bool {
         match self {
             Self::Counter { .. } => kind == MetricKind::Counter,
-            Self::Gauge { .. } => kind == MetricKind::Gauge,
-            Self::Histogram { .. } => kind == MetricKind::Histogram,
+            Self::Gauge { .. } => matches!(
+                kind,
+                MetricKind::Gauge | MetricKind::Info | MetricKind::StateSet
+            ),
+            Self::Histogram { .. } => {
+                matches!(kind, MetricKind::Histogram | MetricKind::GaugeHistogram)
+            }
             Self::Summary { .. } => kind == MetricKind::Summary,
             Self::Untyped { .. } => true,
         }
@@ -142,6 +166,7 @@ impl GroupKind {
         &mut self,
         prefix_len: usize,
         metric: Metric,
+        format: Format,
     ) -> Result<Option<Metric>, ParserError> {
         let suffix = &metric.name[prefix_len..];
         let mut key = GroupKey {
@@ -149,8 +174,35 @@ impl GroupKind {
             labels: metric.labels,
         };
         let value = metric.value;
+        let open_metrics = format == Format::OpenMetrics;
 
         match self {
+            // OpenMetrics counters are named without the `_total` suffix of
+            // their samples.
+            Self::Counter(ref mut metrics) if open_metrics => match suffix {
+                "" | "_total" => {
+                    metrics.insert(
+                        key,
+                        SimpleMetric {
+                            value,
+                            created: None,
+                            exemplar: metric.exemplar,
+                        },
+                    );
+                }
+                "_created" => {
+                    created_group(metrics, key).created = Some(value);
+                }
+                _ => {
+                    return Ok(Some(Metric {
+                        name: metric.name,
+                        timestamp: key.timestamp,
+                        labels: key.labels,
+                        value,
+                        exemplar: metric.exemplar,
+                    }))
+                }
+            },
             Self::Counter(ref mut metrics)
             | Self::Gauge(ref mut metrics)
             | Self::Untyped(ref mut metrics) => {
@@ -160,9 +212,16 @@ impl GroupKind {
                         timestamp: key.timestamp,
                         labels: key.labels,
                         value,
+                        exemplar: metric.exemplar,
                     }));
                 }
-                metrics.insert(key, SimpleMetric { value });
+                metrics.insert(
+                    key,
+                    SimpleMetric {
+                        value,
+                        ..Default::default()
+                    },
+                );
             }
             Self::Histogram(ref mut metrics) => match suffix {
                 "_bucket" => {
@@ -171,24 +230,31 @@ impl GroupKind {
                         .map_err(Into::into)
                         .context(ParseLabelValue)?;
                     let count = try_f64_to_u32(metric.value)?;
-                    matching_group(metrics, key)
-                        .buckets
-                        .push(HistogramBucket { bucket, count });
+                    matching_group(metrics, key).buckets.push(HistogramBucket {
+                        bucket,
+                        count,
+                        exemplar: metric.exemplar,
+                    });
                 }
-                "_sum" => {
+                // Gauge histograms use `_gsum` and `_gcount` instead.
+                "_sum" | "_gsum" if suffix == "_sum" || open_metrics => {
                     let sum = metric.value;
                     matching_group(metrics, key).sum = sum;
                 }
-                "_count" => {
+                "_count" | "_gcount" if suffix == "_count" || open_metrics => {
                     let count = try_f64_to_u32(metric.value)?;
                     matching_group(metrics, key).count = count;
                 }
+                "_created" if open_metrics => {
+                    created_group(metrics, key).created = Some(value);
+                }
                 _ => {
                     return Ok(Some(Metric {
                         name: metric.name,
                         timestamp: key.timestamp,
                         labels: key.labels,
                         value,
+                        exemplar: metric.exemplar,
                     }))
                 }
             },
@@ -214,12 +280,16 @@ impl GroupKind {
                     let count = try_f64_to_u32(metric.value)?;
                     matching_group(metrics, key).count = count;
                 }
+                "_created" if open_metrics => {
+                    created_group(metrics, key).created = Some(value);
+                }
                 _ => {
                     return Ok(Some(Metric {
                         name: metric.name,
                         timestamp: key.timestamp,
                         labels: key.labels,
                         value,
+                        exemplar: metric.exemplar,
                     }))
                 }
             },
@@ -255,6 +325,7 @@ impl MetricGroup {
             labels,
             value,
             timestamp,
+            ..
         } = metric;
         let key = GroupKey { timestamp, labels };
         MetricGroup {
@@ -266,11 +337,11 @@ impl MetricGroup {
     /// `Err(_)` if there are irrecoverable error.
     /// `Ok(Some(metric))` if this metric belongs to another group.
     /// `Ok(None)` pushed successfully.
-    fn try_push(&mut self, metric: Metric) -> Result<Option<Metric>, ParserError> {
+    fn try_push(&mut self, metric: Metric, format: Format) -> Result<Option<Metric>, ParserError> {
         if !metric.name.starts_with(&self.name) {
             return Ok(Some(metric));
         }
-        self.metrics.try_push(self.name.len(), metric)
+        self.metrics.try_push(self.name.len(), metric, format)
     }
 }
 
@@ -278,23 +349,62 @@ fn matching_group<T: Default>(values: &mut MetricMap<T>, group: GroupKey) -> &mu
     values.entry(group).or_insert_with(T::default)
 }
 
+/// The group a `_created` sample belongs to. Its timestamp usually differs
+/// from the one of the other samples, so only the labels are matched.
+fn created_group<T: Default>(values: &mut MetricMap<T>, group: GroupKey) -> &mut T {
+    match values
+        .iter()
+        .position(|(key, _)| key.labels == group.labels)
+    {
+        Some(index) => values.get_index_mut(index).unwrap().1,
+        None => matching_group(values, group),
+    }
+}
+
 /// Parse the given text input, and group the result into higher-level
 /// metric types based on the declared types in the text.
 pub fn parse_text(input: &str) -> Result<Vec<MetricGroup>, ParserError> {
+    parse(input, Format::Text)
+}
+
+/// Parse the given input in the OpenMetrics text format, and group the result
+/// into higher-level metric types based on the declared types in the text.
+///
+/// Counters are named after their `_total` samples, and info metrics after
+/// their `_info` samples, like in the Prometheus text format. State sets are
+/// gauges.
+pub fn parse_openmetrics(input: &str) -> Result<Vec<MetricGroup>, ParserError> {
+    let mut groups = parse(input, Format::OpenMetrics)?;
+    for group in &mut groups {
+        if matches!(group.metrics, GroupKind::Counter(_)) && !group.name.ends_with("_total") {
+            group.name.push_str("_total");
+        }
+    }
+    Ok(groups)
+}
+
+fn parse(input: &str, format: Format) -> Result<Vec<MetricGroup>, ParserError> {
     let mut groups = Vec::new();
 
     for line in input.lines() {
-        let line = Line::parse(line).with_context(|| WithLine {
+        if format == Format::OpenMetrics && line.trim() == "# EOF" {
+            break;
+        }
+        let line = Line::parse(line, format).with_context(|| WithLine {
             line: line.to_owned(),
         })?;
         if let Some(line) = line {
             match line {
                 Line::Header(header) => {
-                    groups.push(MetricGroup::new(header.metric_name, header.kind));
+                    let name = match header.kind {
+                        MetricKind::Info => header.metric_name + "_info",
+                        _ => header.metric_name,
+                    };
+                    groups.push(MetricGroup::new(name, header.kind));
                 }
                 Line::Metric(metric) => {
                     let metric = match groups.last_mut() {
-                        Some(group) => group.try_push(metric)?,
+                        Some(group) => group.try_push(metric, format)?,
                         None => Some(metric),
                     };
                     if let Some(metric) = metric {
@@ -357,7 +467,9 @@ impl MetricGroupSet {
                 labels: labels.clone(),
                 value: sample.value,
                 timestamp: Some(sample.timestamp),
+                exemplar: None,
             },
+            Format::Text,
         )? {
             let key = GroupKey {
                 timestamp: metric.timestamp,
@@ -454,7 +566,10 @@ mod test {
                     timestamp: $timestamp,
                     labels: $labels,
                 },
-                &SimpleMetric { value: $value },
+                &SimpleMetric {
+                    value: $value,
+                    ..Default::default()
+                },
             )
         };
     }
@@ -540,15 +655,16 @@ mod test {
                 },
                 &HistogramMetric {
                     buckets: vec![
-                        HistogramBucket { bucket: 0.05, count: 24054 },
-                        HistogramBucket { bucket: 0.1, count: 33444 },
-                        HistogramBucket { bucket: 0.2, count: 100392 },
-                        HistogramBucket { bucket: 0.5, count: 129389 },
-                        HistogramBucket { bucket: 1.0, count: 133988 },
-                        HistogramBucket { bucket: f64::INFINITY, count: 144320 },
+                        HistogramBucket { bucket: 0.05, count: 24054, exemplar: None },
+                        HistogramBucket { bucket: 0.1, count: 33444, exemplar: None },
+                        HistogramBucket { bucket: 0.2, count: 100392, exemplar: None },
+                        HistogramBucket { bucket: 0.5, count: 129389, exemplar: None },
+                        HistogramBucket { bucket: 1.0, count: 133988, exemplar: None },
+                        HistogramBucket { bucket: f64::INFINITY, count: 144320, exemplar: None },
                     ],
                     count: 144320,
                     sum: 53423.0,
+                    created: None,
                 },
             ));
         });
@@ -569,9 +685,131 @@ mod test {
                     ],
                     count: 2693,
                     sum: 1.7560473e+07,
+                    created: None,
+                },
+            ));
+        });
+    }
+
+    #[test]
+    fn test_parse_openmetrics() {
+        let input = r##"
+            # HELP http_requests The total number of HTTP requests.
+            # TYPE http_requests counter
+            http_requests_total{code="200"} 1027 1395066363 # {trace_id="abc"} 1 1395066362.5
+            http_requests_created{code="200"} 1395066000
+            http_requests_total{code="400"} 3 1395066363
+            # TYPE build info
+            build_info{version="1.2.3"} 1
+            # TYPE state stateset
+            state{state="a"} 1
+            state{state="b"} 0
+            # TYPE queue_size gaugehistogram
+            queue_size_bucket{le="10"} 4
+            queue_size_bucket{le="+Inf"} 5 # {} 12
+            queue_size_gcount 5
+            queue_size_gsum 26
+            # TYPE rpc_duration_seconds summary
+            rpc_duration_seconds{quantile="0.5"} NaN
+            rpc_duration_seconds_sum 1.5
+            rpc_duration_seconds_count 2
+            rpc_duration_seconds_created 1395066000.5
+            # TYPE temperature unknown
+            temperature 21.5
+            # EOF
+            ignored 1
+            "##;
+        let output = parse_openmetrics(input).unwrap();
+        assert_eq!(output.len(), 6);
+        match_group!(output[0], "http_requests_total", Counter => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 2);
+            assert_eq!(
+                metrics.get_index(0).unwrap(),
+                (
+                    &GroupKey {
+                        timestamp: Some(1395066363000),
+                        labels: labels!(code => 200),
+                    },
+                    &SimpleMetric {
+                        value: 1027.0,
+                        created: Some(1395066000.0),
+                        exemplar: Some(Exemplar {
+                            labels: labels!(trace_id => "abc"),
+                            value: 1.0,
+                            timestamp: Some(1395066362500),
+                        }),
+                    },
+                )
+            );
+            assert_eq!(
+                metrics.get_index(1).unwrap(),
+                simple_metric!(Some(1395066363000), labels!(code => 400), 3.0)
+            );
+        });
+        match_group!(output[1], "build_info", Gauge => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(
+                metrics.get_index(0).unwrap(),
+                simple_metric!(None, labels!(version => "1.2.3"), 1.0)
+            );
+        });
+        match_group!(output[2], "state", Gauge => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 2);
+            assert_eq!(metrics.get_index(0).unwrap(), simple_metric!(None, labels!(state => "a"), 1.0));
+            assert_eq!(metrics.get_index(1).unwrap(), simple_metric!(None, labels!(state => "b"), 0.0));
+        });
+        match_group!(output[3], "queue_size", Histogram => |metrics: &MetricMap<HistogramMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(metrics.get_index(0).unwrap(), (
+                &GroupKey {
+                    timestamp: None,
+                    labels: labels!(),
+                },
+                &HistogramMetric {
+                    buckets: vec![
+                        HistogramBucket { bucket: 10.0, count: 4, exemplar: None },
+                        HistogramBucket {
+                            bucket: f64::INFINITY,
+                            count: 5,
+                            exemplar: Some(Exemplar {
+                                labels: labels!(),
+                                value: 12.0,
+                                timestamp: None,
+                            }),
+                        },
+                    ],
+                    count: 5,
+                    sum: 26.0,
+                    created: None,
                 },
             ));
         });
+        match_group!(output[4], "rpc_duration_seconds", Summary => |metrics: &MetricMap<SummaryMetric>| {
+            assert_eq!(metrics.len(), 1);
+            let (_, metric) = metrics.get_index(0).unwrap();
+            assert_eq!(metric.quantiles.len(), 1);
+            assert!(metric.quantiles[0].value.is_nan());
+            assert_eq!(metric.count, 2);
+            assert_eq!(metric.created, Some(1395066000.5));
+        });
+        match_group!(output[5], "temperature", Untyped => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(metrics.get_index(0).unwrap(), simple_metric!(None, labels!(), 21.5));
+        });
+    }
+
+    #[test]
+    fn test_parse_text_ignores_openmetrics_suffixes() {
+        let input = r##"
+            # TYPE http_requests_total counter
+            http_requests_total 1027
+            http_requests_total_created 1395066000
+            "##;
+        let output = parse_text(input).unwrap();
+        assert_eq!(output.len(), 2);
+        match_group!(output[1], "http_requests_total_created", Untyped => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+        });
     }
 
     #[test]
@@ -785,11 +1023,12 @@ mod test {
                     },
                     &HistogramMetric {
                         buckets: vec![
-                            HistogramBucket { bucket: 1.0, count: 15 },
-                            HistogramBucket { bucket: f64::INFINITY, count: 19 },
+                            HistogramBucket { bucket: 1.0, count: 15, exemplar: None },
+                            HistogramBucket { bucket: f64::INFINITY, count: 19, exemplar: None },
                         ],
                         count: 19,
                         sum: 12.0,
+                        created: None,
                     })
             );
         });
@@ -829,6 +1068,7 @@ mod test {
                         ],
                         count: 21,
                         sum: 12.0,
+                        created: None,
                     })
             );
         });
@@ -1,4 +1,5 @@
-//! Parse a single line of Prometheus text format.
+//! Parse a single line of Prometheus text format, or of the OpenMetrics text
+//! format.
 
 use nom::{
     branch::alt,
@@ -78,6 +79,15 @@ type NomErrorType<'a> = (&'a str, nom::error::ErrorKind);
 
 type NomError<'a> = nom::Err<NomErrorType<'a>>;
 
+/// The exposition format of the parsed text.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// The Prometheus text format, version 0.0.4.
+    Text,
+    /// The OpenMetrics text format, version 1.0.0.
+    OpenMetrics,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MetricKind {
     Counter,
@@ -85,6 +95,10 @@ pub enum MetricKind {
     Histogram,
     Summary,
     Untyped,
+    // Only in the OpenMetrics format.
+    GaugeHistogram,
+    Info,
+    StateSet,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -93,12 +107,23 @@ pub struct Header {
     pub kind: MetricKind,
 }
 
+/// An exemplar attached to a counter or histogram bucket sample of the
+/// OpenMetrics format, referencing an individual observation.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Exemplar {
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+    /// In milliseconds.
+    pub timestamp: Option<i64>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Metric {
     pub name: String,
     pub labels: BTreeMap<String, String>,
     pub value: f64,
     pub timestamp: Option<i64>,
+    pub exemplar: Option<Exemplar>,
 }
 
 impl Metric {
@@ -110,13 +135,27 @@ impl Metric {
     /// ] value [ timestamp ]
     /// ```
     ///
-    /// We don't parse timestamp.
-    fn parse(input: &str) -> IResult<Self> {
+    /// The OpenMetrics format may end the line with an exemplar:
+    ///
+    /// ``` text
+    /// "#" "{" label_name "=" `"` label_value `"` { "," ... } "}" value [ timestamp ]
+    /// ```
+    fn parse(input: &str, format: Format) -> IResult<Self> {
         let input = trim_space(input);
         let (input, name) = parse_name(input)?;
         let (input, labels) = Self::parse_labels(input)?;
         let (input, value) = Self::parse_value(input)?;
-        let (input, timestamp) = Self::parse_timestamp(input)?;
+        let (input, timestamp, exemplar) = match format {
+            Format::Text => {
+                let (input, timestamp) = Self::parse_timestamp(input)?;
+                (input, timestamp, None)
+            }
+            Format::OpenMetrics => {
+                let (input, timestamp) = Self::parse_float_timestamp(input)?;
+                let (input, exemplar) = Self::parse_exemplar(input)?;
+                (input, timestamp, exemplar)
+            }
+        };
         Ok((
             input,
             Metric {
@@ -124,17 +163,19 @@ impl Metric {
                 labels,
                 value,
                 timestamp,
+                exemplar,
             },
         ))
     }
 
-    /// Float value, and +Inf, -Int, Nan.
+    /// Float value, and +Inf, -Int, Nan, NaN.
     pub(crate) fn parse_value(input: &str) -> IResult<f64> {
         let input = trim_space(input);
         alt((
             value(f64::INFINITY, tag("+Inf")),
             value(f64::NEG_INFINITY, tag("-Inf")),
             value(f64::NAN, tag("Nan")),
+            value(f64::NAN, tag("NaN")),
             double,
         ))(input)
         .map_err(|_: NomError| {
@@ -152,6 +193,34 @@ impl Metric {
         }))(input)
     }
 
+    /// OpenMetrics timestamps are in seconds, with an optional fraction.
+    /// Return them in milliseconds, like the text format ones.
+    fn parse_float_timestamp(input: &str) -> IResult<Option<i64>> {
+        let input = trim_space(input);
+        opt(map(double, |seconds| (seconds * 1000.0).round() as i64))(input)
+    }
+
+    /// Parse ` # {label_name="value",...} value [ timestamp ]`.
+    fn parse_exemplar(input: &str) -> IResult<Option<Exemplar>> {
+        let input = trim_space(input);
+        let input = match char::<_, NomErrorType>('#')(input) {
+            Ok((input, _)) => input,
+            Err(_) => return Ok((input, None)),
+        };
+        let (input, _) = match_char('{')(input)?;
+        let (input, labels) = Self::parse_labels_inner(input)?;
+        let (input, value) = Self::parse_value(input)?;
+        let (input, timestamp) = Self::parse_float_timestamp(input)?;
+        Ok((
+            input,
+            Some(Exemplar {
+                labels,
+                value,
+                timestamp,
+            }),
+        ))
+    }
+
     fn parse_name_value(input: &str) -> IResult<(String, String)> {
         map(
             tuple((parse_name, match_char('='), Self::parse_escaped_string)),
@@ -286,7 +355,7 @@ impl Header {
     }
 
     /// `# TYPE <metric_name> <metric_type>`
-    fn parse(input: &str) -> IResult<Self> {
+    fn parse(input: &str, format: Format) -> IResult<Self> {
         let input = trim_space(input);
         let (input, _) = char('#')(input).map_err(|_: NomError| ErrorKind::ExpectedChar {
             expected: '#',
@@ -300,14 +369,26 @@ impl Header {
         let (input, _) = Self::space1(input)?;
         let (input, metric_name) = parse_name(input)?;
         let (input, _) = Self::space1(input)?;
-        let (input, kind) = alt((
-            value(MetricKind::Counter, tag("counter")),
-            value(MetricKind::Gauge, tag("gauge")),
-            value(MetricKind::Summary, tag("summary")),
-            value(MetricKind::Histogram, tag("histogram")),
-            value(MetricKind::Untyped, tag("untyped")),
-        ))(input)
-        .map_err(|_: NomError| ErrorKind::InvalidMetricKind {
+        let kind = match format {
+            Format::Text => alt((
+                value(MetricKind::Counter, tag("counter")),
+                value(MetricKind::Gauge, tag("gauge")),
+                value(MetricKind::Summary, tag("summary")),
+                value(MetricKind::Histogram, tag("histogram")),
+                value(MetricKind::Untyped, tag("untyped")),
+            ))(input),
+            Format::OpenMetrics => alt((
+                value(MetricKind::Counter, tag("counter")),
+                value(MetricKind::GaugeHistogram, tag("gaugehistogram")),
+                value(MetricKind::Gauge, tag("gauge")),
+                value(MetricKind::Summary, tag("summary")),
+                value(MetricKind::Histogram, tag("histogram")),
+                value(MetricKind::Info, tag("info")),
+                value(MetricKind::StateSet, tag("stateset")),
+                value(MetricKind::Untyped, tag("unknown")),
+            ))(input),
+        };
+        let (input, kind) = kind.map_err(|_: NomError| ErrorKind::InvalidMetricKind {
             input: input.to_owned(),
         })?;
         Ok((input, Header { metric_name, kind }))
@@ -324,20 +405,20 @@ pub enum Line {
 
 impl Line {
     /// Parse a single line. Return `None` if it is a comment or an empty line.
-    pub(crate) fn parse(input: &str) -> Result<Option<Self>, ErrorKind> {
+    pub(crate) fn parse(input: &str, format: Format) -> Result<Option<Self>, ErrorKind> {
         let input = input.trim();
         if input.is_empty() {
             return Ok(None);
         }
 
-        let metric_error = match Metric::parse(input) {
+        let metric_error = match Metric::parse(input, format) {
             Ok((_, metric)) => {
                 return Ok(Some(Line::Metric(metric)));
             }
             Err(e) => e.into(),
         };
 
-        let header_error = match Header::parse(input) {
+        let header_error = match Header::parse(input, format) {
             Ok((_, header)) => {
                 return Ok(Some(Line::Header(header)));
             }
@@ -462,7 +543,7 @@ mod test {
         let tail = "  .";
 
         let input = wrap("#  TYPE abc_def counter");
-        let (left, r) = Header::parse(&input).unwrap();
+        let (left, r) = Header::parse(&input, Format::Text).unwrap();
         assert_eq!(left, tail);
         assert_eq!(
             r,
@@ -474,7 +555,7 @@ mod test {
 
         // We allow this case
         let input = wrap("#  TYPE abc_def counteraaaaaaaaaaa");
-        let (_, r) = Header::parse(&input).unwrap();
+        let (_, r) = Header::parse(&input, Format::Text).unwrap();
         assert_eq!(
             r,
             Header {
@@ -484,7 +565,7 @@ mod test {
         );
 
         let input = wrap("#TYPE \t abc_def \t gauge");
-        let (left, r) = Header::parse(&input).unwrap();
+        let (left, r) = Header::parse(&input, Format::Text).unwrap();
         assert_eq!(left, tail);
         assert_eq!(
             r,
@@ -495,7 +576,7 @@ mod test {
         );
 
         let input = wrap("# TYPE abc_def histogram");
-        let (left, r) = Header::parse(&input).unwrap();
+        let (left, r) = Header::parse(&input, Format::Text).unwrap();
         assert_eq!(left, tail);
         assert_eq!(
             r,
@@ -506,7 +587,7 @@ mod test {
         );
 
         let input = wrap("# TYPE abc_def summary");
-        let (left, r) = Header::parse(&input).unwrap();
+        let (left, r) = Header::parse(&input, Format::Text).unwrap();
         assert_eq!(left, tail);
         assert_eq!(
             r,
@@ -517,7 +598,7 @@ mod test {
         );
 
         let input = wrap("# TYPE abc_def untyped");
-        let (left, r) = Header::parse(&input).unwrap();
+        let (left, r) = Header::parse(&input, Format::Text).unwrap();
         assert_eq!(left, tail);
         assert_eq!(
             r,
@@ -630,6 +711,73 @@ mod test {
         assert_eq!(Metric::parse_timestamp(" -23"), Ok(("", Some(-23))));
     }
 
+    #[test]
+    fn test_parse_openmetrics_header() {
+        let tests = [
+            ("# TYPE a counter", MetricKind::Counter),
+            ("# TYPE a gauge", MetricKind::Gauge),
+            ("# TYPE a gaugehistogram", MetricKind::GaugeHistogram),
+            ("# TYPE a histogram", MetricKind::Histogram),
+            ("# TYPE a summary", MetricKind::Summary),
+            ("# TYPE a info", MetricKind::Info),
+            ("# TYPE a stateset", MetricKind::StateSet),
+            ("# TYPE a unknown", MetricKind::Untyped),
+        ];
+        for (input, kind) in &tests {
+            let (_, r) = Header::parse(input, Format::OpenMetrics).unwrap();
+            assert_eq!(r.kind, *kind);
+        }
+
+        // These are only OpenMetrics types.
+        assert!(Header::parse("# TYPE a info", Format::Text).is_err());
+        assert!(Header::parse("# TYPE a untyped", Format::OpenMetrics).is_err());
+    }
+
+    #[test]
+    fn test_parse_openmetrics_metric() {
+        let (_, r) = Metric::parse(
+            r#"foo_total{a="b"} 17.0 1520879607.789 # {trace_id="KOO5S4vxi0o"} 0.67 1520879602.12"#,
+            Format::OpenMetrics,
+        )
+        .unwrap();
+        assert_eq!(
+            r,
+            Metric {
+                name: "foo_total".into(),
+                labels: btreemap! { "a" => "b" },
+                value: 17.0,
+                timestamp: Some(1520879607789),
+                exemplar: Some(Exemplar {
+                    labels: btreemap! { "trace_id" => "KOO5S4vxi0o" },
+                    value: 0.67,
+                    timestamp: Some(1520879602120),
+                }),
+            }
+        );
+
+        let (_, r) = Metric::parse("foo_bucket{le=\"1\"} 3 # {} 0.5", Format::OpenMetrics).unwrap();
+        assert_eq!(r.timestamp, None);
+        assert_eq!(
+            r.exemplar,
+            Some(Exemplar {
+                labels: btreemap! {},
+                value: 0.5,
+                timestamp: None,
+            })
+        );
+
+        let (_, r) = Metric::parse("foo NaN", Format::OpenMetrics).unwrap();
+        assert!(r.value.is_nan());
+
+        let error = Metric::parse("foo 1 # 0.5", Format::OpenMetrics)
+            .unwrap_err()
+            .into();
+        assert!(matches!(
+            error,
+            ErrorKind::ExpectedChar { expected: '{', .. }
+        ));
+    }
+
     #[test]
     fn test_parse_line() {
         let input = r##"
@@ -670,6 +818,9 @@ mod test {
             rpc_duration_seconds_sum 1.7560473e+07
             rpc_duration_seconds_count 2693
             "##;
-        assert!(input.lines().map(Line::parse).all(|r| r.is_ok()));
+        assert!(input
+            .lines()
+            .map(|line| Line::parse(line, Format::Text))
+            .all(|r| r.is_ok()));
     }
 }
@@ -126,6 +126,20 @@ impl<'a> ToLua<'a> for Metric {
                 aggregated_summary.raw_set("sum", sum)?;
                 tbl.raw_set("aggregated_summary", aggregated_summary)?;
             }
+            MetricValue::Sketch { sketch, statistic } => {
+                let lua_sketch = lua.create_table()?;
+                let keys: Vec<_> = sketch.bins().iter().map(|b| b.key).collect();
+                let counts: Vec<_> = sketch.bins().iter().map(|b| b.count).collect();
+                lua_sketch.raw_set("relative_accuracy", sketch.relative_accuracy())?;
+                lua_sketch.raw_set("keys", keys)?;
+                lua_sketch.raw_set("counts", counts)?;
+                lua_sketch.raw_set("count", sketch.count())?;
+                lua_sketch.raw_set("sum", sketch.sum())?;
+                lua_sketch.raw_set("min", sketch.min())?;
+                lua_sketch.raw_set("max", sketch.max())?;
+                lua_sketch.raw_set("statistic", statistic)?;
+                tbl.raw_set("sketch", lua_sketch)?;
+            }
         }
 
         Ok(LuaValue::Table(tbl))
@@ -196,11 +210,34 @@ impl<'a> FromLua<'a> for Metric {
                 count: aggregated_summary.raw_get("count")?,
                 sum: aggregated_summary.raw_get("sum")?,
             }
+        } else if let Some(sketch) = table.raw_get::<_, Option<LuaTable>>("sketch")? {
+            let keys: Vec<i32> = sketch.raw_get("keys")?;
+            let counts: Vec<u32> = sketch.raw_get("counts")?;
+            let bins = keys
+                .into_iter()
+                .zip(counts)
+                .map(|(key, count)| metric::SketchBin { key, count })
+                .collect();
+            MetricValue::Sketch {
+                sketch: metric::DDSketch::from_parts(
+                    sketch.raw_get("relative_accuracy")?,
+                    bins,
+                    sketch.raw_get("sum")?,
+                    sketch.raw_get("min")?,
+                    sketch.raw_get("max")?,
+                )
+                .ok_or_else(|| LuaError::FromLuaConversionError {
+                    from: "table",
+                    to: "Metric",
+                    message: Some("Sketch keys must be sorted and the relative accuracy must be in range (0.0, 1.0)".to_string()),
+                })?,
+                statistic: sketch.raw_get("statistic")?,
+            }
         } else {
             return Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
                 to: "Metric",
-                message: Some("Cannot find metric value, expected presence one of \"counter\", \"gauge\", \"set\", \"distribution\", \"aggregated_histogram\", \"aggregated_summary\"".to_string()),
+                message: Some("Cannot find metric value, expected presence one of \"counter\", \"gauge\", \"set\", \"distribution\", \"aggregated_histogram\", \"aggregated_summary\", \"sketch\"".to_string()),
             });
         };
 
@@ -335,6 +372,35 @@ mod test {
         assert_metric(metric, assertions);
     }
 
+    #[test]
+    fn to_lua_sketch() {
+        let metric = Metric::new(
+            "example sketch",
+            MetricKind::Incremental,
+            MetricValue::Sketch {
+                sketch: metric::DDSketch::from_samples(
+                    0.01,
+                    &crate::samples![1.0 => 10, 2.0 => 20],
+                ),
+                statistic: StatisticKind::Summary,
+            },
+        );
+        let assertions = vec![
+            "type(metric.sketch) == 'table'",
+            "metric.sketch.relative_accuracy == 0.01",
+            "#metric.sketch.keys == 2",
+            "metric.sketch.keys[1] < metric.sketch.keys[2]",
+            "metric.sketch.counts[1] == 10",
+            "metric.sketch.counts[2] == 20",
+            "metric.sketch.count == 30",
+            "metric.sketch.sum == 50",
+            "metric.sketch.min == 1",
+            "metric.sketch.max == 2",
+            "metric.sketch.statistic == 'summary'",
+        ];
+        assert_metric(metric, assertions);
+    }
+
     #[test]
     fn to_lua_aggregated_histogram() {
         let metric = Metric::new(
@@ -542,4 +608,34 @@ mod test {
         );
         assert_event_data_eq!(Lua::new().load(value).eval::<Metric>().unwrap(), expected);
     }
+
+    #[test]
+    fn from_lua_sketch() {
+        let sketch = metric::DDSketch::from_samples(0.01, &crate::samples![1.0 => 10, 2.0 => 20]);
+        let value = format!(
+            r#"{{
+            name = "example sketch",
+            sketch = {{
+                relative_accuracy = 0.01,
+                keys = {{ {}, {} }},
+                counts = {{ 10, 20 }},
+                sum = 50,
+                min = 1,
+                max = 2,
+                statistic = "summary"
+            }}
+        }}"#,
+            sketch.bins()[0].key,
+            sketch.bins()[1].key
+        );
+        let expected = Metric::new(
+            "example sketch",
+            MetricKind::Absolute,
+            MetricValue::Sketch {
+                sketch,
+                statistic: StatisticKind::Summary,
+            },
+        );
+        assert_event_data_eq!(Lua::new().load(&value).eval::<Metric>().unwrap(), expected);
+    }
 }
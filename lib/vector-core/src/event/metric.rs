@@ -14,6 +14,10 @@ use std::{
     sync::Arc,
 };
 
+mod sketch;
+
+pub use self::sketch::{DDSketch, SketchBin};
+
 #[derive(Clone, Debug, Deserialize, Getters, MutGetters, PartialEq, PartialOrd, Serialize)]
 pub struct Metric {
     #[getset(get = "pub")]
@@ -166,6 +170,14 @@ pub enum MetricValue {
         count: u32,
         sum: f64,
     },
+    /// A Sketch contains a set of sampled values counted into a quantile
+    /// sketch. Unlike a Distribution, it has a fixed memory footprint
+    /// and can be merged with other sketches of the same accuracy without
+    /// losing information.
+    Sketch {
+        sketch: DDSketch,
+        statistic: StatisticKind,
+    },
 }
 
 impl ByteSizeOf for MetricValue {
@@ -176,6 +188,7 @@ impl ByteSizeOf for MetricValue {
             Self::Distribution { samples, .. } => samples.allocated_bytes(),
             Self::AggregatedHistogram { buckets, .. } => buckets.allocated_bytes(),
             Self::AggregatedSummary { quantiles, .. } => quantiles.allocated_bytes(),
+            Self::Sketch { sketch, .. } => sketch.allocated_bytes(),
         }
     }
 }
@@ -294,6 +307,7 @@ impl From<MetricValue> for vrl_core::Value {
             MetricValue::Distribution { .. } => "distribution",
             MetricValue::AggregatedHistogram { .. } => "aggregated histogram",
             MetricValue::AggregatedSummary { .. } => "aggregated summary",
+            MetricValue::Sketch { .. } => "sketch",
         }
         .into()
     }
@@ -643,8 +657,8 @@ impl AsRef<MetricData> for MetricData {
 impl MetricValue {
     /// Zero out all the values contained in this. This keeps all the
     /// bucket/value vectors for the histogram and summary metric types
-    /// intact while zeroing the counts. Distribution metrics are
-    /// emptied of all their values.
+    /// intact while zeroing the counts. Distribution and sketch metrics
+    /// are emptied of all their values.
     pub fn zero(&mut self) {
         match self {
             Self::Counter { value } | Self::Gauge { value } => *value = 0.0,
@@ -672,6 +686,7 @@ impl MetricValue {
                 *count = 0;
                 *sum = 0.0;
             }
+            Self::Sketch { sketch, .. } => sketch.clear(),
         }
     }
 
@@ -725,6 +740,16 @@ impl MetricValue {
                 *sum += sum2;
                 true
             }
+            (
+                Self::Sketch {
+                    ref mut sketch,
+                    statistic: statistic_a,
+                },
+                Self::Sketch {
+                    sketch: sketch2,
+                    statistic: statistic_b,
+                },
+            ) if statistic_a == statistic_b => sketch.merge(sketch2),
 
             _ => false,
         }
@@ -789,6 +814,16 @@ impl MetricValue {
                 *sum -= sum2;
                 true
             }
+            (
+                Self::Sketch {
+                    ref mut sketch,
+                    statistic: statistic_a,
+                },
+                Self::Sketch {
+                    sketch: sketch2,
+                    statistic: statistic_b,
+                },
+            ) if statistic_a == statistic_b => sketch.subtract(sketch2),
             _ => false,
         }
     }
@@ -810,7 +845,8 @@ impl Display for Metric {
     /// representation of the data contents. In particular,
     /// distributions, histograms, and summaries are represented as a
     /// list of `X@Y` words, where `X` is the rate, count, or quantile,
-    /// and `Y` is the value or bucket.
+    /// and `Y` is the value or bucket. Sketches are represented by their
+    /// count, sum, minimum and maximum.
     ///
     /// example:
     /// ```text
@@ -865,6 +901,20 @@ impl Display for Metric {
                     write!(fmt, "{}@{}", quantile.upper_limit, quantile.value)
                 })
             }
+            MetricValue::Sketch { sketch, statistic } => {
+                write!(
+                    fmt,
+                    "{} count={} sum={} min={} max={}",
+                    match statistic {
+                        StatisticKind::Histogram => "histogram",
+                        StatisticKind::Summary => "summary",
+                    },
+                    sketch.count(),
+                    sketch.sum(),
+                    sketch.min(),
+                    sketch.max()
+                )
+            }
         }
     }
 }
@@ -1057,6 +1107,35 @@ mod test {
         assert_eq!(dist, expected);
     }
 
+    #[test]
+    fn merge_sketches() {
+        let sketch = |samples: &[Sample]| MetricValue::Sketch {
+            sketch: DDSketch::from_samples(DDSketch::DEFAULT_RELATIVE_ACCURACY, samples),
+            statistic: StatisticKind::Summary,
+        };
+
+        let mut metric = Metric::new(
+            "sketch",
+            MetricKind::Incremental,
+            sketch(&samples![1.0 => 10]),
+        );
+
+        let delta = Metric::new(
+            "sketch",
+            MetricKind::Incremental,
+            sketch(&samples![2.0 => 20]),
+        )
+        .with_timestamp(Some(ts()));
+
+        let expected = metric
+            .clone()
+            .with_value(sketch(&samples![1.0 => 10, 2.0 => 20]))
+            .with_timestamp(Some(ts()));
+
+        assert!(metric.data.add(&delta.data));
+        assert_eq!(metric, expected);
+    }
+
     #[test]
     // `too_many_lines` is mostly just useful for production code but we're not
     // able to flag the lint on only for non-test.
@@ -1173,5 +1252,20 @@ mod test {
             ),
             r#"six{} = count=2 sum=127 1@63 2@64"#
         );
+
+        assert_eq!(
+            format!(
+                "{}",
+                Metric::new(
+                    "seven",
+                    MetricKind::Absolute,
+                    MetricValue::Sketch {
+                        sketch: DDSketch::from_samples(0.01, &samples![1.0 => 3, 2.0 => 4]),
+                        statistic: StatisticKind::Summary,
+                    }
+                )
+            ),
+            r#"seven{} = summary count=7 sum=11 min=1 max=2"#
+        );
     }
 }
@@ -0,0 +1,466 @@
+//! A mergeable quantile sketch in the style of [`DDSketch`][ddsketch].
+//!
+//! Values are counted into logarithmically sized bins, so that any
+//! quantile read back from the sketch is within a fixed relative error of
+//! the true value. Two sketches with the same accuracy merge losslessly by
+//! adding up the counts of their bins.
+//!
+//! [ddsketch]: https://arxiv.org/abs/1908.10693
+
+use super::{Bucket, Sample};
+use crate::ByteSizeOf;
+use serde::{Deserialize, Serialize};
+
+/// Values with a smaller magnitude than this are counted as zero.
+const MIN_VALUE: f64 = 1e-9;
+
+/// A quantile sketch with a configurable relative accuracy.
+///
+/// The bins are sorted by key. The key of a bin is zero for values too
+/// close to zero to be told apart from it, positive for positive values
+/// and negative for negative ones, with its magnitude growing with the
+/// magnitude of the values it holds. Ordering bins by key therefore orders
+/// them by value.
+#[derive(Clone, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+pub struct DDSketch {
+    relative_accuracy: f64,
+    bins: Vec<SketchBin>,
+    count: u32,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+/// The number of values counted into a single bin of a `DDSketch`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, PartialOrd, Serialize)]
+pub struct SketchBin {
+    pub key: i32,
+    pub count: u32,
+}
+
+impl ByteSizeOf for DDSketch {
+    fn allocated_bytes(&self) -> usize {
+        self.bins.allocated_bytes()
+    }
+}
+
+impl ByteSizeOf for SketchBin {
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
+impl DDSketch {
+    /// The relative accuracy used when none is configured, 1%.
+    pub const DEFAULT_RELATIVE_ACCURACY: f64 = 0.01;
+
+    /// Creates an empty sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the relative accuracy isn't in the range (0.0, 1.0).
+    pub fn new(relative_accuracy: f64) -> Self {
+        assert!(
+            relative_accuracy > 0.0 && relative_accuracy < 1.0,
+            "relative accuracy must be in range (0.0, 1.0)"
+        );
+        Self {
+            relative_accuracy,
+            bins: Vec::new(),
+            count: 0,
+            sum: 0.0,
+            min: 0.0,
+            max: 0.0,
+        }
+    }
+
+    /// Builds a sketch from the samples of a distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the relative accuracy isn't in the range (0.0, 1.0).
+    pub fn from_samples(relative_accuracy: f64, samples: &[Sample]) -> Self {
+        let mut sketch = Self::new(relative_accuracy);
+        for sample in samples {
+            sketch.insert(sample.value, sample.rate);
+        }
+        sketch
+    }
+
+    /// Rebuilds a sketch from its parts, as returned by its accessors.
+    /// Returns `None` if the parts are inconsistent.
+    pub fn from_parts(
+        relative_accuracy: f64,
+        bins: Vec<SketchBin>,
+        sum: f64,
+        min: f64,
+        max: f64,
+    ) -> Option<Self> {
+        let valid = relative_accuracy > 0.0
+            && relative_accuracy < 1.0
+            && bins.windows(2).all(|pair| pair[0].key < pair[1].key);
+        if !valid {
+            return None;
+        }
+
+        let count = bins
+            .iter()
+            .fold(0u32, |count, bin| count.saturating_add(bin.count));
+        Some(Self {
+            relative_accuracy,
+            bins,
+            count,
+            sum,
+            min,
+            max,
+        })
+    }
+
+    pub fn relative_accuracy(&self) -> f64 {
+        self.relative_accuracy
+    }
+
+    pub fn bins(&self) -> &[SketchBin] {
+        &self.bins
+    }
+
+    /// The number of values in the sketch.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// The exact sum of the values in the sketch.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// The exact minimum of the values in the sketch, zero if it is empty.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// The exact maximum of the values in the sketch, zero if it is empty.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Counts `value` into the sketch `count` times. Values that are not
+    /// finite are ignored.
+    pub fn insert(&mut self, value: f64, count: u32) {
+        if !value.is_finite() || count == 0 {
+            return;
+        }
+
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count = self.count.saturating_add(count);
+        self.sum += value * f64::from(count);
+
+        let key = self.key(value);
+        match self.bins.binary_search_by_key(&key, |bin| bin.key) {
+            Ok(index) => {
+                let bin = &mut self.bins[index];
+                bin.count = bin.count.saturating_add(count);
+            }
+            Err(index) => self.bins.insert(index, SketchBin { key, count }),
+        }
+    }
+
+    /// Adds the values of `other` to this sketch. Returns `false` and
+    /// leaves this sketch untouched if the accuracies differ.
+    #[must_use]
+    pub fn merge(&mut self, other: &Self) -> bool {
+        if self.relative_accuracy != other.relative_accuracy {
+            return false;
+        }
+        if other.count == 0 {
+            return true;
+        }
+
+        if self.count == 0 {
+            self.min = other.min;
+            self.max = other.max;
+        } else {
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+        }
+        self.count = self.count.saturating_add(other.count);
+        self.sum += other.sum;
+
+        let mut bins = self.bins.clone();
+        bins.extend_from_slice(&other.bins);
+        bins.sort_by_key(|bin| bin.key);
+        bins.dedup_by(|bin, previous| {
+            let same = bin.key == previous.key;
+            if same {
+                previous.count = previous.count.saturating_add(bin.count);
+            }
+            same
+        });
+        self.bins = bins;
+
+        true
+    }
+
+    /// Removes the values of `other` from this sketch. Returns `false` and
+    /// leaves this sketch untouched if the accuracies differ. The minimum
+    /// and maximum can't be recovered, so they are left as they are.
+    #[must_use]
+    pub fn subtract(&mut self, other: &Self) -> bool {
+        if self.relative_accuracy != other.relative_accuracy {
+            return false;
+        }
+
+        for other_bin in &other.bins {
+            if let Ok(index) = self
+                .bins
+                .binary_search_by_key(&other_bin.key, |bin| bin.key)
+            {
+                let bin = &mut self.bins[index];
+                bin.count = bin.count.saturating_sub(other_bin.count);
+            }
+        }
+        self.bins.retain(|bin| bin.count > 0);
+        self.count = self
+            .bins
+            .iter()
+            .fold(0u32, |count, bin| count.saturating_add(bin.count));
+        self.sum -= other.sum;
+        if self.count == 0 {
+            self.clear();
+        }
+
+        true
+    }
+
+    /// Removes all values from the sketch.
+    pub fn clear(&mut self) {
+        self.bins.clear();
+        self.count = 0;
+        self.sum = 0.0;
+        self.min = 0.0;
+        self.max = 0.0;
+    }
+
+    /// Estimates the value at quantile `q`, which is clamped to the range
+    /// [0.0, 1.0]. Returns `None` if the sketch is empty.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let q = q.clamp(0.0, 1.0);
+        if q == 0.0 {
+            return Some(self.min);
+        }
+        if q == 1.0 {
+            return Some(self.max);
+        }
+
+        let rank = q * f64::from(self.count - 1);
+        let mut seen = 0u32;
+        let key = self
+            .bins
+            .iter()
+            .find(|bin| {
+                seen = seen.saturating_add(bin.count);
+                f64::from(seen) > rank
+            })
+            .or_else(|| self.bins.last())
+            .map(|bin| bin.key)?;
+
+        Some(self.value(key).max(self.min).min(self.max))
+    }
+
+    /// Counts the values into histogram buckets with the given upper
+    /// limits, which must be sorted. Each bucket counts the values that
+    /// are larger than the previous limit, values larger than the last
+    /// limit aren't counted.
+    pub fn buckets(&self, upper_limits: &[f64]) -> Vec<Bucket> {
+        let mut buckets = upper_limits
+            .iter()
+            .map(|&upper_limit| Bucket {
+                upper_limit,
+                count: 0,
+            })
+            .collect::<Vec<_>>();
+
+        for bin in &self.bins {
+            let value = self.value(bin.key).max(self.min).min(self.max);
+            let index = buckets.partition_point(|bucket| bucket.upper_limit < value);
+            if let Some(bucket) = buckets.get_mut(index) {
+                bucket.count = bucket.count.saturating_add(bin.count);
+            }
+        }
+
+        buckets
+    }
+
+    fn gamma(&self) -> f64 {
+        (1.0 + self.relative_accuracy) / (1.0 - self.relative_accuracy)
+    }
+
+    /// The offset added to the logarithm of the magnitude of a value,
+    /// which makes the key of the smallest value that isn't zero one.
+    #[allow(clippy::cast_possible_truncation)]
+    fn bias(&self) -> i32 {
+        1 - (MIN_VALUE.ln() / self.gamma().ln()).floor() as i32
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn key(&self, value: f64) -> i32 {
+        let magnitude = value.abs();
+        if magnitude < MIN_VALUE {
+            return 0;
+        }
+
+        let key = (magnitude.ln() / self.gamma().ln()).ceil() as i32 + self.bias();
+        if value < 0.0 {
+            -key
+        } else {
+            key
+        }
+    }
+
+    /// The value that is within the relative accuracy of all the values of
+    /// the bin with `key`.
+    fn value(&self, key: i32) -> f64 {
+        if key == 0 {
+            return 0.0;
+        }
+
+        let gamma = self.gamma();
+        let upper = gamma.powi(key.abs() - self.bias());
+        let value = 2.0 * upper / (1.0 + gamma);
+        if key < 0 {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sketch(values: impl IntoIterator<Item = f64>) -> DDSketch {
+        let mut sketch = DDSketch::new(DDSketch::DEFAULT_RELATIVE_ACCURACY);
+        for value in values {
+            sketch.insert(value, 1);
+        }
+        sketch
+    }
+
+    fn assert_accurate(sketch: &DDSketch, q: f64, expected: f64) {
+        let actual = sketch.quantile(q).unwrap();
+        let error = ((actual - expected) / expected).abs();
+        assert!(
+            error <= sketch.relative_accuracy(),
+            "quantile {} is {}, expected {}",
+            q,
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn estimates_quantiles() {
+        let sketch = sketch((1..=1000).map(f64::from));
+
+        assert_eq!(sketch.count(), 1000);
+        assert_eq!(sketch.sum(), 500_500.0);
+        assert_eq!(sketch.quantile(0.0), Some(1.0));
+        assert_eq!(sketch.quantile(1.0), Some(1000.0));
+        assert_accurate(&sketch, 0.5, 500.5);
+        assert_accurate(&sketch, 0.9, 900.1);
+        assert_accurate(&sketch, 0.99, 990.01);
+    }
+
+    #[test]
+    fn orders_negative_and_zero_values() {
+        let sketch = sketch(vec![-100.0, -1.0, 0.0, 1.0, 100.0]);
+
+        let keys = sketch.bins().iter().map(|bin| bin.key).collect::<Vec<_>>();
+        let mut sorted = keys.clone();
+        sorted.sort_unstable();
+        assert_eq!(keys, sorted);
+        assert_eq!(sketch.bins()[2], SketchBin { key: 0, count: 1 });
+
+        assert_accurate(&sketch, 0.25, -1.0);
+        assert_eq!(sketch.quantile(0.5), Some(0.0));
+        assert_accurate(&sketch, 0.75, 1.0);
+    }
+
+    #[test]
+    fn ignores_values_that_are_not_finite() {
+        let sketch = sketch(vec![1.0, f64::NAN, f64::INFINITY]);
+        assert_eq!(sketch.count(), 1);
+        assert_eq!(sketch.max(), 1.0);
+    }
+
+    #[test]
+    fn merges_losslessly() {
+        let mut merged = sketch((1..=500).map(f64::from));
+        assert!(merged.merge(&sketch((501..=1000).map(f64::from))));
+
+        assert_eq!(merged, sketch((1..=1000).map(f64::from)));
+    }
+
+    #[test]
+    fn does_not_merge_different_accuracies() {
+        let mut merged = sketch(vec![1.0]);
+        let mut other = DDSketch::new(0.05);
+        other.insert(2.0, 1);
+
+        assert!(!merged.merge(&other));
+        assert_eq!(merged, sketch(vec![1.0]));
+    }
+
+    #[test]
+    fn subtracts_values() {
+        let mut sketch = sketch(vec![1.0, 2.0, 2.0, 3.0]);
+        assert!(sketch.subtract(&self::sketch(vec![2.0, 3.0])));
+
+        assert_eq!(sketch.count(), 2);
+        assert_eq!(sketch.sum(), 3.0);
+        assert_eq!(sketch.bins().len(), 2);
+    }
+
+    #[test]
+    fn counts_into_buckets() {
+        let sketch = sketch(vec![0.5, 1.0, 3.0, 4.0, 20.0]);
+
+        assert_eq!(
+            sketch.buckets(&[1.0, 5.0, 10.0]),
+            crate::buckets![1.0 => 2, 5.0 => 2, 10.0 => 0]
+        );
+    }
+
+    #[test]
+    fn rebuilds_from_parts() {
+        let sketch = sketch(vec![-1.0, 0.0, 10.0]);
+        let rebuilt = DDSketch::from_parts(
+            sketch.relative_accuracy(),
+            sketch.bins().to_vec(),
+            sketch.sum(),
+            sketch.min(),
+            sketch.max(),
+        );
+        assert_eq!(rebuilt, Some(sketch));
+
+        let unsorted = vec![
+            SketchBin { key: 2, count: 1 },
+            SketchBin { key: 1, count: 1 },
+        ];
+        assert_eq!(DDSketch::from_parts(0.01, unsorted, 0.0, 0.0, 0.0), None);
+    }
+}
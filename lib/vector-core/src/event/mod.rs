@@ -290,6 +290,24 @@ impl From<proto::SummaryQuantile> for metric::Quantile {
     }
 }
 
+impl From<metric::SketchBin> for proto::SketchBin {
+    fn from(bin: metric::SketchBin) -> Self {
+        Self {
+            key: bin.key,
+            count: bin.count,
+        }
+    }
+}
+
+impl From<proto::SketchBin> for metric::SketchBin {
+    fn from(bin: proto::SketchBin) -> Self {
+        Self {
+            key: bin.key,
+            count: bin.count,
+        }
+    }
+}
+
 impl From<Bytes> for Event {
     fn from(message: Bytes) -> Self {
         Event::Log(LogEvent::from(message))
@@ -96,6 +96,20 @@ impl From<Metric> for event::Metric {
                 count: summary.count,
                 sum: summary.sum,
             },
+            MetricValue::Sketch(sketch) => event::MetricValue::Sketch {
+                statistic: sketch.statistic().into(),
+                sketch: event::metric::DDSketch::from_parts(
+                    sketch.relative_accuracy,
+                    sketch.bins.into_iter().map(Into::into).collect(),
+                    sketch.sum,
+                    sketch.min,
+                    sketch.max,
+                )
+                .unwrap_or_else(|| {
+                    error!("Encoded sketch is invalid, replacing it with an empty one.");
+                    event::metric::DDSketch::new(event::metric::DDSketch::DEFAULT_RELATIVE_ACCURACY)
+                }),
+            },
         };
 
         Self::new(name, kind, value)
@@ -194,6 +208,18 @@ impl From<event::Metric> for WithMetadata<Metric> {
                 count,
                 sum,
             }),
+            event::MetricValue::Sketch { sketch, statistic } => MetricValue::Sketch(Sketch {
+                relative_accuracy: sketch.relative_accuracy(),
+                bins: sketch.bins().iter().copied().map(Into::into).collect(),
+                sum: sketch.sum(),
+                min: sketch.min(),
+                max: sketch.max(),
+                statistic: match statistic {
+                    event::StatisticKind::Histogram => StatisticKind::Histogram,
+                    event::StatisticKind::Summary => StatisticKind::Summary,
+                }
+                .into(),
+            }),
         };
 
         let data = Metric {
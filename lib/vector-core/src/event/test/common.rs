@@ -1,5 +1,5 @@
 use crate::event::{
-    metric::{Bucket, MetricData, MetricName, MetricSeries, Quantile, Sample},
+    metric::{Bucket, DDSketch, MetricData, MetricName, MetricSeries, Quantile, Sample},
     Event, EventMetadata, LogEvent, Metric, MetricKind, MetricValue, StatisticKind, Value,
 };
 use bytes::Bytes;
@@ -151,7 +151,7 @@ impl Arbitrary for MetricValue {
         // constant here are the number of fields in `MetricValue`. Because the
         // field total is not a power of two we introduce a bias into choice
         // here toward `MetricValue::Counter` and `MetricValue::Gauge`.
-        match u8::arbitrary(g) % 6 {
+        match u8::arbitrary(g) % 7 {
             0 => MetricValue::Counter {
                 value: f64::arbitrary(g) % MAX_F64_SIZE,
             },
@@ -175,6 +175,13 @@ impl Arbitrary for MetricValue {
                 count: u32::arbitrary(g),
                 sum: f64::arbitrary(g) % MAX_F64_SIZE,
             },
+            6 => MetricValue::Sketch {
+                sketch: DDSketch::from_samples(
+                    DDSketch::DEFAULT_RELATIVE_ACCURACY,
+                    &Vec::<Sample>::arbitrary(g),
+                ),
+                statistic: StatisticKind::arbitrary(g),
+            },
             _ => unreachable!(),
         }
     }
@@ -302,6 +309,17 @@ impl Arbitrary for MetricValue {
                         }),
                 )
             }
+            MetricValue::Sketch { sketch, statistic } => {
+                let sketch = sketch.clone();
+                Box::new(
+                    statistic
+                        .shrink()
+                        .map(move |statistic| MetricValue::Sketch {
+                            sketch: sketch.clone(),
+                            statistic,
+                        }),
+                )
+            }
         }
     }
 }
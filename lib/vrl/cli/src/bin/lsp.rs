@@ -0,0 +1,8 @@
+extern crate vrl_cli;
+
+fn main() {
+    if let Err(err) = vrl_cli::lsp::run() {
+        eprintln!("{}", err);
+        std::process::exit(exitcode::SOFTWARE);
+    }
+}
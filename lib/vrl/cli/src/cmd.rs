@@ -8,7 +8,8 @@ use std::io::{self, Read};
 use std::iter::IntoIterator;
 use std::path::PathBuf;
 use structopt::StructOpt;
-use vrl::{diagnostic::Formatter, state, Program, Runtime, Target, Value};
+use vrl::diagnostic::{Diagnostic, DiagnosticError, Format, Formatter};
+use vrl::{state, Program, Runtime, Target, Terminate, Value};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "VRL", about = "Vector Remap Language CLI")]
@@ -34,6 +35,11 @@ pub struct Opts {
     /// The timezone used to parse dates.
     #[structopt(short = "tz", long)]
     timezone: Option<String>,
+
+    /// The format of compile-time diagnostics and runtime errors. `json` renders them as a JSON
+    /// array, for use by editors and other tooling.
+    #[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+    format: Format,
 }
 
 impl Opts {
@@ -103,7 +109,12 @@ fn run(opts: &Opts) -> Result<(), Error> {
         let objects = opts.read_into_objects()?;
         let source = opts.read_program()?;
         let program = vrl::compile(&source, &stdlib::all()).map_err(|diagnostics| {
-            Error::Parse(Formatter::new(&source, diagnostics).colored().to_string())
+            let formatter = Formatter::new(&source, diagnostics);
+
+            Error::Parse(match opts.format {
+                Format::Text => formatter.colored().to_string(),
+                Format::Json => formatter.to_json().to_string(),
+            })
         })?;
 
         for mut object in objects {
@@ -117,6 +128,12 @@ fn run(opts: &Opts) -> Result<(), Error> {
 
             match result {
                 Ok(ok) => println!("{}", ok),
+                Err(Error::Runtime(Terminate::Abort(err) | Terminate::Error(err)))
+                    if opts.format == Format::Json =>
+                {
+                    let diagnostic = Diagnostic::from(Box::new(err) as Box<dyn DiagnosticError>);
+                    eprintln!("{}", Formatter::new(&source, diagnostic).to_json());
+                }
                 Err(err) => eprintln!("{}", err),
             }
         }
@@ -1,4 +1,6 @@
 pub mod cmd;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 #[cfg(feature = "repl")]
 mod repl;
 
@@ -0,0 +1,510 @@
+//! A [Language Server Protocol][lsp] server for VRL programs.
+//!
+//! The server reports compile-time diagnostics of open documents, completes
+//! stdlib function names and their parameter keywords, and shows function
+//! documentation on hover. Documents are synced in full on every change.
+//!
+//! [lsp]: https://microsoft.github.io/language-server-protocol/
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{Completion, HoverRequest, Request as RequestTrait},
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionResponse,
+    DiagnosticRelatedInformation, DiagnosticSeverity, Documentation, Hover, HoverContents,
+    HoverProviderCapability, Location, MarkupContent, MarkupKind, NumberOrString, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Url,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use vrl::diagnostic::{DiagnosticList, Severity, Span, Urls};
+use vrl::{value::Kind, Function};
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Runs the language server over stdin and stdout, until the client asks it
+/// to shut down.
+pub fn run() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::Full)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["(".to_owned(), ",".to_owned()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+    Server::new(connection).run()?;
+    io_threads.join()?;
+
+    Ok(())
+}
+
+struct Server {
+    connection: Connection,
+    functions: Vec<Box<dyn Function>>,
+    documents: HashMap<Url, String>,
+}
+
+impl Server {
+    fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            functions: stdlib::all(),
+            documents: HashMap::new(),
+        }
+    }
+
+    fn run(mut self) -> Result<()> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Result<()> {
+        let response = match request.method.as_str() {
+            Completion::METHOD => {
+                let (id, params) = extract_request::<Completion>(request)?;
+                let position = params.text_document_position;
+
+                Response::new_ok(
+                    id,
+                    self.documents
+                        .get(&position.text_document.uri)
+                        .map(|source| {
+                            CompletionResponse::Array(self.completions(source, position.position))
+                        }),
+                )
+            }
+            HoverRequest::METHOD => {
+                let (id, params) = extract_request::<HoverRequest>(request)?;
+                let position = params.text_document_position_params;
+
+                Response::new_ok(
+                    id,
+                    self.documents
+                        .get(&position.text_document.uri)
+                        .and_then(|source| self.hover(source, position.position)),
+                )
+            }
+            method => Response::new_err(
+                request.id,
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                format!("unsupported method `{}`", method),
+            ),
+        };
+
+        self.connection.sender.send(response.into())?;
+
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidOpenTextDocument as NotificationTrait>::Params>(
+                        DidOpenTextDocument::METHOD,
+                    )
+                    .map_err(|_| "invalid didOpen notification")?;
+                let document = params.text_document;
+
+                self.update(document.uri, document.text, Some(document.version))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidChangeTextDocument as NotificationTrait>::Params>(
+                        DidChangeTextDocument::METHOD,
+                    )
+                    .map_err(|_| "invalid didChange notification")?;
+
+                // With full document sync, the last change holds the entire
+                // document.
+                match params.content_changes.into_iter().last() {
+                    Some(change) => self.update(
+                        params.text_document.uri,
+                        change.text,
+                        Some(params.text_document.version),
+                    ),
+                    None => Ok(()),
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidCloseTextDocument as NotificationTrait>::Params>(
+                        DidCloseTextDocument::METHOD,
+                    )
+                    .map_err(|_| "invalid didClose notification")?;
+                let uri = params.text_document.uri;
+
+                self.documents.remove(&uri);
+                self.publish_diagnostics(uri, vec![], None)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Stores the new contents of a document, and publishes its diagnostics.
+    fn update(&mut self, uri: Url, source: String, version: Option<i32>) -> Result<()> {
+        let diagnostics = self.diagnostics(&uri, &source);
+        self.documents.insert(uri.clone(), source);
+
+        self.publish_diagnostics(uri, diagnostics, version)
+    }
+
+    fn publish_diagnostics(
+        &self,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        version: Option<i32>,
+    ) -> Result<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, version);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+        self.connection.sender.send(notification.into())?;
+
+        Ok(())
+    }
+
+    /// Compiles the document and converts the resulting diagnostics. The
+    /// primary label determines the range of a diagnostic, other labels are
+    /// reported as related information.
+    fn diagnostics(&self, uri: &Url, source: &str) -> Vec<lsp_types::Diagnostic> {
+        let diagnostics = match vrl::compile(source, &self.functions) {
+            Ok(_) => return vec![],
+            Err(errors) => DiagnosticList::from(errors),
+        };
+
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                let labels = diagnostic.labels();
+                let primary = labels
+                    .iter()
+                    .find(|label| label.primary)
+                    .or_else(|| labels.first());
+
+                let related = labels
+                    .iter()
+                    .filter(|label| Some(*label) != primary)
+                    .map(|label| DiagnosticRelatedInformation {
+                        location: Location::new(uri.clone(), range(source, label.span)),
+                        message: label.message.clone(),
+                    })
+                    .collect::<Vec<_>>();
+
+                let message = match primary {
+                    Some(label) if !label.message.is_empty() => {
+                        format!("{}\n{}", diagnostic.message(), label.message)
+                    }
+                    _ => diagnostic.message().to_owned(),
+                };
+
+                lsp_types::Diagnostic {
+                    range: primary.map_or_else(Range::default, |label| range(source, label.span)),
+                    severity: Some(severity(diagnostic.severity())),
+                    code: Some(NumberOrString::String(format!("E{:03}", diagnostic.code()))),
+                    source: Some("vrl".to_owned()),
+                    message,
+                    related_information: if related.is_empty() {
+                        None
+                    } else {
+                        Some(related)
+                    },
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Completes function names anywhere, and the parameter keywords of the
+    /// function if the position is within its argument list.
+    fn completions(&self, source: &str, position: Position) -> Vec<CompletionItem> {
+        let offset = offset(source, position);
+
+        let keywords = enclosing_call(&source[..offset])
+            .and_then(|ident| self.function(ident))
+            .map(|function| {
+                function
+                    .parameters()
+                    .iter()
+                    .map(|parameter| CompletionItem {
+                        label: format!("{}:", parameter.keyword),
+                        kind: Some(CompletionItemKind::Field),
+                        detail: Some(Kind::new(parameter.kind).to_string()),
+                        insert_text: Some(format!("{}: ", parameter.keyword)),
+                        ..Default::default()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let functions = self.functions.iter().map(|function| CompletionItem {
+            label: function.identifier().to_owned(),
+            kind: Some(CompletionItemKind::Function),
+            detail: Some(signature(function.as_ref())),
+            documentation: Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: docs(function.as_ref()),
+            })),
+            ..Default::default()
+        });
+
+        keywords.into_iter().chain(functions).collect()
+    }
+
+    /// Shows the documentation of the function under the cursor, if any.
+    fn hover(&self, source: &str, position: Position) -> Option<Hover> {
+        let offset = offset(source, position);
+
+        let start = ident_start(&source[..offset]);
+        let end = source[offset..]
+            .find(|c| !is_ident(c))
+            .map_or(source.len(), |idx| offset + idx);
+
+        let function = self.function(&source[start..end])?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```vrl\n{}\n```\n\n{}", signature(function), docs(function)),
+            }),
+            range: Some(range(source, Span::new(start, end))),
+        })
+    }
+
+    fn function(&self, ident: &str) -> Option<&dyn Function> {
+        self.functions
+            .iter()
+            .find(|function| function.identifier() == ident)
+            .map(AsRef::as_ref)
+    }
+}
+
+fn extract_request<R: RequestTrait>(request: Request) -> Result<(RequestId, R::Params)> {
+    request
+        .extract(R::METHOD)
+        .map_err(|request| format!("invalid `{}` request", request.method).into())
+}
+
+fn severity(severity: Severity) -> DiagnosticSeverity {
+    match severity {
+        Severity::Bug | Severity::Error => DiagnosticSeverity::Error,
+        Severity::Warning => DiagnosticSeverity::Warning,
+        Severity::Note => DiagnosticSeverity::Information,
+    }
+}
+
+/// Renders the signature of a function, e.g. `parse_csv(value: "string", [delimiter: "string"])`.
+fn signature(function: &dyn Function) -> String {
+    let parameters = function
+        .parameters()
+        .iter()
+        .map(|parameter| {
+            let parameter_signature =
+                format!("{}: {}", parameter.keyword, Kind::new(parameter.kind));
+
+            if parameter.required {
+                parameter_signature
+            } else {
+                format!("[{}]", parameter_signature)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("{}({})", function.identifier(), parameters)
+}
+
+/// Renders the Markdown documentation of a function from its summary and
+/// examples.
+fn docs(function: &dyn Function) -> String {
+    let mut docs = String::new();
+
+    // Most functions don't provide a summary yet.
+    if function.summary() != "TODO" {
+        docs.push_str(function.summary());
+        docs.push_str("\n\n");
+    }
+
+    for example in function.examples() {
+        let result = match example.result {
+            Ok(result) => result.to_owned(),
+            Err(error) => format!("error: {}", error),
+        };
+
+        docs.push_str(&format!(
+            "**{}**\n\n```vrl\n{}\n# => {}\n```\n\n",
+            example.title,
+            example.source.trim(),
+            result
+        ));
+    }
+
+    docs.push_str(&format!(
+        "[Documentation]({})",
+        Urls::func_docs(function.identifier())
+    ));
+
+    docs
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Returns the byte offset of the identifier at the end of `source`.
+fn ident_start(source: &str) -> usize {
+    source
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !is_ident(*c))
+        .map_or(0, |(idx, c)| idx + c.len_utf8())
+}
+
+/// Returns the identifier of the innermost function call whose argument list
+/// is still open at the end of `source`.
+fn enclosing_call(source: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut in_string = false;
+
+    for (idx, c) in source.char_indices().rev() {
+        if in_string {
+            // Scanning backwards, a quote ends the string literal unless it is
+            // preceded by an odd number of backslashes.
+            let backslashes = source[..idx]
+                .chars()
+                .rev()
+                .take_while(|c| *c == '\\')
+                .count();
+            if c == '"' && backslashes % 2 == 0 {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            ')' | ']' | '}' => depth += 1,
+            '[' | '{' if depth > 0 => depth -= 1,
+            '(' if depth > 0 => depth -= 1,
+            '(' => {
+                let ident = source[..idx].trim_end_matches('!');
+                let start = ident_start(ident);
+
+                return Some(&ident[start..]).filter(|ident| !ident.is_empty());
+            }
+            '[' | '{' => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn range(source: &str, span: Span) -> Range {
+    Range::new(position(source, span.start()), position(source, span.end()))
+}
+
+/// Converts a byte offset into an LSP position, with the character counted in
+/// UTF-16 code units.
+fn position(source: &str, offset: usize) -> Position {
+    let offset = floor_char_boundary(source, offset);
+    let line_start = source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    let line = source[..line_start].matches('\n').count();
+    let character = source[line_start..offset].encode_utf16().count();
+
+    Position::new(line as u32, character as u32)
+}
+
+/// Converts an LSP position into a byte offset, clamped to the source.
+fn offset(source: &str, position: Position) -> usize {
+    let line_start = if position.line == 0 {
+        0
+    } else {
+        match source.match_indices('\n').nth(position.line as usize - 1) {
+            Some((idx, _)) => idx + 1,
+            None => return source.len(),
+        }
+    };
+
+    let mut units = 0;
+    for (idx, c) in source[line_start..].char_indices() {
+        if units >= position.character as usize || c == '\n' {
+            return line_start + idx;
+        }
+        units += c.len_utf16();
+    }
+
+    source.len()
+}
+
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let source = "foo\nbär = 1\n";
+
+        assert_eq!(position(source, 0), Position::new(0, 0));
+        assert_eq!(position(source, 4), Position::new(1, 0));
+        assert_eq!(position(source, 7), Position::new(1, 2));
+        assert_eq!(position(source, source.len()), Position::new(2, 0));
+
+        assert_eq!(offset(source, Position::new(1, 2)), 7);
+        assert_eq!(offset(source, Position::new(0, 10)), 3);
+        assert_eq!(offset(source, Position::new(5, 0)), source.len());
+    }
+
+    #[test]
+    fn enclosing_calls() {
+        assert_eq!(enclosing_call("parse_csv("), Some("parse_csv"));
+        assert_eq!(enclosing_call("parse_json!(.message, "), Some("parse_json"));
+        assert_eq!(enclosing_call("upcase(downcase(.foo), "), Some("upcase"));
+        assert_eq!(enclosing_call(r#"contains("a(\")", "#), Some("contains"));
+        assert_eq!(enclosing_call("parse_csv(.foo)"), None);
+        assert_eq!(enclosing_call(".foo = [1, "), None);
+        assert_eq!(enclosing_call("(1 + "), None);
+        assert_eq!(enclosing_call("\"é\" + é("), None);
+        assert_eq!(enclosing_call("éupcase("), Some("upcase"));
+    }
+
+    #[test]
+    fn hovers_after_non_ascii_text() {
+        let (connection, _client) = Connection::memory();
+        let server = Server::new(connection);
+        let source = "éupcase(.foo)";
+
+        let hover = server.hover(source, Position::new(0, 3)).unwrap();
+        assert_eq!(hover.range, Some(range(source, Span::new(2, 8))));
+        assert!(server.hover("éé", Position::new(0, 1)).is_none());
+    }
+}
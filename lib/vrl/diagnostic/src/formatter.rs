@@ -1,5 +1,31 @@
-use crate::DiagnosticList;
-use std::fmt;
+use crate::{Diagnostic, DiagnosticList, Label};
+use codespan_reporting::files::{Files, SimpleFile};
+use serde_json::{json, Value};
+use std::{fmt, str::FromStr};
+
+/// The format diagnostics are rendered in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Human-readable text, as displayed by [`Formatter`].
+    Text,
+    /// A JSON array, as rendered by [`Formatter::to_json`].
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            s => Err(format!(
+                "{} is not a valid option, expected `text` or `json`",
+                s
+            )),
+        }
+    }
+}
 
 /// A formatter to display diagnostics tied to a given source.
 pub struct Formatter<'a> {
@@ -25,11 +51,61 @@ impl<'a> Formatter<'a> {
     pub fn enable_colors(&mut self, color: bool) {
         self.color = color
     }
+
+    /// Renders the diagnostics as a JSON array, for consumption by editors and
+    /// other tooling.
+    ///
+    /// Label positions are reported both as byte offsets into the source, and
+    /// as one-based line and column numbers.
+    pub fn to_json(&self) -> Value {
+        let file = SimpleFile::new("", self.source);
+
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic_to_json(&file, diagnostic))
+            .collect()
+    }
+}
+
+fn diagnostic_to_json(file: &SimpleFile<&str, &str>, diagnostic: &Diagnostic) -> Value {
+    json!({
+        "severity": diagnostic.severity().as_str(),
+        "code": format!("E{:03}", diagnostic.code()),
+        "message": diagnostic.message(),
+        "labels": diagnostic
+            .labels()
+            .iter()
+            .map(|label| label_to_json(file, label))
+            .collect::<Vec<_>>(),
+        "notes": diagnostic
+            .notes()
+            .iter()
+            .map(ToString::to_string)
+            .filter(|note| !note.trim().is_empty())
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn label_to_json(file: &SimpleFile<&str, &str>, label: &Label) -> Value {
+    let location = |offset: usize| {
+        let offset = offset.min(file.source().len());
+
+        file.location((), offset)
+            .map(|location| json!({ "line": location.line_number, "column": location.column_number }))
+            .unwrap_or(Value::Null)
+    };
+
+    json!({
+        "message": label.message,
+        "primary": label.primary,
+        "span": { "start": label.span.start(), "end": label.span.end() },
+        "start": location(label.span.start()),
+        "end": location(label.span.end()),
+    })
 }
 
 impl<'a> fmt::Display for Formatter<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use codespan_reporting::files::SimpleFile;
         use codespan_reporting::term;
         use std::str::from_utf8;
         use termcolor::Buffer;
@@ -63,3 +139,63 @@ impl<'a> fmt::Display for Formatter<'a> {
         f.write_str(&string)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{span, Note};
+
+    #[test]
+    fn parses_format() {
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn renders_json() {
+        let source = ".foo = 1\n.bar = baz\n";
+        let diagnostic = Diagnostic::error(701, "call to undefined variable")
+            .with_primary("undefined variable", span(16, 19))
+            .with_context("did you mean \"bar\"?", span(10, 14))
+            .with_note(Note::SeeErrorDocs);
+
+        assert_eq!(
+            Formatter::new(source, vec![diagnostic]).to_json(),
+            json!([{
+                "severity": "error",
+                "code": "E701",
+                "message": "call to undefined variable",
+                "labels": [
+                    {
+                        "message": "undefined variable",
+                        "primary": true,
+                        "span": { "start": 16, "end": 19 },
+                        "start": { "line": 2, "column": 8 },
+                        "end": { "line": 2, "column": 11 },
+                    },
+                    {
+                        "message": "did you mean \"bar\"?",
+                        "primary": false,
+                        "span": { "start": 10, "end": 14 },
+                        "start": { "line": 2, "column": 2 },
+                        "end": { "line": 2, "column": 6 },
+                    },
+                ],
+                "notes": ["see documentation about error handling at https://errors.vrl.dev/#handling"],
+            }])
+        );
+    }
+
+    #[test]
+    fn renders_json_offsets_past_the_end() {
+        let diagnostic = Diagnostic::error(100, "unexpected end").with_primary("here", span(3, 10));
+        let json = Formatter::new("abc", vec![diagnostic]).to_json();
+
+        assert_eq!(
+            json[0]["labels"][0]["end"],
+            json!({ "line": 1, "column": 4 })
+        );
+        assert_eq!(json[0]["notes"], json!([]));
+    }
+}
@@ -6,7 +6,7 @@ mod severity;
 mod span;
 
 pub use diagnostic::{Diagnostic, DiagnosticList};
-pub use formatter::Formatter;
+pub use formatter::{Format, Formatter};
 pub use label::Label;
 pub use note::Note;
 pub use severity::Severity;
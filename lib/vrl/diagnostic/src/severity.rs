@@ -32,6 +32,17 @@ impl Severity {
     pub fn is_note(self) -> bool {
         matches!(self, Severity::Note)
     }
+
+    /// Returns the lowercase name of the severity, as used in
+    /// machine-readable output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Bug => "bug",
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
 }
 
 impl From<Severity> for diagnostic::Severity {
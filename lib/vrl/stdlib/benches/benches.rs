@@ -13,17 +13,27 @@ criterion_group!(
               assert_eq,
               ceil,
               compact,
+              community_id,
               contains,
+              crc32,
+              decode_base16,
               decode_base64,
+              decode_gzip,
               decode_percent,
+              decode_zlib,
               // TODO: Cannot pass a Path to bench_function
               //del,
               downcase,
+              encode_base16,
               encode_base64,
+              encode_csv,
+              encode_gzip,
               encode_key_value,
               encode_json,
               encode_logfmt,
               encode_percent,
+              encode_xml,
+              encode_zlib,
               ends_with,
               // TODO: Cannot pass a Path to bench_function
               //exists
@@ -34,20 +44,27 @@ criterion_group!(
               format_timestamp,
               get_env_var,
               get_hostname,
+              hmac,
               includes,
               ip_aton,
               ip_cidr_contains,
               ip_ntoa,
+              ip_ntop,
+              ip_pton,
               ip_subnet,
               ip_to_ipv6,
+              ip_version,
               ipv6_to_ipv4,
               is_array,
               is_boolean,
               is_float,
               is_integer,
+              is_ipv4,
+              is_ipv6,
               is_null,
               is_nullish,
               is_object,
+              is_private_ip,
               is_regex,
               is_string,
               is_timestamp,
@@ -66,6 +83,7 @@ criterion_group!(
               parse_aws_alb_log,
               parse_aws_cloudwatch_log_subscription_message,
               parse_aws_vpc_flow_log,
+              parse_cef,
               parse_common_log,
               parse_csv,
               parse_duration,
@@ -73,6 +91,7 @@ criterion_group!(
               parse_grok,
               parse_key_value,
               parse_klog,
+              parse_leef,
               parse_int,
               parse_json,
               parse_nginx_log,
@@ -113,7 +132,8 @@ criterion_group!(
               //unnest
               // TODO: value is dynamic so we cannot assert equality
               //uuidv4,
-              upcase
+              upcase,
+              xxhash
 );
 criterion_main!(benches);
 
@@ -171,6 +191,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    community_id => vrl_stdlib::CommunityId;
+
+    tcp {
+        args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122, destination_port: 3344],
+        want: Ok("1:wCb3OG7yAFWelaUydu0D+125CLM="),
+    }
+}
+
 bench_function! {
     contains => vrl_stdlib::Contains;
 
@@ -185,6 +214,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    crc32 => vrl_stdlib::Crc32;
+
+    literal {
+        args: func_args![value: "foobar"],
+        want: Ok(2666930069_i64),
+    }
+}
+
+bench_function! {
+    decode_base16 => vrl_stdlib::DecodeBase16;
+
+    literal {
+        args: func_args![value: "736f6d652b3d737472696e672f76616c7565"],
+        want: Ok("some+=string/value"),
+    }
+}
+
 bench_function! {
     decode_base64 => vrl_stdlib::DecodeBase64;
 
@@ -194,6 +241,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decode_gzip => vrl_stdlib::DecodeGzip;
+
+    literal {
+        args: func_args![value: &b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x00\xff+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x00\x8e\x11\xe2\xf7\x10\x00\x00\x00"[..]],
+        want: Ok("please encode me"),
+    }
+}
+
 bench_function! {
     decode_percent => vrl_stdlib::DecodePercent;
 
@@ -203,6 +259,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decode_zlib => vrl_stdlib::DecodeZlib;
+
+    literal {
+        args: func_args![value: &b"x\x9c+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x004E\x05\xfb"[..]],
+        want: Ok("please encode me"),
+    }
+}
+
 bench_function! {
     downcase => vrl_stdlib::Downcase;
 
@@ -212,6 +277,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_base16 => vrl_stdlib::EncodeBase16;
+
+    literal {
+        args: func_args![value: "some+=string/value"],
+        want: Ok("736f6d652b3d737472696e672f76616c7565"),
+    }
+}
+
 bench_function! {
     encode_base64 => vrl_stdlib::EncodeBase64;
 
@@ -221,6 +295,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_csv => vrl_stdlib::EncodeCsv;
+
+    literal {
+        args: func_args![value: value!(["foo", "bar", "foo \", bar"])],
+        want: Ok("foo,bar,\"foo \"\", bar\""),
+    }
+}
+
+bench_function! {
+    encode_gzip => vrl_stdlib::EncodeGzip;
+
+    literal {
+        args: func_args![value: "please encode me"],
+        want: Ok(&b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x00\xff+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x00\x8e\x11\xe2\xf7\x10\x00\x00\x00"[..]),
+    }
+}
+
 bench_function! {
     encode_key_value => vrl_stdlib::EncodeKeyValue;
 
@@ -322,6 +414,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_xml => vrl_stdlib::EncodeXml;
+
+    simple_text {
+        args: func_args![value: value!({ "a": { "@href": "https://vector.dev", "text": "test" } })],
+        want: Ok(r#"<a href="https://vector.dev">test</a>"#),
+    }
+}
+
+bench_function! {
+    encode_zlib => vrl_stdlib::EncodeZlib;
+
+    literal {
+        args: func_args![value: "please encode me"],
+        want: Ok(&b"x\x9c+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x004E\x05\xfb"[..]),
+    }
+}
+
 bench_function! {
     ends_with => vrl_stdlib::EndsWith;
 
@@ -431,6 +541,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    hmac => vrl_stdlib::Hmac;
+
+    literal {
+        args: func_args![value: "foobar", key: "secret"],
+        want: Ok("4fcc06915b43d8a49aff193441e9e18654e6a27c2c428b02e8fcc41ccc2299f9"),
+    }
+}
+
 bench_function! {
     includes => vrl_stdlib::Includes;
 
@@ -461,6 +580,11 @@ bench_function! {
         args: func_args![cidr: "2001:4f8:3:ba::/64", value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"],
         want: Ok(true),
     }
+
+    array {
+        args: func_args![cidr: value!(["10.0.0.0/8", "172.16.0.0/12", "192.168.0.0/16"]), value: "192.168.10.32"],
+        want: Ok(true),
+    }
 }
 
 bench_function! {
@@ -472,6 +596,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_ntop => vrl_stdlib::IpNtop;
+
+    ipv4 {
+        args: func_args![value: &[192u8, 168, 0, 1][..]],
+        want: Ok("192.168.0.1"),
+    }
+}
+
+bench_function! {
+    ip_pton => vrl_stdlib::IpPton;
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok(&[192u8, 168, 0, 1][..]),
+    }
+}
+
 bench_function! {
     ip_subnet => vrl_stdlib::IpSubnet;
 
@@ -510,6 +652,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_version => vrl_stdlib::IpVersion;
+
+    ipv6 {
+        args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"],
+        want: Ok("IPv6"),
+    }
+}
+
 bench_function! {
     ipv6_to_ipv4 => vrl_stdlib::Ipv6ToIpV4;
 
@@ -580,6 +731,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    is_ipv4 => vrl_stdlib::IsIpv4;
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok(true),
+    }
+}
+
+bench_function! {
+    is_ipv6 => vrl_stdlib::IsIpv6;
+
+    ipv6 {
+        args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"],
+        want: Ok(true),
+    }
+}
+
 bench_function! {
     is_null => vrl_stdlib::IsNull;
 
@@ -632,6 +801,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    is_private_ip => vrl_stdlib::IsPrivateIp;
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok(true),
+    }
+}
+
 bench_function! {
     is_regex => vrl_stdlib::IsRegex;
 
@@ -1038,6 +1216,27 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_cef => vrl_stdlib::ParseCef;
+
+    literal {
+        args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232 msg=a\\=b is now c"],
+        want: Ok(value!({
+            "cefVersion": "0",
+            "deviceVendor": "Security",
+            "deviceProduct": "threatmanager",
+            "deviceVersion": "1.0",
+            "deviceEventClassId": "100",
+            "name": "worm successfully stopped",
+            "severity": "10",
+            "src": "10.0.0.1",
+            "dst": "2.1.2.2",
+            "spt": "1232",
+            "msg": "a=b is now c",
+        })),
+    }
+}
+
 bench_function! {
     parse_apache_log => vrl_stdlib::ParseApacheLog;
 
@@ -1242,6 +1441,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_leef => vrl_stdlib::ParseLeef;
+
+    literal {
+        args: func_args![value: "LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5"],
+        want: Ok(value!({
+            "leefVersion": "2.0",
+            "vendor": "Lancope",
+            "productName": "StealthWatch",
+            "productVersion": "1.0",
+            "eventId": "41",
+            "src": "10.0.1.8",
+            "dst": "10.0.0.5",
+            "sev": "5",
+        })),
+    }
+}
+
 bench_function! {
     parse_nginx_log => vrl_stdlib::ParseNginxLog;
 
@@ -2074,3 +2291,12 @@ bench_function! {
         want: Ok("FOO")
     }
 }
+
+bench_function! {
+    xxhash => vrl_stdlib::Xxhash;
+
+    literal {
+        args: func_args![value: "foobar"],
+        want: Ok(-6725556575634347271_i64),
+    }
+}
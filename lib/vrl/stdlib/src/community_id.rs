@@ -0,0 +1,335 @@
+use ::sha1::{Digest, Sha1};
+use std::net::IpAddr;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CommunityId;
+
+impl Function for CommunityId {
+    fn identifier(&self) -> &'static str {
+        "community_id"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "source_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "destination_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "protocol",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "source_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "destination_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "seed",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "TCP",
+                source: r#"community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", source_port: 1122, destination_port: 3344, protocol: 6)"#,
+                result: Ok("1:wCb3OG7yAFWelaUydu0D+125CLM="),
+            },
+            Example {
+                title: "ICMP echo request",
+                source: r#"community_id!(source_ip: "192.168.0.89", destination_ip: "192.168.0.1", source_port: 8, destination_port: 0, protocol: 1)"#,
+                result: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let source_ip = arguments.required("source_ip");
+        let destination_ip = arguments.required("destination_ip");
+        let protocol = arguments.required("protocol");
+        let source_port = arguments.optional("source_port");
+        let destination_port = arguments.optional("destination_port");
+        let seed = arguments.optional("seed");
+
+        Ok(Box::new(CommunityIdFn {
+            source_ip,
+            destination_ip,
+            protocol,
+            source_port,
+            destination_port,
+            seed,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CommunityIdFn {
+    source_ip: Box<dyn Expression>,
+    destination_ip: Box<dyn Expression>,
+    protocol: Box<dyn Expression>,
+    source_port: Option<Box<dyn Expression>>,
+    destination_port: Option<Box<dyn Expression>>,
+    seed: Option<Box<dyn Expression>>,
+}
+
+impl Expression for CommunityIdFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let source_ip = resolve_ip(self.source_ip.as_ref(), ctx)?;
+        let destination_ip = resolve_ip(self.destination_ip.as_ref(), ctx)?;
+        let protocol = resolve_integer(self.protocol.as_ref(), ctx, "protocol", u8::MAX as u16)?;
+
+        let ports =
+            match (&self.source_port, &self.destination_port) {
+                (Some(source), Some(destination)) => Some((
+                    resolve_integer(source.as_ref(), ctx, "source_port", u16::MAX)?,
+                    resolve_integer(destination.as_ref(), ctx, "destination_port", u16::MAX)?,
+                )),
+                (None, None) => None,
+                _ => return Err(
+                    "source_port and destination_port must either both be set or both be omitted"
+                        .into(),
+                ),
+            };
+
+        let seed = match &self.seed {
+            Some(expr) => resolve_integer(expr.as_ref(), ctx, "seed", u16::MAX)?,
+            None => 0,
+        };
+
+        let flow = Flow {
+            source: octets(source_ip),
+            destination: octets(destination_ip),
+            protocol: protocol as u8,
+            ports,
+        };
+
+        if flow.source.len() != flow.destination.len() {
+            return Err("source_ip and destination_ip must be of the same IP version".into());
+        }
+
+        Ok(flow.community_id(seed).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+fn resolve_ip(expr: &dyn Expression, ctx: &mut Context) -> Result<IpAddr> {
+    expr.resolve(ctx)?
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse IP address: {}", err).into())
+}
+
+/// Resolves an integer argument that must fit into its unsigned field of the
+/// hashed tuple.
+fn resolve_integer(expr: &dyn Expression, ctx: &mut Context, name: &str, max: u16) -> Result<u16> {
+    let value = expr.resolve(ctx)?.try_integer()?;
+
+    if value < 0 || value > max as i64 {
+        return Err(format!("{} must be between 0 and {}, got {}", name, max, value).into());
+    }
+
+    Ok(value as u16)
+}
+
+fn octets(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+const ICMP: u8 = 1;
+const TCP: u8 = 6;
+const UDP: u8 = 17;
+const ICMP6: u8 = 58;
+const SCTP: u8 = 132;
+
+/// A network flow tuple, hashed as described by the Community ID
+/// specification: https://github.com/corelight/community-id-spec
+struct Flow {
+    source: Vec<u8>,
+    destination: Vec<u8>,
+    protocol: u8,
+    ports: Option<(u16, u16)>,
+}
+
+impl Flow {
+    fn community_id(self, seed: u16) -> String {
+        // Only port based protocols contribute ports to the hash, for ICMP
+        // these hold the message type and code.
+        let ports = match self.protocol {
+            TCP | UDP | SCTP | ICMP | ICMP6 => self.ports,
+            _ => None,
+        };
+
+        // ICMP messages are mapped onto their counterpart, so that requests
+        // and replies end up with the same ID. Messages without counterpart
+        // are one-way, and keep the order of their endpoints.
+        let (ports, one_way) = match ports {
+            Some((icmp_type, code)) if self.protocol == ICMP || self.protocol == ICMP6 => {
+                match icmp_counterpart(self.protocol, icmp_type) {
+                    Some(counterpart) => (Some((icmp_type, counterpart)), false),
+                    None => (Some((icmp_type, code)), true),
+                }
+            }
+            ports => (ports, false),
+        };
+
+        let ordered = one_way
+            || self.source < self.destination
+            || (self.source == self.destination
+                && ports.map_or(true, |(source, destination)| source <= destination));
+
+        let (source, destination, ports) = if ordered {
+            (self.source, self.destination, ports)
+        } else {
+            (
+                self.destination,
+                self.source,
+                ports.map(|(source, destination)| (destination, source)),
+            )
+        };
+
+        let mut hasher = Sha1::new();
+        hasher.update(seed.to_be_bytes());
+        hasher.update(&source);
+        hasher.update(&destination);
+        hasher.update([self.protocol, 0]);
+        if let Some((source, destination)) = ports {
+            hasher.update(source.to_be_bytes());
+            hasher.update(destination.to_be_bytes());
+        }
+
+        format!("1:{}", base64::encode(hasher.finalize()))
+    }
+}
+
+/// Returns the message type answering the given ICMP or ICMPv6 message type,
+/// if any.
+fn icmp_counterpart(protocol: u8, icmp_type: u16) -> Option<u16> {
+    let counterpart = match (protocol, icmp_type) {
+        // Echo, router, timestamp, information and address mask messages.
+        (ICMP, 8) => 0,
+        (ICMP, 0) => 8,
+        (ICMP, 10) => 9,
+        (ICMP, 9) => 10,
+        (ICMP, 13) => 14,
+        (ICMP, 14) => 13,
+        (ICMP, 15) => 16,
+        (ICMP, 16) => 15,
+        (ICMP, 17) => 18,
+        (ICMP, 18) => 17,
+        // Echo, multicast listener, router, neighbor, node information and
+        // home agent address discovery messages.
+        (ICMP6, 128) => 129,
+        (ICMP6, 129) => 128,
+        (ICMP6, 130) => 131,
+        (ICMP6, 131) => 130,
+        (ICMP6, 133) => 134,
+        (ICMP6, 134) => 133,
+        (ICMP6, 135) => 136,
+        (ICMP6, 136) => 135,
+        (ICMP6, 139) => 140,
+        (ICMP6, 140) => 139,
+        (ICMP6, 144) => 145,
+        (ICMP6, 145) => 144,
+        _ => return None,
+    };
+
+    Some(counterpart)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        community_id => CommunityId;
+
+        tcp {
+            args: func_args![source_ip: "128.232.110.120", destination_ip: "66.35.250.204", protocol: 6, source_port: 34855, destination_port: 80],
+            want: Ok(value!("1:LQU9qZlK+B5F3KDmev6m5PMibrg=")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        tcp_reversed {
+            args: func_args![source_ip: "66.35.250.204", destination_ip: "128.232.110.120", protocol: 6, source_port: 80, destination_port: 34855],
+            want: Ok(value!("1:LQU9qZlK+B5F3KDmev6m5PMibrg=")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        tcp_seed {
+            args: func_args![source_ip: "128.232.110.120", destination_ip: "66.35.250.204", protocol: 6, source_port: 34855, destination_port: 80, seed: 1],
+            want: Ok(value!("1:3V71V58M3Ksw/yuFALMcW0LAHvc=")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        udp {
+            args: func_args![source_ip: "192.168.1.52", destination_ip: "8.8.8.8", protocol: 17, source_port: 54585, destination_port: 53],
+            want: Ok(value!("1:d/FP5EW3wiY1vCndhwleRRKHowQ=")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_reply {
+            args: func_args![source_ip: "192.168.0.1", destination_ip: "192.168.0.89", protocol: 1, source_port: 0, destination_port: 0],
+            want: Ok(value!("1:X0snYXpgwiv9TZtqg64sgzUn6Dk=")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp6 {
+            args: func_args![source_ip: "fe80::200:86ff:fe05:80da", destination_ip: "fe80::260:97ff:fe07:69ea", protocol: 58, source_port: 135, destination_port: 0],
+            want: Ok(value!("1:dGHyGvjMfljg6Bppwm3bg0LO8TY=")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        without_ports {
+            args: func_args![source_ip: "10.0.0.1", destination_ip: "10.0.0.2", protocol: 47],
+            want: Ok(value!("1:+KlEHDT0vJgzs/eNmzHq0aSpRYw=")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        single_port {
+            args: func_args![source_ip: "10.0.0.1", destination_ip: "10.0.0.2", protocol: 6, source_port: 80],
+            want: Err("source_port and destination_port must either both be set or both be omitted"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        mixed_versions {
+            args: func_args![source_ip: "10.0.0.1", destination_ip: "2001:db8::1", protocol: 6],
+            want: Err("source_ip and destination_ip must be of the same IP version"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_protocol {
+            args: func_args![source_ip: "10.0.0.1", destination_ip: "10.0.0.2", protocol: 256],
+            want: Err("protocol must be between 0 and 255, got 256"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_port {
+            args: func_args![source_ip: "10.0.0.1", destination_ip: "10.0.0.2", protocol: 6, source_port: -1, destination_port: 80],
+            want: Err("source_port must be between 0 and 65535, got -1"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,93 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeBase16;
+
+impl Function for DecodeBase16 {
+    fn identifier(&self) -> &'static str {
+        "decode_base16"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(DecodeBase16Fn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_base16!("736f6d6520737472696e672076616c7565")"#,
+            result: Ok("some string value"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeBase16Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for DecodeBase16Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        match hex::decode(value) {
+            Ok(s) => Ok(Value::from(s)),
+            Err(_) => Err("unable to decode value from base16".into()),
+        }
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Always fallible due to the possibility of decoding errors that VRL can't detect in
+        // advance: https://docs.rs/hex/0.4.3/hex/enum.FromHexError.html
+        TypeDef::new().bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_function![
+        decode_base16 => DecodeBase16;
+
+        with_defaults {
+            args: func_args![value: value!("736f6d6520737472696e672076616c7565")],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        uppercase {
+            args: func_args![value: value!("000FFF")],
+            want: Ok(value!(vec![0_u8, 15, 255])),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        empty_string {
+            args: func_args![value: value!("")],
+            want: Ok(value!("")),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        odd_length {
+            args: func_args![value: value!("abc")],
+            want: Err("unable to decode value from base16"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        invalid_character {
+            args: func_args![value: value!("zz")],
+            want: Err("unable to decode value from base16"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
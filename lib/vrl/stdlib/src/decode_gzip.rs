@@ -0,0 +1,90 @@
+use flate2::read::MultiGzDecoder;
+use std::io::Read;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeGzip;
+
+impl Function for DecodeGzip {
+    fn identifier(&self) -> &'static str {
+        "decode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(DecodeGzipFn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_gzip!(decode_base64!("H4sIAAAAAAACAyvISU0sTlVIzUvOT0lVyE0FAI4R4vcQAAAA"))"#,
+            result: Ok("please encode me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeGzipFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for DecodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        // Payloads can consist of multiple concatenated gzip members, such as
+        // the ones delivered by CloudWatch Logs subscriptions.
+        let mut buf = Vec::new();
+        match MultiGzDecoder::new(&value[..]).read_to_end(&mut buf) {
+            Ok(_) => Ok(buf.into()),
+            Err(_) => Err("unable to decode value with gzip decoder".into()),
+        }
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Always fallible due to the possibility of decoding errors that VRL can't detect in
+        // advance.
+        TypeDef::new().bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_base64(text: &str) -> Vec<u8> {
+        base64::decode(text).expect("valid base64")
+    }
+
+    test_function![
+        decode_gzip => DecodeGzip;
+
+        right_gzip {
+            args: func_args![value: value!(decode_base64("H4sIAAAAAAACAw3LyQ3AIAwEwFZcAT05ZjkUgiUfkeg+mf8cTRr8gjxF4N5yrUMVohWVHhTR3Y0jF8fU7eX8gQ10GThG8D13Lx+GGe6BRgAAAA=="))],
+            want: Ok(value!("you have successfully decoded me.congratulations.you are breathtaking.")),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        concatenated_members {
+            args: func_args![value: value!([decode_base64("H4sIAAAAAAACA8tIzcnJBwCGphA2BQAAAA=="), decode_base64("H4sIAAAAAAACA1Mozy/KSQEAy0I7SgYAAAA=")].concat())],
+            want: Ok(value!("hello world")),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        wrong_gzip {
+            args: func_args![value: value!("some string value")],
+            want: Err("unable to decode value with gzip decoder"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,82 @@
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeZlib;
+
+impl Function for DecodeZlib {
+    fn identifier(&self) -> &'static str {
+        "decode_zlib"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(DecodeZlibFn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_zlib!(decode_base64!("eJwryElNLE5VSM1Lzk9JVchNBQA0RQX7"))"#,
+            result: Ok("please encode me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeZlibFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for DecodeZlibFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let mut buf = Vec::new();
+        match ZlibDecoder::new(&value[..]).read_to_end(&mut buf) {
+            Ok(_) => Ok(buf.into()),
+            Err(_) => Err("unable to decode value with zlib decoder".into()),
+        }
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Always fallible due to the possibility of decoding errors that VRL can't detect in
+        // advance.
+        TypeDef::new().bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_base64(text: &str) -> Vec<u8> {
+        base64::decode(text).expect("valid base64")
+    }
+
+    test_function![
+        decode_zlib => DecodeZlib;
+
+        right_zlib {
+            args: func_args![value: value!(decode_base64("eJwNy8kNwCAMBMBWXAE9OWY5FIIlH5HoPpn/HE0a/II8ReDecq1DFaIVlR4U0d2NIxfH1O3l/IENdBk4RvA9dy8fv7wa8Q=="))],
+            want: Ok(value!("you have successfully decoded me.congratulations.you are breathtaking.")),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        wrong_zlib {
+            args: func_args![value: value!("some string value")],
+            want: Err("unable to decode value with zlib decoder"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,76 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeBase16;
+
+impl Function for EncodeBase16 {
+    fn identifier(&self) -> &'static str {
+        "encode_base16"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(EncodeBase16Fn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"encode_base16("some string value")"#,
+            result: Ok("736f6d6520737472696e672076616c7565"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeBase16Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for EncodeBase16Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        Ok(hex::encode(value).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().bytes().infallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_function![
+        encode_base16 => EncodeBase16;
+
+        with_defaults {
+            args: func_args![value: value!("some string value")],
+            want: Ok(value!("736f6d6520737472696e672076616c7565")),
+            tdef: TypeDef::new().bytes().infallible(),
+        }
+
+        binary {
+            args: func_args![value: value!(vec![0_u8, 15, 255])],
+            want: Ok(value!("000fff")),
+            tdef: TypeDef::new().bytes().infallible(),
+        }
+
+        empty_string {
+            args: func_args![value: value!("")],
+            want: Ok(value!("")),
+            tdef: TypeDef::new().bytes().infallible(),
+        }
+    ];
+}
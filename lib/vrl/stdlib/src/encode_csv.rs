@@ -0,0 +1,168 @@
+use chrono::SecondsFormat;
+use csv::WriterBuilder;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeCsv;
+
+impl Function for EncodeCsv {
+    fn identifier(&self) -> &'static str {
+        "encode_csv"
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "encode a single CSV formatted row",
+                source: r#"encode_csv!(["foo", "bar", "foo \", bar", 1, true])"#,
+                result: Ok(r#"s'foo,bar,"foo "", bar",1,true'"#),
+            },
+            Example {
+                title: "custom delimiter",
+                source: r#"encode_csv!(["foo", "bar"], delimiter: "\t")"#,
+                result: Ok("foo\tbar"),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let delimiter = arguments.optional("delimiter").unwrap_or(expr!(","));
+        Ok(Box::new(EncodeCsvFn { value, delimiter }))
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "delimiter",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeCsvFn {
+    value: Box<dyn Expression>,
+    delimiter: Box<dyn Expression>,
+}
+
+impl Expression for EncodeCsvFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let fields = self.value.resolve(ctx)?.try_array()?;
+        let delimiter = self.delimiter.resolve(ctx)?.try_bytes()?;
+        if delimiter.len() != 1 {
+            return Err("delimiter must be a single character".into());
+        }
+        let delimiter = delimiter[0];
+
+        let fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(idx, field)| encode_field(field, idx))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .delimiter(delimiter)
+            .from_writer(vec![]);
+
+        writer
+            .write_record(&fields)
+            .map_err(|err| format!("unable to encode csv record: {}", err))?; // shouldn't really happen
+
+        let mut csv = writer
+            .into_inner()
+            .map_err(|err| format!("unable to encode csv record: {}", err))?;
+
+        // A row doesn't carry the record terminator.
+        if csv.last() == Some(&b'\n') {
+            csv.pop();
+        }
+
+        Ok(csv.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+fn encode_field(value: Value, idx: usize) -> Result<Vec<u8>> {
+    let field = match value {
+        Value::Bytes(bytes) => bytes.to_vec(),
+        Value::Integer(int) => int.to_string().into_bytes(),
+        Value::Float(float) => float.to_string().into_bytes(),
+        Value::Boolean(boolean) => boolean.to_string().into_bytes(),
+        Value::Timestamp(ts) => ts.to_rfc3339_opts(SecondsFormat::AutoSi, true).into_bytes(),
+        Value::Null => vec![],
+        value => {
+            return Err(format!(
+                "unable to encode field at index {}: value of type {} can't be encoded as csv field",
+                idx,
+                value.kind()
+            )
+            .into())
+        }
+    };
+
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        encode_csv => EncodeCsv;
+
+        valid {
+            args: func_args![value: value!(["foo", "bar", "foo \", bar"])],
+            want: Ok(value!("foo,bar,\"foo \"\", bar\"")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        scalars {
+            args: func_args![value: vec![
+                Value::from(1),
+                Value::from(1.5),
+                Value::from(false),
+                Value::Null,
+                Value::from(Utc.ymd(2021, 1, 1).and_hms(0, 0, 0)),
+            ]],
+            want: Ok(value!("1,1.5,false,,2021-01-01T00:00:00Z")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        custom_delimiter {
+            args: func_args![value: value!(["foo", "bar baz"]), delimiter: value!(" ")],
+            want: Ok(value!("foo \"bar baz\"")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_delimiter {
+            args: func_args![value: value!(["foo", "bar"]), delimiter: value!(",,")],
+            want: Err("delimiter must be a single character"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        single_empty_field {
+            args: func_args![value: value!([""])],
+            want: Ok(value!("\"\"")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        nested {
+            args: func_args![value: value!(["foo", ["bar"]])],
+            want: Err(r#"unable to encode field at index 1: value of type "array" can't be encoded as csv field"#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
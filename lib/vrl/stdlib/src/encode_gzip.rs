@@ -0,0 +1,120 @@
+use crate::util::compression_level;
+use flate2::{read::GzEncoder, Compression};
+use std::io::Read;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeGzip;
+
+impl Function for EncodeGzip {
+    fn identifier(&self) -> &'static str {
+        "encode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let compression_level = arguments.optional("compression_level");
+
+        Ok(Box::new(EncodeGzipFn {
+            value,
+            compression_level,
+        }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"encode_base64(encode_gzip("please encode me"))"#,
+            result: Ok("H4sIAAAAAAAA/yvISU0sTlVIzUvOT0lVyE0FAI4R4vcQAAAA"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeGzipFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let level = match &self.compression_level {
+            Some(expr) => compression_level(expr.resolve(ctx)?.try_integer()?)?,
+            None => Compression::default(),
+        };
+
+        let mut buf = Vec::new();
+        // Reading from an in-memory buffer can't fail.
+        GzEncoder::new(&value[..], level)
+            .read_to_end(&mut buf)
+            .expect("gzip encoding failed");
+
+        Ok(buf.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Only fallible when an out of range compression level is given.
+        TypeDef::new()
+            .bytes()
+            .with_fallibility(self.compression_level.is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use shared::TimeZone;
+
+    fn decode(value: Value) -> String {
+        let bytes = value.try_bytes().unwrap();
+        let mut buf = String::new();
+        GzDecoder::new(&bytes[..]).read_to_string(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut state = vrl::state::Runtime::default();
+        let mut object: Value = map![].into();
+        let tz = TimeZone::default();
+        let mut ctx = Context::new(&mut object, &mut state, &tz);
+
+        for level in &[None, Some(0), Some(9)] {
+            let func = EncodeGzipFn {
+                value: expr!("you have successfully decoded me"),
+                compression_level: level.map(|level| expr!(level)),
+            };
+
+            let got = func.resolve(&mut ctx).unwrap();
+            assert_eq!(decode(got), "you have successfully decoded me");
+        }
+    }
+
+    test_function![
+        encode_gzip => EncodeGzip;
+
+        invalid_level {
+            args: func_args![value: "foo", compression_level: 10],
+            want: Err("compression level must be between 0 and 9, got 10"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
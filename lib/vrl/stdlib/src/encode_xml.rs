@@ -0,0 +1,358 @@
+use chrono::SecondsFormat;
+use std::{borrow::Cow, collections::BTreeMap, fmt::Write};
+use vrl::prelude::*;
+
+struct EncodeXmlConfig<'a> {
+    /// Encode keys starting with `attr_prefix` as XML attributes. Default: true.
+    include_attr: bool,
+    /// XML attribute prefix, e.g. `{a: { "@href": "test" }}` -> `<a href="test"/>`. Default: "@".
+    attr_prefix: Cow<'a, str>,
+    /// Key holding the text of an element. Default: "text".
+    text_key: Cow<'a, str>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeXml;
+
+impl Function for EncodeXml {
+    fn identifier(&self) -> &'static str {
+        "encode_xml"
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "encode XML",
+            source: r#"encode_xml!({ "book": { "@category": "CHILDREN", "author": "J K. Rowling", "title": { "@lang": "en", "value": "Harry Potter" }, "year": 2005 } }, text_key: "value")"#,
+            result: Ok(
+                r#"s'<book category="CHILDREN"><author>J K. Rowling</author><title lang="en">Harry Potter</title><year>2005</year></book>'"#,
+            ),
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        let include_attr = arguments.optional("include_attr");
+        let attr_prefix = arguments.optional("attr_prefix");
+        let text_key = arguments.optional("text_key");
+
+        Ok(Box::new(EncodeXmlFn {
+            value,
+            include_attr,
+            attr_prefix,
+            text_key,
+        }))
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "include_attr",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+            Parameter {
+                keyword: "attr_prefix",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "text_key",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeXmlFn {
+    value: Box<dyn Expression>,
+
+    include_attr: Option<Box<dyn Expression>>,
+    attr_prefix: Option<Box<dyn Expression>>,
+    text_key: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeXmlFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let object = self.value.resolve(ctx)?.try_object()?;
+
+        let include_attr = match &self.include_attr {
+            Some(expr) => expr.resolve(ctx)?.try_boolean()?,
+            None => true,
+        };
+
+        let attr_prefix = match &self.attr_prefix {
+            Some(expr) => Cow::from(expr.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned()),
+            None => Cow::from("@"),
+        };
+
+        let text_key = match &self.text_key {
+            Some(expr) => Cow::from(expr.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned()),
+            None => Cow::from("text"),
+        };
+
+        let config = EncodeXmlConfig {
+            include_attr,
+            attr_prefix,
+            text_key,
+        };
+
+        // A document has exactly one root element.
+        let mut root = object.into_iter();
+        let (name, value) = match (root.next(), root.next()) {
+            (Some(root), None) => root,
+            _ => return Err("unable to encode xml: object must have a single root key".into()),
+        };
+
+        let mut output = String::new();
+        encode_element(&mut output, &name, &value, &config)?;
+
+        Ok(output.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+/// Encode a VRL `Value` as XML element(s) named `name`. Arrays are encoded as
+/// repeated elements, the way `parse_xml` collects them.
+fn encode_element(
+    output: &mut String,
+    name: &str,
+    value: &Value,
+    config: &EncodeXmlConfig,
+) -> Result<()> {
+    if !is_valid_name(name) {
+        return Err(format!("unable to encode xml: invalid element name `{}`", name).into());
+    }
+
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                encode_element(output, name, value, config)?;
+            }
+        }
+        Value::Object(map) => {
+            let (attributes, children) = split_attributes(map, config);
+
+            write!(output, "<{}", name).expect("write to String");
+            for (key, value) in attributes {
+                if !is_valid_name(key) {
+                    return Err(
+                        format!("unable to encode xml: invalid attribute name `{}`", key).into(),
+                    );
+                }
+
+                let value = encode_text(value).ok_or_else(|| {
+                    format!(
+                        "unable to encode xml: attribute `{}` must not be an object or array",
+                        key
+                    )
+                })?;
+                write!(output, r#" {}="{}""#, key, escape(&value, true)).expect("write to String");
+            }
+
+            if children.is_empty() {
+                output.push_str("/>");
+                return Ok(());
+            }
+
+            output.push('>');
+            for (key, value) in children {
+                if key == config.text_key.as_ref() {
+                    encode_text_node(output, key, value)?;
+                } else {
+                    encode_element(output, key, value, config)?;
+                }
+            }
+            write!(output, "</{}>", name).expect("write to String");
+        }
+        Value::Null => write!(output, "<{}/>", name).expect("write to String"),
+        value => {
+            write!(output, "<{}>", name).expect("write to String");
+            encode_text_node(output, name, value)?;
+            write!(output, "</{}>", name).expect("write to String");
+        }
+    }
+
+    Ok(())
+}
+
+type Nodes<'a> = Vec<(&'a str, &'a Value)>;
+
+/// Split the keys of an object into attributes, with their prefix stripped,
+/// and child nodes.
+fn split_attributes<'a>(
+    map: &'a BTreeMap<String, Value>,
+    config: &EncodeXmlConfig,
+) -> (Nodes<'a>, Nodes<'a>) {
+    let mut attributes = vec![];
+    let mut children = vec![];
+
+    for (key, value) in map {
+        match key.strip_prefix(config.attr_prefix.as_ref()) {
+            Some(attribute) if config.include_attr && !config.attr_prefix.is_empty() => {
+                attributes.push((attribute, value))
+            }
+            _ => children.push((key.as_str(), value)),
+        }
+    }
+
+    (attributes, children)
+}
+
+fn encode_text_node(output: &mut String, name: &str, value: &Value) -> Result<()> {
+    let text = encode_text(value).ok_or_else(|| {
+        format!(
+            "unable to encode xml: text of `{}` must not be an object or array",
+            name
+        )
+    })?;
+    output.push_str(&escape(&text, false));
+
+    Ok(())
+}
+
+/// Returns the text representation of a scalar value, or `None` for objects
+/// and arrays.
+fn encode_text(value: &Value) -> Option<Cow<str>> {
+    let text = match value {
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes),
+        Value::Integer(int) => int.to_string().into(),
+        Value::Float(float) => float.to_string().into(),
+        Value::Boolean(boolean) => boolean.to_string().into(),
+        Value::Timestamp(ts) => ts.to_rfc3339_opts(SecondsFormat::AutoSi, true).into(),
+        Value::Regex(regex) => regex.to_string().into(),
+        Value::Null => "".into(),
+        Value::Object(_) | Value::Array(_) => return None,
+    };
+
+    Some(text)
+}
+
+fn escape(text: &str, attribute: bool) -> Cow<str> {
+    let needs_escape = |c: char| match c {
+        '&' | '<' | '>' => true,
+        '"' => attribute,
+        _ => false,
+    };
+
+    if !text.contains(needs_escape) {
+        return text.into();
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.into()
+}
+
+/// A conservative check of the XML `Name` production, which rejects names
+/// that would result in a malformed document.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' || c == ':' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        encode_xml => EncodeXml;
+
+        simple_text {
+            args: func_args![ value: value!({ "a": "test" }) ],
+            want: Ok(value!("<a>test</a>")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        include_attr {
+            args: func_args![ value: value!({ "a": { "@href": "https://vector.dev", "text": "test" } }) ],
+            want: Ok(value!(r#"<a href="https://vector.dev">test</a>"#)),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        exclude_attr {
+            args: func_args![ value: value!({ "a": { "_href": "https://vector.dev" } }), attr_prefix: "@", include_attr: false ],
+            want: Ok(value!("<a><_href>https://vector.dev</_href></a>")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        custom_attr_prefix {
+            args: func_args![ value: value!({ "a": { "_href": "https://vector.dev" } }), attr_prefix: "_" ],
+            want: Ok(value!(r#"<a href="https://vector.dev"/>"#)),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        custom_text_key {
+            args: func_args![ value: value!({ "b": { "node": "test" } }), text_key: "node" ],
+            want: Ok(value!("<b>test</b>")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        nested_object {
+            args: func_args![ value: value!({ "a": { "b": "one", "c": "two" } }) ],
+            want: Ok(value!("<a><b>one</b><c>two</c></a>")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        nested_object_array {
+            args: func_args![ value: value!({ "a": { "b": ["one", "two"] } }) ],
+            want: Ok(value!("<a><b>one</b><b>two</b></a>")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        scalars {
+            args: func_args![ value: value!({ "a": { "b": 1, "c": 1.5, "d": true, "e": null } }) ],
+            want: Ok(value!("<a><b>1</b><c>1.5</c><d>true</d><e/></a>")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        escaping {
+            args: func_args![ value: value!({ "a": { "@title": r#"say "hi" & <bye>"#, "text": "1 < 2 & 3 > 2" } }) ],
+            want: Ok(value!(r#"<a title="say &quot;hi&quot; &amp; &lt;bye&gt;">1 &lt; 2 &amp; 3 &gt; 2</a>"#)),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        multiple_roots {
+            args: func_args![ value: value!({ "a": "one", "b": "two" }) ],
+            want: Err("unable to encode xml: object must have a single root key"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_name {
+            args: func_args![ value: value!({ "a b": "one" }) ],
+            want: Err("unable to encode xml: invalid element name `a b`"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        nested_attribute {
+            args: func_args![ value: value!({ "a": { "@b": ["one"] } }) ],
+            want: Err("unable to encode xml: attribute `b` must not be an object or array"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,122 @@
+use crate::util::compression_level;
+use flate2::{read::ZlibEncoder, Compression};
+use std::io::Read;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeZlib;
+
+impl Function for EncodeZlib {
+    fn identifier(&self) -> &'static str {
+        "encode_zlib"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let compression_level = arguments.optional("compression_level");
+
+        Ok(Box::new(EncodeZlibFn {
+            value,
+            compression_level,
+        }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"encode_base64(encode_zlib("please encode me"))"#,
+            result: Ok("eJwryElNLE5VSM1Lzk9JVchNBQA0RQX7"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeZlibFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeZlibFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let level = match &self.compression_level {
+            Some(expr) => compression_level(expr.resolve(ctx)?.try_integer()?)?,
+            None => Compression::default(),
+        };
+
+        let mut buf = Vec::new();
+        // Reading from an in-memory buffer can't fail.
+        ZlibEncoder::new(&value[..], level)
+            .read_to_end(&mut buf)
+            .expect("zlib encoding failed");
+
+        Ok(buf.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Only fallible when an out of range compression level is given.
+        TypeDef::new()
+            .bytes()
+            .with_fallibility(self.compression_level.is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+    use shared::TimeZone;
+
+    fn decode(value: Value) -> String {
+        let bytes = value.try_bytes().unwrap();
+        let mut buf = String::new();
+        ZlibDecoder::new(&bytes[..])
+            .read_to_string(&mut buf)
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut state = vrl::state::Runtime::default();
+        let mut object: Value = map![].into();
+        let tz = TimeZone::default();
+        let mut ctx = Context::new(&mut object, &mut state, &tz);
+
+        for level in &[None, Some(0), Some(9)] {
+            let func = EncodeZlibFn {
+                value: expr!("you have successfully decoded me"),
+                compression_level: level.map(|level| expr!(level)),
+            };
+
+            let got = func.resolve(&mut ctx).unwrap();
+            assert_eq!(decode(got), "you have successfully decoded me");
+        }
+    }
+
+    test_function![
+        encode_zlib => EncodeZlib;
+
+        invalid_level {
+            args: func_args![value: "foo", compression_level: 10],
+            want: Err("compression level must be between 0 and 9, got 10"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
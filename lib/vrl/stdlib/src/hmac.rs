@@ -0,0 +1,127 @@
+use h_mac::{Hmac as HmacImpl, Mac, NewMac};
+use sha_2::{Sha256, Sha512};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Hmac;
+
+impl Function for Hmac {
+    fn identifier(&self) -> &'static str {
+        "hmac"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default algorithm",
+                source: r#"hmac("foobar", "secret")"#,
+                result: Ok("4fcc06915b43d8a49aff193441e9e18654e6a27c2c428b02e8fcc41ccc2299f9"),
+            },
+            Example {
+                title: "custom algorithm",
+                source: r#"hmac("foobar", "secret", "SHA-512")"#,
+                result: Ok("ac76d1f21ab3affcab713dcec165cc517a1d9b79b1ac21fe99619fda7dfbee98b926080dc90117a8aa600875f4dbe7d50b0f13712bbfc9db8b57d7eddb91bc0c"),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let algorithms = vec![value!("SHA-256"), value!("SHA-512")];
+
+        let value = arguments.required("value");
+        let key = arguments.required("key");
+        let algorithm = arguments
+            .optional_enum("algorithm", &algorithms)?
+            .unwrap_or_else(|| value!("SHA-256"))
+            .try_bytes()
+            .expect("algorithm not bytes");
+
+        Ok(Box::new(HmacFn {
+            value,
+            key,
+            algorithm,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HmacFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+    algorithm: Bytes,
+}
+
+impl Expression for HmacFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+        let key = self.key.resolve(ctx)?.try_bytes()?;
+
+        let hash = match self.algorithm.as_ref() {
+            b"SHA-256" => encode::<HmacImpl<Sha256>>(&key, &value),
+            b"SHA-512" => encode::<HmacImpl<Sha512>>(&key, &value),
+            _ => unreachable!("enum invariant"),
+        };
+
+        Ok(hash.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().bytes()
+    }
+}
+
+#[inline]
+fn encode<T: Mac + NewMac>(key: &[u8], value: &[u8]) -> String {
+    // HMAC accepts keys of any length, so this can't fail.
+    let mut mac = T::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(value);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        hmac => Hmac;
+
+        hmac {
+            args: func_args![value: "foobar", key: "secret"],
+            want: Ok("4fcc06915b43d8a49aff193441e9e18654e6a27c2c428b02e8fcc41ccc2299f9"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        hmac_sha256 {
+            args: func_args![value: "foobar", key: "secret", algorithm: "SHA-256"],
+            want: Ok("4fcc06915b43d8a49aff193441e9e18654e6a27c2c428b02e8fcc41ccc2299f9"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        hmac_sha512 {
+            args: func_args![value: "foobar", key: "secret", algorithm: "SHA-512"],
+            want: Ok("ac76d1f21ab3affcab713dcec165cc517a1d9b79b1ac21fe99619fda7dfbee98b926080dc90117a8aa600875f4dbe7d50b0f13712bbfc9db8b57d7eddb91bc0c"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+    ];
+}
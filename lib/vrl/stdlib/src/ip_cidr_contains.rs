@@ -13,7 +13,7 @@ impl Function for IpCidrContains {
         &[
             Parameter {
                 keyword: "cidr",
-                kind: kind::BYTES,
+                kind: kind::BYTES | kind::ARRAY,
                 required: true,
             },
             Parameter {
@@ -36,6 +36,11 @@ impl Function for IpCidrContains {
                 source: r#"ip_cidr_contains!("192.168.0.0/24", "192.168.10.32")"#,
                 result: Ok("false"),
             },
+            Example {
+                title: "any of multiple ranges",
+                source: r#"ip_cidr_contains!(["10.0.0.0/8", "192.168.0.0/16"], "192.168.0.1")"#,
+                result: Ok("true"),
+            },
             Example {
                 title: "invalid cidr",
                 source: r#"ip_cidr_contains!("INVALID", "192.168.10.32")"#,
@@ -78,14 +83,24 @@ impl Expression for IpCidrContainsFn {
                 .map_err(|err| format!("unable to parse IP address: {}", err))?
         };
 
-        let cidr = {
-            let value = self.cidr.resolve(ctx)?;
-            let cidr = value.try_bytes_utf8_lossy()?;
-
-            IpCidr::from_str(cidr).map_err(|err| format!("unable to parse CIDR: {}", err))?
-        };
-
-        Ok(cidr.contains(value).into())
+        match self.cidr.resolve(ctx)? {
+            Value::Bytes(cidr) => Ok(parse_cidr(&cidr)?.contains(value).into()),
+            Value::Array(cidrs) => {
+                // Parse every range up front, so an invalid entry is reported
+                // regardless of its position in the array.
+                let cidrs = cidrs
+                    .into_iter()
+                    .map(|cidr| parse_cidr(&cidr.try_bytes()?))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(cidrs.iter().any(|cidr| cidr.contains(value)).into())
+            }
+            value => Err(value::Error::Expected {
+                got: value.kind(),
+                expected: Kind::Bytes | Kind::Array,
+            }
+            .into()),
+        }
     }
 
     fn type_def(&self, _: &state::Compiler) -> TypeDef {
@@ -93,6 +108,11 @@ impl Expression for IpCidrContainsFn {
     }
 }
 
+fn parse_cidr(cidr: &[u8]) -> Result<IpCidr> {
+    IpCidr::from_str(String::from_utf8_lossy(cidr))
+        .map_err(|err| format!("unable to parse CIDR: {}", err).into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,5 +151,37 @@ mod tests {
             want: Ok(value!(false)),
             tdef: TypeDef::new().fallible().boolean(),
         }
+
+        array_yes {
+            args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1",
+                             cidr: vec!["192.168.0.0/16", "2001:4f8:3:ba::/64"],
+            ],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        array_no {
+            args: func_args![value: "192.168.10.32",
+                             cidr: vec!["10.0.0.0/8", "192.168.0.0/24"],
+            ],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        array_empty {
+            args: func_args![value: "192.168.10.32",
+                             cidr: Vec::<Value>::new(),
+            ],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        array_invalid {
+            args: func_args![value: "192.168.10.32",
+                             cidr: vec!["192.168.0.0/16", "INVALID"],
+            ],
+            want: Err("unable to parse CIDR: The CIDR string is incorrect."),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
     ];
 }
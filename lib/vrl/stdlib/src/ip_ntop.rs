@@ -0,0 +1,91 @@
+use std::convert::TryFrom;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpNtop;
+
+impl Function for IpNtop {
+    fn identifier(&self) -> &'static str {
+        "ip_ntop"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Example",
+            source: r#"ip_ntop!(decode_base64!("AQIDBA=="))"#,
+            result: Ok("1.2.3.4"),
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpNtopFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpNtopFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpNtopFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let ip = if let Ok(octets) = <[u8; 4]>::try_from(value.as_ref()) {
+            Ipv4Addr::from(octets).to_string()
+        } else if let Ok(octets) = <[u8; 16]>::try_from(value.as_ref()) {
+            Ipv6Addr::from(octets).to_string()
+        } else {
+            return Err(format!(
+                "unable to convert bytes to IP address: expected 4 or 16 bytes, got {}",
+                value.len()
+            )
+            .into());
+        };
+
+        Ok(ip.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_ntop => IpNtop;
+
+        invalid {
+            args: func_args![value: &[1u8, 2, 3][..]],
+            want: Err("unable to convert bytes to IP address: expected 4 or 16 bytes, got 3"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        valid_ipv4 {
+            args: func_args![value: &[1u8, 2, 3, 4][..]],
+            want: Ok(value!("1.2.3.4")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        valid_ipv6 {
+            args: func_args![value: &[0x20u8, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1][..]],
+            want: Ok(value!("2001:db8::1")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,89 @@
+use std::net::IpAddr;
+
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpPton;
+
+impl Function for IpPton {
+    fn identifier(&self) -> &'static str {
+        "ip_pton"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Example",
+            source: r#"encode_base64(ip_pton!("1.2.3.4"))"#,
+            result: Ok("AQIDBA=="),
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpPtonFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpPtonFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpPtonFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        // Network byte order, 4 bytes for IPv4 and 16 bytes for IPv6.
+        let bytes = match ip {
+            IpAddr::V4(ip) => ip.octets().to_vec(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        };
+
+        Ok(bytes.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_pton => IpPton;
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        valid_ipv4 {
+            args: func_args![value: "1.2.3.4"],
+            want: Ok(value!(&[1u8, 2, 3, 4][..])),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        valid_ipv6 {
+            args: func_args![value: "2001:db8::1"],
+            want: Ok(value!(&[0x20u8, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1][..])),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,101 @@
+use std::net::IpAddr;
+
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpVersion;
+
+impl Function for IpVersion {
+    fn identifier(&self) -> &'static str {
+        "ip_version"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv4",
+                source: r#"ip_version!("192.168.0.1")"#,
+                result: Ok("IPv4"),
+            },
+            Example {
+                title: "IPv6",
+                source: r#"ip_version!("2001:db8::1")"#,
+                result: Ok("IPv6"),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpVersionFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpVersionFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpVersionFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let version = match ip {
+            IpAddr::V4(_) => "IPv4",
+            IpAddr::V6(_) => "IPv6",
+        };
+
+        Ok(version.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_version => IpVersion;
+
+        ipv4 {
+            args: func_args![value: "192.168.0.1"],
+            want: Ok(value!("IPv4")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv6 {
+            args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"],
+            want: Ok(value!("IPv6")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv4_mapped_ipv6 {
+            args: func_args![value: "::ffff:192.168.0.1"],
+            want: Ok(value!("IPv6")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
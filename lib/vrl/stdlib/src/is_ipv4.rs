@@ -0,0 +1,91 @@
+use std::net::Ipv4Addr;
+
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IsIpv4;
+
+impl Function for IsIpv4 {
+    fn identifier(&self) -> &'static str {
+        "is_ipv4"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv4 address",
+                source: r#"is_ipv4("192.168.0.1")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "other address",
+                source: r#"is_ipv4("2001:db8::1")"#,
+                result: Ok("false"),
+            },
+            Example {
+                title: "not an address",
+                source: r#"is_ipv4("foobar")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IsIpv4Fn { value }))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct IsIpv4Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IsIpv4Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let value = value.try_bytes_utf8_lossy()?;
+
+        Ok(value.parse::<Ipv4Addr>().is_ok().into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().boolean()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        is_ipv4 => IsIpv4;
+
+        ipv4 {
+            args: func_args![value: "10.0.0.1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+
+        ipv6 {
+            args: func_args![value: "::ffff:10.0.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+
+        cidr {
+            args: func_args![value: "10.0.0.0/8"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+    ];
+}
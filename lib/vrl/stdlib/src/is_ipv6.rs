@@ -0,0 +1,91 @@
+use std::net::Ipv6Addr;
+
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IsIpv6;
+
+impl Function for IsIpv6 {
+    fn identifier(&self) -> &'static str {
+        "is_ipv6"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv6 address",
+                source: r#"is_ipv6("2001:db8::1")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "other address",
+                source: r#"is_ipv6("192.168.0.1")"#,
+                result: Ok("false"),
+            },
+            Example {
+                title: "not an address",
+                source: r#"is_ipv6("foobar")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IsIpv6Fn { value }))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct IsIpv6Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IsIpv6Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let value = value.try_bytes_utf8_lossy()?;
+
+        Ok(value.parse::<Ipv6Addr>().is_ok().into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().boolean()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        is_ipv6 => IsIpv6;
+
+        ipv6 {
+            args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+
+        ipv4_mapped {
+            args: func_args![value: "::ffff:10.0.0.1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+
+        ipv4 {
+            args: func_args![value: "10.0.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+    ];
+}
@@ -0,0 +1,142 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IsPrivateIp;
+
+impl Function for IsPrivateIp {
+    fn identifier(&self) -> &'static str {
+        "is_private_ip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "private IPv4",
+                source: r#"is_private_ip!("172.16.10.1")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "unique local IPv6",
+                source: r#"is_private_ip!("fd12:3456:789a:1::1")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "public",
+                source: r#"is_private_ip!("8.8.8.8")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IsPrivateIpFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IsPrivateIpFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IsPrivateIpFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let private = match ip {
+            IpAddr::V4(ip) => is_private_ipv4(ip),
+            IpAddr::V6(ip) => match ip.to_ipv4() {
+                // Only IPv4-mapped addresses (`::ffff:a.b.c.d`) carry an IPv4
+                // address, the deprecated IPv4-compatible form is ignored.
+                Some(mapped) if ip.segments()[..6] == [0, 0, 0, 0, 0, 0xffff] => {
+                    is_private_ipv4(mapped)
+                }
+                _ => is_private_ipv6(ip),
+            },
+        };
+
+        Ok(private.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().boolean()
+    }
+}
+
+/// Matches the RFC 1918 ranges `10.0.0.0/8`, `172.16.0.0/12` and
+/// `192.168.0.0/16`.
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_private()
+}
+
+/// Matches the RFC 4193 unique local range `fc00::/7`.
+fn is_private_ipv6(ip: Ipv6Addr) -> bool {
+    ip.segments()[0] & 0xfe00 == 0xfc00
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        is_private_ip => IsPrivateIp;
+
+        ipv4_private {
+            args: func_args![value: "192.168.10.32"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_range_boundary {
+            args: func_args![value: "172.32.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_loopback {
+            args: func_args![value: "127.0.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_unique_local {
+            args: func_args![value: "fc00::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_public {
+            args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_mapped {
+            args: func_args![value: "::ffff:10.1.2.3"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+    ];
+}
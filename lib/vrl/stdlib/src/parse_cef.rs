@@ -0,0 +1,460 @@
+use crate::util::{find_unescaped, unescape};
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseCef;
+
+impl Function for ParseCef {
+    fn identifier(&self) -> &'static str {
+        "parse_cef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "translate_keys",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "simple event",
+                source: r#"parse_cef!(s'CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232')"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "0",
+                    "deviceVendor": "Security",
+                    "deviceProduct": "threatmanager",
+                    "deviceVersion": "1.0",
+                    "deviceEventClassId": "100",
+                    "name": "worm successfully stopped",
+                    "severity": "10",
+                    "src": "10.0.0.1",
+                    "dst": "2.1.2.2",
+                    "spt": "1232"
+                }"#}),
+            },
+            Example {
+                title: "escaped values",
+                source: r#"parse_cef!(s'CEF:0|Vendor|Prod\|uct|1.0|42|detected a \\ in a header|5|msg=a\=b is now c spt=80')"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "0",
+                    "deviceVendor": "Vendor",
+                    "deviceProduct": "Prod|uct",
+                    "deviceVersion": "1.0",
+                    "deviceEventClassId": "42",
+                    "name": "detected a \\ in a header",
+                    "severity": "5",
+                    "msg": "a=b is now c",
+                    "spt": "80"
+                }"#}),
+            },
+            Example {
+                title: "translate keys",
+                source: r#"parse_cef!(s'CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 spt=1232', translate_keys: true)"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "0",
+                    "deviceVendor": "Security",
+                    "deviceProduct": "threatmanager",
+                    "deviceVersion": "1.0",
+                    "deviceEventClassId": "100",
+                    "name": "worm successfully stopped",
+                    "severity": "10",
+                    "sourceAddress": "10.0.0.1",
+                    "sourcePort": "1232"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let translate_keys = arguments
+            .optional("translate_keys")
+            .unwrap_or_else(|| expr!(false));
+
+        Ok(Box::new(ParseCefFn {
+            value,
+            translate_keys,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseCefFn {
+    value: Box<dyn Expression>,
+    translate_keys: Box<dyn Expression>,
+}
+
+impl Expression for ParseCefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let message = value.try_bytes_utf8_lossy()?;
+        let translate_keys = self.translate_keys.resolve(ctx)?.try_boolean()?;
+
+        Ok(parse(&message, translate_keys)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().object::<(), Kind>(map! {
+            (): Kind::Bytes,
+        })
+    }
+}
+
+/// The names of the pipe delimited header fields, in order of appearance.
+const HEADER_FIELDS: [&str; 7] = [
+    "cefVersion",
+    "deviceVendor",
+    "deviceProduct",
+    "deviceVersion",
+    "deviceEventClassId",
+    "name",
+    "severity",
+];
+
+fn parse(
+    input: &str,
+    translate_keys: bool,
+) -> std::result::Result<BTreeMap<String, Value>, String> {
+    // Messages are commonly shipped with a syslog prefix, which we skip.
+    let start = input
+        .find("CEF:")
+        .ok_or("unable to find CEF header in input")?;
+    let input = &input[start + "CEF:".len()..];
+
+    let mut result = BTreeMap::new();
+    let mut rest = input;
+
+    for field in HEADER_FIELDS.iter() {
+        let end =
+            find_unescaped(rest, '|').ok_or_else(|| format!("missing header field `{}`", field))?;
+
+        result.insert(field.to_string(), unescape_header(&rest[..end]).into());
+        rest = &rest[end + 1..];
+    }
+
+    for (key, value) in parse_extension(rest)? {
+        let key = if translate_keys {
+            full_name(key).unwrap_or(key)
+        } else {
+            key
+        };
+
+        result.insert(key.to_owned(), unescape_extension(value).into());
+    }
+
+    Ok(result)
+}
+
+/// Splits the extension into its raw key/value pairs.
+///
+/// Values can contain unescaped spaces, so a value runs until the last space
+/// preceding the next key, where a key is a run of key characters directly
+/// followed by an unescaped `=`.
+fn parse_extension(input: &str) -> std::result::Result<Vec<(&str, &str)>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Collect the (key start, equals sign) positions of every key.
+    let mut keys = vec![];
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' => {
+                let key_start = input[..i]
+                    .char_indices()
+                    .rev()
+                    .find(|(_, c)| !is_key_char(*c))
+                    .map(|(pos, c)| pos + c.len_utf8())
+                    .unwrap_or(0);
+
+                let preceded_by_space = key_start == 0 || input[..key_start].ends_with(' ');
+
+                if key_start < i && preceded_by_space {
+                    keys.push((key_start, i));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match keys.first() {
+        Some((0, _)) => {}
+        _ => return Err("extension must start with a key".to_owned()),
+    }
+
+    Ok(keys
+        .iter()
+        .enumerate()
+        .map(|(index, &(key_start, equals))| {
+            let value_end = keys
+                .get(index + 1)
+                .map(|&(next_start, _)| next_start)
+                .unwrap_or_else(|| input.len());
+
+            (
+                &input[key_start..equals],
+                input[equals + 1..value_end].trim_end(),
+            )
+        })
+        .collect())
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+/// Header fields only escape pipes and backslashes.
+fn unescape_header(input: &str) -> String {
+    unescape(input, |c| match c {
+        '|' | '\\' => Some(c),
+        _ => None,
+    })
+}
+
+/// Extension values escape equal signs, backslashes and line breaks.
+fn unescape_extension(input: &str) -> String {
+    unescape(input, |c| match c {
+        '=' | '\\' => Some(c),
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        _ => None,
+    })
+}
+
+/// Translates the short extension key names to the full names defined in the
+/// ArcSight CEF specification.
+fn full_name(key: &str) -> Option<&'static str> {
+    let name = match key {
+        "act" => "deviceAction",
+        "ahost" => "agentHostName",
+        "aid" => "agentId",
+        "amac" => "agentMacAddress",
+        "app" => "applicationProtocol",
+        "art" => "agentReceiptTime",
+        "at" => "agentType",
+        "atz" => "agentTimeZone",
+        "av" => "agentVersion",
+        "c6a1" => "deviceCustomIPv6Address1",
+        "c6a1Label" => "deviceCustomIPv6Address1Label",
+        "c6a2" => "deviceCustomIPv6Address2",
+        "c6a2Label" => "deviceCustomIPv6Address2Label",
+        "c6a3" => "deviceCustomIPv6Address3",
+        "c6a3Label" => "deviceCustomIPv6Address3Label",
+        "c6a4" => "deviceCustomIPv6Address4",
+        "c6a4Label" => "deviceCustomIPv6Address4Label",
+        "cat" => "deviceEventCategory",
+        "cfp1" => "deviceCustomFloatingPoint1",
+        "cfp1Label" => "deviceCustomFloatingPoint1Label",
+        "cfp2" => "deviceCustomFloatingPoint2",
+        "cfp2Label" => "deviceCustomFloatingPoint2Label",
+        "cfp3" => "deviceCustomFloatingPoint3",
+        "cfp3Label" => "deviceCustomFloatingPoint3Label",
+        "cfp4" => "deviceCustomFloatingPoint4",
+        "cfp4Label" => "deviceCustomFloatingPoint4Label",
+        "cn1" => "deviceCustomNumber1",
+        "cn1Label" => "deviceCustomNumber1Label",
+        "cn2" => "deviceCustomNumber2",
+        "cn2Label" => "deviceCustomNumber2Label",
+        "cn3" => "deviceCustomNumber3",
+        "cn3Label" => "deviceCustomNumber3Label",
+        "cnt" => "baseEventCount",
+        "cs1" => "deviceCustomString1",
+        "cs1Label" => "deviceCustomString1Label",
+        "cs2" => "deviceCustomString2",
+        "cs2Label" => "deviceCustomString2Label",
+        "cs3" => "deviceCustomString3",
+        "cs3Label" => "deviceCustomString3Label",
+        "cs4" => "deviceCustomString4",
+        "cs4Label" => "deviceCustomString4Label",
+        "cs5" => "deviceCustomString5",
+        "cs5Label" => "deviceCustomString5Label",
+        "cs6" => "deviceCustomString6",
+        "cs6Label" => "deviceCustomString6Label",
+        "dhost" => "destinationHostName",
+        "dlat" => "destinationGeoLatitude",
+        "dlong" => "destinationGeoLongitude",
+        "dmac" => "destinationMacAddress",
+        "dntdom" => "destinationNtDomain",
+        "dpid" => "destinationProcessId",
+        "dpriv" => "destinationUserPrivileges",
+        "dproc" => "destinationProcessName",
+        "dpt" => "destinationPort",
+        "dst" => "destinationAddress",
+        "dtz" => "deviceTimeZone",
+        "duid" => "destinationUserId",
+        "duser" => "destinationUserName",
+        "dvc" => "deviceAddress",
+        "dvchost" => "deviceHostName",
+        "dvcmac" => "deviceMacAddress",
+        "dvcpid" => "deviceProcessId",
+        "end" => "endTime",
+        "fname" => "fileName",
+        "fsize" => "fileSize",
+        "in" => "bytesIn",
+        "msg" => "message",
+        "out" => "bytesOut",
+        "outcome" => "eventOutcome",
+        "proto" => "transportProtocol",
+        "request" => "requestUrl",
+        "rt" => "deviceReceiptTime",
+        "shost" => "sourceHostName",
+        "slat" => "sourceGeoLatitude",
+        "slong" => "sourceGeoLongitude",
+        "smac" => "sourceMacAddress",
+        "sntdom" => "sourceNtDomain",
+        "spid" => "sourceProcessId",
+        "spriv" => "sourceUserPrivileges",
+        "sproc" => "sourceProcessName",
+        "spt" => "sourcePort",
+        "src" => "sourceAddress",
+        "start" => "startTime",
+        "suid" => "sourceUserId",
+        "suser" => "sourceUserName",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_extension_values_with_spaces() {
+        assert_eq!(
+            parse_extension("msg=hello world src=10.0.0.1 url=http://a?b=c").unwrap(),
+            vec![
+                ("msg", "hello world"),
+                ("src", "10.0.0.1"),
+                ("url", "http://a?b=c")
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_extension_escaped_equals() {
+        assert_eq!(
+            parse_extension(r#"msg=a \= b cs1=c"#).unwrap(),
+            vec![("msg", r#"a \= b"#), ("cs1", "c")]
+        );
+    }
+
+    #[test]
+    fn parse_extension_empty_value() {
+        assert_eq!(
+            parse_extension("suser= dst=1.1.1.1").unwrap(),
+            vec![("suser", ""), ("dst", "1.1.1.1")]
+        );
+    }
+
+    #[test]
+    fn parse_extension_missing_key() {
+        assert!(parse_extension("hello src=1.1.1.1").is_err());
+    }
+
+    #[test]
+    fn unescape_values() {
+        assert_eq!(unescape_header(r#"a\|b\\c\=d"#), r#"a|b\c\=d"#);
+        assert_eq!(unescape_extension(r#"a\=b\\c\nd\|e"#), "a=b\\c\nd\\|e");
+    }
+
+    test_function![
+        parse_cef => ParseCef;
+
+        simple {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232"],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Security",
+                "deviceProduct": "threatmanager",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "100",
+                "name": "worm successfully stopped",
+                "severity": "10",
+                "src": "10.0.0.1",
+                "dst": "2.1.2.2",
+                "spt": "1232",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        syslog_prefix {
+            args: func_args![value: "Sep 19 08:26:10 host CEF:0|Security|threatmanager|1.0|100|detected|10|"],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Security",
+                "deviceProduct": "threatmanager",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "100",
+                "name": "detected",
+                "severity": "10",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        escapes {
+            args: func_args![value: r#"CEF:0|Vendor|Prod\|uct|1.0|42|a \\ in a header|5|msg=a\=b|c\nd spt=80"#],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Vendor",
+                "deviceProduct": "Prod|uct",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "42",
+                "name": r#"a \ in a header"#,
+                "severity": "5",
+                "msg": "a=b|c\nd",
+                "spt": "80",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        translate_keys {
+            args: func_args![
+                value: "CEF:0|Security|threatmanager|1.0|100|stopped|10|src=10.0.0.1 cs1=foo cs1Label=bar unknownKey=baz",
+                translate_keys: true,
+            ],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Security",
+                "deviceProduct": "threatmanager",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "100",
+                "name": "stopped",
+                "severity": "10",
+                "sourceAddress": "10.0.0.1",
+                "deviceCustomString1": "foo",
+                "deviceCustomString1Label": "bar",
+                "unknownKey": "baz",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        missing_header {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0"],
+            want: Err("missing header field `deviceVersion`"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        not_cef {
+            args: func_args![value: "foo=bar"],
+            want: Err("unable to find CEF header in input"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+    ];
+}
@@ -0,0 +1,313 @@
+use crate::util::{find_unescaped, unescape};
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseLeef;
+
+impl Function for ParseLeef {
+    fn identifier(&self) -> &'static str {
+        "parse_leef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "delimiter",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "LEEF 1.0",
+                source: r#"parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5")"#,
+                result: Ok(indoc! {r#"{
+                    "leefVersion": "1.0",
+                    "vendor": "Microsoft",
+                    "productName": "MSExchange",
+                    "productVersion": "4.0 SP1",
+                    "eventId": "15345",
+                    "src": "192.0.2.0",
+                    "dst": "172.50.123.1",
+                    "sev": "5"
+                }"#}),
+            },
+            Example {
+                title: "LEEF 2.0 with custom delimiter",
+                source: r#"parse_leef!(s'LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^msg=a \^ b')"#,
+                result: Ok(indoc! {r#"{
+                    "leefVersion": "2.0",
+                    "vendor": "Lancope",
+                    "productName": "StealthWatch",
+                    "productVersion": "1.0",
+                    "eventId": "41",
+                    "src": "10.0.1.8",
+                    "dst": "10.0.0.5",
+                    "msg": "a ^ b"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let delimiter = arguments.optional("delimiter");
+
+        Ok(Box::new(ParseLeefFn { value, delimiter }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseLeefFn {
+    value: Box<dyn Expression>,
+    delimiter: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseLeefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let message = value.try_bytes_utf8_lossy()?;
+
+        let delimiter = match &self.delimiter {
+            Some(expr) => {
+                let value = expr.resolve(ctx)?;
+                let delimiter = value.try_bytes_utf8_lossy()?;
+                Some(parse_delimiter(&delimiter)?)
+            }
+            None => None,
+        };
+
+        Ok(parse(&message, delimiter)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().object::<(), Kind>(map! {
+            (): Kind::Bytes,
+        })
+    }
+}
+
+/// The names of the pipe delimited header fields shared by all LEEF versions,
+/// in order of appearance.
+const HEADER_FIELDS: [&str; 5] = [
+    "leefVersion",
+    "vendor",
+    "productName",
+    "productVersion",
+    "eventId",
+];
+
+fn parse(
+    input: &str,
+    delimiter: Option<char>,
+) -> std::result::Result<BTreeMap<String, Value>, String> {
+    // Messages are commonly shipped with a syslog prefix, which we skip.
+    let start = input
+        .find("LEEF:")
+        .ok_or("unable to find LEEF header in input")?;
+    let input = &input[start + "LEEF:".len()..];
+
+    let mut result = BTreeMap::new();
+    let mut rest = input;
+
+    for field in HEADER_FIELDS.iter() {
+        let (value, remainder) =
+            header_field(rest).ok_or_else(|| format!("missing header field `{}`", field))?;
+
+        result.insert(field.to_string(), value.into());
+        rest = remainder;
+    }
+
+    // Version 2 adds a header field holding the delimiter of the attributes.
+    let header_delimiter = match result.get("leefVersion") {
+        Some(Value::Bytes(version)) if version.starts_with(b"2") => {
+            let (value, remainder) =
+                header_field(rest).ok_or_else(|| "missing header field `delimiter`".to_owned())?;
+            rest = remainder;
+
+            if value.is_empty() {
+                None
+            } else {
+                Some(parse_delimiter(&value)?)
+            }
+        }
+        _ => None,
+    };
+
+    let delimiter = delimiter.or(header_delimiter).unwrap_or('\t');
+
+    let mut rest = rest.trim_end_matches(|c| c == '\r' || c == '\n');
+    while !rest.is_empty() {
+        let (attribute, remainder) = match find_unescaped(rest, delimiter) {
+            Some(end) => (&rest[..end], &rest[end + delimiter.len_utf8()..]),
+            None => (rest, ""),
+        };
+        rest = remainder;
+
+        if attribute.trim().is_empty() {
+            continue;
+        }
+
+        let equals = find_unescaped(attribute, '=')
+            .ok_or_else(|| format!("attribute `{}` has no value", attribute.trim()))?;
+
+        let key = attribute[..equals].trim();
+        let value = unescape(&attribute[equals + 1..], |c| match c {
+            '=' | '\\' | '|' => Some(c),
+            c if c == delimiter => Some(c),
+            _ => None,
+        });
+
+        result.insert(key.to_owned(), value.into());
+    }
+
+    Ok(result)
+}
+
+/// Returns the unescaped header field and the remaining input following the
+/// pipe that terminates it.
+fn header_field(input: &str) -> Option<(String, &str)> {
+    let end = find_unescaped(input, '|')?;
+    let value = unescape(&input[..end], |c| match c {
+        '|' | '\\' => Some(c),
+        _ => None,
+    });
+
+    Some((value, &input[end + 1..]))
+}
+
+/// Parses an attribute delimiter, which is either a single character or its
+/// hexadecimal code point prefixed with `x` or `0x`.
+fn parse_delimiter(input: &str) -> std::result::Result<char, String> {
+    let mut chars = input.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => return Ok(c),
+        (None, _) => return Err("delimiter must not be empty".to_owned()),
+        _ => {}
+    }
+
+    input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix('x'))
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .and_then(std::char::from_u32)
+        .ok_or_else(|| format!("invalid delimiter `{}`", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delimiters() {
+        assert_eq!(parse_delimiter("^"), Ok('^'));
+        assert_eq!(parse_delimiter("x09"), Ok('\t'));
+        assert_eq!(parse_delimiter("0x5E"), Ok('^'));
+        assert!(parse_delimiter("").is_err());
+        assert!(parse_delimiter("0xZZ").is_err());
+        assert!(parse_delimiter("ab").is_err());
+    }
+
+    test_function![
+        parse_leef => ParseLeef;
+
+        leef_1 {
+            args: func_args![value: "LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tcat=anomaly\tmsg=this is a message"],
+            want: Ok(value!({
+                "leefVersion": "1.0",
+                "vendor": "Microsoft",
+                "productName": "MSExchange",
+                "productVersion": "4.0 SP1",
+                "eventId": "15345",
+                "src": "192.0.2.0",
+                "dst": "172.50.123.1",
+                "sev": "5",
+                "cat": "anomaly",
+                "msg": "this is a message",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        leef_2_hex_delimiter {
+            args: func_args![value: "Jan 18 11:07:53 host LEEF:2.0|Lancope|StealthWatch|1.0|41|x5E|src=10.0.1.8^dst=10.0.0.5^url=http://a?b=c"],
+            want: Ok(value!({
+                "leefVersion": "2.0",
+                "vendor": "Lancope",
+                "productName": "StealthWatch",
+                "productVersion": "1.0",
+                "eventId": "41",
+                "src": "10.0.1.8",
+                "dst": "10.0.0.5",
+                "url": "http://a?b=c",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        leef_2_default_delimiter {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.0|1||src=10.0.1.8\tdst=10.0.0.5\t"],
+            want: Ok(value!({
+                "leefVersion": "2.0",
+                "vendor": "Vendor",
+                "productName": "Product",
+                "productVersion": "1.0",
+                "eventId": "1",
+                "src": "10.0.1.8",
+                "dst": "10.0.0.5",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        escapes {
+            args: func_args![value: r#"LEEF:2.0|Ven\|dor|Product|1.0|1|^|msg=a\^b\=c^path=C:\\temp"#],
+            want: Ok(value!({
+                "leefVersion": "2.0",
+                "vendor": "Ven|dor",
+                "productName": "Product",
+                "productVersion": "1.0",
+                "eventId": "1",
+                "msg": "a^b=c",
+                "path": r#"C:\temp"#,
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        delimiter_argument {
+            args: func_args![
+                value: "LEEF:1.0|Vendor|Product|1.0|1|src=10.0.1.8|dst=10.0.0.5",
+                delimiter: "|",
+            ],
+            want: Ok(value!({
+                "leefVersion": "1.0",
+                "vendor": "Vendor",
+                "productName": "Product",
+                "productVersion": "1.0",
+                "eventId": "1",
+                "src": "10.0.1.8",
+                "dst": "10.0.0.5",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        missing_value {
+            args: func_args![value: "LEEF:1.0|Vendor|Product|1.0|1|src"],
+            want: Err("attribute `src` has no value"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        not_leef {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|"],
+            want: Err("unable to find LEEF header in input"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+    ];
+}
@@ -119,3 +119,56 @@ impl FromStr for Base64Charset {
         }
     }
 }
+
+/// Returns the byte position of the first occurrence of `delimiter` that is
+/// not escaped with a backslash.
+#[cfg(any(feature = "parse_cef", feature = "parse_leef"))]
+pub(crate) fn find_unescaped(input: &str, delimiter: char) -> Option<usize> {
+    let mut escaped = false;
+
+    for (i, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == delimiter => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Replaces the escape sequences recognized by `escape`, leaving any other
+/// backslashes untouched.
+#[cfg(any(feature = "parse_cef", feature = "parse_leef"))]
+pub(crate) fn unescape(input: &str, escape: impl Fn(char) -> Option<char>) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(unescaped) = chars.peek().copied().and_then(&escape) {
+                output.push(unescaped);
+                chars.next();
+                continue;
+            }
+        }
+
+        output.push(c);
+    }
+
+    output
+}
+
+/// Converts the compression level argument, which must be between 0 (no
+/// compression) and 9 (best compression).
+#[cfg(any(feature = "encode_gzip", feature = "encode_zlib"))]
+pub(crate) fn compression_level(level: i64) -> std::result::Result<flate2::Compression, String> {
+    match level {
+        0..=9 => Ok(flate2::Compression::new(level as u32)),
+        _ => Err(format!(
+            "compression level must be between 0 and 9, got {}",
+            level
+        )),
+    }
+}
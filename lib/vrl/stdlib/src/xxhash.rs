@@ -0,0 +1,120 @@
+use std::hash::Hasher;
+use twox_hash::{XxHash32, XxHash64};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Xxhash;
+
+impl Function for Xxhash {
+    fn identifier(&self) -> &'static str {
+        "xxhash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "variant",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default variant",
+                source: r#"xxhash("foobar")"#,
+                result: Ok("-6725556575634347271"),
+            },
+            Example {
+                title: "custom variant",
+                source: r#"xxhash("foobar", "XXH32")"#,
+                result: Ok("3986901679"),
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Compiled {
+        let variants = vec![value!("XXH32"), value!("XXH64")];
+
+        let value = arguments.required("value");
+        let variant = arguments
+            .optional_enum("variant", &variants)?
+            .unwrap_or_else(|| value!("XXH64"))
+            .try_bytes()
+            .expect("variant not bytes");
+
+        Ok(Box::new(XxhashFn { value, variant }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct XxhashFn {
+    value: Box<dyn Expression>,
+    variant: Bytes,
+}
+
+impl Expression for XxhashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let hash = match self.variant.as_ref() {
+            b"XXH32" => hash(XxHash32::with_seed(0), &value),
+            b"XXH64" => hash(XxHash64::with_seed(0), &value),
+            _ => unreachable!("enum invariant"),
+        };
+
+        // VRL integers are signed, so 64-bit hashes above `i64::MAX` wrap
+        // around to negative numbers.
+        Ok(Value::from(hash as i64))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().integer()
+    }
+}
+
+#[inline]
+fn hash<T: Hasher>(mut hasher: T, value: &[u8]) -> u64 {
+    hasher.write(value);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_values() {
+        assert_eq!(hash(XxHash32::with_seed(0), b""), 0x02CC_5D05);
+        assert_eq!(hash(XxHash64::with_seed(0), b""), 0xEF46_DB37_51D8_E999);
+    }
+
+    test_function![
+        xxhash => Xxhash;
+
+        xxhash {
+            args: func_args![value: "foobar"],
+            want: Ok(-6725556575634347271_i64),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        xxh32 {
+            args: func_args![value: "foobar", variant: "XXH32"],
+            want: Ok(3986901679_i64),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        xxh64 {
+            args: func_args![value: "foobar", variant: "XXH64"],
+            want: Ok(-6725556575634347271_i64),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+    ];
+}
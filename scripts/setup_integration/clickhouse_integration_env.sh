@@ -19,13 +19,13 @@ ACTION=$1
 #
 
 start_podman () {
-  podman pod create --replace --name vector-test-integration-clickhouse -p 8123:8123
+  podman pod create --replace --name vector-test-integration-clickhouse -p 8123:8123 -p 9000:9000
   podman run -d --pod=vector-test-integration-clickhouse --name vector_clickhouse yandex/clickhouse-server:19
 }
 
 start_docker () {
   docker network create vector-test-integration-clickhouse
-  docker run -d --network=vector-test-integration-clickhouse -p 8123:8123 --name vector_clickhouse yandex/clickhouse-server:19
+  docker run -d --network=vector-test-integration-clickhouse -p 8123:8123 -p 9000:9000 --name vector_clickhouse yandex/clickhouse-server:19
 }
 
 stop_podman () {
@@ -0,0 +1,46 @@
+#!/usr/bin/env bash
+set -o pipefail
+
+# postgres_integration_env.sh
+#
+# SUMMARY
+#
+#   Builds and pulls down the Vector PostgreSQL sink Integration test environment
+
+if [ $# -ne 1 ]
+then
+    echo "Usage: $0 {stop|start}" 1>&2; exit 1;
+    exit 1
+fi
+ACTION=$1
+
+#
+# Functions
+#
+
+start_podman () {
+  podman pod create --replace --name vector-test-integration-postgres -p 5432:5432
+  podman run -d --pod=vector-test-integration-postgres --name vector_postgres \
+  --env POSTGRES_USER=vector --env POSTGRES_PASSWORD=vector postgres:13.1
+}
+
+start_docker () {
+  docker network create vector-test-integration-postgres
+  docker run -d --network=vector-test-integration-postgres -p 5432:5432 --name vector_postgres \
+  --env POSTGRES_USER=vector --env POSTGRES_PASSWORD=vector postgres:13.1
+}
+
+stop_podman () {
+  podman rm --force vector_postgres 2>/dev/null; true
+  podman pod stop vector-test-integration-postgres 2>/dev/null; true
+  podman pod rm --force vector-test-integration-postgres 2>/dev/null; true
+}
+
+stop_docker () {
+  docker rm --force vector_postgres 2>/dev/null; true
+  docker network rm vector-test-integration-postgres 2>/dev/null; true
+}
+
+echo "Running $ACTION action for PostgreSQL integration tests environment"
+
+"${ACTION}"_"${CONTAINER_TOOL}"
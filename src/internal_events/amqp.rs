@@ -0,0 +1,78 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct AmqpEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AmqpEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpConsumeFailed {
+    pub error: lapin::Error,
+}
+
+impl InternalEvent for AmqpConsumeFailed {
+    fn emit_logs(&self) {
+        error!(message = "Failed to consume message.", error = ?self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpAckFailed {
+    pub error: lapin::Error,
+}
+
+impl InternalEvent for AmqpAckFailed {
+    fn emit_logs(&self) {
+        error!(message = "Unable to acknowledge message.", error = ?self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("consumer_offset_updates_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpEventSendSuccess {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AmqpEventSendSuccess {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpDeliveryFailed {
+    pub error: String,
+}
+
+impl InternalEvent for AmqpDeliveryFailed {
+    fn emit_logs(&self) {
+        error!(message = "Failed to deliver message.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+    }
+}
@@ -0,0 +1,43 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ClickhouseTypeCoercionFailed<'a> {
+    pub column: &'a str,
+    pub column_type: &'a str,
+}
+
+impl<'a> InternalEvent for ClickhouseTypeCoercionFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Could not convert field to the type of its column.",
+            column = %self.column,
+            column_type = %self.column_type,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "type_conversion_failed");
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ClickhouseInvalidTableName<'a> {
+    pub table: &'a str,
+}
+
+impl<'a> InternalEvent for ClickhouseInvalidTableName<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rendered table name is not a valid identifier; discarding event.",
+            table = %self.table,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_table_name");
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -16,3 +16,35 @@ impl InternalEvent for ElasticSearchEventEncoded {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct ElasticSearchHitsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ElasticSearchHitsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received hits.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_in_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchSourceRequestFailed<'a> {
+    pub error: &'a crate::Error,
+}
+
+impl<'a> InternalEvent for ElasticSearchSourceRequestFailed<'a> {
+    fn emit_logs(&self) {
+        error!(message = "Search request failed.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_request_errors_total", 1);
+    }
+}
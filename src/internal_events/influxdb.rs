@@ -0,0 +1,84 @@
+use super::InternalEvent;
+use crate::sources::influxdb::parser::ParseError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct InfluxDbEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for InfluxDbEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received events.", count = %self.count, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_in_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct InfluxDbInvalidLine<'a> {
+    pub error: &'a ParseError,
+    pub text: &'a str,
+}
+
+impl InternalEvent for InfluxDbInvalidLine<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Invalid line protocol, discarding.",
+            error = %self.error,
+            text = %self.text,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("invalid_record_total", 1);
+        counter!("invalid_record_bytes_total", self.text.len() as u64);
+    }
+}
+
+#[derive(Debug)]
+enum InfluxDbSocketErrorType {
+    Bind,
+    Read,
+}
+
+#[derive(Debug)]
+pub struct InfluxDbSocketError<T> {
+    r#type: InfluxDbSocketErrorType,
+    pub error: T,
+}
+
+impl<T> InfluxDbSocketError<T> {
+    pub fn bind(error: T) -> Self {
+        Self {
+            r#type: InfluxDbSocketErrorType::Bind,
+            error,
+        }
+    }
+
+    pub fn read(error: T) -> Self {
+        Self {
+            r#type: InfluxDbSocketErrorType::Read,
+            error,
+        }
+    }
+}
+
+impl<T: std::fmt::Debug + std::fmt::Display> InternalEvent for InfluxDbSocketError<T> {
+    fn emit_logs(&self) {
+        let message = match self.r#type {
+            InfluxDbSocketErrorType::Bind => "Failed to bind to UDP listener socket.",
+            InfluxDbSocketErrorType::Read => "Failed to read UDP datagram.",
+        };
+        error!(message, error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
@@ -0,0 +1,48 @@
+use super::InternalEvent;
+use metrics::counter;
+#[cfg(feature = "sources-loki")]
+use std::error::Error;
+
+#[cfg(feature = "sinks-loki")]
+#[derive(Debug)]
+pub(crate) struct LokiLabelCardinalityLimited<'a> {
+    pub label: &'a str,
+    pub action: &'static str,
+}
+
+#[cfg(feature = "sinks-loki")]
+impl<'a> InternalEvent for LokiLabelCardinalityLimited<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Label cardinality limit reached; limiting label.",
+            label = %self.label,
+            action = %self.action,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("label_cardinality_limited_total", 1, "action" => self.action);
+    }
+}
+
+#[cfg(feature = "sources-loki")]
+#[derive(Debug)]
+pub(crate) struct LokiPushParseError<'a> {
+    pub error: &'a dyn Error,
+}
+
+#[cfg(feature = "sources-loki")]
+impl<'a> InternalEvent for LokiPushParseError<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Could not decode push request.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
@@ -0,0 +1,11 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct MetricRelabelEventDropped;
+
+impl InternalEvent for MetricRelabelEventDropped {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
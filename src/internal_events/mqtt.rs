@@ -0,0 +1,85 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct MqttEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for MqttEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttAckFailed {
+    pub error: rumqttc::ClientError,
+}
+
+impl InternalEvent for MqttAckFailed {
+    fn emit_logs(&self) {
+        error!(message = "Unable to acknowledge message.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "acknowledgment_failed",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttConnectionError {
+    pub error: rumqttc::ConnectionError,
+}
+
+impl InternalEvent for MqttConnectionError {
+    fn emit_logs(&self) {
+        error!(
+            message = "MQTT connection error; reconnecting.",
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttEventSendSuccess {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for MqttEventSendSuccess {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttEventSendFail {
+    pub error: rumqttc::ClientError,
+}
+
+impl InternalEvent for MqttEventSendFail {
+    fn emit_logs(&self) {
+        error!(message = "Failed to send message.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+    }
+}
@@ -47,3 +47,21 @@ impl InternalEvent for NatsEventSendFail {
         counter!("send_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct NatsAckFailed {
+    pub error: Error,
+}
+
+impl InternalEvent for NatsAckFailed {
+    fn emit_logs(&self) {
+        error!(message = "Unable to acknowledge message.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "acknowledgment_failed",
+        );
+    }
+}
@@ -0,0 +1,19 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct PostgresEventsSent {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for PostgresEventsSent {
+    fn emit_logs(&self) {
+        trace!(message = "Inserted events.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
@@ -5,6 +5,8 @@ use metrics::{counter, histogram};
 use prometheus_parser::ParserError;
 #[cfg(feature = "sources-prometheus")]
 use std::borrow::Cow;
+#[cfg(feature = "sources-prometheus")]
+use std::path::Path;
 use std::time::Instant;
 
 #[derive(Debug)]
@@ -104,6 +106,29 @@ impl InternalEvent for PrometheusHttpError {
     }
 }
 
+#[cfg(feature = "sources-prometheus")]
+#[derive(Debug)]
+pub struct PrometheusFileDiscoveryError<'a> {
+    pub path: &'a Path,
+    pub error: crate::Error,
+}
+
+#[cfg(feature = "sources-prometheus")]
+impl<'a> InternalEvent for PrometheusFileDiscoveryError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed reading targets file.",
+            path = ?self.path,
+            error = ?self.error,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("file_discovery_errors_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct PrometheusRemoteWriteParseError {
     pub error: prost::DecodeError,
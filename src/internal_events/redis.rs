@@ -55,3 +55,34 @@ impl InternalEvent for RedisSendEventFailed {
         counter!("send_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct RedisEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for RedisEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisAckFailed {
+    pub error: redis::RedisError,
+}
+
+impl InternalEvent for RedisAckFailed {
+    fn emit_logs(&self) {
+        error!(message = "Unable to acknowledge stream entry.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("consumer_offset_updates_failed_total", 1);
+    }
+}
@@ -0,0 +1,11 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct SloFlushed;
+
+impl InternalEvent for SloFlushed {
+    fn emit_metrics(&self) {
+        counter!("slo_flushes_total", 1);
+    }
+}
@@ -0,0 +1,118 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct WsConnectionEstablished;
+
+impl InternalEvent for WsConnectionEstablished {
+    fn emit_logs(&self) {
+        debug!(message = "Connected.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_established_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WsConnectionFailed<E> {
+    pub error: E,
+}
+
+impl<E> InternalEvent for WsConnectionFailed<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        error!(message = "Unable to connect.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WsConnectionShutdown;
+
+impl InternalEvent for WsConnectionShutdown {
+    fn emit_logs(&self) {
+        warn!(message = "Closed by the server.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_shutdown_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WsConnectionError<E> {
+    pub error: E,
+}
+
+impl<E> InternalEvent for WsConnectionError<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        error!(message = "Connection error.", error = %self.error, internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WsEventSent {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for WsEventSent {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Processed one event.",
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct WsEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for WsEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received events.", count = %self.count, internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_in_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct WsReceiveError<E> {
+    pub error: E,
+}
+
+impl<E> InternalEvent for WsReceiveError<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        warn!(message = "Error reading from connection.", error = %self.error, internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
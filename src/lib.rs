@@ -31,6 +31,8 @@ pub mod generate;
 pub mod wasm;
 #[macro_use]
 pub mod internal_events;
+#[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
+pub mod amqp;
 #[cfg(feature = "api")]
 pub mod api;
 pub mod app;
@@ -45,6 +47,10 @@ pub mod kafka;
 pub mod kubernetes;
 pub mod line_agg;
 pub mod list;
+#[cfg(any(feature = "sources-mqtt", feature = "sinks-mqtt"))]
+pub mod mqtt;
+#[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
+pub mod nats;
 pub(crate) mod pipeline;
 pub(crate) mod proto;
 pub mod providers;
@@ -0,0 +1,251 @@
+use crate::tls::{TlsConfig, TlsSettings};
+use openssl::pkcs12::Pkcs12;
+use rumqttc::{AsyncClient, EventLoop, Key, MqttOptions, QoS, TlsConfiguration, Transport};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::convert::TryFrom;
+
+// Capacity of the channel between the client and its event loop.
+const REQUEST_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Snafu)]
+enum MqttError {
+    #[snafu(display("TLS requires a `ca_file` to verify the broker against"))]
+    MissingCa,
+    #[snafu(display(
+        "TLS `verify_certificate` and `verify_hostname` can't be disabled, the broker is always verified"
+    ))]
+    VerificationUnsupported,
+    #[snafu(display("Could not export TLS certificate authorities: {}", source))]
+    ExportCa { source: openssl::error::ErrorStack },
+    #[snafu(display("Could not export TLS identity: {}", source))]
+    ExportIdentity { source: openssl::error::ErrorStack },
+}
+
+/// Connection options shared by the `mqtt` source and sink.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct MqttConfig {
+    pub(crate) host: String,
+    #[serde(default = "default_port")]
+    pub(crate) port: u16,
+    pub(crate) user: Option<String>,
+    pub(crate) password: Option<String>,
+    /// A random identifier is generated when unset.
+    pub(crate) client_id: Option<String>,
+    #[serde(default = "default_keep_alive")]
+    pub(crate) keep_alive: u16,
+    #[serde(default = "default_clean_session")]
+    pub(crate) clean_session: bool,
+    pub(crate) tls: Option<TlsConfig>,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_keep_alive() -> u16 {
+    60
+}
+
+fn default_clean_session() -> bool {
+    true
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".into(),
+            port: default_port(),
+            user: None,
+            password: None,
+            client_id: None,
+            keep_alive: default_keep_alive(),
+            clean_session: default_clean_session(),
+            tls: None,
+        }
+    }
+}
+
+impl MqttConfig {
+    /// Creates a client and the event loop driving its connection. No
+    /// connection is made until the event loop is first polled.
+    pub(crate) fn build_client(&self) -> crate::Result<(AsyncClient, EventLoop)> {
+        Ok(AsyncClient::new(self.options()?, REQUEST_CHANNEL_CAPACITY))
+    }
+
+    /// Like `build_client`, but incoming publishes are only acknowledged to
+    /// the broker through `AsyncClient::ack`.
+    pub(crate) fn build_manual_ack_client(&self) -> crate::Result<(AsyncClient, EventLoop)> {
+        let mut options = self.options()?;
+        options.set_manual_acks(true);
+        Ok(AsyncClient::new(options, REQUEST_CHANNEL_CAPACITY))
+    }
+
+    fn options(&self) -> crate::Result<MqttOptions> {
+        // Brokers disconnect existing connections sharing a client id, so
+        // components left without one don't share the same default. Random
+        // ids fit within the 23 characters every broker accepts.
+        let client_id = self
+            .client_id
+            .clone()
+            .unwrap_or_else(|| format!("vector-{:016x}", rand::random::<u64>()));
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options.set_keep_alive(self.keep_alive);
+        options.set_clean_session(self.clean_session);
+        if let (Some(user), Some(password)) = (&self.user, &self.password) {
+            options.set_credentials(user, password);
+        }
+
+        if let Some(tls) = &self.tls {
+            if tls.enabled.unwrap_or(false) {
+                if tls.options.verify_certificate == Some(false)
+                    || tls.options.verify_hostname == Some(false)
+                {
+                    return Err(MqttError::VerificationUnsupported.into());
+                }
+                let settings = TlsSettings::from_options(&Some(tls.options.clone()))?;
+                options.set_transport(Transport::Tls(to_rumqttc_tls(&settings)?));
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn to_rumqttc_tls(tls: &TlsSettings) -> crate::Result<TlsConfiguration> {
+    let mut ca = Vec::new();
+    for authority in tls.authorities_pem() {
+        ca.extend(authority.context(ExportCa)?);
+    }
+    if ca.is_empty() {
+        return Err(MqttError::MissingCa.into());
+    }
+
+    let client_auth = match tls.identity_pkcs12_der() {
+        Some((der, password)) => {
+            let identity = Pkcs12::from_der(&der)
+                .and_then(|pkcs12| pkcs12.parse(&password))
+                .context(ExportIdentity)?;
+            let crt = identity.cert.to_pem().context(ExportIdentity)?;
+            let key = identity
+                .pkey
+                .private_key_to_pem_pkcs8()
+                .context(ExportIdentity)?;
+            // `Key::ECC` is read as any PKCS#8 encoded key.
+            Some((crt, Key::ECC(key)))
+        }
+        None => None,
+    };
+
+    Ok(TlsConfiguration::Simple {
+        ca,
+        alpn: None,
+        client_auth,
+    })
+}
+
+/// The MQTT quality of service levels, configured by their number.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(try_from = "u8", into = "u8")]
+pub(crate) enum MqttQoS {
+    AtMostOnce,
+    #[derivative(Default)]
+    AtLeastOnce,
+    ExactlyOnce,
+}
+
+impl TryFrom<u8> for MqttQoS {
+    type Error = String;
+
+    fn try_from(qos: u8) -> Result<Self, Self::Error> {
+        match qos {
+            0 => Ok(Self::AtMostOnce),
+            1 => Ok(Self::AtLeastOnce),
+            2 => Ok(Self::ExactlyOnce),
+            qos => Err(format!("invalid QoS {}, expected 0, 1 or 2", qos)),
+        }
+    }
+}
+
+impl From<MqttQoS> for u8 {
+    fn from(qos: MqttQoS) -> Self {
+        match qos {
+            MqttQoS::AtMostOnce => 0,
+            MqttQoS::AtLeastOnce => 1,
+            MqttQoS::ExactlyOnce => 2,
+        }
+    }
+}
+
+impl From<MqttQoS> for QoS {
+    fn from(qos: MqttQoS) -> Self {
+        match qos {
+            MqttQoS::AtMostOnce => QoS::AtMostOnce,
+            MqttQoS::AtLeastOnce => QoS::AtLeastOnce,
+            MqttQoS::ExactlyOnce => QoS::ExactlyOnce,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        qos: MqttQoS,
+    }
+
+    #[test]
+    fn parse_qos() {
+        let parse = |s: &str| toml::from_str::<Wrapper>(s).map(|wrapper| wrapper.qos);
+
+        assert_eq!(parse("qos = 0").unwrap(), MqttQoS::AtMostOnce);
+        assert_eq!(parse("qos = 1").unwrap(), MqttQoS::AtLeastOnce);
+        assert_eq!(parse("qos = 2").unwrap(), MqttQoS::ExactlyOnce);
+        assert!(parse("qos = 3").is_err());
+    }
+
+    #[test]
+    fn generates_unique_client_ids() {
+        let config = MqttConfig::default();
+        let first = config.options().unwrap().client_id();
+        let second = config.options().unwrap().client_id();
+        assert!(first.starts_with("vector-"));
+        assert_eq!(first.len(), 23);
+        assert_ne!(first, second);
+
+        let config = MqttConfig {
+            client_id: Some("vector-edge-1".into()),
+            ..MqttConfig::default()
+        };
+        assert_eq!(config.options().unwrap().client_id(), "vector-edge-1");
+    }
+
+    #[test]
+    fn tls_requires_ca() {
+        let config = MqttConfig {
+            tls: Some(TlsConfig::enabled()),
+            ..MqttConfig::default()
+        };
+        assert!(config.build_client().is_err());
+
+        let config = MqttConfig {
+            tls: Some(TlsConfig::test_config()),
+            ..MqttConfig::default()
+        };
+        assert!(config.build_client().is_ok());
+    }
+
+    #[test]
+    fn tls_rejects_disabled_verification() {
+        let mut tls = TlsConfig::test_config();
+        tls.options.verify_hostname = Some(false);
+        let config = MqttConfig {
+            tls: Some(tls),
+            ..MqttConfig::default()
+        };
+        assert!(config.build_client().is_err());
+    }
+}
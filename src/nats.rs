@@ -0,0 +1,173 @@
+use crate::tls::TlsOptions;
+use nkeys::KeyPair;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::path::PathBuf;
+
+#[derive(Debug, Snafu)]
+enum NatsConfigError {
+    #[snafu(display("invalid nkey seed: {}", source))]
+    InvalidSeed { source: nkeys::error::Error },
+    #[snafu(display("TLS `key_pass` is not supported, the key file must be unencrypted"))]
+    KeyPassUnsupported,
+    #[snafu(display(
+        "TLS `verify_certificate` and `verify_hostname` can't be disabled, the server is always verified"
+    ))]
+    VerificationUnsupported,
+    #[snafu(display("TLS `crt_file` and `key_file` must be set together"))]
+    IncompleteIdentity,
+}
+
+/// Authentication strategies shared by the `nats` source and sink.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub(crate) enum NatsAuthConfig {
+    UserPassword { user: String, password: String },
+    Token { token: String },
+    CredentialsFile { path: PathBuf },
+    Nkey { nkey: String, seed: String },
+}
+
+impl NatsAuthConfig {
+    fn to_nats_options(&self) -> crate::Result<async_nats::Options> {
+        Ok(match self {
+            Self::UserPassword { user, password } => {
+                async_nats::Options::with_user_pass(user, password)
+            }
+            Self::Token { token } => async_nats::Options::with_token(token),
+            Self::CredentialsFile { path } => async_nats::Options::with_credentials(path),
+            Self::Nkey { nkey, seed } => {
+                let key_pair = KeyPair::from_seed(seed).context(InvalidSeed)?;
+                // Signing can only fail on a key pair without a seed, which
+                // `from_seed` never returns.
+                async_nats::Options::with_nkey(nkey, move |nonce| {
+                    key_pair.sign(nonce).expect("key pair has a seed")
+                })
+            }
+        })
+    }
+}
+
+/// Builds the client options for a connection, applying authentication and,
+/// when any TLS options are given, requiring TLS.
+pub(crate) fn to_nats_options(
+    connection_name: &str,
+    auth: Option<&NatsAuthConfig>,
+    tls: Option<&TlsOptions>,
+) -> crate::Result<async_nats::Options> {
+    let options = match auth {
+        Some(auth) => auth.to_nats_options()?,
+        None => async_nats::Options::new(),
+    };
+    // Set reconnect_buffer_size on the nats client to 0 bytes so that the
+    // client doesn't buffer internally (to avoid message loss).
+    let mut options = options.with_name(connection_name).reconnect_buffer_size(0);
+
+    if let Some(tls) = tls {
+        if tls.key_pass.is_some() {
+            return Err(NatsConfigError::KeyPassUnsupported.into());
+        }
+        if tls.verify_certificate == Some(false) || tls.verify_hostname == Some(false) {
+            return Err(NatsConfigError::VerificationUnsupported.into());
+        }
+
+        options = options.tls_required(true);
+        if let Some(ca_file) = &tls.ca_file {
+            options = options.add_root_certificate(ca_file);
+        }
+        match (&tls.crt_file, &tls.key_file) {
+            (Some(crt_file), Some(key_file)) => options = options.client_cert(crt_file, key_file),
+            (None, None) => {}
+            _ => return Err(NatsConfigError::IncompleteIdentity.into()),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        auth: NatsAuthConfig,
+    }
+
+    fn parse_auth(s: &str) -> NatsAuthConfig {
+        toml::from_str::<Wrapper>(s).unwrap().auth
+    }
+
+    #[test]
+    fn parse_auth_strategies() {
+        assert_eq!(
+            parse_auth(
+                r#"
+                auth.strategy = "user_password"
+                auth.user = "vector"
+                auth.password = "secret"
+                "#
+            ),
+            NatsAuthConfig::UserPassword {
+                user: "vector".into(),
+                password: "secret".into(),
+            }
+        );
+        assert_eq!(
+            parse_auth(
+                r#"
+                auth.strategy = "token"
+                auth.token = "secret"
+                "#
+            ),
+            NatsAuthConfig::Token {
+                token: "secret".into()
+            }
+        );
+        assert_eq!(
+            parse_auth(
+                r#"
+                auth.strategy = "credentials_file"
+                auth.path = "/etc/nats/vector.creds"
+                "#
+            ),
+            NatsAuthConfig::CredentialsFile {
+                path: "/etc/nats/vector.creds".into()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_nkey_seed() {
+        let auth = NatsAuthConfig::Nkey {
+            nkey: "UDXU4RCSJNZOIQHZNWXHXORDPRTGNJAHAHFRGZNEEJCPQTT2M7NLCNF4".into(),
+            seed: "not a seed".into(),
+        };
+        assert!(to_nats_options("vector", Some(&auth), None).is_err());
+
+        let seed = KeyPair::new_user().seed().unwrap();
+        let auth = NatsAuthConfig::Nkey {
+            nkey: KeyPair::from_seed(&seed).unwrap().public_key(),
+            seed,
+        };
+        assert!(to_nats_options("vector", Some(&auth), None).is_ok());
+    }
+
+    #[test]
+    fn rejects_tls_key_pass() {
+        let tls = TlsOptions {
+            key_pass: Some("secret".into()),
+            ..TlsOptions::default()
+        };
+        assert!(to_nats_options("vector", None, Some(&tls)).is_err());
+    }
+
+    #[test]
+    fn rejects_disabled_tls_verification() {
+        let tls = TlsOptions {
+            verify_certificate: Some(false),
+            ..TlsOptions::default()
+        };
+        assert!(to_nats_options("vector", None, Some(&tls)).is_err());
+    }
+}
@@ -1,21 +1,36 @@
+mod native;
+mod schema;
+
+use self::{
+    native::{ClickhouseNativeRetryLogic, NativeService},
+    schema::{DescribeRow, SchemaCache, TableSchema},
+};
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
     event::Event,
     http::{Auth, HttpClient, HttpError, MaybeAuth},
+    internal_events::{ClickhouseInvalidTableName, TemplateRenderingFailed},
     sinks::util::{
+        buffer::GZIP_FAST,
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpRetryLogic, HttpSink},
+        http::{HttpRetryLogic, HttpSink, PartitionHttpSink},
         retries::{RetryAction, RetryLogic},
-        sink, BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig, UriSerde,
+        BatchConfig, BatchSettings, BoxedRawValue, Compression, EncodedEvent, JsonArrayBuffer,
+        PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig, UriSerde,
     },
+    template::Template,
     tls::{TlsOptions, TlsSettings},
 };
 use bytes::Bytes;
-use futures::{FutureExt, SinkExt};
+use clickhouse_rs::{Options, Pool};
+use flate2::write::GzEncoder;
+use futures::{stream, FutureExt, SinkExt, StreamExt};
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
 use serde::{Deserialize, Serialize};
-use snafu::ResultExt;
+use snafu::{ResultExt, Snafu};
+use std::{io::Write, sync::Arc};
+use url::Url;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -23,10 +38,12 @@ pub struct ClickhouseConfig {
     // Deprecated name
     #[serde(alias = "host")]
     pub endpoint: UriSerde,
-    pub table: String,
+    pub table: Template,
     pub database: Option<String>,
     #[serde(default)]
     pub skip_unknown_fields: bool,
+    #[serde(default)]
+    pub coerce_types: bool,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(
@@ -56,6 +73,26 @@ pub enum Encoding {
     Default,
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("TLS is not supported by the native protocol"))]
+    NativeTls,
+    #[snafu(display("Only basic authentication is supported by the native protocol"))]
+    NativeBearerAuth,
+    #[snafu(display("Invalid native protocol endpoint: {}", source))]
+    NativeEndpoint { source: url::ParseError },
+}
+
+#[derive(Debug, Snafu)]
+enum DescribeError {
+    #[snafu(display("Describing table {} failed with status {}: {}", table, status, body))]
+    UnexpectedStatus {
+        table: String,
+        status: StatusCode,
+        body: String,
+    },
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "clickhouse")]
 impl SinkConfig for ClickhouseConfig {
@@ -63,6 +100,15 @@ impl SinkConfig for ClickhouseConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let config = ClickhouseConfig {
+            auth: self.auth.choose_one(&self.endpoint.auth)?,
+            ..self.clone()
+        };
+
+        if config.endpoint.uri.scheme_str() == Some("tcp") {
+            return config.build_native(cx);
+        }
+
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
             .timeout(1)
@@ -71,20 +117,18 @@ impl SinkConfig for ClickhouseConfig {
         let tls_settings = TlsSettings::from_options(&self.tls)?;
         let client = HttpClient::new(tls_settings, &cx.proxy)?;
 
-        let config = ClickhouseConfig {
-            auth: self.auth.choose_one(&self.endpoint.auth)?,
-            ..self.clone()
-        };
-
-        let sink = BatchedHttpSink::with_logic(
-            config.clone(),
-            Buffer::new(batch.size, self.compression),
+        let sink = PartitionHttpSink::with_retry_logic(
+            ClickhouseHttpSink {
+                config: config.clone(),
+                client: client.clone(),
+                schemas: SchemaCache::default(),
+            },
+            PartitionBuffer::new(JsonArrayBuffer::new(batch.size)),
             ClickhouseRetryLogic::default(),
             request,
             batch.timeout,
             client.clone(),
             cx.acker(),
-            sink::StdServiceLogic::default(),
         )
         .sink_map_err(|error| error!(message = "Fatal clickhouse sink error.", %error));
 
@@ -102,45 +146,204 @@ impl SinkConfig for ClickhouseConfig {
     }
 }
 
-#[async_trait::async_trait]
-impl HttpSink for ClickhouseConfig {
-    type Input = Vec<u8>;
-    type Output = Vec<u8>;
+impl ClickhouseConfig {
+    fn database(&self) -> &str {
+        self.database.as_deref().unwrap_or("default")
+    }
+
+    /// Builds a sink inserting over the native protocol, selected by a
+    /// `tcp://` endpoint. Values are always converted to the types of their
+    /// columns, since the protocol sends typed columns.
+    fn build_native(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if self.tls.is_some() {
+            return Err(BuildError::NativeTls.into());
+        }
+
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(10u64))
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+
+        let mut url = Url::parse(&self.endpoint.uri.to_string()).context(NativeEndpoint)?;
+        if url.port().is_none() {
+            url.set_port(Some(9000))
+                .map_err(|_| "Native protocol endpoint must have a host")?;
+        }
+        let mut options = Options::new(url).database(self.database());
+        match &self.auth {
+            Some(Auth::Basic { user, password }) => {
+                options = options.username(user).password(password);
+            }
+            Some(Auth::Bearer { .. }) => return Err(BuildError::NativeBearerAuth.into()),
+            None => {}
+        }
+        if self.compression != Compression::None {
+            options = options.with_compression();
+        }
+        let pool = Pool::new(options);
+
+        let service = request.service(
+            ClickhouseNativeRetryLogic,
+            NativeService::new(pool.clone(), self.database().to_owned()),
+        );
+        let buffer = PartitionBuffer::new(JsonArrayBuffer::new(batch.size));
+        let config = self.clone();
+
+        let sink = PartitionBatchSink::new(service, buffer, batch.timeout, cx.acker())
+            .with_flat_map(move |mut event: Event| {
+                let finalizers = event.metadata_mut().take_finalizers();
+                stream::iter(
+                    encode_event(&config, event).map(|item| EncodedEvent { item, finalizers }),
+                )
+                .map(Ok)
+            })
+            .sink_map_err(|error| error!(message = "Fatal clickhouse sink error.", %error));
+
+        let healthcheck = native::healthcheck(pool).boxed();
+
+        Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+}
+
+/// Renders the table the event is inserted into, and the event as a row of
+/// `JSONEachRow`.
+fn encode_event(
+    config: &ClickhouseConfig,
+    mut event: Event,
+) -> Option<PartitionInnerBuffer<serde_json::Value, String>> {
+    let table = match config.table.render_string(&event) {
+        Ok(table) => table,
+        Err(error) => {
+            emit!(TemplateRenderingFailed {
+                error,
+                field: Some("table"),
+                drop_event: true,
+            });
+            return None;
+        }
+    };
+    if !is_valid_table_name(&table) {
+        emit!(ClickhouseInvalidTableName { table: &table });
+        return None;
+    }
+
+    config.encoding.apply_rules(&mut event);
+    let log = event.into_log();
+    let row = serde_json::to_value(&log).expect("Events should be valid json!");
 
-    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        self.encoding.apply_rules(&mut event);
-        let log = event.into_log();
+    Some(PartitionInnerBuffer::new(row, table))
+}
+
+struct ClickhouseHttpSink {
+    config: ClickhouseConfig,
+    client: HttpClient,
+    schemas: SchemaCache,
+}
+
+impl ClickhouseHttpSink {
+    async fn schema(&self, table: &str) -> crate::Result<Arc<TableSchema>> {
+        let table = quote_table(self.config.database(), table);
+        if let Some(schema) = self.schemas.get(&table) {
+            return Ok(schema);
+        }
+
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair(
+                "query",
+                &format!("DESCRIBE TABLE {} FORMAT JSONEachRow", table),
+            )
+            .finish();
+        let mut uri = self.config.endpoint.uri.to_string();
+        if !uri.ends_with('/') {
+            uri.push('/');
+        }
+        let mut request = Request::get(format!("{}?{}", uri, query))
+            .body(Body::empty())
+            .unwrap();
+
+        if let Some(auth) = &self.config.auth {
+            auth.apply(&mut request);
+        }
+
+        // Failing to send is an `HttpError`, which the request is retried on.
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if status != StatusCode::OK {
+            return Err(DescribeError::UnexpectedStatus {
+                table,
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }
+            .into());
+        }
 
-        let mut body = serde_json::to_vec(&log).expect("Events should be valid json!");
-        body.push(b'\n');
+        let rows = body
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_slice::<DescribeRow>)
+            .collect::<Result<Vec<_>, _>>()?;
+        let schema = Arc::new(TableSchema::new(rows));
+        self.schemas.insert(table, Arc::clone(&schema));
+        Ok(schema)
+    }
+}
 
-        Some(body)
+#[async_trait::async_trait]
+impl HttpSink for ClickhouseHttpSink {
+    type Input = PartitionInnerBuffer<serde_json::Value, String>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, String>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        encode_event(&self.config, event)
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let database = if let Some(database) = &self.database {
-            database.as_str()
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (events, table) = output.into_parts();
+
+        let mut body = Vec::new();
+        if self.config.coerce_types {
+            let schema = self.schema(&table).await?;
+            for event in events {
+                let row = schema.coerce_json(&serde_json::from_str(event.get())?);
+                serde_json::to_writer(&mut body, &row)?;
+                body.push(b'\n');
+            }
         } else {
-            "default"
-        };
+            for event in events {
+                body.extend_from_slice(event.get().as_bytes());
+                body.push(b'\n');
+            }
+        }
 
         let uri = set_uri_query(
-            &self.endpoint.uri,
-            database,
-            &self.table,
-            self.skip_unknown_fields,
-        )
-        .expect("Unable to encode uri");
+            &self.config.endpoint.uri,
+            self.config.database(),
+            &table,
+            self.config.skip_unknown_fields,
+        )?;
 
         let mut builder = Request::post(&uri).header("Content-Type", "application/x-ndjson");
 
-        if let Some(ce) = self.compression.content_encoding() {
-            builder = builder.header("Content-Encoding", ce);
+        if let Compression::Gzip(level) = self.config.compression {
+            builder = builder.header("Content-Encoding", "gzip");
+
+            let level = level.unwrap_or(GZIP_FAST) as u32;
+            let mut encoder = GzEncoder::new(
+                Vec::with_capacity(body.len()),
+                flate2::Compression::new(level),
+            );
+            encoder.write_all(&body).expect("Writing to Vec can't fail");
+            body = encoder.finish().expect("Writing to Vec can't fail");
         }
 
-        let mut request = builder.body(events).unwrap();
+        let mut request = builder.body(body).unwrap();
 
-        if let Some(auth) = &self.auth {
+        if let Some(auth) = &self.config.auth {
             auth.apply(&mut request);
         }
 
@@ -165,14 +368,28 @@ async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Res
     }
 }
 
+/// Table names are rendered from events, so only plain identifiers are let
+/// into queries.
+fn is_valid_table_name(table: &str) -> bool {
+    !table.is_empty()
+        && table
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Quotes a table name, qualified by its database.
+fn quote_table(database: &str, table: &str) -> String {
+    let quote = |name: &str| name.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\".\"{}\"", quote(database), quote(table))
+}
+
 fn set_uri_query(uri: &Uri, database: &str, table: &str, skip_unknown: bool) -> crate::Result<Uri> {
     let query = url::form_urlencoded::Serializer::new(String::new())
         .append_pair(
             "query",
             format!(
-                "INSERT INTO \"{}\".\"{}\" FORMAT JSONEachRow",
-                database,
-                table.replace("\"", "\\\"")
+                "INSERT INTO {} FORMAT JSONEachRow",
+                quote_table(database, table)
             )
             .as_str(),
         )
@@ -235,6 +452,8 @@ impl RetryLogic for ClickhouseRetryLogic {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::LogEvent;
+    use std::convert::TryFrom;
 
     #[test]
     fn generate_config() {
@@ -272,6 +491,55 @@ mod tests {
         )
         .unwrap_err();
     }
+
+    #[test]
+    fn encode_event_renders_table() {
+        let config = ClickhouseConfig {
+            table: Template::try_from("logs_{{ tenant }}").unwrap(),
+            ..Default::default()
+        };
+
+        let mut event = LogEvent::from("hello");
+        event.insert("tenant", "acme");
+        let (row, table) = encode_event(&config, event.into()).unwrap().into_parts();
+        assert_eq!(table, "logs_acme");
+        assert_eq!(row["message"], "hello");
+
+        assert!(encode_event(&config, LogEvent::from("hello").into()).is_none());
+    }
+
+    #[test]
+    fn encode_event_rejects_invalid_tables() {
+        let config = ClickhouseConfig {
+            table: Template::try_from("logs_{{ tenant }}").unwrap(),
+            ..Default::default()
+        };
+
+        for tenant in &["acme\\\" FORMAT Values", "acme-1", "acme\" OR 1"] {
+            let mut event = LogEvent::from("hello");
+            event.insert("tenant", *tenant);
+            assert!(encode_event(&config, event.into()).is_none());
+        }
+    }
+
+    #[test]
+    fn quotes_backslashes() {
+        assert_eq!(quote_table("db", "a\\\"b"), r#""db"."a\\\"b""#);
+    }
+
+    #[tokio::test]
+    async fn native_rejects_tls() {
+        let config: ClickhouseConfig = toml::from_str(
+            r#"
+endpoint = "tcp://localhost:9000"
+table = "logs"
+[tls]
+verify_certificate = false"#,
+        )
+        .unwrap();
+
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
 }
 
 #[cfg(test)]
@@ -286,7 +554,7 @@ mod integration_tests {
     use futures::{future, stream};
     use serde_json::Value;
     use std::{
-        convert::Infallible,
+        convert::{Infallible, TryFrom},
         future::ready,
         net::SocketAddr,
         sync::{
@@ -307,7 +575,7 @@ mod integration_tests {
 
         let config = ClickhouseConfig {
             endpoint: host.parse().unwrap(),
-            table: table.clone(),
+            table: Template::try_from(table.as_str()).unwrap(),
             compression: Compression::None,
             batch: BatchConfig {
                 max_events: Some(1),
@@ -357,7 +625,7 @@ mod integration_tests {
 
         let config = ClickhouseConfig {
             endpoint: host.parse().unwrap(),
-            table: table.clone(),
+            table: Template::try_from(table.as_str()).unwrap(),
             skip_unknown_fields: true,
             compression: Compression::None,
             batch: BatchConfig {
@@ -408,7 +676,7 @@ mod integration_tests {
 
         let config = ClickhouseConfig {
             endpoint: host.parse().unwrap(),
-            table: table.clone(),
+            table: Template::try_from(table.as_str()).unwrap(),
             compression: Compression::None,
             encoding,
             batch: BatchConfig {
@@ -527,7 +795,7 @@ timestamp_format = "unix""#,
 
         let config = ClickhouseConfig {
             endpoint: host.parse().unwrap(),
-            table: table.clone(),
+            table: Template::try_from(table.as_str()).unwrap(),
             compression: Compression::None,
             batch: BatchConfig {
                 max_events: Some(1),
@@ -581,7 +849,7 @@ timestamp_format = "unix""#,
 
         let config = ClickhouseConfig {
             endpoint: host.parse().unwrap(),
-            table: gen_table(),
+            table: Template::try_from(gen_table()).unwrap(),
             batch: BatchConfig {
                 max_events: Some(1),
                 ..Default::default()
@@ -605,6 +873,166 @@ timestamp_format = "unix""#,
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Errored));
     }
 
+    #[tokio::test]
+    async fn insert_events_templated_table() {
+        trace_init();
+
+        let prefix = gen_table();
+        let host = String::from("http://localhost:8123");
+
+        let config = ClickhouseConfig {
+            endpoint: host.parse().unwrap(),
+            table: Template::try_from(format!("{}_{{{{ tenant }}}}", prefix)).unwrap(),
+            compression: Compression::None,
+            request: TowerRequestConfig {
+                retry_attempts: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let client = ClickhouseClient::new(host);
+        for tenant in &["a", "b"] {
+            client
+                .create_table(
+                    &format!("{}_{}", prefix, tenant),
+                    "host String, timestamp String, message String, tenant String",
+                )
+                .await;
+        }
+
+        let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (mut first, mut receiver) = make_event();
+        first.as_mut_log().insert("tenant", "a");
+        let (mut second, _) = make_event();
+        second.as_mut_log().insert("tenant", "b");
+
+        sink.run(stream::iter(vec![first.clone(), second.clone()]))
+            .await
+            .unwrap();
+
+        for (tenant, event) in &[("a", first), ("b", second)] {
+            let output = client.select_all(&format!("{}_{}", prefix, tenant)).await;
+            assert_eq!(1, output.rows);
+            let expected = serde_json::to_value(event.as_log()).unwrap();
+            assert_eq!(expected, output.data[0]);
+        }
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn insert_events_coerce_types() {
+        trace_init();
+
+        let table = gen_table();
+        let host = String::from("http://localhost:8123");
+
+        let config = ClickhouseConfig {
+            endpoint: host.parse().unwrap(),
+            table: Template::try_from(table.as_str()).unwrap(),
+            coerce_types: true,
+            compression: Compression::None,
+            batch: BatchConfig {
+                max_events: Some(1),
+                ..Default::default()
+            },
+            request: TowerRequestConfig {
+                retry_attempts: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let client = ClickhouseClient::new(host);
+        client.create_table(&table, TYPED_SCHEMA).await;
+
+        let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (input_event, mut receiver) = make_typed_event();
+
+        sink.run(stream::once(ready(input_event.clone())))
+            .await
+            .unwrap();
+
+        let output = client.select_all(&table).await;
+        assert_eq!(1, output.rows);
+        assert_eq!(typed_output(&input_event), output.data[0]);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn insert_events_native() {
+        trace_init();
+
+        let table = gen_table();
+
+        let config = ClickhouseConfig {
+            endpoint: "tcp://localhost:9000".parse().unwrap(),
+            table: Template::try_from(table.as_str()).unwrap(),
+            batch: BatchConfig {
+                max_events: Some(1),
+                ..Default::default()
+            },
+            request: TowerRequestConfig {
+                retry_attempts: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let client = ClickhouseClient::new(String::from("http://localhost:8123"));
+        client.create_table(&table, TYPED_SCHEMA).await;
+
+        let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+        healthcheck.await.unwrap();
+
+        let (input_event, mut receiver) = make_typed_event();
+
+        sink.run(stream::once(ready(input_event.clone())))
+            .await
+            .unwrap();
+
+        let output = client.select_all(&table).await;
+        assert_eq!(1, output.rows);
+        assert_eq!(typed_output(&input_event), output.data[0]);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    const TYPED_SCHEMA: &str = "host String, timestamp DateTime('UTC'), message String, \
+        count UInt32, tags Array(String), level LowCardinality(String), user Nullable(String)";
+
+    fn make_typed_event() -> (Event, BatchStatusReceiver) {
+        let (mut event, receiver) = make_event();
+        let log = event.as_mut_log();
+        log.insert("count", "42");
+        log.insert("tags", "single");
+        log.insert("level", "info");
+        (event, receiver)
+    }
+
+    fn typed_output(event: &Event) -> Value {
+        let log = event.as_log();
+        serde_json::json!({
+            "host": "example.com",
+            "timestamp": log
+                .get(log_schema().timestamp_key())
+                .unwrap()
+                .as_timestamp()
+                .unwrap()
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            "message": "raw log line",
+            "count": 42,
+            "tags": ["single"],
+            "level": "info",
+            "user": null,
+        })
+    }
+
     fn make_event() -> (Event, BatchStatusReceiver) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let mut event = LogEvent::from("raw log line").with_batch_notifier(&batch);
@@ -0,0 +1,429 @@
+//! Inserts over the native TCP protocol, which sends blocks of typed columns
+//! rather than rows of JSON text.
+
+use super::{
+    quote_table,
+    schema::{
+        datetime64_ticks, lookup, to_float, to_signed, to_text, to_timestamp, to_unsigned,
+        ColumnType, DescribeRow, SchemaCache, TableSchema,
+    },
+};
+use crate::{
+    internal_events::ClickhouseTypeCoercionFailed,
+    sinks::util::{
+        retries::{RetryAction, RetryLogic},
+        BoxedRawValue, PartitionInnerBuffer,
+    },
+};
+use chrono::{Date, DateTime, TimeZone};
+use chrono_tz::Tz;
+use clickhouse_rs::{
+    errors::Error,
+    types::{DateTimeType, SqlType},
+    Block, Pool,
+};
+use futures::future::BoxFuture;
+use serde_json::Value as JsonValue;
+use std::{
+    convert::TryFrom,
+    net::{Ipv4Addr, Ipv6Addr},
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::Service;
+
+pub(super) type Value = clickhouse_rs::types::Value;
+
+#[derive(Clone)]
+pub(super) struct NativeService {
+    pool: Pool,
+    database: String,
+    schemas: Arc<SchemaCache>,
+}
+
+impl NativeService {
+    pub(super) fn new(pool: Pool, database: String) -> Self {
+        Self {
+            pool,
+            database,
+            schemas: Arc::default(),
+        }
+    }
+
+    async fn insert(self, table: String, rows: Vec<JsonValue>) -> Result<(), Error> {
+        let mut handle = self.pool.get_handle().await?;
+        let table = quote_table(&self.database, &table);
+
+        let schema = match self.schemas.get(&table) {
+            Some(schema) => schema,
+            None => {
+                let block = handle
+                    .query(format!("DESCRIBE TABLE {}", table))
+                    .fetch_all()
+                    .await?;
+                let rows = block
+                    .rows()
+                    .map(|row| {
+                        Ok(DescribeRow {
+                            name: row.get("name")?,
+                            type_name: row.get("type")?,
+                            default_type: row.get("default_type")?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let schema = Arc::new(TableSchema::new(rows));
+                self.schemas.insert(table.clone(), Arc::clone(&schema));
+                schema
+            }
+        };
+
+        let block = build_block(&schema, &rows)?;
+        handle.insert(table, block).await
+    }
+}
+
+impl Service<PartitionInnerBuffer<Vec<BoxedRawValue>, String>> for NativeService {
+    type Response = ();
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PartitionInnerBuffer<Vec<BoxedRawValue>, String>) -> Self::Future {
+        let (events, table) = request.into_parts();
+        let service = self.clone();
+
+        Box::pin(async move {
+            let rows = events
+                .iter()
+                .map(|event| serde_json::from_str(event.get()))
+                .collect::<Result<Vec<JsonValue>, _>>()
+                .map_err(|error| Error::Other(error.to_string().into()))?;
+            service.insert(table, rows).await
+        })
+    }
+}
+
+/// Builds a block holding the columns that any of the events has a value
+/// for. Since a block has the same columns in every row, the events missing
+/// one get the default of its type, or null if the column is nullable.
+pub(super) fn build_block(schema: &TableSchema, rows: &[JsonValue]) -> Result<Block, Error> {
+    let columns = schema
+        .columns
+        .iter()
+        .filter(|column| {
+            rows.iter()
+                .any(|row| lookup(row, &column.name).map_or(false, |value| !value.is_null()))
+        })
+        .map(|column| match default_native(&column.type_) {
+            Some(default) => Ok((column, default)),
+            None => Err(Error::Other(
+                format!(
+                    "Column `{}` has type `{}`, which can't be inserted with the native protocol.",
+                    column.name, column.type_name
+                )
+                .into(),
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if columns.is_empty() {
+        return Err(Error::Other(
+            "None of the events have fields for the columns of the table.".into(),
+        ));
+    }
+
+    let mut block = Block::with_capacity(rows.len());
+    for row in rows {
+        let values = columns
+            .iter()
+            .map(|(column, default)| {
+                let value = match lookup(row, &column.name).filter(|value| !value.is_null()) {
+                    Some(value) => to_native(value, &column.type_).unwrap_or_else(|| {
+                        emit!(ClickhouseTypeCoercionFailed {
+                            column: &column.name,
+                            column_type: &column.type_name,
+                        });
+                        default.clone()
+                    }),
+                    None => default.clone(),
+                };
+                (column.name.clone(), value)
+            })
+            .collect::<Vec<_>>();
+        block.push(values)?;
+    }
+    Ok(block)
+}
+
+fn to_native(value: &JsonValue, type_: &ColumnType) -> Option<Value> {
+    Some(match type_ {
+        ColumnType::String | ColumnType::FixedString(_) => Value::from(to_text(value)?),
+        ColumnType::UInt8 => Value::UInt8(to_unsigned(value, u8::MAX.into())? as u8),
+        ColumnType::UInt16 => Value::UInt16(to_unsigned(value, u16::MAX.into())? as u16),
+        ColumnType::UInt32 => Value::UInt32(to_unsigned(value, u32::MAX.into())? as u32),
+        ColumnType::UInt64 => Value::UInt64(to_unsigned(value, u64::MAX)?),
+        ColumnType::Int8 => Value::Int8(to_signed(value, i8::MIN.into(), i8::MAX.into())? as i8),
+        ColumnType::Int16 => {
+            Value::Int16(to_signed(value, i16::MIN.into(), i16::MAX.into())? as i16)
+        }
+        ColumnType::Int32 => {
+            Value::Int32(to_signed(value, i32::MIN.into(), i32::MAX.into())? as i32)
+        }
+        ColumnType::Int64 => Value::Int64(to_signed(value, i64::MIN, i64::MAX)?),
+        ColumnType::Float32 => Value::Float32(to_float(value)? as f32),
+        ColumnType::Float64 => Value::Float64(to_float(value)?),
+        ColumnType::Date => {
+            let days = to_timestamp(value)?.timestamp().div_euclid(86_400);
+            Value::Date(u16::try_from(days).ok()?, Tz::UTC)
+        }
+        ColumnType::DateTime => {
+            let seconds = to_timestamp(value)?.timestamp();
+            Value::DateTime(u32::try_from(seconds).ok()?, Tz::UTC)
+        }
+        ColumnType::DateTime64(precision) => Value::DateTime64(
+            datetime64_ticks(to_timestamp(value)?, *precision),
+            (*precision, Tz::UTC),
+        ),
+        ColumnType::Uuid => {
+            let uuid = uuid::Uuid::parse_str(value.as_str()?).ok()?;
+            // Each half is sent as a little endian integer.
+            let mut bytes = *uuid.as_bytes();
+            bytes[..8].reverse();
+            bytes[8..].reverse();
+            Value::Uuid(bytes)
+        }
+        ColumnType::Ipv4 => {
+            let mut octets = value.as_str()?.parse::<Ipv4Addr>().ok()?.octets();
+            octets.reverse();
+            Value::Ipv4(octets)
+        }
+        ColumnType::Ipv6 => Value::Ipv6(value.as_str()?.parse::<Ipv6Addr>().ok()?.octets()),
+        ColumnType::Nullable(inner) => nullable(to_native(value, inner)?)?,
+        ColumnType::LowCardinality(inner) => to_native(value, inner)?,
+        ColumnType::Array(inner) => {
+            let values = match value {
+                JsonValue::Array(values) => values
+                    .iter()
+                    .map(|value| to_native(value, inner))
+                    .collect::<Option<Vec<_>>>()?,
+                value => vec![to_native(value, inner)?],
+            };
+            Value::Array(sql_type(inner)?.into(), Arc::new(values))
+        }
+        ColumnType::Other(_) => return None,
+    })
+}
+
+/// The value of a column that the event has no field for, or `None` if the
+/// type of the column isn't supported.
+fn default_native(type_: &ColumnType) -> Option<Value> {
+    Some(match type_ {
+        ColumnType::String => Value::from(""),
+        ColumnType::FixedString(length) => Value::from(vec![0; *length]),
+        ColumnType::UInt8 => Value::UInt8(0),
+        ColumnType::UInt16 => Value::UInt16(0),
+        ColumnType::UInt32 => Value::UInt32(0),
+        ColumnType::UInt64 => Value::UInt64(0),
+        ColumnType::Int8 => Value::Int8(0),
+        ColumnType::Int16 => Value::Int16(0),
+        ColumnType::Int32 => Value::Int32(0),
+        ColumnType::Int64 => Value::Int64(0),
+        ColumnType::Float32 => Value::Float32(0.0),
+        ColumnType::Float64 => Value::Float64(0.0),
+        ColumnType::Date => Value::Date(0, Tz::UTC),
+        ColumnType::DateTime => Value::DateTime(0, Tz::UTC),
+        ColumnType::DateTime64(precision) => Value::DateTime64(0, (*precision, Tz::UTC)),
+        ColumnType::Uuid => Value::Uuid([0; 16]),
+        ColumnType::Ipv4 => Value::Ipv4([0; 4]),
+        ColumnType::Ipv6 => Value::Ipv6([0; 16]),
+        ColumnType::Nullable(inner) => null_native(inner)?,
+        ColumnType::LowCardinality(inner) => default_native(inner)?,
+        ColumnType::Array(inner) => Value::Array(sql_type(inner)?.into(), Arc::new(Vec::new())),
+        ColumnType::Other(_) => return None,
+    })
+}
+
+/// Wraps a value in `Nullable`. The client library only builds nullable
+/// values through `Option`, which limits them to these types.
+fn nullable(value: Value) -> Option<Value> {
+    Some(match value {
+        Value::UInt8(value) => Some(value).into(),
+        Value::UInt16(value) => Some(value).into(),
+        Value::UInt32(value) => Some(value).into(),
+        Value::UInt64(value) => Some(value).into(),
+        Value::Int8(value) => Some(value).into(),
+        Value::Int16(value) => Some(value).into(),
+        Value::Int32(value) => Some(value).into(),
+        Value::Int64(value) => Some(value).into(),
+        Value::Float32(value) => Some(value).into(),
+        Value::Float64(value) => Some(value).into(),
+        Value::String(bytes) => Some(String::from_utf8(bytes.to_vec()).ok()?).into(),
+        Value::Date(days, tz) => Some(date(days, tz)).into(),
+        Value::DateTime(seconds, tz) => Some(tz.timestamp(i64::from(seconds), 0)).into(),
+        _ => return None,
+    })
+}
+
+fn null_native(type_: &ColumnType) -> Option<Value> {
+    Some(match type_ {
+        ColumnType::String => None::<String>.into(),
+        ColumnType::UInt8 => None::<u8>.into(),
+        ColumnType::UInt16 => None::<u16>.into(),
+        ColumnType::UInt32 => None::<u32>.into(),
+        ColumnType::UInt64 => None::<u64>.into(),
+        ColumnType::Int8 => None::<i8>.into(),
+        ColumnType::Int16 => None::<i16>.into(),
+        ColumnType::Int32 => None::<i32>.into(),
+        ColumnType::Int64 => None::<i64>.into(),
+        ColumnType::Float32 => None::<f32>.into(),
+        ColumnType::Float64 => None::<f64>.into(),
+        ColumnType::Date => None::<Date<Tz>>.into(),
+        ColumnType::DateTime => None::<DateTime<Tz>>.into(),
+        ColumnType::LowCardinality(inner) => null_native(inner)?,
+        _ => return None,
+    })
+}
+
+fn date(days: u16, tz: Tz) -> Date<Tz> {
+    tz.timestamp(i64::from(days) * 86_400, 0).date()
+}
+
+fn sql_type(type_: &ColumnType) -> Option<SqlType> {
+    Some(match type_ {
+        ColumnType::String => SqlType::String,
+        ColumnType::FixedString(length) => SqlType::FixedString(*length),
+        ColumnType::UInt8 => SqlType::UInt8,
+        ColumnType::UInt16 => SqlType::UInt16,
+        ColumnType::UInt32 => SqlType::UInt32,
+        ColumnType::UInt64 => SqlType::UInt64,
+        ColumnType::Int8 => SqlType::Int8,
+        ColumnType::Int16 => SqlType::Int16,
+        ColumnType::Int32 => SqlType::Int32,
+        ColumnType::Int64 => SqlType::Int64,
+        ColumnType::Float32 => SqlType::Float32,
+        ColumnType::Float64 => SqlType::Float64,
+        ColumnType::Date => SqlType::Date,
+        ColumnType::DateTime => SqlType::DateTime(DateTimeType::DateTime32),
+        ColumnType::DateTime64(precision) => {
+            SqlType::DateTime(DateTimeType::DateTime64(*precision, Tz::UTC))
+        }
+        ColumnType::Uuid => SqlType::Uuid,
+        ColumnType::Ipv4 => SqlType::Ipv4,
+        ColumnType::Ipv6 => SqlType::Ipv6,
+        ColumnType::Nullable(inner) => SqlType::Nullable(sql_type(inner)?.into()),
+        ColumnType::LowCardinality(inner) => sql_type(inner)?,
+        ColumnType::Array(inner) => SqlType::Array(sql_type(inner)?.into()),
+        ColumnType::Other(_) => return None,
+    })
+}
+
+#[derive(Debug, Default, Clone)]
+pub(super) struct ClickhouseNativeRetryLogic;
+
+impl RetryLogic for ClickhouseNativeRetryLogic {
+    type Error = Error;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            Error::Io(_) | Error::Connection(_) | Error::Driver(_) => true,
+            // Reference: https://github.com/ClickHouse/ClickHouse/blob/master/src/Common/ErrorCodes.cpp
+            //
+            // 159: TIMEOUT_EXCEEDED, 202: TOO_MANY_SIMULTANEOUS_QUERIES,
+            // 209: SOCKET_TIMEOUT, 210: NETWORK_ERROR, 252: TOO_MANY_PARTS
+            Error::Server(error) => matches!(error.code, 159 | 202 | 209 | 210 | 252),
+            _ => false,
+        }
+    }
+
+    fn should_retry_response(&self, _response: &Self::Response) -> RetryAction {
+        RetryAction::Successful
+    }
+}
+
+pub(super) async fn healthcheck(pool: Pool) -> crate::Result<()> {
+    pool.get_handle().await?.ping().await.map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(columns: &[(&str, &str)]) -> TableSchema {
+        TableSchema::new(columns.iter().map(|(name, type_name)| DescribeRow {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            default_type: String::new(),
+        }))
+    }
+
+    #[test]
+    fn converts_values() {
+        assert_eq!(
+            to_native(&json!("2021-08-01T12:30:00Z"), &ColumnType::DateTime),
+            Some(Value::DateTime(1627821000, Tz::UTC))
+        );
+        // `Value` doesn't compare `DateTime64` or `IPv4` values.
+        assert!(matches!(
+            to_native(&json!(1627821000.5), &ColumnType::DateTime64(3)),
+            Some(Value::DateTime64(1627821000500, (3, _)))
+        ));
+        assert!(matches!(
+            to_native(&json!("10.0.0.1"), &ColumnType::Ipv4),
+            Some(Value::Ipv4([1, 0, 0, 10]))
+        ));
+        assert_eq!(to_native(&json!(300), &ColumnType::UInt8), None);
+        assert_eq!(
+            to_native(
+                &json!(["a", 1]),
+                &ColumnType::Array(Box::new(ColumnType::String))
+            ),
+            Some(Value::Array(
+                SqlType::String.into(),
+                Arc::new(vec![Value::from("a"), Value::from("1")])
+            ))
+        );
+        assert_eq!(
+            to_native(
+                &json!("a"),
+                &ColumnType::LowCardinality(Box::new(ColumnType::Nullable(Box::new(
+                    ColumnType::String
+                ))))
+            ),
+            Some(Some("a").into())
+        );
+    }
+
+    #[test]
+    fn builds_blocks() {
+        let schema = schema(&[
+            ("message", "String"),
+            ("count", "UInt32"),
+            ("host", "Nullable(String)"),
+            ("unused", "Decimal(9, 2)"),
+        ]);
+        let rows = vec![
+            json!({ "message": "one", "count": 1, "host": "a" }),
+            json!({ "message": "two", "count": "not a number" }),
+        ];
+
+        let block = build_block(&schema, &rows).unwrap();
+        assert_eq!(block.row_count(), 2);
+        assert_eq!(block.column_count(), 3);
+
+        let row = block.rows().nth(1).unwrap();
+        assert_eq!(row.get::<String, _>("message").unwrap(), "two");
+        assert_eq!(row.get::<u32, _>("count").unwrap(), 0);
+        assert_eq!(row.get::<Option<String>, _>("host").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_unsupported_columns() {
+        let schema = schema(&[("amount", "Decimal(9, 2)")]);
+        build_block(&schema, &[json!({ "amount": 1.5 })]).unwrap_err();
+    }
+}
@@ -0,0 +1,454 @@
+//! Table schemas, as described by `DESCRIBE TABLE`, and the coercion of event
+//! fields to the types of their columns.
+
+use crate::internal_events::ClickhouseTypeCoercionFailed;
+use chrono::{DateTime, TimeZone, Utc};
+use lru::LruCache;
+use serde::Deserialize;
+use serde_json::{Map, Number, Value};
+use std::sync::{Arc, Mutex};
+
+/// The type of a column, with the parameters coercion needs.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum ColumnType {
+    String,
+    FixedString(usize),
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Date,
+    DateTime,
+    DateTime64(u32),
+    Uuid,
+    Ipv4,
+    Ipv6,
+    Nullable(Box<ColumnType>),
+    LowCardinality(Box<ColumnType>),
+    Array(Box<ColumnType>),
+    /// Types whose values are passed on unchanged, such as `Decimal`, `Enum8`
+    /// or `Map`.
+    Other(String),
+}
+
+impl ColumnType {
+    pub(super) fn parse(type_name: &str) -> Self {
+        let type_name = type_name.trim();
+        let (name, args) = match type_name.find('(') {
+            Some(start) if type_name.ends_with(')') => (
+                &type_name[..start],
+                Some(&type_name[start + 1..type_name.len() - 1]),
+            ),
+            _ => (type_name, None),
+        };
+
+        match (name, args) {
+            ("String", None) => Self::String,
+            ("FixedString", Some(length)) => match length.trim().parse() {
+                Ok(length) => Self::FixedString(length),
+                Err(_) => Self::Other(type_name.to_owned()),
+            },
+            ("UInt8", None) => Self::UInt8,
+            ("UInt16", None) => Self::UInt16,
+            ("UInt32", None) => Self::UInt32,
+            ("UInt64", None) => Self::UInt64,
+            ("Int8", None) => Self::Int8,
+            ("Int16", None) => Self::Int16,
+            ("Int32", None) => Self::Int32,
+            ("Int64", None) => Self::Int64,
+            ("Float32", None) => Self::Float32,
+            ("Float64", None) => Self::Float64,
+            ("Date", None) => Self::Date,
+            // The time zone only affects how values are displayed.
+            ("DateTime", _) => Self::DateTime,
+            ("DateTime64", Some(args)) => match args.split(',').next().unwrap_or("").trim().parse()
+            {
+                Ok(precision) if precision <= 9 => Self::DateTime64(precision),
+                _ => Self::Other(type_name.to_owned()),
+            },
+            ("UUID", None) => Self::Uuid,
+            ("IPv4", None) => Self::Ipv4,
+            ("IPv6", None) => Self::Ipv6,
+            ("Nullable", Some(inner)) => Self::Nullable(Box::new(Self::parse(inner))),
+            ("LowCardinality", Some(inner)) => Self::LowCardinality(Box::new(Self::parse(inner))),
+            ("Array", Some(inner)) => Self::Array(Box::new(Self::parse(inner))),
+            _ => Self::Other(type_name.to_owned()),
+        }
+    }
+
+    pub(super) fn is_nullable(&self) -> bool {
+        match self {
+            Self::Nullable(_) => true,
+            Self::LowCardinality(inner) => inner.is_nullable(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Column {
+    pub(super) name: String,
+    pub(super) type_name: String,
+    pub(super) type_: ColumnType,
+}
+
+/// A row of the output of `DESCRIBE TABLE`.
+#[derive(Debug, Deserialize)]
+pub(super) struct DescribeRow {
+    pub(super) name: String,
+    #[serde(rename = "type")]
+    pub(super) type_name: String,
+    #[serde(default)]
+    pub(super) default_type: String,
+}
+
+/// The columns of a table that can be inserted into.
+#[derive(Debug, Default)]
+pub(super) struct TableSchema {
+    pub(super) columns: Vec<Column>,
+}
+
+impl TableSchema {
+    pub(super) fn new(rows: impl IntoIterator<Item = DescribeRow>) -> Self {
+        let columns = rows
+            .into_iter()
+            // The server computes these columns itself and refuses values for them.
+            .filter(|row| row.default_type != "MATERIALIZED" && row.default_type != "ALIAS")
+            .map(|row| Column {
+                type_: ColumnType::parse(&row.type_name),
+                name: row.name,
+                type_name: row.type_name,
+            })
+            .collect();
+        Self { columns }
+    }
+
+    /// Builds the `JSONEachRow` row for an event. Columns the event has no
+    /// value for, or a value that can't be coerced to their type, are left
+    /// out for the server to fill in with their default.
+    pub(super) fn coerce_json(&self, event: &Value) -> Map<String, Value> {
+        let mut row = Map::new();
+        for column in &self.columns {
+            let value = match lookup(event, &column.name) {
+                Some(value) if !value.is_null() || column.type_.is_nullable() => value,
+                _ => continue,
+            };
+            match coerce_json(value, &column.type_) {
+                Some(value) => {
+                    row.insert(column.name.clone(), value);
+                }
+                None => emit!(ClickhouseTypeCoercionFailed {
+                    column: &column.name,
+                    column_type: &column.type_name,
+                }),
+            }
+        }
+        row
+    }
+}
+
+/// How many table schemas are kept, bounding the cache when the table is
+/// templated.
+const SCHEMA_CACHE_CAPACITY: usize = 256;
+
+/// The schemas of the most recently written to tables, looked up on first use.
+pub(super) struct SchemaCache(Mutex<LruCache<String, Arc<TableSchema>>>);
+
+impl Default for SchemaCache {
+    fn default() -> Self {
+        Self(Mutex::new(LruCache::new(SCHEMA_CACHE_CAPACITY)))
+    }
+}
+
+impl SchemaCache {
+    pub(super) fn get(&self, table: &str) -> Option<Arc<TableSchema>> {
+        self.0
+            .lock()
+            .expect("schema cache lock poisoned")
+            .get(table)
+            .cloned()
+    }
+
+    pub(super) fn insert(&self, table: String, schema: Arc<TableSchema>) {
+        self.0
+            .lock()
+            .expect("schema cache lock poisoned")
+            .put(table, schema);
+    }
+}
+
+/// Finds the value for a column, either under its name or, for names with
+/// dots, as a path into the nested objects of the event.
+pub(super) fn lookup<'a>(event: &'a Value, name: &str) -> Option<&'a Value> {
+    event
+        .get(name)
+        .or_else(|| name.split('.').try_fold(event, |value, key| value.get(key)))
+}
+
+fn coerce_json(value: &Value, type_: &ColumnType) -> Option<Value> {
+    match type_ {
+        ColumnType::Nullable(inner) => match value {
+            Value::Null => Some(Value::Null),
+            value => coerce_json(value, inner),
+        },
+        ColumnType::LowCardinality(inner) => coerce_json(value, inner),
+        ColumnType::Array(inner) => match value {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| coerce_json(value, inner))
+                .collect::<Option<_>>()
+                .map(Value::Array),
+            value => coerce_json(value, inner).map(|value| Value::Array(vec![value])),
+        },
+        ColumnType::String
+        | ColumnType::FixedString(_)
+        | ColumnType::Uuid
+        | ColumnType::Ipv4
+        | ColumnType::Ipv6 => to_text(value).map(Value::String),
+        ColumnType::UInt8 => to_unsigned(value, u8::MAX.into()).map(Value::from),
+        ColumnType::UInt16 => to_unsigned(value, u16::MAX.into()).map(Value::from),
+        ColumnType::UInt32 => to_unsigned(value, u32::MAX.into()).map(Value::from),
+        ColumnType::UInt64 => to_unsigned(value, u64::MAX).map(Value::from),
+        ColumnType::Int8 => to_signed(value, i8::MIN.into(), i8::MAX.into()).map(Value::from),
+        ColumnType::Int16 => to_signed(value, i16::MIN.into(), i16::MAX.into()).map(Value::from),
+        ColumnType::Int32 => to_signed(value, i32::MIN.into(), i32::MAX.into()).map(Value::from),
+        ColumnType::Int64 => to_signed(value, i64::MIN, i64::MAX).map(Value::from),
+        ColumnType::Float32 | ColumnType::Float64 => to_float(value)
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        ColumnType::Date => coerce_time(value, |timestamp| {
+            Value::String(timestamp.format("%Y-%m-%d").to_string())
+        }),
+        ColumnType::DateTime => coerce_time(value, |timestamp| Value::from(timestamp.timestamp())),
+        ColumnType::DateTime64(precision) => coerce_time(value, |timestamp| {
+            Value::String(format_datetime64(timestamp, *precision))
+        }),
+        ColumnType::Other(_) => Some(value.clone()),
+    }
+}
+
+/// Converts timestamps with `format`, leaving strings in other formats for
+/// the server to parse.
+fn coerce_time(value: &Value, format: impl Fn(DateTime<Utc>) -> Value) -> Option<Value> {
+    match (to_timestamp(value), value) {
+        (Some(timestamp), _) => Some(format(timestamp)),
+        (None, Value::String(_)) => Some(value.clone()),
+        (None, _) => None,
+    }
+}
+
+/// Reads RFC 3339 strings, and numbers of seconds since the Unix epoch.
+pub(super) fn to_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        Value::Number(number) => match number.as_i64() {
+            Some(seconds) => Utc.timestamp_opt(seconds, 0).single(),
+            None => {
+                let seconds = number.as_f64()?;
+                let nanos = ((seconds - seconds.floor()) * 1e9) as u32;
+                Utc.timestamp_opt(seconds.floor() as i64, nanos.min(999_999_999))
+                    .single()
+            }
+        },
+        _ => None,
+    }
+}
+
+/// The number of `10^-precision` second ticks since the Unix epoch.
+pub(super) fn datetime64_ticks(timestamp: DateTime<Utc>, precision: u32) -> i64 {
+    timestamp.timestamp() * 10i64.pow(precision)
+        + i64::from(timestamp.timestamp_subsec_nanos()) / 10i64.pow(9 - precision)
+}
+
+fn format_datetime64(timestamp: DateTime<Utc>, precision: u32) -> String {
+    if precision == 0 {
+        return timestamp.timestamp().to_string();
+    }
+    let fraction = i64::from(timestamp.timestamp_subsec_nanos()) / 10i64.pow(9 - precision);
+    format!(
+        "{}.{:0width$}",
+        timestamp.timestamp(),
+        fraction,
+        width = precision as usize
+    )
+}
+
+pub(super) fn to_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Array(_) | Value::Object(_) => serde_json::to_string(value).ok(),
+    }
+}
+
+pub(super) fn to_unsigned(value: &Value, max: u64) -> Option<u64> {
+    let number = match value {
+        Value::Number(number) => number.as_u64().or_else(|| {
+            number
+                .as_f64()
+                .filter(|float| float.fract() == 0.0 && *float >= 0.0 && *float <= u64::MAX as f64)
+                .map(|float| float as u64)
+        }),
+        Value::String(text) => text.trim().parse().ok(),
+        Value::Bool(boolean) => Some(u64::from(*boolean)),
+        _ => None,
+    }?;
+    Some(number).filter(|number| *number <= max)
+}
+
+pub(super) fn to_signed(value: &Value, min: i64, max: i64) -> Option<i64> {
+    let number = match value {
+        Value::Number(number) => number.as_i64().or_else(|| {
+            number
+                .as_f64()
+                .filter(|float| {
+                    float.fract() == 0.0 && *float >= i64::MIN as f64 && *float <= i64::MAX as f64
+                })
+                .map(|float| float as i64)
+        }),
+        Value::String(text) => text.trim().parse().ok(),
+        Value::Bool(boolean) => Some(i64::from(*boolean)),
+        _ => None,
+    }?;
+    Some(number).filter(|number| (min..=max).contains(number))
+}
+
+pub(super) fn to_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        Value::Bool(boolean) => Some(f64::from(u8::from(*boolean))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(columns: &[(&str, &str, &str)]) -> TableSchema {
+        TableSchema::new(
+            columns
+                .iter()
+                .map(|(name, type_name, default_type)| DescribeRow {
+                    name: name.to_string(),
+                    type_name: type_name.to_string(),
+                    default_type: default_type.to_string(),
+                }),
+        )
+    }
+
+    #[test]
+    fn parses_column_types() {
+        assert_eq!(ColumnType::parse("String"), ColumnType::String);
+        assert_eq!(
+            ColumnType::parse("FixedString(16)"),
+            ColumnType::FixedString(16)
+        );
+        assert_eq!(ColumnType::parse("DateTime('UTC')"), ColumnType::DateTime);
+        assert_eq!(
+            ColumnType::parse("DateTime64(3, 'Europe/Paris')"),
+            ColumnType::DateTime64(3)
+        );
+        assert_eq!(
+            ColumnType::parse("Array(Nullable(UInt8))"),
+            ColumnType::Array(Box::new(ColumnType::Nullable(Box::new(ColumnType::UInt8))))
+        );
+        assert_eq!(
+            ColumnType::parse("LowCardinality(Nullable(String))"),
+            ColumnType::LowCardinality(Box::new(ColumnType::Nullable(Box::new(
+                ColumnType::String
+            ))))
+        );
+        assert_eq!(
+            ColumnType::parse("Decimal(9, 2)"),
+            ColumnType::Other("Decimal(9, 2)".into())
+        );
+        assert!(ColumnType::parse("LowCardinality(Nullable(String))").is_nullable());
+    }
+
+    #[test]
+    fn skips_computed_columns() {
+        let schema = schema(&[
+            ("message", "String", ""),
+            ("length", "UInt64", "MATERIALIZED"),
+            ("host", "String", "DEFAULT"),
+        ]);
+        let names: Vec<_> = schema.columns.iter().map(|column| &column.name).collect();
+        assert_eq!(names, vec!["message", "host"]);
+    }
+
+    #[test]
+    fn coerces_json_rows() {
+        let schema = schema(&[
+            ("message", "LowCardinality(String)", ""),
+            ("count", "UInt8", ""),
+            ("ratio", "Float64", ""),
+            ("timestamp", "DateTime('UTC')", ""),
+            ("precise", "DateTime64(3)", ""),
+            ("day", "Date", ""),
+            ("tags", "Array(String)", ""),
+            ("labels.app", "String", ""),
+            ("missing", "Nullable(String)", ""),
+            ("nothing", "Nullable(String)", ""),
+            ("status", "Int32", ""),
+        ]);
+        let event = json!({
+            "message": 42,
+            "count": "7",
+            "ratio": "0.5",
+            "timestamp": "2021-08-01T12:30:00Z",
+            "precise": "2021-08-01T12:30:00.123456Z",
+            "day": 1627821000,
+            "tags": "single",
+            "labels": { "app": "vector" },
+            "nothing": null,
+            "status": "not a number",
+        });
+
+        assert_eq!(
+            Value::Object(schema.coerce_json(&event)),
+            json!({
+                "message": "42",
+                "count": 7,
+                "ratio": 0.5,
+                "timestamp": 1627821000,
+                "precise": "1627821000.123",
+                "day": "2021-08-01",
+                "tags": ["single"],
+                "labels.app": "vector",
+                "nothing": null,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_integers() {
+        assert_eq!(to_unsigned(&json!(256), u8::MAX.into()), None);
+        assert_eq!(to_unsigned(&json!(-1), u64::MAX), None);
+        assert_eq!(
+            to_signed(&json!(2.0), i8::MIN.into(), i8::MAX.into()),
+            Some(2)
+        );
+        assert_eq!(to_signed(&json!(2.5), i8::MIN.into(), i8::MAX.into()), None);
+    }
+
+    #[test]
+    fn passes_unparsed_times_through() {
+        assert_eq!(
+            coerce_json(&json!("2021-08-01 12:30:00"), &ColumnType::DateTime),
+            Some(json!("2021-08-01 12:30:00"))
+        );
+        assert_eq!(coerce_json(&json!(true), &ColumnType::DateTime), None);
+    }
+}
@@ -1,7 +1,7 @@
 use super::healthcheck;
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
-    event::metric::{Metric, MetricKind, MetricValue, Sample, StatisticKind},
+    event::metric::{DDSketch, Metric, MetricKind, MetricValue, Sample, StatisticKind},
     event::Event,
     http::HttpClient,
     sinks::{
@@ -10,6 +10,7 @@ use crate::{
             buffer::metrics::{MetricNormalize, MetricNormalizer, MetricSet, MetricsBuffer},
             encode_namespace,
             http::{HttpBatchService, HttpRetryLogic},
+            statistic::DistributionStatistic,
             EncodedEvent, PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer,
             TowerRequestConfig,
         },
@@ -349,6 +350,19 @@ fn stats(source: &[Sample]) -> Option<DatadogStats> {
     })
 }
 
+fn sketch_stats(sketch: &DDSketch) -> Option<DatadogStats> {
+    let statistic = DistributionStatistic::from_sketch(sketch, &[0.95])?;
+    Some(DatadogStats {
+        min: statistic.min,
+        max: statistic.max,
+        median: statistic.median,
+        avg: statistic.avg,
+        sum: statistic.sum,
+        count: statistic.count as f64,
+        quantiles: statistic.quantiles,
+    })
+}
+
 struct DatadogMetricNormalize;
 
 impl MetricNormalize for DatadogMetricNormalize {
@@ -387,57 +401,10 @@ fn encode_events(
                     statistic: StatisticKind::Histogram,
                 } => {
                     // https://docs.datadoghq.com/developers/metrics/metrics_type/?tab=histogram#metric-type-definition
-                    if let Some(s) = stats(samples) {
-                        let mut result = vec![
-                            DatadogMetric {
-                                metric: format!("{}.min", &fullname),
-                                r#type: DatadogMetricType::Gauge,
-                                interval: Some(interval),
-                                points: vec![DatadogPoint(ts, s.min)],
-                                tags: tags.clone(),
-                            },
-                            DatadogMetric {
-                                metric: format!("{}.avg", &fullname),
-                                r#type: DatadogMetricType::Gauge,
-                                interval: Some(interval),
-                                points: vec![DatadogPoint(ts, s.avg)],
-                                tags: tags.clone(),
-                            },
-                            DatadogMetric {
-                                metric: format!("{}.count", &fullname),
-                                r#type: DatadogMetricType::Rate,
-                                interval: Some(interval),
-                                points: vec![DatadogPoint(ts, s.count)],
-                                tags: tags.clone(),
-                            },
-                            DatadogMetric {
-                                metric: format!("{}.median", &fullname),
-                                r#type: DatadogMetricType::Gauge,
-                                interval: Some(interval),
-                                points: vec![DatadogPoint(ts, s.median)],
-                                tags: tags.clone(),
-                            },
-                            DatadogMetric {
-                                metric: format!("{}.max", &fullname),
-                                r#type: DatadogMetricType::Gauge,
-                                interval: Some(interval),
-                                points: vec![DatadogPoint(ts, s.max)],
-                                tags: tags.clone(),
-                            },
-                        ];
-                        for (q, v) in s.quantiles {
-                            result.push(DatadogMetric {
-                                metric: format!("{}.{}percentile", &fullname, (q * 100.0) as u32),
-                                r#type: DatadogMetricType::Gauge,
-                                interval: Some(interval),
-                                points: vec![DatadogPoint(ts, v)],
-                                tags: tags.clone(),
-                            })
-                        }
-                        Some(result)
-                    } else {
-                        None
-                    }
+                    stats(samples).map(|s| encode_stats(&fullname, ts, tags, interval, s))
+                }
+                MetricValue::Sketch { sketch, .. } => {
+                    sketch_stats(sketch).map(|s| encode_stats(&fullname, ts, tags, interval, s))
                 }
                 MetricValue::Set { values } => Some(vec![DatadogMetric {
                     metric: fullname,
@@ -462,6 +429,62 @@ fn encode_events(
     DatadogRequest { series }
 }
 
+fn encode_stats(
+    fullname: &str,
+    ts: i64,
+    tags: Option<Vec<String>>,
+    interval: i64,
+    s: DatadogStats,
+) -> Vec<DatadogMetric> {
+    let mut result = vec![
+        DatadogMetric {
+            metric: format!("{}.min", fullname),
+            r#type: DatadogMetricType::Gauge,
+            interval: Some(interval),
+            points: vec![DatadogPoint(ts, s.min)],
+            tags: tags.clone(),
+        },
+        DatadogMetric {
+            metric: format!("{}.avg", fullname),
+            r#type: DatadogMetricType::Gauge,
+            interval: Some(interval),
+            points: vec![DatadogPoint(ts, s.avg)],
+            tags: tags.clone(),
+        },
+        DatadogMetric {
+            metric: format!("{}.count", fullname),
+            r#type: DatadogMetricType::Rate,
+            interval: Some(interval),
+            points: vec![DatadogPoint(ts, s.count)],
+            tags: tags.clone(),
+        },
+        DatadogMetric {
+            metric: format!("{}.median", fullname),
+            r#type: DatadogMetricType::Gauge,
+            interval: Some(interval),
+            points: vec![DatadogPoint(ts, s.median)],
+            tags: tags.clone(),
+        },
+        DatadogMetric {
+            metric: format!("{}.max", fullname),
+            r#type: DatadogMetricType::Gauge,
+            interval: Some(interval),
+            points: vec![DatadogPoint(ts, s.max)],
+            tags: tags.clone(),
+        },
+    ];
+    for (q, v) in s.quantiles {
+        result.push(DatadogMetric {
+            metric: format!("{}.{}percentile", fullname, (q * 100.0) as u32),
+            r#type: DatadogMetricType::Gauge,
+            interval: Some(interval),
+            points: vec![DatadogPoint(ts, v)],
+            tags: tags.clone(),
+        })
+    }
+    result
+}
+
 fn encode_distribution_events(
     events: Vec<Metric>,
     default_namespace: Option<&str>,
@@ -794,4 +817,41 @@ mod tests {
             r#"{"series":[{"metric":"requests","interval":60,"points":[[1542182950,[1.0,1.0,1.0,2.0,2.0,2.0,3.0,3.0]]],"tags":null}]}"#
         );
     }
+
+    #[test]
+    fn encode_sketch() {
+        let events = vec![Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Sketch {
+                sketch: DDSketch::from_samples(
+                    0.01,
+                    &vector_core::samples![1.0 => 3, 2.0 => 3, 3.0 => 2],
+                ),
+                statistic: StatisticKind::Summary,
+            },
+        )
+        .with_timestamp(Some(ts()))];
+        let input = encode_events(events, None, 60);
+        let metrics = input
+            .series
+            .iter()
+            .map(|metric| (metric.metric.as_str(), metric.points[0].1))
+            .collect::<Vec<_>>();
+
+        assert_eq!(metrics.len(), 6);
+        assert_eq!(
+            &metrics[..3],
+            &[
+                ("requests.min", 1.0),
+                ("requests.avg", 1.875),
+                ("requests.count", 8.0)
+            ]
+        );
+        assert_eq!(metrics[3].0, "requests.median");
+        assert!((metrics[3].1 - 2.0).abs() <= 0.02);
+        assert_eq!(metrics[4], ("requests.max", 3.0));
+        assert_eq!(metrics[5].0, "requests.95percentile");
+        assert!((metrics[5].1 - 3.0).abs() <= 0.03);
+    }
 }
@@ -720,7 +720,7 @@ impl ElasticSearchCommon {
     }
 }
 
-async fn finish_signer(
+pub(crate) async fn finish_signer(
     signer: &mut SignedRequest,
     credentials_provider: &rusoto::AwsCredentialsProvider,
     mut builder: http::request::Builder,
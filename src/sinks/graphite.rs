@@ -0,0 +1,402 @@
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::metric::{Metric, MetricValue},
+    event::Event,
+    sinks::util::{
+        encode_namespace,
+        statistic::{validate_quantiles, DistributionStatistic},
+        tcp::TcpSinkConfig,
+        udp::UdpSinkConfig,
+    },
+};
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+// TODO: add back when serde-rs/serde#1358 is addressed
+// #[serde(deny_unknown_fields)]
+pub struct GraphiteSinkConfig {
+    #[serde(alias = "namespace")]
+    pub default_namespace: Option<String>,
+    #[serde(default)]
+    pub format: Format,
+    #[serde(default = "default_quantiles")]
+    pub quantiles: Vec<f64>,
+    #[serde(flatten)]
+    pub mode: Mode,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Mode {
+    Tcp(TcpSinkConfig),
+    Udp(UdpSinkConfig),
+}
+
+/// How the tags of the metrics are written.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// The tag values are added to the dotted path, ordered by tag name. The
+    /// tags with an empty value are left out.
+    Dotted,
+    /// The tags are added to the path as in `name;tag=value`, as supported
+    /// since Graphite 1.1.
+    Tagged,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self::Dotted
+    }
+}
+
+fn default_quantiles() -> Vec<f64> {
+    vec![0.5, 0.75, 0.9, 0.95, 0.99]
+}
+
+inventory::submit! {
+    SinkDescription::new::<GraphiteSinkConfig>("graphite")
+}
+
+impl GenerateConfig for GraphiteSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(&Self {
+            default_namespace: None,
+            format: Format::default(),
+            quantiles: default_quantiles(),
+            mode: Mode::Tcp(TcpSinkConfig::from_address("127.0.0.1:2003".into())),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "graphite")]
+impl SinkConfig for GraphiteSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        validate_quantiles(&self.quantiles)?;
+
+        let encoder = Encoder {
+            default_namespace: self.default_namespace.clone(),
+            format: self.format,
+            quantiles: self.quantiles.clone(),
+        };
+        let encode_event = move |event: Event| encoder.encode_metric(event.as_metric());
+        match &self.mode {
+            Mode::Tcp(config) => config.build(cx, encode_event),
+            Mode::Udp(config) => config.build(cx, encode_event),
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "graphite"
+    }
+}
+
+struct Encoder {
+    default_namespace: Option<String>,
+    format: Format,
+    quantiles: Vec<f64>,
+}
+
+impl Encoder {
+    /// Encode the lines of the plaintext protocol for a metric, one per value
+    /// for the metrics made of several values, like distributions.
+    fn encode_metric(&self, metric: &Metric) -> Option<Bytes> {
+        let timestamp = metric.timestamp().unwrap_or_else(Utc::now).timestamp();
+        let name = encode_namespace(
+            metric
+                .namespace()
+                .or_else(|| self.default_namespace.as_deref()),
+            '.',
+            metric.name(),
+        );
+        let name = sanitize(&name, &[';']);
+
+        let mut output = String::new();
+        for (suffix, value) in encode_values(metric.value(), &self.quantiles) {
+            let mut path = name.clone();
+            if self.format == Format::Dotted {
+                let values = metric.tags().into_iter().flat_map(|tags| tags.values());
+                for value in values.filter(|value| !value.is_empty()) {
+                    path.push('.');
+                    path.push_str(&sanitize(value, &['.', ';']));
+                }
+            }
+            if let Some(suffix) = suffix {
+                path.push('.');
+                path.push_str(&suffix);
+            }
+            if self.format == Format::Tagged {
+                let tags = metric.tags().into_iter().flatten();
+                // Graphite rejects the tags with an empty value.
+                for (name, value) in tags.filter(|(_, value)| !value.is_empty()) {
+                    let _ = write!(
+                        path,
+                        ";{}={}",
+                        sanitize(name, &[';', '=', '!', '^']),
+                        sanitize(value, &[';'])
+                    );
+                }
+            }
+            let _ = writeln!(output, "{} {} {}", path, value, timestamp);
+        }
+
+        Some(output)
+            .filter(|output| !output.is_empty())
+            .map(Into::into)
+    }
+}
+
+/// The values of a metric, with the suffix added to their path.
+fn encode_values(value: &MetricValue, quantiles: &[f64]) -> Vec<(Option<String>, f64)> {
+    match value {
+        MetricValue::Counter { value } | MetricValue::Gauge { value } => vec![(None, *value)],
+        MetricValue::Set { values } => vec![(None, values.len() as f64)],
+        MetricValue::Distribution { samples, .. } => {
+            DistributionStatistic::from_samples(samples, quantiles)
+                .map(encode_statistic)
+                .unwrap_or_default()
+        }
+        MetricValue::Sketch { sketch, .. } => DistributionStatistic::from_sketch(sketch, quantiles)
+            .map(encode_statistic)
+            .unwrap_or_default(),
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => buckets
+            .iter()
+            .map(|bucket| {
+                let suffix = format!("bucket_le_{}", encode_limit(bucket.upper_limit));
+                (Some(suffix), bucket.count as f64)
+            })
+            .chain(vec![
+                (Some("count".into()), *count as f64),
+                (Some("sum".into()), *sum),
+            ])
+            .collect(),
+        MetricValue::AggregatedSummary {
+            quantiles,
+            count,
+            sum,
+        } => quantiles
+            .iter()
+            .map(|quantile| {
+                let suffix = encode_percentile(quantile.upper_limit);
+                (Some(suffix), quantile.value)
+            })
+            .chain(vec![
+                (Some("count".into()), *count as f64),
+                (Some("sum".into()), *sum),
+            ])
+            .collect(),
+    }
+}
+
+fn encode_statistic(statistic: DistributionStatistic) -> Vec<(Option<String>, f64)> {
+    vec![
+        (Some("min".into()), statistic.min),
+        (Some("max".into()), statistic.max),
+        (Some("median".into()), statistic.median),
+        (Some("avg".into()), statistic.avg),
+        (Some("sum".into()), statistic.sum),
+        (Some("count".into()), statistic.count as f64),
+    ]
+    .into_iter()
+    .chain(
+        statistic
+            .quantiles
+            .iter()
+            .map(|&(quantile, value)| (Some(encode_percentile(quantile)), value)),
+    )
+    .collect()
+}
+
+/// Name a quantile after its percentile, so that `0.99` is `p99` and `0.999`
+/// is `p99_9`, as dots separate the parts of the paths.
+fn encode_percentile(quantile: f64) -> String {
+    let percentile = (quantile * 1000.0).round() / 10.0;
+    format!("p{}", percentile).replace('.', "_")
+}
+
+fn encode_limit(limit: f64) -> String {
+    if limit.is_infinite() {
+        "inf".into()
+    } else {
+        limit.to_string().replace('.', "_")
+    }
+}
+
+/// Replace the whitespace, which separates the path from the value, and the
+/// given characters.
+fn sanitize(value: &str, forbidden: &[char]) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || forbidden.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::metric::{MetricKind, StatisticKind},
+        test_util::{next_addr, trace_init, CountReceiver},
+    };
+    use chrono::TimeZone;
+    use futures::stream;
+    use shared::btreemap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GraphiteSinkConfig>();
+    }
+
+    fn encoder(format: Format) -> Encoder {
+        Encoder {
+            default_namespace: Some("vector".into()),
+            format,
+            quantiles: vec![0.5, 0.999],
+        }
+    }
+
+    fn encode(encoder: &Encoder, metric: Metric) -> String {
+        let metric = metric.with_timestamp(Some(Utc.timestamp(1622548800, 0)));
+        let output = encoder.encode_metric(&metric).unwrap();
+        String::from_utf8(output.to_vec()).unwrap()
+    }
+
+    fn tagged_gauge() -> Metric {
+        Metric::new(
+            "cpu load",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 0.5 },
+        )
+        .with_tags(Some(btreemap! {
+            "host" => "web01.example.com",
+            "datacenter" => "dc1",
+            "rack" => "",
+        }))
+    }
+
+    #[test]
+    fn encodes_dotted_paths() {
+        assert_eq!(
+            encode(&encoder(Format::Dotted), tagged_gauge()),
+            "vector.cpu_load.dc1.web01_example_com 0.5 1622548800\n"
+        );
+    }
+
+    #[test]
+    fn encodes_tagged_series() {
+        assert_eq!(
+            encode(&encoder(Format::Tagged), tagged_gauge()),
+            "vector.cpu_load;datacenter=dc1;host=web01.example.com 0.5 1622548800\n"
+        );
+    }
+
+    #[test]
+    fn encodes_distributions() {
+        let metric = Metric::new(
+            "latency",
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 1, 3.0 => 1],
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_namespace(Some("app"))
+        .with_tags(Some(btreemap! {"host" => "web01"}));
+
+        assert_eq!(
+            encode(&encoder(Format::Tagged), metric),
+            "app.latency.min;host=web01 1 1622548800\n\
+             app.latency.max;host=web01 3 1622548800\n\
+             app.latency.median;host=web01 1 1622548800\n\
+             app.latency.avg;host=web01 2 1622548800\n\
+             app.latency.sum;host=web01 4 1622548800\n\
+             app.latency.count;host=web01 2 1622548800\n\
+             app.latency.p50;host=web01 1 1622548800\n\
+             app.latency.p99_9;host=web01 3 1622548800\n"
+        );
+    }
+
+    #[test]
+    fn encodes_aggregated_histograms() {
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![0.5 => 2, f64::INFINITY => 3],
+                count: 3,
+                sum: 2.5,
+            },
+        );
+
+        assert_eq!(
+            encode(&encoder(Format::Dotted), metric),
+            "vector.requests.bucket_le_0_5 2 1622548800\n\
+             vector.requests.bucket_le_inf 3 1622548800\n\
+             vector.requests.count 3 1622548800\n\
+             vector.requests.sum 2.5 1622548800\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_over_tcp() {
+        trace_init();
+
+        let addr = next_addr();
+        let config = GraphiteSinkConfig {
+            default_namespace: None,
+            format: Format::Tagged,
+            quantiles: default_quantiles(),
+            mode: Mode::Tcp(TcpSinkConfig::from_address(addr.to_string())),
+        };
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let mut receiver = CountReceiver::receive_lines(addr);
+
+        let events = (0..3).map(|index| {
+            Event::Metric(
+                Metric::new(
+                    "requests",
+                    MetricKind::Incremental,
+                    MetricValue::Counter {
+                        value: index as f64,
+                    },
+                )
+                .with_timestamp(Some(Utc.timestamp(1622548800, 0)))
+                .with_tags(Some(btreemap! {"status" => "200"})),
+            )
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+
+        receiver.connected().await;
+        assert_eq!(
+            receiver.await,
+            vec![
+                "requests;status=200 0 1622548800",
+                "requests;status=200 1 1622548800",
+                "requests;status=200 2 1622548800",
+            ]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
     event::{
-        metric::{Metric, MetricValue, Sample, StatisticKind},
+        metric::{Metric, MetricValue, StatisticKind},
         Event,
     },
     http::HttpClient,
@@ -317,15 +317,23 @@ fn get_type_and_fields(
                 StatisticKind::Histogram => &[0.95] as &[_],
                 StatisticKind::Summary => quantiles,
             };
-            let fields = encode_distribution(samples, quantiles);
+            let fields =
+                DistributionStatistic::from_samples(samples, quantiles).map(encode_distribution);
             ("distribution", fields)
         }
+        MetricValue::Sketch { sketch, statistic } => {
+            let quantiles = match statistic {
+                StatisticKind::Histogram => &[0.95] as &[_],
+                StatisticKind::Summary => quantiles,
+            };
+            let fields =
+                DistributionStatistic::from_sketch(sketch, quantiles).map(encode_distribution);
+            ("sketch", fields)
+        }
     }
 }
 
-fn encode_distribution(samples: &[Sample], quantiles: &[f64]) -> Option<HashMap<String, Field>> {
-    let statistic = DistributionStatistic::from_samples(samples, quantiles)?;
-
+fn encode_distribution(statistic: DistributionStatistic) -> HashMap<String, Field> {
     let fields: HashMap<String, Field> = vec![
         ("min".to_owned(), Field::Float(statistic.min)),
         ("max".to_owned(), Field::Float(statistic.max)),
@@ -343,7 +351,7 @@ fn encode_distribution(samples: &[Sample], quantiles: &[f64]) -> Option<HashMap<
     )
     .collect();
 
-    Some(fields)
+    fields
 }
 
 fn to_fields(value: f64) -> HashMap<String, Field> {
@@ -356,7 +364,7 @@ fn to_fields(value: f64) -> HashMap<String, Field> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use crate::event::metric::{DDSketch, Metric, MetricKind, MetricValue, Sample, StatisticKind};
     use crate::sinks::influxdb::test_util::{assert_fields, split_line_protocol, tags, ts};
     use pretty_assertions::assert_eq;
 
@@ -781,6 +789,68 @@ mod tests {
         assert_eq!("1542182950000000011", line_protocol.3);
     }
 
+    #[test]
+    fn test_encode_sketch_summary() {
+        let events = vec![Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Sketch {
+                sketch: DDSketch::from_samples(
+                    0.01,
+                    &vector_core::samples![1.0 => 3, 2.0 => 3, 3.0 => 2],
+                ),
+                statistic: StatisticKind::Summary,
+            },
+        )
+        .with_namespace(Some("ns"))
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(ts()))];
+
+        let line_protocols = encode_events(ProtocolVersion::V2, events, None, None, &[0.0, 1.0]);
+        let line_protocols: Vec<&str> = line_protocols.split('\n').collect();
+        assert_eq!(line_protocols.len(), 1);
+
+        let line_protocol = split_line_protocol(line_protocols[0]);
+        assert_eq!("ns.requests", line_protocol.0);
+        assert_eq!(
+            "metric_type=sketch,normal_tag=value,true_tag=true",
+            line_protocol.1
+        );
+        assert_fields(
+            line_protocol.2.to_string(),
+            [
+                "avg=1.875",
+                "count=8",
+                "max=3",
+                "median=2",
+                "min=1",
+                "sum=15",
+                "quantile_0.00=1",
+                "quantile_1.00=3",
+            ]
+            .to_vec(),
+        );
+        assert_eq!("1542182950000000011", line_protocol.3);
+    }
+
+    #[test]
+    fn test_encode_sketch_empty_stats() {
+        let events = vec![Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Sketch {
+                sketch: DDSketch::new(0.01),
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_namespace(Some("ns"))
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(ts()))];
+
+        let line_protocols = encode_events(ProtocolVersion::V2, events, None, None, &[]);
+        assert_eq!(line_protocols.len(), 0);
+    }
+
     #[test]
     fn test_encode_with_some_tags() {
         crate::test_util::trace_init();
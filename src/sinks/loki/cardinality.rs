@@ -0,0 +1,241 @@
+//! Label cardinality guard
+//!
+//! Every distinct label set is a separate stream in Loki, so a label with an
+//! unbounded number of values creates an unbounded number of streams. This
+//! limiter caps the number of distinct streams seen during a time window.
+//! Once the cap is reached, new streams have the label with the most distinct
+//! values in the window dropped or rolled up, though a stream's last label is
+//! always rolled up. The streams of limited label sets collapse into one
+//! another, so the cap is a soft one.
+
+use crate::{
+    internal_events::LokiLabelCardinalityLimited, sinks::util::buffer::loki::PartitionKey,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+/// The value given to a label that is rolled up.
+pub const ROLLUP_VALUE: &str = "__overflow__";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CardinalityLimitConfig {
+    pub max_streams: usize,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    #[serde(default)]
+    pub action: CardinalityAction,
+}
+
+const fn default_window_secs() -> u64 {
+    60
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CardinalityAction {
+    #[derivative(Default)]
+    Drop,
+    Rollup,
+}
+
+impl CardinalityAction {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Drop => "drop",
+            Self::Rollup => "rollup",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CardinalityLimiter {
+    max_streams: usize,
+    window: Duration,
+    action: CardinalityAction,
+
+    window_start: Instant,
+    streams: HashSet<PartitionKey>,
+    values: HashMap<String, HashSet<String>>,
+}
+
+impl CardinalityLimiter {
+    pub fn new(config: &CardinalityLimitConfig) -> Self {
+        Self {
+            max_streams: config.max_streams,
+            window: Duration::from_secs(config.window_secs),
+            action: config.action,
+            window_start: Instant::now(),
+            streams: HashSet::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// Limits the labels of `key` and records the resulting stream.
+    pub fn limit(&mut self, key: &mut PartitionKey) {
+        self.limit_at(key, Instant::now())
+    }
+
+    fn limit_at(&mut self, key: &mut PartitionKey, now: Instant) {
+        if now.saturating_duration_since(self.window_start) >= self.window {
+            self.window_start = now;
+            self.streams.clear();
+            self.values.clear();
+        }
+
+        if self.streams.len() >= self.max_streams && !self.streams.contains(key) {
+            let values = &self.values;
+            let offending = key
+                .labels
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, (name, _))| values.get(name).map_or(0, HashSet::len))
+                .map(|(index, _)| index);
+
+            if let Some(index) = offending {
+                // Loki rejects streams without labels, so the last label is
+                // rolled up rather than dropped.
+                let action = match self.action {
+                    CardinalityAction::Drop if key.labels.len() == 1 => CardinalityAction::Rollup,
+                    action => action,
+                };
+                emit!(LokiLabelCardinalityLimited {
+                    label: &key.labels[index].0,
+                    action: action.as_str(),
+                });
+
+                match action {
+                    CardinalityAction::Drop => {
+                        key.labels.remove(index);
+                    }
+                    CardinalityAction::Rollup => {
+                        key.labels[index].1 = ROLLUP_VALUE.to_string();
+                    }
+                }
+            }
+        }
+
+        if self.streams.insert(key.clone()) {
+            for (name, value) in &key.labels {
+                self.values
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(labels: &[(&str, &str)]) -> PartitionKey {
+        PartitionKey {
+            tenant_id: None,
+            labels: labels
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn limiter(action: CardinalityAction) -> CardinalityLimiter {
+        CardinalityLimiter::new(&CardinalityLimitConfig {
+            max_streams: 2,
+            window_secs: 60,
+            action,
+        })
+    }
+
+    #[test]
+    fn passes_streams_under_the_limit() {
+        let mut limiter = limiter(CardinalityAction::Drop);
+
+        for labels in &[
+            [("app", "web"), ("pod", "a")],
+            [("app", "web"), ("pod", "b")],
+            [("app", "web"), ("pod", "a")],
+        ] {
+            let mut limited = key(labels);
+            limiter.limit(&mut limited);
+            assert_eq!(limited, key(labels));
+        }
+    }
+
+    #[test]
+    fn drops_offending_label() {
+        let mut limiter = limiter(CardinalityAction::Drop);
+
+        let mut first = key(&[("app", "web"), ("pod", "a")]);
+        let mut second = key(&[("app", "web"), ("pod", "b")]);
+        let mut third = key(&[("app", "web"), ("pod", "c")]);
+        limiter.limit(&mut first);
+        limiter.limit(&mut second);
+        limiter.limit(&mut third);
+
+        assert_eq!(third, key(&[("app", "web")]));
+        assert_eq!(limiter.streams.len(), 3);
+
+        // The stream without the dropped label is known now.
+        let mut fourth = key(&[("app", "web"), ("pod", "d")]);
+        limiter.limit(&mut fourth);
+        assert_eq!(fourth, key(&[("app", "web")]));
+        assert_eq!(limiter.streams.len(), 3);
+    }
+
+    #[test]
+    fn rolls_up_offending_label() {
+        let mut limiter = limiter(CardinalityAction::Rollup);
+
+        for pod in &["a", "b", "c", "d"] {
+            limiter.limit(&mut key(&[("app", "web"), ("pod", pod)]));
+        }
+
+        let mut limited = key(&[("app", "web"), ("pod", "e")]);
+        limiter.limit(&mut limited);
+        assert_eq!(limited, key(&[("app", "web"), ("pod", ROLLUP_VALUE)]));
+        assert_eq!(limiter.streams.len(), 3);
+    }
+
+    #[test]
+    fn limits_label_with_most_values() {
+        let mut limiter = limiter(CardinalityAction::Rollup);
+
+        limiter.limit(&mut key(&[("app", "web"), ("pod", "a")]));
+        limiter.limit(&mut key(&[("app", "web"), ("pod", "b")]));
+
+        let mut limited = key(&[("app", "db"), ("pod", "c")]);
+        limiter.limit(&mut limited);
+        assert_eq!(limited, key(&[("app", "db"), ("pod", ROLLUP_VALUE)]));
+    }
+
+    #[test]
+    fn rolls_up_last_label() {
+        let mut limiter = limiter(CardinalityAction::Drop);
+        limiter.limit(&mut key(&[("pod", "a")]));
+        limiter.limit(&mut key(&[("pod", "b")]));
+
+        let mut limited = key(&[("pod", "c")]);
+        limiter.limit(&mut limited);
+        assert_eq!(limited, key(&[("pod", ROLLUP_VALUE)]));
+    }
+
+    #[test]
+    fn resets_after_window() {
+        let mut limiter = limiter(CardinalityAction::Drop);
+        let start = limiter.window_start;
+
+        limiter.limit_at(&mut key(&[("pod", "a")]), start);
+        limiter.limit_at(&mut key(&[("pod", "b")]), start);
+
+        let mut limited = key(&[("pod", "c")]);
+        limiter.limit_at(&mut limited, start + Duration::from_secs(60));
+        assert_eq!(limited, key(&[("pod", "c")]));
+        assert_eq!(limiter.streams.len(), 1);
+    }
+}
@@ -1,35 +1,50 @@
 //! Loki sink
 //!
 //! This sink provides downstream support for `Loki` via
-//! the v1 http json endpoint.
+//! the v1 http push endpoint, either as snappy compressed
+//! protobuf or as (optionally gzipped) json.
 //!
 //! https://github.com/grafana/loki/blob/master/docs/api.md
 //!
-//! This sink does not use `PartitionBatching` but elects to do
-//! stream multiplexing by organizing the streams in the `build_request`
-//! phase. There must be at least one valid set of labels.
+//! Batches are partitioned by tenant and stream label set, and the
+//! requests of a stream are sent in order while different streams
+//! are sent concurrently. There must be at least one valid set of labels.
 //!
 //! If an event produces no labels, this can happen if the template
 //! does not match, we will add a default label `{agent="vector"}`.
 
+mod cardinality;
+mod ordering;
+
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::{self, Event, Value},
     http::{Auth, HttpClient, MaybeAuth},
+    proto::loki as proto,
     sinks::util::{
-        buffer::loki::{GlobalTimestamps, LokiBuffer, LokiEvent, LokiRecord, PartitionKey},
+        buffer::{
+            loki::{GlobalTimestamps, LokiBuffer, LokiEvent, LokiRecord, LokiStream, PartitionKey},
+            GZIP_FAST,
+        },
         encoding::{EncodingConfig, EncodingConfiguration},
-        http::{HttpSink, PartitionHttpSink},
-        service::ConcurrencyOption,
-        BatchConfig, BatchSettings, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
-        UriSerde,
+        http::{HttpBatchService, HttpRetryLogic, HttpSink},
+        BatchConfig, BatchSettings, Concurrency, EncodedEvent, PartitionBatchSink, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig, UriSerde,
     },
     template::Template,
     tls::{TlsOptions, TlsSettings},
 };
-use futures::{FutureExt, SinkExt};
+use cardinality::{CardinalityLimitConfig, CardinalityLimiter};
+use flate2::write::GzEncoder;
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use ordering::OrderedService;
+use prost::Message;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    sync::{Arc, Mutex},
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -46,6 +61,9 @@ pub struct LokiConfig {
     remove_timestamp: bool,
     #[serde(default)]
     out_of_order_action: OutOfOrderAction,
+    #[serde(default)]
+    compression: LokiCompression,
+    cardinality_limit: Option<CardinalityLimitConfig>,
 
     auth: Option<Auth>,
 
@@ -67,6 +85,16 @@ pub enum OutOfOrderAction {
     RewriteTimestamp,
 }
 
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LokiCompression {
+    None,
+    Gzip,
+    #[derivative(Default)]
+    Snappy,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 enum Encoding {
@@ -100,11 +128,11 @@ impl SinkConfig for LokiConfig {
             return Err("`labels` must include at least one label.".into());
         }
 
-        if self.request.concurrency.is_some() {
-            warn!("Option `request.concurrency` is not supported.");
-        }
-        let mut request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
-        request_settings.concurrency = Some(1);
+        let mut request_opts = self.request;
+        // Requests are only ordered within a stream, so the streams of a batch
+        // window can be sent in parallel.
+        request_opts.concurrency = request_opts.concurrency.if_none(Concurrency::Adaptive);
+        let request_settings = request_opts.unwrap_with(&TowerRequestConfig::default());
 
         let batch_settings = BatchSettings::default()
             .bytes(102_400)
@@ -119,20 +147,38 @@ impl SinkConfig for LokiConfig {
             ..self.clone()
         };
 
-        let sink = LokiSink::new(config.clone());
+        let sink = Arc::new(LokiSink::new(config.clone()));
+
+        let request_sink = Arc::clone(&sink);
+        let svc = HttpBatchService::new(
+            client.clone(),
+            move |output| -> BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>> {
+                let sink = Arc::clone(&request_sink);
+                Box::pin(async move { sink.build_request(output).await })
+            },
+        );
+        // Retries happen below the ordering, so that a stream's next request
+        // waits for the retries of the previous one.
+        let svc = OrderedService::new(request_settings.service(HttpRetryLogic, svc));
 
-        let sink = PartitionHttpSink::new(
-            sink,
+        let sink = PartitionBatchSink::new(
+            svc,
             PartitionBuffer::new(LokiBuffer::new(
                 batch_settings.size,
                 GlobalTimestamps::default(),
                 config.out_of_order_action.clone(),
             )),
-            request_settings,
             batch_settings.timeout,
-            client.clone(),
             cx.acker(),
         )
+        .with_flat_map(move |mut event: Event| {
+            let finalizers = event.metadata_mut().take_finalizers();
+            stream::iter(
+                sink.encode_event(event)
+                    .map(|item| EncodedEvent { item, finalizers }),
+            )
+            .map(Ok)
+        })
         .sink_map_err(|error| error!(message = "Fatal loki sink error.", %error));
 
         let healthcheck = healthcheck(config, client).boxed();
@@ -158,6 +204,8 @@ struct LokiSink {
 
     remove_label_fields: bool,
     remove_timestamp: bool,
+    compression: LokiCompression,
+    cardinality_limiter: Option<Mutex<CardinalityLimiter>>,
 
     auth: Option<Auth>,
 }
@@ -171,6 +219,11 @@ impl LokiSink {
             labels: config.labels,
             remove_label_fields: config.remove_label_fields,
             remove_timestamp: config.remove_timestamp,
+            compression: config.compression,
+            cardinality_limiter: config
+                .cardinality_limit
+                .as_ref()
+                .map(|config| Mutex::new(CardinalityLimiter::new(config))),
             auth: config.auth,
         }
     }
@@ -179,7 +232,7 @@ impl LokiSink {
 #[async_trait::async_trait]
 impl HttpSink for LokiSink {
     type Input = PartitionInnerBuffer<LokiRecord, PartitionKey>;
-    type Output = PartitionInnerBuffer<serde_json::Value, PartitionKey>;
+    type Output = PartitionInnerBuffer<Vec<LokiStream>, PartitionKey>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
         let tenant_id = self.tenant_id.as_ref().and_then(|t| {
@@ -193,7 +246,6 @@ impl HttpSink for LokiSink {
                 })
                 .ok()
         });
-        let key = PartitionKey { tenant_id };
 
         let mut labels = Vec::new();
 
@@ -236,17 +288,25 @@ impl HttpSink for LokiSink {
                 .unwrap_or_default(),
         };
 
+        // Equal label sets have to make the same partition.
+        labels.sort_unstable();
+        let mut key = PartitionKey { tenant_id, labels };
+
+        if let Some(limiter) = &self.cardinality_limiter {
+            limiter.lock().expect("Lock is poisoned").limit(&mut key);
+        }
+
         // If no labels are provided we set our own default
         // `{agent="vector"}` label. This can happen if the only
         // label is a templatable one but the event doesn't match.
-        if labels.is_empty() {
-            labels = vec![("agent".to_string(), "vector".to_string())]
+        if key.labels.is_empty() {
+            key.labels = vec![("agent".to_string(), "vector".to_string())]
         }
 
         let event = LokiEvent { timestamp, event };
         Some(PartitionInnerBuffer::new(
             LokiRecord {
-                labels,
+                labels: key.labels.clone(),
                 event,
                 partition: key.clone(),
             },
@@ -255,14 +315,39 @@ impl HttpSink for LokiSink {
     }
 
     async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let (json, key) = output.into_parts();
+        let (streams, key) = output.into_parts();
         let tenant_id = key.tenant_id;
 
-        let body = serde_json::to_vec(&json).unwrap();
-
         let uri = format!("{}loki/api/v1/push", self.endpoint.uri);
 
-        let mut req = http::Request::post(uri).header("Content-Type", "application/json");
+        let (mut req, body) = match self.compression {
+            LokiCompression::None => (
+                http::Request::post(uri).header("Content-Type", "application/json"),
+                encode_json(&streams),
+            ),
+            LokiCompression::Gzip => {
+                let body = encode_json(&streams);
+                let mut encoder = GzEncoder::new(
+                    Vec::with_capacity(body.len()),
+                    flate2::Compression::new(GZIP_FAST as u32),
+                );
+                encoder.write_all(&body).expect("Writing to Vec can't fail");
+                (
+                    http::Request::post(uri)
+                        .header("Content-Type", "application/json")
+                        .header("Content-Encoding", "gzip"),
+                    encoder.finish().expect("Writing to Vec can't fail"),
+                )
+            }
+            // Loki always expects protobuf push requests to be snappy
+            // compressed, without a `Content-Encoding` header.
+            LokiCompression::Snappy => (
+                http::Request::post(uri).header("Content-Type", "application/x-protobuf"),
+                snap::raw::Encoder::new()
+                    .compress_vec(&encode_protobuf(&streams))
+                    .expect("Out of memory"),
+            ),
+        };
 
         if let Some(tenant_id) = tenant_id {
             req = req.header("X-Scope-OrgID", tenant_id);
@@ -278,6 +363,81 @@ impl HttpSink for LokiSink {
     }
 }
 
+#[derive(Serialize)]
+struct JsonPushRequest<'a> {
+    streams: Vec<JsonStream<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonStream<'a> {
+    stream: BTreeMap<&'a str, &'a str>,
+    values: Vec<(String, &'a str)>,
+}
+
+pub(crate) fn encode_json(streams: &[LokiStream]) -> Vec<u8> {
+    let request = JsonPushRequest {
+        streams: streams
+            .iter()
+            .map(|stream| JsonStream {
+                stream: stream
+                    .labels
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect(),
+                values: stream
+                    .events
+                    .iter()
+                    .map(|event| (event.timestamp.to_string(), event.event.as_str()))
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_vec(&request).expect("JSON encoding should never fail")
+}
+
+pub(crate) fn encode_protobuf(streams: &[LokiStream]) -> Vec<u8> {
+    let request = proto::PushRequest {
+        streams: streams
+            .iter()
+            .map(|stream| proto::StreamAdapter {
+                labels: format_labels(&stream.labels),
+                entries: stream
+                    .events
+                    .iter()
+                    .map(|event| proto::EntryAdapter {
+                        timestamp: Some(prost_types::Timestamp {
+                            seconds: event.timestamp.div_euclid(1_000_000_000),
+                            nanos: event.timestamp.rem_euclid(1_000_000_000) as i32,
+                        }),
+                        line: event.event.clone(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    let mut body = Vec::with_capacity(request.encoded_len());
+    request.encode(&mut body).expect("Out of memory");
+    body
+}
+
+/// Formats labels in the Prometheus exposition format Loki parses the
+/// labels of protobuf streams from: `{key="value", ...}`.
+fn format_labels(labels: &[(String, String)]) -> String {
+    let labels = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', r"\\")
+                .replace('"', r#"\""#)
+                .replace('\n', r"\n");
+            format!(r#"{}="{}""#, key, value)
+        })
+        .collect::<Vec<_>>();
+    format!("{{{}}}", labels.join(", "))
+}
+
 async fn healthcheck(config: LokiConfig, client: HttpClient) -> crate::Result<()> {
     let uri = format!("{}ready", config.endpoint.uri);
 
@@ -380,6 +540,178 @@ mod tests {
         assert_eq!(record.labels[0], ("bar".to_string(), "bar".to_string()));
     }
 
+    fn streams() -> Vec<LokiStream> {
+        vec![
+            LokiStream {
+                labels: vec![("app".into(), "web".into())],
+                events: vec![
+                    LokiEvent {
+                        timestamp: 1_500_000_000,
+                        event: "first".into(),
+                    },
+                    LokiEvent {
+                        timestamp: 2_000_000_000,
+                        event: "second".into(),
+                    },
+                ],
+            },
+            LokiStream {
+                labels: vec![
+                    ("app".into(), "api".into()),
+                    ("path".into(), r#"C:\"quoted""#.into()),
+                ],
+                events: vec![LokiEvent {
+                    timestamp: 3,
+                    event: "third".into(),
+                }],
+            },
+        ]
+    }
+
+    #[test]
+    fn encodes_json_push_request() {
+        let json = String::from_utf8(encode_json(&streams())).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"streams":[{"stream":{"app":"web"},"values":[["1500000000","first"],["2000000000","second"]]},{"stream":{"app":"api","path":"C:\\\"quoted\""},"values":[["3","third"]]}]}"#
+        );
+    }
+
+    #[test]
+    fn encodes_protobuf_push_request() {
+        let request = proto::PushRequest::decode(&encode_protobuf(&streams())[..]).unwrap();
+
+        assert_eq!(request.streams.len(), 2);
+        assert_eq!(request.streams[0].labels, r#"{app="web"}"#);
+        assert_eq!(
+            request.streams[1].labels,
+            r#"{app="api", path="C:\\\"quoted\""}"#
+        );
+
+        let entries = &request.streams[0].entries;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].line, "first");
+        assert_eq!(
+            entries[0].timestamp,
+            Some(prost_types::Timestamp {
+                seconds: 1,
+                nanos: 500_000_000
+            })
+        );
+        assert_eq!(entries[1].line, "second");
+        assert_eq!(
+            request.streams[1].entries[0].timestamp,
+            Some(prost_types::Timestamp {
+                seconds: 0,
+                nanos: 3
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn build_request_compression() {
+        let output = || {
+            PartitionInnerBuffer::new(
+                streams(),
+                PartitionKey {
+                    tenant_id: Some("tenant".into()),
+                    labels: Vec::new(),
+                },
+            )
+        };
+
+        for (compression, content_type, content_encoding) in &[
+            (LokiCompression::None, "application/json", None),
+            (LokiCompression::Gzip, "application/json", Some("gzip")),
+            (LokiCompression::Snappy, "application/x-protobuf", None),
+        ] {
+            let (mut config, _cx) = load_sink::<LokiConfig>(
+                r#"
+                endpoint = "http://localhost:3100"
+                labels = {test_name = "placeholder"}
+                encoding = "json"
+            "#,
+            )
+            .unwrap();
+            config.compression = *compression;
+            let sink = LokiSink::new(config);
+
+            let request = sink.build_request(output()).await.unwrap();
+            let headers = request.headers();
+            assert_eq!(headers["content-type"], *content_type);
+            assert_eq!(
+                headers.get("content-encoding").map(|h| h.to_str().unwrap()),
+                *content_encoding
+            );
+            assert_eq!(headers["x-scope-orgid"], "tenant");
+            assert_eq!(
+                request.uri().to_string(),
+                "http://localhost:3100/loki/api/v1/push"
+            );
+
+            let body = match compression {
+                LokiCompression::None => request.into_body(),
+                LokiCompression::Gzip => {
+                    let mut body = Vec::new();
+                    std::io::Read::read_to_end(
+                        &mut flate2::read::GzDecoder::new(&request.body()[..]),
+                        &mut body,
+                    )
+                    .unwrap();
+                    body
+                }
+                LokiCompression::Snappy => {
+                    let body = snap::raw::Decoder::new()
+                        .decompress_vec(request.body())
+                        .unwrap();
+                    assert_eq!(body, encode_protobuf(&streams()));
+                    continue;
+                }
+            };
+            assert_eq!(body, encode_json(&streams()));
+        }
+    }
+
+    #[test]
+    fn limits_label_cardinality() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {app = "web", pod = "{{ pod }}"}
+            encoding = "text"
+            cardinality_limit.max_streams = 1
+            cardinality_limit.action = "rollup"
+        "#,
+        )
+        .unwrap();
+        let sink = LokiSink::new(config);
+
+        let labels = ["a", "b", "c"]
+            .iter()
+            .map(|pod| {
+                let mut event = Event::from("hello world");
+                event.as_mut_log().insert("pod", *pod);
+                sink.encode_event(event).unwrap().into_parts().1.labels
+            })
+            .collect::<Vec<_>>();
+
+        let pod = |value: &str| {
+            vec![
+                ("app".to_string(), "web".to_string()),
+                ("pod".to_string(), value.to_string()),
+            ]
+        };
+        assert_eq!(
+            labels,
+            vec![
+                pod("a"),
+                pod(cardinality::ROLLUP_VALUE),
+                pod(cardinality::ROLLUP_VALUE)
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn healthcheck_includes_auth() {
         let (mut config, _cx) = load_sink::<LokiConfig>(
@@ -438,6 +770,13 @@ mod integration_tests {
     use vector_core::event::{BatchNotifier, BatchStatus, Event, LogEvent};
 
     async fn build_sink(encoding: &str) -> (uuid::Uuid, VectorSink) {
+        build_sink_with_compression(encoding, "snappy").await
+    }
+
+    async fn build_sink_with_compression(
+        encoding: &str,
+        compression: &str,
+    ) -> (uuid::Uuid, VectorSink) {
         let stream = uuid::Uuid::new_v4();
 
         let config = format!(
@@ -445,10 +784,11 @@ mod integration_tests {
             endpoint = "http://localhost:3100"
             labels = {{test_name = "placeholder"}}
             encoding = "{}"
+            compression = "{}"
             remove_timestamp = false
             tenant_id = "default"
         "#,
-            encoding
+            encoding, compression
         );
 
         let (mut config, cx) = load_sink::<LokiConfig>(&config).unwrap();
@@ -491,6 +831,30 @@ mod integration_tests {
         }
     }
 
+    #[tokio::test]
+    async fn text_json_push() {
+        for compression in &["none", "gzip"] {
+            let (stream, sink) = build_sink_with_compression("text", compression).await;
+
+            let lines = random_lines(100).take(10).collect::<Vec<_>>();
+
+            let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+            let events = lines
+                .clone()
+                .into_iter()
+                .map(move |line| Event::from(LogEvent::from(line).with_batch_notifier(&batch)));
+            let _ = sink
+                .into_sink()
+                .send_all(&mut stream::iter(events).map(Ok))
+                .await
+                .unwrap();
+            assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+            let (_, outputs) = fetch_stream(stream.to_string(), "default").await;
+            assert_eq!(lines, outputs);
+        }
+    }
+
     #[tokio::test]
     async fn json() {
         let (stream, sink) = build_sink("json").await;
@@ -0,0 +1,168 @@
+//! Per-stream request ordering
+//!
+//! Loki rejects entries that are older than the newest entry it has already
+//! accepted for a stream. Batches are partitioned by stream, so requests for
+//! different streams can be sent concurrently as long as the requests of a
+//! single stream, including their retries, are sent one after another.
+
+use crate::sinks::util::Partition;
+use futures::{
+    channel::oneshot,
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    mem,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tower::Service;
+
+type Pending<K> = HashMap<K, (u64, Shared<oneshot::Receiver<()>>)>;
+
+#[derive(Debug)]
+struct State<K> {
+    next_id: u64,
+    pending: Pending<K>,
+}
+
+/// Delays each request until the previous request of the same partition
+/// has completed.
+#[derive(Debug)]
+pub struct OrderedService<S, K> {
+    inner: S,
+    state: Arc<Mutex<State<K>>>,
+}
+
+impl<S, K> OrderedService<S, K> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(State {
+                next_id: 0,
+                pending: HashMap::new(),
+            })),
+        }
+    }
+}
+
+impl<S: Clone, K> Clone for OrderedService<S, K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl<S, K, Request> Service<Request> for OrderedService<S, K>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    K: Hash + Eq + Clone + Send + 'static,
+    Request: Partition<K> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let key = request.partition();
+        let (done, receiver) = oneshot::channel();
+
+        let (id, previous) = {
+            let mut state = self.state.lock().expect("Lock is poisoned");
+            let id = state.next_id;
+            state.next_id = state.next_id.wrapping_add(1);
+            let previous = state
+                .pending
+                .insert(key.clone(), (id, receiver.shared()))
+                .map(|(_, previous)| previous);
+            (id, previous)
+        };
+
+        // The service that was polled ready is the one to call, leave a
+        // fresh clone behind to be polled for the next request.
+        let clone = self.inner.clone();
+        let mut inner = mem::replace(&mut self.inner, clone);
+        let state = Arc::clone(&self.state);
+
+        Box::pin(async move {
+            if let Some(previous) = previous {
+                // A dropped sender means the previous request was abandoned,
+                // there is nothing left to wait for in that case.
+                let _ = previous.await;
+            }
+
+            let result = inner.call(request).await;
+
+            let _ = done.send(());
+            let mut state = state.lock().expect("Lock is poisoned");
+            if matches!(state.pending.get(&key), Some((pending, _)) if *pending == id) {
+                state.pending.remove(&key);
+            }
+
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::util::PartitionInnerBuffer;
+    use futures::future;
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    #[derive(Clone, Default)]
+    struct RecordingService {
+        calls: Arc<Mutex<Vec<(&'static str, u64)>>>,
+    }
+
+    impl Service<PartitionInnerBuffer<u64, &'static str>> for RecordingService {
+        type Response = ();
+        type Error = crate::Error;
+        type Future = BoxFuture<'static, crate::Result<()>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<crate::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: PartitionInnerBuffer<u64, &'static str>) -> Self::Future {
+            let calls = Arc::clone(&self.calls);
+            Box::pin(async move {
+                let (delay, key) = request.into_parts();
+                sleep(Duration::from_millis(delay)).await;
+                calls.lock().unwrap().push((key, delay));
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn orders_requests_of_a_partition() {
+        let inner = RecordingService::default();
+        let mut service = OrderedService::new(inner.clone());
+
+        let requests = vec![("a", 50), ("b", 30), ("a", 10), ("b", 0)]
+            .into_iter()
+            .map(|(key, delay)| service.call(PartitionInnerBuffer::new(delay, key)))
+            .collect::<Vec<_>>();
+        future::try_join_all(requests).await.unwrap();
+
+        // Each partition completes in the order it was called, while `b`
+        // doesn't wait for `a`.
+        assert_eq!(
+            *inner.calls.lock().unwrap(),
+            vec![("b", 30), ("b", 0), ("a", 50), ("a", 10)]
+        );
+        assert!(service.state.lock().unwrap().pending.is_empty());
+    }
+}
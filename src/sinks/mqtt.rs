@@ -0,0 +1,515 @@
+use crate::{
+    buffers::Acker,
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    emit,
+    event::Event,
+    internal_events::{
+        MqttConnectionError, MqttEventSendFail, MqttEventSendSuccess, TemplateRenderingFailed,
+    },
+    mqtt::{MqttConfig, MqttQoS},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        StreamSink,
+    },
+    template::{Template, TemplateParseError},
+};
+use async_trait::async_trait;
+use futures::{stream::BoxStream, FutureExt, StreamExt};
+use rumqttc::{AsyncClient, Event as MqttEvent, EventLoop, Outgoing, Packet};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::Notify;
+use vector_core::event::{EventFinalizers, EventStatus};
+
+// How long the healthcheck waits for the broker to accept a connection.
+const HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid topic template: {}", source))]
+    TopicTemplate { source: TemplateParseError },
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("timed out connecting to the broker"))]
+    Timeout,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MqttSinkConfig {
+    topic: String,
+    #[serde(default)]
+    qos: MqttQoS,
+    #[serde(default)]
+    retain: bool,
+    encoding: EncodingConfig<Encoding>,
+    #[serde(flatten)]
+    connection: MqttConfig,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<MqttSinkConfig>("mqtt")
+}
+
+impl GenerateConfig for MqttSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            host = "localhost"
+            topic = "vector/{{ host }}"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SinkConfig for MqttSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let sink = MqttSink::new(self.clone(), cx.acker())?;
+        let healthcheck = healthcheck(self.connection.clone()).boxed();
+        Ok((super::VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "mqtt"
+    }
+}
+
+/// Checks that the broker accepts a connection with the configured
+/// credentials.
+async fn healthcheck(mut config: MqttConfig) -> crate::Result<()> {
+    // Brokers drop existing connections sharing a client id, so use a separate
+    // one, without a session, to avoid disturbing the sink's own connection.
+    config.client_id = config
+        .client_id
+        .map(|client_id| format!("{}-healthcheck", client_id));
+    config.clean_session = true;
+
+    let (client, mut eventloop) = config.build_client()?;
+    let connect = async {
+        loop {
+            if let MqttEvent::Incoming(Packet::ConnAck(_)) = eventloop.poll().await? {
+                return Ok::<_, rumqttc::ConnectionError>(());
+            }
+        }
+    };
+    tokio::time::timeout(HEALTHCHECK_TIMEOUT, connect)
+        .await
+        .map_err(|_| HealthcheckError::Timeout)??;
+
+    let _ = client.try_disconnect();
+    Ok(())
+}
+
+pub struct MqttSink {
+    client: AsyncClient,
+    // Taken when the sink starts running, as the event loop is driven by its
+    // own task.
+    eventloop: Option<EventLoop>,
+    topic: Template,
+    qos: MqttQoS,
+    retain: bool,
+    encoding: EncodingConfig<Encoding>,
+    acker: Acker,
+}
+
+impl MqttSink {
+    fn new(config: MqttSinkConfig, acker: Acker) -> crate::Result<Self> {
+        let topic = Template::try_from(config.topic).context(TopicTemplate)?;
+        let (client, eventloop) = config.connection.build_client()?;
+
+        Ok(Self {
+            client,
+            eventloop: Some(eventloop),
+            topic,
+            qos: config.qos,
+            retain: config.retain,
+            encoding: config.encoding,
+            acker,
+        })
+    }
+}
+
+/// Publishes with QoS 1 or 2 that the broker has yet to acknowledge.
+///
+/// The client sends publishes in the order they are made, so finalizers are
+/// queued before handing their publish to the client, and take the packet id
+/// of the next publish the event loop reports as sent.
+#[derive(Default)]
+struct Unacknowledged {
+    state: Mutex<UnacknowledgedState>,
+    settled: Notify,
+}
+
+#[derive(Default)]
+struct UnacknowledgedState {
+    queued: VecDeque<PendingPublish>,
+    // A publish colliding with an in-flight packet id waits for it to be
+    // acknowledged, so an id can be shared by two publishes.
+    in_flight: HashMap<u16, VecDeque<PendingPublish>>,
+}
+
+struct PendingPublish {
+    finalizers: EventFinalizers,
+    byte_size: usize,
+}
+
+impl Unacknowledged {
+    fn push(&self, finalizers: EventFinalizers, byte_size: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.queued.push_back(PendingPublish {
+            finalizers,
+            byte_size,
+        });
+    }
+
+    /// Drops the last queued publish, which the client didn't accept.
+    fn pop_unsent(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.queued.pop_back();
+        self.notify_if_settled(&state);
+    }
+
+    fn handle(&self, event: &MqttEvent) {
+        let mut state = self.state.lock().unwrap();
+        match event {
+            MqttEvent::Outgoing(Outgoing::AwaitAck(pkid)) => state.assign(*pkid),
+            // Publishes are resent with the same packet id after reconnecting.
+            MqttEvent::Outgoing(Outgoing::Publish(pkid)) => {
+                if !state.in_flight.contains_key(pkid) {
+                    state.assign(*pkid);
+                }
+            }
+            MqttEvent::Incoming(Packet::PubAck(ack)) => state.acknowledge(ack.pkid),
+            MqttEvent::Incoming(Packet::PubComp(comp)) => state.acknowledge(comp.pkid),
+            _ => return,
+        }
+        self.notify_if_settled(&state);
+    }
+
+    fn notify_if_settled(&self, state: &UnacknowledgedState) {
+        if state.queued.is_empty() && state.in_flight.is_empty() {
+            self.settled.notify_one();
+        }
+    }
+
+    /// Waits until every publish has been acknowledged.
+    async fn settled(&self) {
+        loop {
+            {
+                let state = self.state.lock().unwrap();
+                if state.queued.is_empty() && state.in_flight.is_empty() {
+                    return;
+                }
+            }
+            self.settled.notified().await;
+        }
+    }
+}
+
+impl UnacknowledgedState {
+    fn assign(&mut self, pkid: u16) {
+        if let Some(publish) = self.queued.pop_front() {
+            self.in_flight.entry(pkid).or_default().push_back(publish);
+        }
+    }
+
+    fn acknowledge(&mut self, pkid: u16) {
+        if let Entry::Occupied(mut entry) = self.in_flight.entry(pkid) {
+            if let Some(publish) = entry.get_mut().pop_front() {
+                emit!(MqttEventSendSuccess {
+                    byte_size: publish.byte_size
+                });
+                publish.finalizers.update_status(EventStatus::Delivered);
+            }
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+        }
+    }
+}
+
+/// Drives the connection until the client disconnects. Polling after an error
+/// reconnects, so errors are only reported.
+async fn drive(mut eventloop: EventLoop, unacknowledged: Option<Arc<Unacknowledged>>) {
+    loop {
+        match eventloop.poll().await {
+            Ok(MqttEvent::Outgoing(Outgoing::Disconnect)) => break,
+            Ok(event) => {
+                if let Some(unacknowledged) = &unacknowledged {
+                    unacknowledged.handle(&event);
+                }
+            }
+            Err(error) => {
+                emit!(MqttConnectionError { error });
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSink for MqttSink {
+    async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let eventloop = self.eventloop.take().expect("sink run twice");
+        // With QoS 0 the broker never acknowledges publishes, so events are
+        // delivered once they are handed to the client.
+        let unacknowledged =
+            (self.qos != MqttQoS::AtMostOnce).then(|| Arc::new(Unacknowledged::default()));
+        let connection = tokio::spawn(drive(eventloop, unacknowledged.clone()));
+
+        while let Some(mut event) = input.next().await {
+            let topic = match self.topic.render_string(&event) {
+                Ok(topic) => topic,
+                Err(error) => {
+                    emit!(TemplateRenderingFailed {
+                        error,
+                        field: Some("topic"),
+                        drop_event: true,
+                    });
+                    self.acker.ack(1);
+                    continue;
+                }
+            };
+
+            let finalizers = event.metadata_mut().take_finalizers();
+            let payload = encode_event(event, &self.encoding);
+            let byte_size = payload.len();
+
+            if let Some(unacknowledged) = &unacknowledged {
+                unacknowledged.push(finalizers.clone(), byte_size);
+            }
+            match self
+                .client
+                .publish(topic, self.qos.into(), self.retain, payload)
+                .await
+            {
+                Ok(()) => {
+                    if unacknowledged.is_none() {
+                        emit!(MqttEventSendSuccess { byte_size });
+                        finalizers.update_status(EventStatus::Delivered);
+                    }
+                }
+                Err(error) => {
+                    emit!(MqttEventSendFail { error });
+                    if let Some(unacknowledged) = &unacknowledged {
+                        unacknowledged.pop_unsent();
+                    }
+                    finalizers.update_status(EventStatus::Errored);
+                }
+            }
+            self.acker.ack(1);
+        }
+
+        if let Some(unacknowledged) = &unacknowledged {
+            unacknowledged.settled().await;
+        }
+        let _ = self.client.disconnect().await;
+        let _ = connection.await;
+
+        Ok(())
+    }
+}
+
+fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Vec<u8> {
+    encoding.apply_rules(&mut event);
+
+    match encoding.codec() {
+        Encoding::Json => serde_json::to_vec(event.as_log()).unwrap(),
+        Encoding::Text => event
+            .as_log()
+            .get(log_schema().message_key())
+            .map(|v| v.as_bytes().to_vec())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, Value};
+    use rumqttc::{PubAck, PubComp};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSinkConfig>();
+    }
+
+    #[test]
+    fn encodes_raw_logs() {
+        let event = Event::from("foo");
+        assert_eq!(
+            b"foo".to_vec(),
+            encode_event(event, &EncodingConfig::from(Encoding::Text))
+        );
+    }
+
+    #[test]
+    fn encodes_log_events() {
+        let mut event = Event::new_empty_log();
+        let log = event.as_mut_log();
+        log.insert("x", Value::from("23"));
+        log.insert("z", Value::from(25));
+        log.insert("a", Value::from("0"));
+
+        let encoded = encode_event(event, &EncodingConfig::from(Encoding::Json));
+        assert_eq!(encoded, br#"{"a":"0","x":"23","z":25}"#.to_vec());
+    }
+
+    fn push_publish(unacknowledged: &Unacknowledged) -> BatchStatusReceiver {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        unacknowledged.push(EventFinalizers::new(EventFinalizer::new(batch)), 3);
+        receiver
+    }
+
+    fn handle_all(unacknowledged: &Unacknowledged, events: Vec<MqttEvent>) {
+        for event in events {
+            unacknowledged.handle(&event);
+        }
+    }
+
+    #[test]
+    fn resolves_publishes_on_acknowledgement() {
+        let unacknowledged = Unacknowledged::default();
+        let mut first = push_publish(&unacknowledged);
+        let mut second = push_publish(&unacknowledged);
+
+        handle_all(
+            &unacknowledged,
+            vec![
+                MqttEvent::Outgoing(Outgoing::Publish(1)),
+                MqttEvent::Outgoing(Outgoing::Publish(2)),
+                MqttEvent::Incoming(Packet::PubAck(PubAck::new(2))),
+            ],
+        );
+        assert!(first.try_recv().is_err());
+        assert_eq!(second.try_recv(), Ok(BatchStatus::Delivered));
+
+        // Resent after reconnecting, then completed with QoS 2.
+        handle_all(
+            &unacknowledged,
+            vec![
+                MqttEvent::Outgoing(Outgoing::Publish(1)),
+                MqttEvent::Incoming(Packet::PubComp(PubComp::new(1))),
+            ],
+        );
+        assert_eq!(first.try_recv(), Ok(BatchStatus::Delivered));
+        assert!(unacknowledged.state.lock().unwrap().in_flight.is_empty());
+    }
+
+    #[test]
+    fn resolves_colliding_publishes_in_order() {
+        let unacknowledged = Unacknowledged::default();
+        let mut first = push_publish(&unacknowledged);
+        let mut second = push_publish(&unacknowledged);
+
+        handle_all(
+            &unacknowledged,
+            vec![
+                MqttEvent::Outgoing(Outgoing::Publish(1)),
+                MqttEvent::Outgoing(Outgoing::AwaitAck(1)),
+                // The colliding publish is sent before the acknowledgement
+                // freeing its packet id is reported.
+                MqttEvent::Outgoing(Outgoing::Publish(1)),
+                MqttEvent::Incoming(Packet::PubAck(PubAck::new(1))),
+            ],
+        );
+        assert_eq!(first.try_recv(), Ok(BatchStatus::Delivered));
+        assert!(second.try_recv().is_err());
+
+        unacknowledged.handle(&MqttEvent::Incoming(Packet::PubAck(PubAck::new(1))));
+        assert_eq!(second.try_recv(), Ok(BatchStatus::Delivered));
+    }
+}
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        config::SinkContext,
+        test_util::{random_lines_with_stream, random_string, trace_init},
+    };
+    use rumqttc::QoS;
+    use vector_core::event::{BatchNotifier, BatchStatus};
+
+    #[tokio::test]
+    async fn mqtt_happy() {
+        trace_init();
+
+        let topic = format!("test/{}", random_string(10));
+        let config = MqttSinkConfig {
+            topic: format!("{}/{{{{ device }}}}", topic),
+            qos: MqttQoS::AtLeastOnce,
+            retain: false,
+            encoding: EncodingConfig::from(Encoding::Text),
+            connection: MqttConfig {
+                client_id: Some(format!("vector-sink-{}", random_string(10))),
+                ..MqttConfig::default()
+            },
+        };
+
+        // Subscribe before publishing, as the messages aren't retained.
+        let subscriber = MqttConfig {
+            client_id: Some(format!("vector-subscriber-{}", random_string(10))),
+            ..MqttConfig::default()
+        };
+        let (client, mut eventloop) = subscriber.build_client().unwrap();
+        client
+            .subscribe(format!("{}/#", topic), QoS::AtLeastOnce)
+            .await
+            .unwrap();
+        loop {
+            if let MqttEvent::Incoming(Packet::SubAck(_)) = eventloop.poll().await.unwrap() {
+                break;
+            }
+        }
+
+        let cx = SinkContext::new_test();
+        let (sink, healthcheck) = config.build(cx).await.unwrap();
+        healthcheck.await.expect("Health check failed");
+
+        let num_events = 100;
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input, events) = random_lines_with_stream(100, num_events, Some(batch));
+        let events = events.map(|mut event| {
+            event.as_mut_log().insert("device", "device-1");
+            event
+        });
+        sink.run(events).await.unwrap();
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let mut output = Vec::new();
+        while output.len() < num_events {
+            if let MqttEvent::Incoming(Packet::Publish(publish)) = eventloop.poll().await.unwrap() {
+                assert_eq!(publish.topic, format!("{}/device-1", topic));
+                output.push(String::from_utf8(publish.payload.to_vec()).unwrap());
+            }
+        }
+
+        assert_eq!(output, input);
+    }
+}
@@ -4,11 +4,13 @@ use crate::{
     emit,
     event::Event,
     internal_events::{NatsEventSendFail, NatsEventSendSuccess, TemplateRenderingFailed},
+    nats::{to_nats_options, NatsAuthConfig},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
         StreamSink,
     },
     template::{Template, TemplateParseError},
+    tls::TlsOptions,
 };
 use async_trait::async_trait;
 use futures::{stream::BoxStream, FutureExt, StreamExt, TryFutureExt};
@@ -33,6 +35,8 @@ pub struct NatsSinkConfig {
     connection_name: String,
     subject: String,
     url: String,
+    tls: Option<TlsOptions>,
+    auth: Option<NatsAuthConfig>,
 }
 
 fn default_name() -> String {
@@ -85,19 +89,8 @@ impl SinkConfig for NatsSinkConfig {
 }
 
 impl NatsSinkConfig {
-    fn to_nats_options(&self) -> async_nats::Options {
-        // Set reconnect_buffer_size on the nats client to 0 bytes so that the
-        // client doesn't buffer internally (to avoid message loss).
-        async_nats::Options::new()
-            .with_name(&self.connection_name)
-            .reconnect_buffer_size(0)
-    }
-
     async fn connect(&self) -> crate::Result<async_nats::Connection> {
-        self.to_nats_options()
-            .connect(&self.url)
-            .map_err(|e| e.into())
-            .await
+        NatsOptions::from(self).connect(&self.url).await
     }
 }
 
@@ -112,6 +105,17 @@ async fn healthcheck(config: NatsSinkConfig) -> crate::Result<()> {
 #[derive(Clone)]
 struct NatsOptions {
     connection_name: String,
+    tls: Option<TlsOptions>,
+    auth: Option<NatsAuthConfig>,
+}
+
+impl NatsOptions {
+    async fn connect(&self, url: &str) -> crate::Result<async_nats::Connection> {
+        to_nats_options(&self.connection_name, self.auth.as_ref(), self.tls.as_ref())?
+            .connect(url)
+            .map_err(|e| e.into())
+            .await
+    }
 }
 
 pub struct NatsSink {
@@ -134,18 +138,12 @@ impl NatsSink {
     }
 }
 
-impl From<NatsOptions> for async_nats::Options {
-    fn from(options: NatsOptions) -> Self {
-        async_nats::Options::new()
-            .with_name(&options.connection_name)
-            .reconnect_buffer_size(0)
-    }
-}
-
 impl From<&NatsSinkConfig> for NatsOptions {
     fn from(options: &NatsSinkConfig) -> Self {
         Self {
             connection_name: options.connection_name.clone(),
+            tls: options.tls.clone(),
+            auth: options.auth.clone(),
         }
     }
 }
@@ -153,9 +151,7 @@ impl From<&NatsSinkConfig> for NatsOptions {
 #[async_trait]
 impl StreamSink for NatsSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
-        let nats_options: async_nats::Options = self.options.clone().into();
-
-        let nc = nats_options.connect(&self.url).await.map_err(|_| ())?;
+        let nc = self.options.connect(&self.url).await.map_err(|_| ())?;
 
         while let Some(event) = input.next().await {
             let subject = match self.subject.render_string(&event) {
@@ -262,6 +258,8 @@ mod integration_tests {
             connection_name: "".to_owned(),
             subject: subject.clone(),
             url: "nats://127.0.0.1:4222".to_owned(),
+            tls: None,
+            auth: None,
         };
 
         // Establish the consumer subscription.
@@ -5,7 +5,7 @@ use crate::{
 use chrono::Utc;
 use indexmap::map::IndexMap;
 use prometheus_parser::{proto, METRIC_NAME_LABEL};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write as _;
 
 pub(super) trait MetricCollector {
@@ -130,6 +130,65 @@ pub(super) trait MetricCollector {
                         self.emit_value(timestamp, name, "_count", 0.0, tags, None);
                     }
                 }
+                MetricValue::Sketch {
+                    sketch,
+                    statistic: StatisticKind::Histogram,
+                } => {
+                    let mut value = 0f64;
+                    for bucket in sketch.buckets(buckets) {
+                        value += bucket.count as f64;
+                        self.emit_value(
+                            timestamp,
+                            name,
+                            "_bucket",
+                            value,
+                            tags,
+                            Some(("le", bucket.upper_limit.to_string())),
+                        );
+                    }
+                    self.emit_value(
+                        timestamp,
+                        name,
+                        "_bucket",
+                        sketch.count() as f64,
+                        tags,
+                        Some(("le", "+Inf".to_string())),
+                    );
+                    self.emit_value(timestamp, name, "_sum", sketch.sum(), tags, None);
+                    self.emit_value(timestamp, name, "_count", sketch.count() as f64, tags, None);
+                }
+                MetricValue::Sketch {
+                    sketch,
+                    statistic: StatisticKind::Summary,
+                } => {
+                    if let Some(statistic) = DistributionStatistic::from_sketch(sketch, quantiles) {
+                        for (q, v) in statistic.quantiles.iter() {
+                            self.emit_value(
+                                timestamp,
+                                name,
+                                "",
+                                *v,
+                                tags,
+                                Some(("quantile", q.to_string())),
+                            );
+                        }
+                        self.emit_value(timestamp, name, "_sum", statistic.sum, tags, None);
+                        self.emit_value(
+                            timestamp,
+                            name,
+                            "_count",
+                            statistic.count as f64,
+                            tags,
+                            None,
+                        );
+                        self.emit_value(timestamp, name, "_min", statistic.min, tags, None);
+                        self.emit_value(timestamp, name, "_max", statistic.max, tags, None);
+                        self.emit_value(timestamp, name, "_avg", statistic.avg, tags, None);
+                    } else {
+                        self.emit_value(timestamp, name, "_sum", 0.0, tags, None);
+                        self.emit_value(timestamp, name, "_count", 0.0, tags, None);
+                    }
+                }
                 MetricValue::AggregatedHistogram {
                     buckets,
                     count,
@@ -183,22 +242,50 @@ pub(super) trait MetricCollector {
     }
 }
 
+/// The text formats the metrics are exposed in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Format {
+    /// The Prometheus text format, version 0.0.4.
+    Text,
+    /// The OpenMetrics text format, version 1.0.0.
+    OpenMetrics,
+}
+
 pub(super) struct StringCollector {
     // BTreeMap ensures we get sorted output, which whilst not required is preferable
     processed: BTreeMap<String, String>,
+    format: Format,
+    timestamps: bool,
+    /// The OpenMetrics counters named without the `_total` suffix their
+    /// samples need.
+    counters: BTreeSet<String>,
 }
 
 impl MetricCollector for StringCollector {
     type Output = String;
 
     fn new() -> Self {
-        let processed = BTreeMap::new();
-        Self { processed }
+        Self::with_options(Format::Text, true)
     }
 
     fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
         if !self.processed.contains_key(fullname) {
-            let header = Self::encode_header(name, fullname, value);
+            let header = match self.format {
+                Format::Text => Self::encode_header(name, fullname, value),
+                Format::OpenMetrics => {
+                    let is_counter = matches!(value, MetricValue::Counter { .. });
+                    let family = match fullname.strip_suffix("_total") {
+                        Some(family) if is_counter => family,
+                        _ => {
+                            if is_counter {
+                                self.counters.insert(fullname.into());
+                            }
+                            fullname
+                        }
+                    };
+                    Self::encode_header(name, family, value)
+                }
+            };
             self.processed.insert(fullname.into(), header);
         }
     }
@@ -212,6 +299,14 @@ impl MetricCollector for StringCollector {
         tags: Option<&BTreeMap<String, String>>,
         extra: Option<(&str, String)>,
     ) {
+        let suffix = match self.format {
+            Format::Text => suffix,
+            // These are not part of OpenMetrics summaries.
+            Format::OpenMetrics if matches!(suffix, "_min" | "_max" | "_avg") => return,
+            Format::OpenMetrics if suffix.is_empty() && self.counters.contains(name) => "_total",
+            Format::OpenMetrics => suffix,
+        };
+        let timestamp_millis = timestamp_millis.filter(|_| self.timestamps);
         let result = self
             .processed
             .get_mut(name)
@@ -220,18 +315,39 @@ impl MetricCollector for StringCollector {
         result.push_str(name);
         result.push_str(suffix);
         Self::encode_tags(result, tags, extra);
-        let _ = match timestamp_millis {
-            None => writeln!(result, " {}", value),
-            Some(timestamp) => writeln!(result, " {} {}", value, timestamp),
+        let _ = match (timestamp_millis, self.format) {
+            (None, _) => writeln!(result, " {}", value),
+            (Some(timestamp), Format::Text) => writeln!(result, " {} {}", value, timestamp),
+            // OpenMetrics timestamps are in seconds.
+            (Some(timestamp), Format::OpenMetrics) => {
+                writeln!(result, " {} {}", value, timestamp as f64 / 1000.0)
+            }
         };
     }
 
     fn finish(self) -> String {
-        self.processed.into_iter().map(|(_, value)| value).collect()
+        let mut output = self
+            .processed
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<String>();
+        if self.format == Format::OpenMetrics {
+            output.push_str("# EOF\n");
+        }
+        output
     }
 }
 
 impl StringCollector {
+    pub(super) fn with_options(format: Format, timestamps: bool) -> Self {
+        Self {
+            processed: BTreeMap::new(),
+            format,
+            timestamps,
+            counters: BTreeSet::new(),
+        }
+    }
+
     fn encode_tags(
         result: &mut String,
         tags: Option<&BTreeMap<String, String>>,
@@ -381,6 +497,14 @@ fn prometheus_metric_type(metric_value: &MetricValue) -> proto::MetricType {
         } => MetricType::Summary,
         MetricValue::AggregatedHistogram { .. } => MetricType::Histogram,
         MetricValue::AggregatedSummary { .. } => MetricType::Summary,
+        MetricValue::Sketch {
+            statistic: StatisticKind::Histogram,
+            ..
+        } => MetricType::Histogram,
+        MetricValue::Sketch {
+            statistic: StatisticKind::Summary,
+            ..
+        } => MetricType::Summary,
     }
 }
 
@@ -388,7 +512,7 @@ fn prometheus_metric_type(metric_value: &MetricValue) -> proto::MetricType {
 mod tests {
     use super::super::default_summary_quantiles;
     use super::*;
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use crate::event::metric::{DDSketch, Metric, MetricKind, MetricValue, StatisticKind};
     use chrono::{DateTime, TimeZone};
     use indoc::indoc;
     use pretty_assertions::assert_eq;
@@ -780,6 +904,109 @@ mod tests {
         )
     }
 
+    #[test]
+    fn encodes_sketch_histogram_text() {
+        assert_eq!(
+            encode_sketch_histogram::<StringCollector>(),
+            indoc! {r#"
+                # HELP vector_requests requests
+                # TYPE vector_requests histogram
+                vector_requests_bucket{le="0"} 0 1612325106789
+                vector_requests_bucket{le="2.5"} 6 1612325106789
+                vector_requests_bucket{le="5"} 8 1612325106789
+                vector_requests_bucket{le="+Inf"} 8 1612325106789
+                vector_requests_sum 15 1612325106789
+                vector_requests_count 8 1612325106789
+            "#}
+        );
+    }
+
+    #[test]
+    fn encodes_sketch_histogram_request() {
+        assert_eq!(
+            encode_sketch_histogram::<TimeSeries>(),
+            write_request!(
+                "vector_requests", "requests", Histogram [
+                        "_bucket" @ 1612325106789 = 0.0 ["le" => "0"],
+                        "_bucket" @ 1612325106789 = 6.0 ["le" => "2.5"],
+                        "_bucket" @ 1612325106789 = 8.0 ["le" => "5"],
+                        "_bucket" @ 1612325106789 = 8.0 ["le" => "+Inf"],
+                        "_sum" @ 1612325106789 = 15.0 [],
+                        "_count" @ 1612325106789 = 8.0 []
+                ]
+            )
+        );
+    }
+
+    fn encode_sketch_histogram<T: MetricCollector>() -> T::Output {
+        let metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Sketch {
+                sketch: DDSketch::from_samples(
+                    0.01,
+                    &vector_core::samples![1.0 => 3, 2.0 => 3, 3.0 => 2],
+                ),
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_timestamp(Some(timestamp()));
+        encode_one::<T>(Some("vector"), &[0.0, 2.5, 5.0], &[], false, &metric)
+    }
+
+    #[test]
+    fn encodes_sketch_summary_text() {
+        assert_eq!(
+            encode_sketch_summary::<StringCollector>(),
+            indoc! {r#"
+                # HELP ns_requests requests
+                # TYPE ns_requests summary
+                ns_requests{code="200",quantile="0"} 1 1612325106789
+                ns_requests{code="200",quantile="1"} 3 1612325106789
+                ns_requests_sum{code="200"} 15 1612325106789
+                ns_requests_count{code="200"} 8 1612325106789
+                ns_requests_min{code="200"} 1 1612325106789
+                ns_requests_max{code="200"} 3 1612325106789
+                ns_requests_avg{code="200"} 1.875 1612325106789
+            "#}
+        );
+    }
+
+    #[test]
+    fn encodes_sketch_summary_request() {
+        assert_eq!(
+            encode_sketch_summary::<TimeSeries>(),
+            write_request!(
+                "ns_requests", "requests", Summary [
+                    "" @ 1612325106789 = 1.0 ["code" => "200", "quantile" => "0"],
+                    "" @ 1612325106789 = 3.0 ["code" => "200", "quantile" => "1"],
+                    "_sum" @ 1612325106789 = 15.0 ["code" => "200"],
+                    "_count" @ 1612325106789 = 8.0 ["code" => "200"],
+                    "_min" @ 1612325106789 = 1.0 ["code" => "200"],
+                    "_max" @ 1612325106789 = 3.0 ["code" => "200"],
+                    "_avg" @ 1612325106789 = 1.875 ["code" => "200"]
+                ]
+            )
+        );
+    }
+
+    fn encode_sketch_summary<T: MetricCollector>() -> T::Output {
+        let metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Sketch {
+                sketch: DDSketch::from_samples(
+                    0.01,
+                    &vector_core::samples![1.0 => 3, 2.0 => 3, 3.0 => 2],
+                ),
+                statistic: StatisticKind::Summary,
+            },
+        )
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(timestamp()));
+        encode_one::<T>(Some("ns"), &[], &[0.0, 1.0], false, &metric)
+    }
+
     #[test]
     fn encodes_timestamp_text() {
         assert_eq!(
@@ -800,14 +1027,79 @@ mod tests {
         );
     }
 
+    fn encode_openmetrics(timestamps: bool, metrics: &[Metric]) -> String {
+        let mut s = StringCollector::with_options(Format::OpenMetrics, timestamps);
+        for metric in metrics {
+            s.encode_metric(Some("ns"), &[], &[0.5], false, metric);
+        }
+        s.finish()
+    }
+
+    #[test]
+    fn encodes_openmetrics() {
+        let counter = |name: &str| {
+            Metric::new(
+                name.to_owned(),
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 10.0 },
+            )
+            .with_tags(Some(tags()))
+            .with_timestamp(Some(timestamp()))
+        };
+        let summary = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 1, 3.0 => 1],
+                statistic: StatisticKind::Summary,
+            },
+        )
+        .with_timestamp(Some(timestamp()));
+
+        assert_eq!(
+            encode_openmetrics(true, &[counter("hits"), counter("bytes_total"), summary]),
+            indoc! {r#"
+                # HELP ns_bytes bytes_total
+                # TYPE ns_bytes counter
+                ns_bytes_total{code="200"} 10 1612325106.789
+                # HELP ns_hits hits
+                # TYPE ns_hits counter
+                ns_hits_total{code="200"} 10 1612325106.789
+                # HELP ns_requests requests
+                # TYPE ns_requests summary
+                ns_requests{quantile="0.5"} 1 1612325106.789
+                ns_requests_sum 4 1612325106.789
+                ns_requests_count 2 1612325106.789
+                # EOF
+            "#}
+        );
+    }
+
+    #[test]
+    fn suppresses_timestamps() {
+        let mut s = StringCollector::with_options(Format::Text, false);
+        s.encode_metric(None, &[], &[], false, &encode_timestamp_metric());
+        assert_eq!(
+            s.finish(),
+            indoc! {r#"
+                # HELP temperature temperature
+                # TYPE temperature counter
+                temperature 2
+            "#}
+        );
+    }
+
     fn encode_timestamp<T: MetricCollector>() -> T::Output {
-        let metric = Metric::new(
+        encode_one::<T>(None, &[], &[], false, &encode_timestamp_metric())
+    }
+
+    fn encode_timestamp_metric() -> Metric {
+        Metric::new(
             "temperature".to_owned(),
             MetricKind::Absolute,
             MetricValue::Counter { value: 2.0 },
         )
-        .with_timestamp(Some(timestamp()));
-        encode_one::<T>(None, &[], &[], false, &metric)
+        .with_timestamp(Some(timestamp()))
     }
 
     #[test]
@@ -31,10 +31,13 @@ use std::{
 };
 use stream_cancel::{Trigger, Tripwire};
 
-use super::collector::{self, MetricCollector as _};
+use super::collector::{self, Format, MetricCollector as _};
 
 const MIN_FLUSH_PERIOD_SECS: u64 = 1;
 
+const TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Flush period for sets must be greater or equal to {} secs", min))]
@@ -55,6 +58,12 @@ pub struct PrometheusExporterConfig {
     pub quantiles: Vec<f64>,
     #[serde(default = "default_flush_period_secs")]
     pub flush_period_secs: u64,
+    /// The buckets of the distributions of given names, instead of `buckets`.
+    #[serde(default)]
+    pub bucket_overrides: IndexMap<String, Vec<f64>>,
+    pub expire_metrics_secs: Option<u64>,
+    #[serde(default = "crate::serde::default_false")]
+    pub suppress_timestamp: bool,
 }
 
 impl std::default::Default for PrometheusExporterConfig {
@@ -66,6 +75,9 @@ impl std::default::Default for PrometheusExporterConfig {
             buckets: super::default_histogram_buckets(),
             quantiles: super::default_summary_quantiles(),
             flush_period_secs: default_flush_period_secs(),
+            bucket_overrides: IndexMap::new(),
+            expire_metrics_secs: None,
+            suppress_timestamp: false,
         }
     }
 }
@@ -161,34 +173,70 @@ struct PrometheusExporter {
 }
 
 struct ExpiringMetrics {
-    map: IndexMap<MetricEntry, bool>,
+    map: IndexMap<MetricEntry, EntryState>,
     last_flush_timestamp: i64,
 }
 
+struct EntryState {
+    /// Incremental sets are reset every flush period.
+    is_incremental_set: bool,
+    /// When the series was last updated, in seconds.
+    updated_timestamp: i64,
+}
+
+impl PrometheusExporterConfig {
+    /// Whether a series last updated at `updated_timestamp` is stale at `now`.
+    fn is_expired(&self, updated_timestamp: i64, now: i64) -> bool {
+        self.expire_metrics_secs
+            .map_or(false, |secs| now - updated_timestamp > secs as i64)
+    }
+}
+
 fn handle(
     req: Request<Body>,
-    default_namespace: Option<&str>,
-    buckets: &[f64],
-    quantiles: &[f64],
+    config: &PrometheusExporterConfig,
     expired: bool,
-    metrics: &IndexMap<MetricEntry, bool>,
+    metrics: &IndexMap<MetricEntry, EntryState>,
 ) -> Response<Body> {
     let mut response = Response::new(Body::empty());
 
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/metrics") => {
-            let mut s = collector::StringCollector::new();
+            let format = if accepts_openmetrics(&req) {
+                Format::OpenMetrics
+            } else {
+                Format::Text
+            };
+            let mut s =
+                collector::StringCollector::with_options(format, !config.suppress_timestamp);
+            let now = Utc::now().timestamp();
 
-            for (MetricEntry(metric), _) in metrics {
-                s.encode_metric(default_namespace, buckets, quantiles, expired, metric);
+            for (MetricEntry(metric), state) in metrics {
+                if config.is_expired(state.updated_timestamp, now) {
+                    continue;
+                }
+                let buckets = config
+                    .bucket_overrides
+                    .get(metric.name())
+                    .unwrap_or(&config.buckets);
+                s.encode_metric(
+                    config.default_namespace.as_deref(),
+                    buckets,
+                    &config.quantiles,
+                    expired,
+                    metric,
+                );
             }
 
             *response.body_mut() = s.finish().into();
 
-            response.headers_mut().insert(
-                "Content-Type",
-                HeaderValue::from_static("text/plain; version=0.0.4"),
-            );
+            let content_type = match format {
+                Format::Text => TEXT_CONTENT_TYPE,
+                Format::OpenMetrics => OPENMETRICS_CONTENT_TYPE,
+            };
+            response
+                .headers_mut()
+                .insert("Content-Type", HeaderValue::from_static(content_type));
         }
         _ => {
             *response.status_mut() = StatusCode::NOT_FOUND;
@@ -198,6 +246,15 @@ fn handle(
     response
 }
 
+/// Whether the client asks for the OpenMetrics format.
+fn accepts_openmetrics(req: &Request<Body>) -> bool {
+    req.headers()
+        .get_all(hyper::header::ACCEPT)
+        .iter()
+        .filter_map(|accept| accept.to_str().ok())
+        .any(|accept| accept.contains("application/openmetrics-text"))
+}
+
 impl PrometheusExporter {
     fn new(config: PrometheusExporterConfig, acker: Acker) -> Self {
         Self {
@@ -217,39 +274,24 @@ impl PrometheusExporter {
         }
 
         let metrics = Arc::clone(&self.metrics);
-        let default_namespace = self.config.default_namespace.clone();
-        let buckets = self.config.buckets.clone();
-        let quantiles = self.config.quantiles.clone();
-        let flush_period_secs = self.config.flush_period_secs;
+        let config = Arc::new(self.config.clone());
 
         let new_service = make_service_fn(move |_| {
             let metrics = Arc::clone(&metrics);
-            let default_namespace = default_namespace.clone();
-            let buckets = buckets.clone();
-            let quantiles = quantiles.clone();
-            let flush_period_secs = flush_period_secs;
+            let config = Arc::clone(&config);
 
             async move {
                 Ok::<_, Infallible>(service_fn(move |req| {
                     let metrics = metrics.read().unwrap();
                     let interval = (Utc::now().timestamp() - metrics.last_flush_timestamp) as u64;
-                    let expired = interval > flush_period_secs;
+                    let expired = interval > config.flush_period_secs;
 
                     let response = info_span!(
                         "prometheus_server",
                         method = ?req.method(),
                         path = ?req.uri().path(),
                     )
-                    .in_scope(|| {
-                        handle(
-                            req,
-                            default_namespace.as_deref(),
-                            &buckets,
-                            &quantiles,
-                            expired,
-                            &metrics.map,
-                        )
-                    });
+                    .in_scope(|| handle(req, &config, expired, &metrics.map));
 
                     emit!(PrometheusServerRequestComplete {
                         status_code: response.status(),
@@ -301,14 +343,17 @@ impl StreamSink for PrometheusExporter {
             if interval > self.config.flush_period_secs as i64 {
                 metrics.last_flush_timestamp = now;
 
+                let config = &self.config;
                 metrics.map = metrics
                     .map
                     .drain(..)
-                    .map(|(MetricEntry(mut metric), is_incremental_set)| {
-                        if is_incremental_set {
+                    // Stale series are forgotten.
+                    .filter(|(_, state)| !config.is_expired(state.updated_timestamp, now))
+                    .map(|(MetricEntry(mut metric), state)| {
+                        if state.is_incremental_set {
                             metric.zero();
                         }
-                        (MetricEntry(metric), is_incremental_set)
+                        (MetricEntry(metric), state)
                     })
                     .collect();
             }
@@ -316,20 +361,37 @@ impl StreamSink for PrometheusExporter {
             match item.kind() {
                 MetricKind::Incremental => {
                     let mut entry = MetricEntry(item.into_absolute());
-                    if let Some((MetricEntry(mut existing), _)) = metrics.map.remove_entry(&entry) {
-                        if existing.update(&entry) {
-                            entry = MetricEntry(existing);
-                        } else {
-                            warn!(message = "Metric changed type, dropping old value.", series = %entry.series());
+                    if let Some((MetricEntry(mut existing), state)) =
+                        metrics.map.remove_entry(&entry)
+                    {
+                        // A stale series starts over.
+                        if !self.config.is_expired(state.updated_timestamp, now) {
+                            if existing.update(&entry) {
+                                entry = MetricEntry(existing);
+                            } else {
+                                warn!(message = "Metric changed type, dropping old value.", series = %entry.series());
+                            }
                         }
                     }
-                    let is_set = matches!(entry.value(), MetricValue::Set { .. });
-                    metrics.map.insert(entry, is_set);
+                    let is_incremental_set = matches!(entry.value(), MetricValue::Set { .. });
+                    metrics.map.insert(
+                        entry,
+                        EntryState {
+                            is_incremental_set,
+                            updated_timestamp: now,
+                        },
+                    );
                 }
                 MetricKind::Absolute => {
                     let new = MetricEntry(item);
                     metrics.map.remove(&new);
-                    metrics.map.insert(new, false);
+                    metrics.map.insert(
+                        new,
+                        EntryState {
+                            is_incremental_set: false,
+                            updated_timestamp: now,
+                        },
+                    );
                 }
             };
 
@@ -380,6 +442,9 @@ impl Hash for MetricEntry {
                     quantile.upper_limit.to_bits().hash(state);
                 }
             }
+            MetricValue::Sketch { sketch, .. } => {
+                sketch.relative_accuracy().to_bits().hash(state);
+            }
             _ => {}
         }
     }
@@ -424,6 +489,14 @@ impl PartialEq for MetricEntry {
                             .zip(quantiles2.iter())
                             .all(|(q1, q2)| q1.upper_limit == q2.upper_limit)
                 }
+                (
+                    MetricValue::Sketch {
+                        sketch: sketch1, ..
+                    },
+                    MetricValue::Sketch {
+                        sketch: sketch2, ..
+                    },
+                ) => sketch1.relative_accuracy() == sketch2.relative_accuracy(),
                 _ => true,
             }
     }
@@ -434,7 +507,7 @@ mod tests {
     use super::*;
     use crate::{
         config::ProxyConfig,
-        event::metric::{Metric, MetricValue},
+        event::metric::{Metric, MetricValue, StatisticKind},
         http::HttpClient,
         test_util::{next_addr, random_string, trace_init},
         tls::MaybeTlsSettings,
@@ -488,6 +561,152 @@ mod tests {
         );
     }
 
+    async fn run_and_render(
+        mut config: PrometheusExporterConfig,
+        events: Vec<Event>,
+        accept: Option<&str>,
+    ) -> (PrometheusExporter, Response<Body>) {
+        // Not actually scraped, the rendering is tested directly.
+        config.address = next_addr();
+        let mut sink = PrometheusExporter::new(config, SinkContext::new_test().acker());
+        sink.run(Box::pin(futures::stream::iter(events)))
+            .await
+            .unwrap();
+        let response = render(&sink, accept);
+        (sink, response)
+    }
+
+    fn render(sink: &PrometheusExporter, accept: Option<&str>) -> Response<Body> {
+        let mut request = Request::get("/metrics");
+        if let Some(accept) = accept {
+            request = request.header(hyper::header::ACCEPT, accept);
+        }
+        let request = request.body(Body::empty()).unwrap();
+        handle(
+            request,
+            &sink.config,
+            false,
+            &sink.metrics.read().unwrap().map,
+        )
+    }
+
+    async fn body_string(response: Response<Body>) -> String {
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_openmetrics() {
+        let (name, event) = create_metric(None, MetricValue::Counter { value: 3.0 });
+        let config = PrometheusExporterConfig {
+            suppress_timestamp: true,
+            ..Default::default()
+        };
+        let (_, response) = run_and_render(
+            config,
+            vec![event],
+            Some("application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5"),
+        )
+        .await;
+
+        assert_eq!(response.headers()["Content-Type"], OPENMETRICS_CONTENT_TYPE);
+        assert_eq!(
+            body_string(response).await,
+            format!(
+                indoc! {r#"
+                    # HELP {name} {name}
+                    # TYPE {name} counter
+                    {name}_total{{some_tag="some_value"}} 3
+                    # EOF
+                "#},
+                name = name
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn serves_text_by_default() {
+        let (_, event) = create_metric_gauge(None, 1.0);
+        let (_, response) = run_and_render(Default::default(), vec![event], None).await;
+        assert_eq!(response.headers()["Content-Type"], TEXT_CONTENT_TYPE);
+        assert!(!body_string(response).await.contains("# EOF"));
+    }
+
+    #[tokio::test]
+    async fn expires_stale_series() {
+        let (name1, event1) = create_metric_gauge(None, 1.0);
+        let (name2, event2) = create_metric_gauge(None, 2.0);
+        let config = PrometheusExporterConfig {
+            expire_metrics_secs: Some(60),
+            ..Default::default()
+        };
+        let (sink, response) = run_and_render(config, vec![event1, event2], None).await;
+        let body = body_string(response).await;
+        assert!(body.contains(&name1));
+        assert!(body.contains(&name2));
+
+        sink.metrics
+            .write()
+            .unwrap()
+            .map
+            .get_index_mut(0)
+            .unwrap()
+            .1
+            .updated_timestamp -= 61;
+
+        let body = body_string(render(&sink, None)).await;
+        assert!(!body.contains(&name1));
+        assert!(body.contains(&name2));
+    }
+
+    #[tokio::test]
+    async fn overrides_buckets() {
+        let distribution = |name: &str| {
+            create_metric(
+                Some(name.into()),
+                MetricValue::Distribution {
+                    samples: vector_core::samples![0.2 => 1, 300.0 => 1],
+                    statistic: StatisticKind::Histogram,
+                },
+            )
+            .1
+        };
+        let mut config = PrometheusExporterConfig {
+            buckets: vec![1.0],
+            suppress_timestamp: true,
+            ..Default::default()
+        };
+        config
+            .bucket_overrides
+            .insert("response_size".into(), vec![100.0, 1000.0]);
+
+        let (_, response) = run_and_render(
+            config,
+            vec![distribution("latency"), distribution("response_size")],
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            body_string(response).await,
+            indoc! {r#"
+                # HELP latency latency
+                # TYPE latency histogram
+                latency_bucket{le="1",some_tag="some_value"} 1
+                latency_bucket{le="+Inf",some_tag="some_value"} 2
+                latency_sum{some_tag="some_value"} 300.2
+                latency_count{some_tag="some_value"} 2
+                # HELP response_size response_size
+                # TYPE response_size histogram
+                response_size_bucket{le="100",some_tag="some_value"} 1
+                response_size_bucket{le="1000",some_tag="some_value"} 2
+                response_size_bucket{le="+Inf",some_tag="some_value"} 2
+                response_size_sum{some_tag="some_value"} 300.2
+                response_size_count{some_tag="some_value"} 2
+            "#}
+        );
+    }
+
     async fn export_and_fetch(tls_config: Option<TlsConfig>, events: Vec<Event>) -> String {
         trace_init();
 
@@ -13,7 +13,7 @@ use crate::{
     template::{Template, TemplateParseError},
 };
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
-use redis::{aio::ConnectionManager, RedisError, RedisResult};
+use redis::{aio::ConnectionManager, streams::StreamMaxlen, RedisError, RedisResult};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
@@ -41,6 +41,7 @@ pub enum DataTypeConfig {
     #[derivative(Default)]
     List,
     Channel,
+    Stream,
 }
 
 #[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
@@ -49,13 +50,40 @@ pub struct ListOption {
     method: Method,
 }
 
-#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    /// Trims the stream to about this many entries on each `XADD`.
+    maxlen: Option<usize>,
+    /// Trims the stream to exactly `maxlen` entries, which is less efficient.
+    #[serde(default)]
+    exact: bool,
+    #[serde(default = "default_stream_field")]
+    field: String,
+}
+
+fn default_stream_field() -> String {
+    "message".into()
+}
+
+impl Default for StreamOption {
+    fn default() -> Self {
+        Self {
+            maxlen: None,
+            exact: false,
+            field: default_stream_field(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
 #[serde(rename_all = "lowercase")]
 pub enum DataType {
     #[derivative(Default)]
     List(Method),
     Channel,
+    Stream(StreamOption),
 }
 
 #[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
@@ -82,6 +110,8 @@ pub struct RedisSinkConfig {
     data_type: DataTypeConfig,
     #[serde(alias = "list")]
     list_option: Option<ListOption>,
+    #[serde(alias = "stream")]
+    stream_option: Option<StreamOption>,
     url: String,
     key: String,
     #[serde(default)]
@@ -150,6 +180,9 @@ impl RedisSinkConfig {
         let data_type = match self.data_type {
             DataTypeConfig::Channel => DataType::Channel,
             DataTypeConfig::List => DataType::List(method.unwrap_or_default()),
+            DataTypeConfig::Stream => {
+                DataType::Stream(self.stream_option.clone().unwrap_or_default())
+            }
         };
 
         let batch = BatchSettings::default()
@@ -285,7 +318,7 @@ impl Service<Vec<RedisKvEntry>> for RedisSink {
 
         for kv in kvs {
             byte_size += kv.encoded_length();
-            match self.data_type {
+            match &self.data_type {
                 DataType::List(method) => match method {
                     Method::LPush => {
                         if count > 1 {
@@ -309,6 +342,27 @@ impl Service<Vec<RedisKvEntry>> for RedisSink {
                         pipe.publish(kv.key, kv.value);
                     }
                 }
+                DataType::Stream(option) => {
+                    if count > 1 {
+                        pipe.atomic();
+                    }
+                    let items = [(option.field.as_str(), kv.value)];
+                    // `XADD` replies with the entry's id rather than a
+                    // success flag, errors are still reported.
+                    match option.maxlen {
+                        Some(maxlen) => {
+                            let maxlen = if option.exact {
+                                StreamMaxlen::Equals(maxlen)
+                            } else {
+                                StreamMaxlen::Approx(maxlen)
+                            };
+                            pipe.xadd_maxlen(kv.key, maxlen, "*", &items).ignore();
+                        }
+                        None => {
+                            pipe.xadd(kv.key, "*", &items).ignore();
+                        }
+                    }
+                }
             }
         }
 
@@ -342,6 +396,25 @@ mod tests {
         crate::test_util::test_generate_config::<RedisSinkConfig>();
     }
 
+    #[test]
+    fn parse_stream_config() {
+        let config: RedisSinkConfig = toml::from_str(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            data_type = "stream"
+            stream.maxlen = 1000
+            encoding.codec = "json"
+            "#,
+        )
+        .unwrap();
+
+        let option = config.stream_option.unwrap();
+        assert_eq!(option.maxlen, Some(1000));
+        assert!(!option.exact);
+        assert_eq!(option.field, "message");
+    }
+
     #[test]
     fn redis_event_json() {
         let msg = "hello_world".to_owned();
@@ -428,6 +501,7 @@ mod integration_tests {
             list_option: Some(ListOption {
                 method: Method::LPush,
             }),
+            stream_option: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -487,6 +561,7 @@ mod integration_tests {
             list_option: Some(ListOption {
                 method: Method::RPush,
             }),
+            stream_option: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -559,6 +634,7 @@ mod integration_tests {
             encoding: Encoding::Json.into(),
             data_type: DataTypeConfig::Channel,
             list_option: None,
+            stream_option: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -586,4 +662,51 @@ mod integration_tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn redis_sink_stream() {
+        trace_init();
+
+        let key = format!("test-{}", random_string(10));
+        debug!("Test key name: {}.", key);
+        let num_events = 100;
+        let maxlen = 10;
+
+        let cnf = RedisSinkConfig {
+            url: REDIS_SERVER.to_owned(),
+            key: key.clone(),
+            encoding: Encoding::Text.into(),
+            data_type: DataTypeConfig::Stream,
+            list_option: None,
+            stream_option: Some(StreamOption {
+                maxlen: Some(maxlen),
+                exact: true,
+                field: "payload".to_owned(),
+            }),
+            batch: BatchConfig::default(),
+            request: TowerRequestConfig {
+                rate_limit_num: Option::from(u64::MAX),
+                ..Default::default()
+            },
+        };
+
+        // Publish events.
+        let conn = cnf.build_client().await.unwrap();
+        let cx = SinkContext::new_test();
+
+        let sink = cnf.new(conn, cx).unwrap();
+        let (input, events) = random_lines_with_stream(100, num_events, None);
+        sink.run(events).await.unwrap();
+
+        let mut conn = cnf.build_client().await.unwrap();
+
+        // The stream was trimmed to the most recent entries.
+        let reply: redis::streams::StreamRangeReply = conn.xrange_all(key.clone()).await.unwrap();
+        let output: Vec<String> = reply
+            .ids
+            .iter()
+            .map(|entry| entry.get("payload").unwrap())
+            .collect();
+        assert_eq!(output, input[num_events - maxlen..]);
+    }
 }
@@ -3,14 +3,15 @@
 //! This buffer handles stream merging -- when a record is inserted into
 //! the buffer, all records having the same stream label set are grouped
 //! together for more efficient output.
+//!
+//! Batch sizes are counted as the JSON push request would encode them,
+//! which is an upper bound of the size of the protobuf encoding.
 
 use super::{
-    err_event_too_large, json::BoxedRawValue, Batch, BatchConfig, BatchError, BatchSettings,
-    BatchSize, PushResult,
+    err_event_too_large, Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult,
 };
 use crate::sinks::loki::OutOfOrderAction;
 use dashmap::DashMap;
-use serde_json::{json, value::to_raw_value};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -20,12 +21,21 @@ const LABEL_OVERHEAD: usize = r#""":"""#.len();
 
 pub type Labels = Vec<(String, String)>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct LokiEvent {
     pub timestamp: i64,
     pub event: String,
 }
 
+impl LokiEvent {
+    // The JSON encoding of an event is: `["<timestamp>", "<line>"]`.
+    fn encoded_len(&self) -> usize {
+        serde_json::to_string(&(self.timestamp.to_string(), &self.event))
+            .expect("JSON encoding should never fail")
+            .len()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct LokiRecord {
     pub partition: PartitionKey,
@@ -33,27 +43,19 @@ pub struct LokiRecord {
     pub event: LokiEvent,
 }
 
-#[derive(Debug)]
-struct LokiEncodedEvent {
-    pub timestamp: i64,
-    pub encoded: BoxedRawValue,
-}
-
-impl From<&LokiEvent> for LokiEncodedEvent {
-    // Pre-encode the record to JSON, but keep the timestamp for sorting at the end.
-    // The final output should be: `[ts, line]'
-    fn from(event: &LokiEvent) -> Self {
-        Self {
-            timestamp: event.timestamp,
-            encoded: to_raw_value(&json!([format!("{}", event.timestamp), event.event]))
-                .expect("JSON encoding should never fail"),
-        }
-    }
+/// The entries of a stream, sorted by their timestamp.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LokiStream {
+    pub labels: Labels,
+    pub events: Vec<LokiEvent>,
 }
 
+/// Batches are partitioned by stream, so that the requests of each stream can
+/// be sent in order.
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 pub struct PartitionKey {
     pub tenant_id: Option<String>,
+    pub labels: Labels,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -78,7 +80,7 @@ impl GlobalTimestamps {
 pub struct LokiBuffer {
     num_bytes: usize,
     num_items: usize,
-    streams: HashMap<Labels, Vec<LokiEncodedEvent>>,
+    streams: HashMap<Labels, Vec<LokiEvent>>,
     settings: BatchSize<Self>,
 
     partition: Option<PartitionKey>,
@@ -112,7 +114,7 @@ impl LokiBuffer {
 
 impl Batch for LokiBuffer {
     type Input = LokiRecord;
-    type Output = serde_json::Value;
+    type Output = Vec<LokiStream>;
 
     fn get_settings_defaults(
         config: BatchConfig,
@@ -165,8 +167,7 @@ impl Batch for LokiBuffer {
             .map(|label| label.0.len() + label.1.len() + LABEL_OVERHEAD)
             .sum::<usize>()
             + STREAM_OVERHEAD;
-        let event: LokiEncodedEvent = (&item.event).into();
-        let event_len = event.encoded.get().len();
+        let event_len = item.event.encoded_len();
 
         if self.is_empty() && WRAPPER_OVERHEAD + labels_len + event_len > self.settings.bytes {
             err_event_too_large(WRAPPER_OVERHEAD + labels_len + event_len)
@@ -178,7 +179,7 @@ impl Batch for LokiBuffer {
             let new_bytes = match self.streams.get_mut(&item.labels) {
                 // Label exists, and we checked the size, just add it
                 Some(stream) => {
-                    stream.push(event);
+                    stream.push(item.event);
                     event_len + 1
                 }
                 None => {
@@ -188,7 +189,7 @@ impl Batch for LokiBuffer {
                     if self.num_bytes + new_bytes > self.settings.bytes {
                         return PushResult::Overflow(item);
                     } else {
-                        self.streams.insert(item.labels, vec![event]);
+                        self.streams.insert(item.labels, vec![item.event]);
                         new_bytes
                     }
                 }
@@ -213,7 +214,7 @@ impl Batch for LokiBuffer {
 
     fn finish(self) -> Self::Output {
         let mut latest_timestamps = self.latest_timestamps.expect("Batch is empty");
-        let streams_json = self
+        let mut streams = self
             .streams
             .into_iter()
             .map(|(labels, mut events)| {
@@ -225,39 +226,15 @@ impl Batch for LokiBuffer {
                     events.last().expect("Batch is empty").timestamp,
                 );
 
-                let labels = labels.into_iter().collect::<HashMap<_, _>>();
-                let events = events.into_iter().map(|e| e.encoded).collect::<Vec<_>>();
-
-                (
-                    to_raw_value(&labels).expect("JSON encoding should never fail"),
-                    events,
-                )
+                LokiStream { labels, events }
             })
             .collect::<Vec<_>>();
         self.global_timestamps
             .insert(self.partition.expect("Bacth is empty"), latest_timestamps);
 
-        // This is just to guarantee stable key ordering for tests
-        #[cfg(test)]
-        let streams_json = {
-            let mut streams_json = streams_json;
-            streams_json.sort_unstable_by_key(|s| s.0.to_string());
-            streams_json
-        };
-
-        let streams_json = streams_json
-            .into_iter()
-            .map(|(stream, events)| {
-                json!({
-                    "stream": stream,
-                    "values": events,
-                })
-            })
-            .collect::<Vec<_>>();
-
-        json!({
-            "streams": streams_json,
-        })
+        // Keep the order of streams stable.
+        streams.sort_unstable_by(|a, b| a.labels.cmp(&b.labels));
+        streams
     }
 
     fn num_items(&self) -> usize {
@@ -268,10 +245,18 @@ impl Batch for LokiBuffer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sinks::loki::encode_json;
+
+    fn partition() -> PartitionKey {
+        PartitionKey {
+            tenant_id: None,
+            labels: Vec::new(),
+        }
+    }
 
     fn test_finish(buffer: LokiBuffer, expected_json: &str) {
         let buffer_bytes = buffer.num_bytes;
-        let json = serde_json::to_string(&buffer.finish()).unwrap();
+        let json = String::from_utf8(encode_json(&buffer.finish())).unwrap();
         // Does it track the number of bytes exactly before encoding?
         assert!(
             buffer_bytes == expected_json.len(),
@@ -292,7 +277,7 @@ mod tests {
         );
         assert!(matches!(
             buffer.push(LokiRecord {
-                partition: PartitionKey { tenant_id: None },
+                partition: partition(),
                 labels: vec![("label1".into(), "value1".into())],
                 event: LokiEvent {
                     timestamp: 123456789,
@@ -320,7 +305,7 @@ mod tests {
         for n in 1..4 {
             assert!(matches!(
                 buffer.push(LokiRecord {
-                    partition: PartitionKey { tenant_id: None },
+                    partition: partition(),
                     labels: vec![("asdf".into(), format!("value{}", n))],
                     event: LokiEvent {
                         timestamp: 123456780 + n,
@@ -349,7 +334,7 @@ mod tests {
         for n in 1..4 {
             assert!(matches!(
                 buffer.push(LokiRecord {
-                    partition: PartitionKey { tenant_id: None },
+                    partition: partition(),
                     labels: vec![("asdf".into(), "value1".into())],
                     event: LokiEvent {
                         timestamp: 123456780 + n,
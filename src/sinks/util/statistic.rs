@@ -1,4 +1,4 @@
-use crate::event::metric::Sample;
+use crate::event::metric::{DDSketch, Sample};
 use snafu::Snafu;
 use std::cmp::Ordering;
 
@@ -80,6 +80,22 @@ impl DistributionStatistic {
             }),
         }
     }
+
+    pub fn from_sketch(sketch: &DDSketch, quantiles: &[f64]) -> Option<Self> {
+        let count = sketch.count();
+        Some(Self {
+            min: sketch.min(),
+            max: sketch.max(),
+            median: sketch.quantile(0.5)?,
+            avg: sketch.sum() / count as f64,
+            sum: sketch.sum(),
+            count: count as u64,
+            quantiles: quantiles
+                .iter()
+                .map(|&p| sketch.quantile(p).map(|value| (p, value)))
+                .collect::<Option<_>>()?,
+        })
+    }
 }
 
 /// `bins` is a cumulative histogram
@@ -224,4 +240,40 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn test_sketch() {
+        // should return None on empty input
+        assert_eq!(
+            DistributionStatistic::from_sketch(&DDSketch::new(0.01), &[0.5]),
+            None
+        );
+
+        assert_eq!(
+            DistributionStatistic::from_sketch(
+                &DDSketch::from_samples(0.01, &samples(&[(0.9, 100)])),
+                &[0.5, 0.99],
+            )
+            .unwrap(),
+            DistributionStatistic {
+                min: 0.9,
+                max: 0.9,
+                median: 0.9,
+                avg: 0.9,
+                sum: 90.0,
+                count: 100,
+                quantiles: vec![(0.5, 0.9), (0.99, 0.9)],
+            }
+        );
+
+        let statistic = DistributionStatistic::from_sketch(
+            &DDSketch::from_samples(0.01, &samples(&[(1.0, 2), (2.0, 1), (3.0, 4), (4.0, 3)])),
+            &[0.0, 1.0],
+        )
+        .unwrap();
+        assert_eq!(statistic.count, 10);
+        assert_eq!(statistic.sum, 28.0);
+        assert_eq!(statistic.quantiles, vec![(0.0, 1.0), (1.0, 4.0)]);
+        assert!((statistic.median - 3.0).abs() <= 0.03);
+    }
 }
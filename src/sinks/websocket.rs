@@ -0,0 +1,481 @@
+use crate::{
+    buffers::Acker,
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    dns, emit,
+    event::Event,
+    http::{Auth, MaybeAuth},
+    internal_events::{
+        ConnectionOpen, OpenGauge, WsConnectionError, WsConnectionEstablished, WsConnectionFailed,
+        WsConnectionShutdown, WsEventSent,
+    },
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::ExponentialBackoff,
+        StreamSink, UriSerde,
+    },
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError, TlsOptions, TlsSettings},
+};
+use async_trait::async_trait;
+use futures::{
+    future,
+    stream::{BoxStream, Peekable},
+    FutureExt, SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    net::SocketAddr,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+use tokio::{net::TcpStream, time::sleep};
+use tokio_tungstenite::{
+    client_async_with_config,
+    tungstenite::{
+        client::IntoClientRequest, error::Error as WsError, handshake::client::Request,
+        protocol::Message,
+    },
+    WebSocketStream,
+};
+use vector_core::event::EventStatus;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("URI scheme must be `ws` or `wss`, got {:?}", scheme))]
+    InvalidScheme { scheme: Option<String> },
+    #[snafu(display("URI is missing a host"))]
+    MissingHost,
+}
+
+#[derive(Debug, Snafu)]
+pub enum WebSocketError {
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Dns { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("Connect error: {}", source))]
+    Connect { source: TlsError },
+    #[snafu(display("Handshake error: {}", source))]
+    Handshake { source: WsError },
+    #[snafu(display("Connection error: {}", source))]
+    Stream { source: WsError },
+    #[snafu(display("Server did not answer ping in time."))]
+    PongTimeout,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketSinkConfig {
+    uri: UriSerde,
+    tls: Option<TlsOptions>,
+    auth: Option<Auth>,
+    encoding: EncodingConfig<Encoding>,
+    #[serde(default)]
+    message_type: MessageType,
+    ping_interval_secs: Option<u64>,
+    ping_timeout_secs: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+/// The kind of frame encoded events are sent in.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    #[derivative(Default)]
+    Text,
+    Binary,
+}
+
+inventory::submit! {
+    SinkDescription::new::<WebSocketSinkConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            uri = "ws://127.0.0.1:9000/endpoint"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SinkConfig for WebSocketSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let connector = self.build_connector()?;
+        let sink = WebSocketSink::new(self, connector.clone(), cx.acker());
+        let healthcheck = async move { connector.healthcheck().await }.boxed();
+
+        Ok((super::VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "websocket"
+    }
+}
+
+impl WebSocketSinkConfig {
+    fn build_connector(&self) -> crate::Result<WebSocketConnector> {
+        let uri = &self.uri.uri;
+        let tls = match uri.scheme_str() {
+            Some("ws") => MaybeTlsSettings::Raw(()),
+            Some("wss") => MaybeTlsSettings::Tls(TlsSettings::from_options(&self.tls)?),
+            scheme => {
+                return Err(BuildError::InvalidScheme {
+                    scheme: scheme.map(Into::into),
+                }
+                .into())
+            }
+        };
+        let host = uri.host().ok_or(BuildError::MissingHost)?.to_string();
+        let port = uri
+            .port_u16()
+            .unwrap_or_else(|| if tls.is_tls() { 443 } else { 80 });
+
+        Ok(WebSocketConnector {
+            uri: uri.to_string(),
+            host,
+            port,
+            tls,
+            auth: self.auth.choose_one(&self.uri.auth)?,
+        })
+    }
+}
+
+#[derive(Clone)]
+struct WebSocketConnector {
+    uri: String,
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+    auth: Option<Auth>,
+}
+
+impl WebSocketConnector {
+    fn fresh_backoff() -> ExponentialBackoff {
+        ExponentialBackoff::from_millis(2)
+            .factor(250)
+            .max_delay(Duration::from_secs(60))
+    }
+
+    async fn tls_connect(&self) -> Result<MaybeTlsStream<TcpStream>, WebSocketError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(Dns)?
+            .next()
+            .ok_or(WebSocketError::NoAddresses)?;
+
+        let addr = SocketAddr::new(ip, self.port);
+        self.tls.connect(&self.host, &addr).await.context(Connect)
+    }
+
+    async fn connect(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WebSocketError> {
+        let mut request: Request = self.uri.as_str().into_client_request().context(Handshake)?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let stream = self.tls_connect().await?;
+        let (ws_stream, _response) = client_async_with_config(request, stream, None)
+            .await
+            .context(Handshake)?;
+
+        Ok(ws_stream)
+    }
+
+    async fn connect_backoff(&self) -> WebSocketStream<MaybeTlsStream<TcpStream>> {
+        let mut backoff = Self::fresh_backoff();
+        loop {
+            match self.connect().await {
+                Ok(ws_stream) => {
+                    emit!(WsConnectionEstablished);
+                    return ws_stream;
+                }
+                Err(error) => {
+                    emit!(WsConnectionFailed { error });
+                    sleep(backoff.next().unwrap()).await;
+                }
+            }
+        }
+    }
+
+    async fn healthcheck(&self) -> crate::Result<()> {
+        self.connect().await.map(|_| ()).map_err(Into::into)
+    }
+}
+
+pub struct WebSocketSink {
+    connector: WebSocketConnector,
+    encoding: EncodingConfig<Encoding>,
+    message_type: MessageType,
+    ping_interval: Option<Duration>,
+    ping_timeout: Option<Duration>,
+    acker: Acker,
+}
+
+impl WebSocketSink {
+    fn new(config: &WebSocketSinkConfig, connector: WebSocketConnector, acker: Acker) -> Self {
+        Self {
+            connector,
+            encoding: config.encoding.clone(),
+            message_type: config.message_type,
+            ping_interval: config
+                .ping_interval_secs
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            ping_timeout: config
+                .ping_timeout_secs
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            acker,
+        }
+    }
+
+    /// Sends events over the connection until the input ends or the connection
+    /// is lost, in which case the sink reconnects if events are left.
+    async fn handle_events(
+        &mut self,
+        ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        input: &mut Pin<&mut Peekable<BoxStream<'_, Event>>>,
+    ) -> Result<(), WebSocketError> {
+        let (mut ws_sink, mut ws_stream) = ws_stream.split();
+        let mut ping_interval = self
+            .ping_interval
+            .map(|period| tokio::time::interval_at(tokio::time::Instant::now() + period, period));
+        // Set while a ping is waiting for its pong.
+        let mut ping_sent_at: Option<Instant> = None;
+
+        loop {
+            let tick = match &mut ping_interval {
+                Some(interval) => interval.tick().map(|_| ()).boxed(),
+                None => future::pending().boxed(),
+            };
+
+            tokio::select! {
+                _ = tick => {
+                    if let (Some(sent_at), Some(timeout)) = (ping_sent_at, self.ping_timeout) {
+                        if sent_at.elapsed() >= timeout {
+                            return Err(WebSocketError::PongTimeout);
+                        }
+                    }
+                    ws_sink.send(Message::Ping(Vec::new())).await.context(Stream)?;
+                    ping_sent_at.get_or_insert_with(Instant::now);
+                }
+
+                message = ws_stream.next() => match message {
+                    Some(Ok(Message::Pong(_))) => ping_sent_at = None,
+                    Some(Ok(Message::Close(_))) | None => {
+                        emit!(WsConnectionShutdown);
+                        return Ok(());
+                    }
+                    // Pings are answered by the protocol implementation, and
+                    // anything else the server sends is of no interest.
+                    Some(Ok(_)) => {}
+                    Some(Err(error)) => return Err(error).context(Stream),
+                },
+
+                event = input.next() => {
+                    let mut event = match event {
+                        Some(event) => event,
+                        None => break,
+                    };
+
+                    let finalizers = event.metadata_mut().take_finalizers();
+                    let message = encode_event(event, &self.encoding, self.message_type);
+                    let byte_size = message.len();
+
+                    let result = ws_sink.send(message).await;
+                    self.acker.ack(1);
+                    match result {
+                        Ok(()) => {
+                            emit!(WsEventSent { byte_size });
+                            finalizers.update_status(EventStatus::Delivered);
+                        }
+                        Err(error) => {
+                            finalizers.update_status(EventStatus::Errored);
+                            return Err(error).context(Stream);
+                        }
+                    }
+                }
+            }
+        }
+
+        let _ = ws_sink.close().await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StreamSink for WebSocketSink {
+    async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        // The connection is only (re)opened once there is something to send.
+        let input = input.peekable();
+        tokio::pin!(input);
+
+        while input.as_mut().peek().await.is_some() {
+            let ws_stream = self.connector.connect_backoff().await;
+            let _open_token = OpenGauge::new().open(|count| emit!(ConnectionOpen { count }));
+
+            if let Err(error) = self.handle_events(ws_stream, &mut input).await {
+                emit!(WsConnectionError { error });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn encode_event(
+    mut event: Event,
+    encoding: &EncodingConfig<Encoding>,
+    message_type: MessageType,
+) -> Message {
+    encoding.apply_rules(&mut event);
+
+    let payload = match encoding.codec() {
+        Encoding::Json => serde_json::to_vec(event.as_log()).unwrap(),
+        Encoding::Text => event
+            .as_log()
+            .get(log_schema().message_key())
+            .map(|v| v.as_bytes().to_vec())
+            .unwrap_or_default(),
+    };
+
+    match message_type {
+        MessageType::Text => Message::text(String::from_utf8_lossy(&payload)),
+        MessageType::Binary => Message::binary(payload),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::Value,
+        test_util::{next_addr, random_lines_with_stream, trace_init},
+    };
+    use tokio::net::TcpListener;
+    use vector_core::event::{BatchNotifier, BatchStatus};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketSinkConfig>();
+    }
+
+    #[test]
+    fn encodes_text_and_binary_frames() {
+        let encoding = EncodingConfig::from(Encoding::Text);
+        assert_eq!(
+            encode_event(Event::from("foo"), &encoding, MessageType::Text),
+            Message::text("foo")
+        );
+        assert_eq!(
+            encode_event(Event::from("foo"), &encoding, MessageType::Binary),
+            Message::binary(b"foo".to_vec())
+        );
+    }
+
+    #[test]
+    fn encodes_log_events() {
+        let mut event = Event::new_empty_log();
+        let log = event.as_mut_log();
+        log.insert("x", Value::from("23"));
+        log.insert("z", Value::from(25));
+        log.insert("a", Value::from("0"));
+
+        let message = encode_event(
+            event,
+            &EncodingConfig::from(Encoding::Json),
+            MessageType::Text,
+        );
+        assert_eq!(message, Message::text(r#"{"a":"0","x":"23","z":25}"#));
+    }
+
+    #[test]
+    fn rejects_non_websocket_schemes() {
+        let config: WebSocketSinkConfig = toml::from_str(
+            r#"
+            uri = "http://127.0.0.1:9000"
+            encoding.codec = "json"
+            "#,
+        )
+        .unwrap();
+        assert!(config.build_connector().is_err());
+    }
+
+    /// Accepts a single websocket connection and returns the messages
+    /// received on it, along with the request headers of the handshake.
+    async fn accept_one(listener: TcpListener) -> (Vec<Message>, Option<String>) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut auth_header = None;
+        let ws_stream =
+            tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response| {
+                auth_header = request
+                    .headers()
+                    .get("authorization")
+                    .map(|value| value.to_str().unwrap().to_owned());
+                Ok(response)
+            })
+            .await
+            .unwrap();
+
+        let messages = ws_stream
+            .filter_map(|message| async move { message.ok().filter(Message::is_text) })
+            .collect()
+            .await;
+        (messages, auth_header)
+    }
+
+    #[tokio::test]
+    async fn sends_events_as_frames() {
+        trace_init();
+
+        let addr = next_addr();
+        let listener = TcpListener::bind(&addr).await.unwrap();
+        let server = tokio::spawn(accept_one(listener));
+
+        let config: WebSocketSinkConfig = toml::from_str(&format!(
+            r#"
+            uri = "ws://{}/"
+            encoding.codec = "text"
+            auth.strategy = "bearer"
+            auth.token = "secret"
+            ping_interval_secs = 1
+            "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input, events) = random_lines_with_stream(10, 10, Some(batch));
+        sink.run(events).await.unwrap();
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let (messages, auth_header) = server.await.unwrap();
+        let output: Vec<_> = messages
+            .into_iter()
+            .map(|message| message.into_text().unwrap())
+            .collect();
+        assert_eq!(output, input);
+        assert_eq!(auth_header.as_deref(), Some("Bearer secret"));
+    }
+}
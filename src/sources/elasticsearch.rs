@@ -0,0 +1,985 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    http::{Auth, HttpClient, HttpError, MaybeAuth},
+    internal_events::{ElasticSearchHitsReceived, ElasticSearchSourceRequestFailed},
+    rusoto::{self, region_from_endpoint, RegionOrEndpoint},
+    shutdown::ShutdownSignal,
+    sinks::{
+        elasticsearch::{finish_signer, ElasticSearchAuth},
+        util::{retries::ExponentialBackoff, UriSerde},
+    },
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use chrono::Utc;
+use futures::{stream, SinkExt, StreamExt};
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use rusoto_core::Region;
+use rusoto_signature::{SignedRequest, SignedRequestPayload};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryFrom,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use vector_core::ByteSizeOf;
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+
+/// The characters escaped in index names, which may still list several
+/// indices or use wildcards.
+const INDEX_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'*')
+    .remove(b',');
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Host {:?} must include hostname", host))]
+    HostMustIncludeHostname { host: String },
+    #[snafu(display(
+        "Sort must be an array of a stored field and at least one tiebreaker, without `_shard_doc`"
+    ))]
+    InvalidSort,
+}
+
+#[derive(Debug, Snafu)]
+enum RequestError {
+    #[snafu(display("Unexpected status {} for {}: {}", status, path, body))]
+    UnexpectedStatus {
+        status: StatusCode,
+        path: String,
+        body: String,
+    },
+    #[snafu(display("Invalid response for {}: {}", path, source))]
+    InvalidResponse {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[snafu(display(
+        "Point in time has expired and the default sort can't be resumed from; the query will be read again from the start"
+    ))]
+    PitExpired,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ElasticSearchConfig {
+    endpoint: String,
+    index: String,
+    query: Option<JsonValue>,
+    sort: Option<JsonValue>,
+    #[serde(default = "default_page_size")]
+    page_size: usize,
+    #[serde(default = "default_keep_alive_secs")]
+    keep_alive_secs: u64,
+    data_dir: Option<PathBuf>,
+    auth: Option<ElasticSearchAuth>,
+    aws: Option<RegionOrEndpoint>,
+    tls: Option<TlsOptions>,
+}
+
+/// The order of documents within a point in time, which can't be resumed
+/// from in another one.
+fn default_sort() -> JsonValue {
+    json!([{ "_shard_doc": "asc" }])
+}
+
+const fn default_page_size() -> usize {
+    1000
+}
+
+const fn default_keep_alive_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SourceDescription::new::<ElasticSearchConfig>("elasticsearch")
+}
+
+impl GenerateConfig for ElasticSearchConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            endpoint = "http://localhost:9200"
+            index = "logs-*""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "elasticsearch")]
+impl SourceConfig for ElasticSearchConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let client = ElasticSearchClient::new(self, &cx)?;
+        // Resuming from a position in a new point in time needs an order
+        // that doesn't depend on it, with ties broken.
+        if let Some(sort) = &self.sort {
+            let valid = sort.as_array().map_or(false, |fields| {
+                fields.len() >= 2
+                    && fields.iter().all(|field| {
+                        field.as_str() != Some("_shard_doc") && field.get("_shard_doc").is_none()
+                    })
+            });
+            if !valid {
+                return Err(BuildError::InvalidSort.into());
+            }
+        }
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), &cx.name)?;
+        let checkpointer = Checkpointer::new(&data_dir);
+
+        let source = ElasticSearchSource {
+            client,
+            index: self.index.clone(),
+            query: self
+                .query
+                .clone()
+                .unwrap_or_else(|| json!({ "match_all": {} })),
+            sort: self.sort.clone().unwrap_or_else(default_sort),
+            resumable: self.sort.is_some(),
+            page_size: self.page_size,
+            keep_alive: format!("{}s", self.keep_alive_secs),
+        };
+
+        Ok(Box::pin(source.run(
+            checkpointer,
+            cx.out,
+            cx.shutdown,
+            cx.acknowledgements,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "elasticsearch"
+    }
+}
+
+/// Sends JSON requests to Elasticsearch, signing them when AWS
+/// authentication is configured.
+struct ElasticSearchClient {
+    http: HttpClient,
+    base_url: String,
+    authorization: Option<Auth>,
+    credentials: Option<rusoto::AwsCredentialsProvider>,
+    region: Region,
+}
+
+impl ElasticSearchClient {
+    fn new(config: &ElasticSearchConfig, cx: &SourceContext) -> crate::Result<Self> {
+        let uri = config.endpoint.parse::<UriSerde>()?;
+        if uri.uri.host().is_none() {
+            return Err(BuildError::HostMustIncludeHostname {
+                host: config.endpoint.clone(),
+            }
+            .into());
+        }
+
+        let authorization = match &config.auth {
+            Some(ElasticSearchAuth::Basic { user, password }) => Some(Auth::Basic {
+                user: user.clone(),
+                password: password.clone(),
+            }),
+            _ => None,
+        };
+        let authorization = authorization.choose_one(&uri.auth)?;
+        let base_url = uri.uri.to_string().trim_end_matches('/').to_owned();
+
+        let region = match &config.aws {
+            Some(region) => Region::try_from(region)?,
+            None => region_from_endpoint(&base_url)?,
+        };
+
+        let credentials = match &config.auth {
+            Some(ElasticSearchAuth::Basic { .. }) | None => None,
+            Some(ElasticSearchAuth::Aws(aws)) => Some(aws.build(&region, None)?),
+        };
+
+        let tls_settings = TlsSettings::from_options(&config.tls)?;
+        let http = HttpClient::new(tls_settings, &cx.proxy)?;
+
+        Ok(Self {
+            http,
+            base_url,
+            authorization,
+            credentials,
+            region,
+        })
+    }
+
+    /// Sends a request, returning `None` if the requested resource wasn't found.
+    async fn send(
+        &self,
+        method: &str,
+        path: &str,
+        query: Option<(&str, &str)>,
+        body: &JsonValue,
+    ) -> crate::Result<Option<JsonValue>> {
+        let body = serde_json::to_vec(body)?;
+        let mut url = format!("{}{}", self.base_url, path);
+        if let Some((key, value)) = query {
+            url = format!("{}?{}={}", url, key, value);
+        }
+
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(&url)
+            .header("Content-Type", "application/json");
+
+        let request = match &self.credentials {
+            None => {
+                if let Some(authorization) = &self.authorization {
+                    builder = authorization.apply_builder(builder);
+                }
+                builder.body(Body::from(body))?
+            }
+            Some(credentials_provider) => {
+                let uri = url.parse::<Uri>()?;
+                let mut signer = SignedRequest::new(method, "es", &self.region, uri.path());
+                signer.set_hostname(uri.host().map(|host| host.into()));
+                if let Some((key, value)) = query {
+                    signer.add_param(key, value);
+                }
+                signer.add_header("Content-Type", "application/json");
+                signer.set_payload(Some(body));
+
+                builder = finish_signer(&mut signer, credentials_provider, builder).await?;
+
+                // The SignedRequest ends up owning the body.
+                match signer.payload.take() {
+                    Some(SignedRequestPayload::Buffer(body)) => builder.body(Body::from(body))?,
+                    _ => unreachable!(),
+                }
+            }
+        };
+
+        let response = self.http.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+
+        match status {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(
+                serde_json::from_slice(&body).context(InvalidResponse { path })?,
+            )),
+            status => Err(RequestError::UnexpectedStatus {
+                status,
+                path: path.into(),
+                body: String::from_utf8_lossy(&body).into(),
+            }
+            .into()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenPitResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    pit_id: String,
+    hits: Hits,
+}
+
+#[derive(Deserialize)]
+struct Hits {
+    hits: Vec<Hit>,
+}
+
+#[derive(Deserialize)]
+struct Hit {
+    #[serde(rename = "_index")]
+    index: String,
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "_source", default)]
+    source: JsonValue,
+    sort: Vec<JsonValue>,
+}
+
+struct ElasticSearchSource {
+    client: ElasticSearchClient,
+    index: String,
+    query: JsonValue,
+    sort: JsonValue,
+    /// Whether the sort allows resuming in a new point in time.
+    resumable: bool,
+    page_size: usize,
+    keep_alive: String,
+}
+
+impl ElasticSearchSource {
+    async fn run(
+        self,
+        checkpointer: Checkpointer,
+        mut out: Pipeline,
+        mut shutdown: ShutdownSignal,
+        acknowledgements: bool,
+    ) -> Result<(), ()> {
+        let mut checkpoint = match checkpointer.get().await {
+            Ok(checkpoint) => checkpoint.unwrap_or_default(),
+            Err(error) => {
+                error!(message = "Unable to read checkpoint.", %error);
+                return Err(());
+            }
+        };
+        if checkpoint.done {
+            info!(message = "Query has already been read to the end.");
+            return Ok(());
+        }
+
+        let mut backoff = fresh_backoff();
+        loop {
+            let page = tokio::select! {
+                page = self.next_page(&mut checkpoint) => page,
+                _ = &mut shutdown => return Ok(()),
+            };
+
+            let hits = match page {
+                Ok(hits) => {
+                    backoff = fresh_backoff();
+                    hits
+                }
+                Err(error) => {
+                    emit!(ElasticSearchSourceRequestFailed { error: &error });
+                    match error.downcast_ref::<RequestError>() {
+                        // The position was dropped along with the expired
+                        // point in time, so the query is read again from the
+                        // start.
+                        Some(RequestError::PitExpired) => {
+                            checkpointer.set(&checkpoint).await.map_err(|error| {
+                                error!(message = "Unable to write checkpoint.", %error);
+                            })?;
+                        }
+                        _ if is_retriable(&error) => {
+                            let delay = backoff.next().expect("backoff never ends");
+                            tokio::select! {
+                                _ = tokio::time::sleep(delay) => {},
+                                _ = &mut shutdown => return Ok(()),
+                            }
+                        }
+                        _ => return Err(()),
+                    }
+                    continue;
+                }
+            };
+
+            if hits.is_empty() {
+                if let Some(pit_id) = checkpoint.pit_id.take() {
+                    if let Err(error) = self.close_pit(&pit_id).await {
+                        warn!(message = "Unable to close point in time.", %error);
+                    }
+                }
+                checkpoint.done = true;
+                checkpointer.set(&checkpoint).await.map_err(|error| {
+                    error!(message = "Unable to write checkpoint.", %error);
+                })?;
+                info!(message = "Finished reading query.");
+                return Ok(());
+            }
+
+            let search_after = hits.last().map(|hit| hit.sort.clone());
+            let mut events = hits.into_iter().map(hit_to_event).collect::<Vec<_>>();
+            emit!(ElasticSearchHitsReceived {
+                count: events.len(),
+                byte_size: events.size_of(),
+            });
+
+            let receiver = acknowledgements.then(|| {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                for event in &mut events {
+                    event.add_batch_notifier(Arc::clone(&batch));
+                }
+                receiver
+            });
+
+            out.send_all(&mut stream::iter(events).map(Ok))
+                .await
+                .map_err(|error| {
+                    error!(message = "Error sending events.", %error);
+                })?;
+
+            if let Some(receiver) = receiver {
+                let status = receiver.await;
+                if status != BatchStatus::Delivered {
+                    // The position isn't advanced, so a restart reads the
+                    // page again.
+                    error!(message = "Events were not delivered.", ?status);
+                    return Err(());
+                }
+            }
+
+            checkpoint.search_after = search_after;
+            checkpointer.set(&checkpoint).await.map_err(|error| {
+                error!(message = "Unable to write checkpoint.", %error);
+            })?;
+        }
+    }
+
+    /// Reads the page after the checkpointed position, opening a new point
+    /// in time if there is none or if it has expired. Positions in the
+    /// default sort are only valid within their point in time, so they are
+    /// dropped along with it.
+    async fn next_page(&self, checkpoint: &mut Checkpoint) -> crate::Result<Vec<Hit>> {
+        loop {
+            let pit_id = match checkpoint.pit_id.clone() {
+                Some(pit_id) => pit_id,
+                None => self.open_pit().await?,
+            };
+
+            let body = self.search_body(&pit_id, checkpoint.search_after.as_ref());
+            match self.client.send("POST", "/_search", None, &body).await? {
+                Some(response) => {
+                    let response: SearchResponse = serde_json::from_value(response)
+                        .context(InvalidResponse { path: "/_search" })?;
+                    checkpoint.pit_id = Some(response.pit_id);
+                    return Ok(response.hits.hits);
+                }
+                None if checkpoint.pit_id.is_some() && self.resumable => {
+                    debug!(message = "Point in time has expired, opening a new one.");
+                    checkpoint.pit_id = None;
+                }
+                None if checkpoint.pit_id.is_some() => {
+                    *checkpoint = Checkpoint::default();
+                    return Err(RequestError::PitExpired.into());
+                }
+                None => {
+                    return Err(RequestError::UnexpectedStatus {
+                        status: StatusCode::NOT_FOUND,
+                        path: "/_search".into(),
+                        body: String::new(),
+                    }
+                    .into())
+                }
+            }
+        }
+    }
+
+    async fn open_pit(&self) -> crate::Result<String> {
+        let path = format!(
+            "/{}/_pit",
+            utf8_percent_encode(&self.index, INDEX_ENCODE_SET)
+        );
+        let response = self
+            .client
+            .send(
+                "POST",
+                &path,
+                Some(("keep_alive", self.keep_alive.as_str())),
+                &json!({}),
+            )
+            .await?
+            .ok_or_else(|| RequestError::UnexpectedStatus {
+                status: StatusCode::NOT_FOUND,
+                path: path.clone(),
+                body: String::new(),
+            })?;
+        let response: OpenPitResponse =
+            serde_json::from_value(response).context(InvalidResponse { path })?;
+        Ok(response.id)
+    }
+
+    async fn close_pit(&self, pit_id: &str) -> crate::Result<()> {
+        self.client
+            .send("DELETE", "/_pit", None, &json!({ "id": pit_id }))
+            .await
+            .map(|_| ())
+    }
+
+    fn search_body(&self, pit_id: &str, search_after: Option<&Vec<JsonValue>>) -> JsonValue {
+        let mut body = json!({
+            "size": self.page_size,
+            "query": self.query,
+            "pit": {
+                "id": pit_id,
+                "keep_alive": self.keep_alive,
+            },
+            "sort": self.sort,
+            "track_total_hits": false,
+        });
+        if let Some(search_after) = search_after {
+            body["search_after"] = json!(search_after);
+        }
+        body
+    }
+}
+
+fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+/// Whether a request may succeed when retried, as opposed to errors such as
+/// an invalid query.
+fn is_retriable(error: &crate::Error) -> bool {
+    if let Some(RequestError::UnexpectedStatus { status, .. }) = error.downcast_ref() {
+        return status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS;
+    }
+    error.is::<HttpError>() || error.is::<hyper::Error>()
+}
+
+fn hit_to_event(hit: Hit) -> Event {
+    // Hits of indices with `_source` disabled, or filtered by the query,
+    // have no source, and anything else that isn't an object is kept as the
+    // message.
+    let mut log = match hit.source {
+        JsonValue::Object(_) => LogEvent::try_from(hit.source).expect("Source is an object"),
+        source => {
+            warn!(
+                message = "Document source is not an object.",
+                index = %hit.index,
+                id = %hit.id,
+                internal_log_rate_secs = 30
+            );
+            let mut log = LogEvent::default();
+            if !source.is_null() {
+                log.insert(log_schema().message_key(), source);
+            }
+            log
+        }
+    };
+    log.insert("_index", hit.index);
+    log.insert("_id", hit.id);
+    log.insert(log_schema().source_type_key(), "elasticsearch");
+    log.try_insert(log_schema().timestamp_key(), Utc::now());
+    Event::Log(log)
+}
+
+/// The position of the source in the query.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+struct Checkpoint {
+    pit_id: Option<String>,
+    search_after: Option<Vec<JsonValue>>,
+    done: bool,
+}
+
+struct Checkpointer {
+    filename: PathBuf,
+    tmp_filename: PathBuf,
+}
+
+impl Checkpointer {
+    fn new(data_dir: &Path) -> Self {
+        Self {
+            filename: data_dir.join(CHECKPOINT_FILENAME),
+            tmp_filename: data_dir.join(format!("{}.tmp", CHECKPOINT_FILENAME)),
+        }
+    }
+
+    async fn set(&self, checkpoint: &Checkpoint) -> Result<(), io::Error> {
+        let contents = serde_json::to_vec(checkpoint)?;
+        tokio::fs::write(&self.tmp_filename, contents).await?;
+        tokio::fs::rename(&self.tmp_filename, &self.filename).await
+    }
+
+    async fn get(&self) -> Result<Option<Checkpoint>, io::Error> {
+        match tokio::fs::read(&self.filename).await {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{next_addr, temp_dir, wait_for_tcp},
+    };
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::{
+        net::SocketAddr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ElasticSearchConfig>();
+    }
+
+    fn config(endpoint: String, data_dir: PathBuf) -> ElasticSearchConfig {
+        ElasticSearchConfig {
+            endpoint,
+            index: "logs".into(),
+            query: None,
+            sort: None,
+            page_size: 2,
+            keep_alive_secs: default_keep_alive_secs(),
+            data_dir: Some(data_dir),
+            auth: None,
+            aws: None,
+            tls: None,
+        }
+    }
+
+    /// Serves three documents sorted by their number, where `expired` is
+    /// a point in time that no longer exists. The first `failures` searches
+    /// are answered as if the cluster were unavailable.
+    fn spawn_server(addr: SocketAddr, failures: usize) {
+        let failures = Arc::new(AtomicUsize::new(failures));
+        let service = make_service_fn(move |_| {
+            let failures = Arc::clone(&failures);
+            async move {
+                Ok::<_, crate::Error>(service_fn(move |request: Request<Body>| {
+                    let failures = Arc::clone(&failures);
+                    async move { respond(request, &failures).await }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(service));
+    }
+
+    async fn respond(
+        request: Request<Body>,
+        failures: &AtomicUsize,
+    ) -> crate::Result<Response<Body>> {
+        let path = request.uri().path().to_owned();
+        let body = hyper::body::to_bytes(request.into_body()).await?;
+        let body: JsonValue = serde_json::from_slice(&body)?;
+
+        let failed = path == "/_search"
+            && failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+        if failed {
+            return Ok(Response::builder().status(503).body(Body::empty())?);
+        }
+
+        let response = match path.as_str() {
+            "/logs/_pit" => json!({ "id": "current" }),
+            "/_pit" => json!({ "succeeded": true }),
+            "/_search" if body["pit"]["id"] == "expired" => {
+                return Ok(Response::builder().status(404).body(Body::empty())?);
+            }
+            "/_search" => {
+                let after = body["search_after"][0].as_u64().unwrap_or(0);
+                let size = body["size"].as_u64().unwrap();
+                let hits = (after + 1..=3)
+                    .take(size as usize)
+                    .map(|n| {
+                        json!({
+                            "_index": "logs",
+                            "_id": n.to_string(),
+                            "_source": { "message": format!("log {}", n) },
+                            "sort": [n],
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({ "pit_id": "current", "hits": { "hits": hits } })
+            }
+            _ => return Ok(Response::builder().status(400).body(Body::empty())?),
+        };
+        Ok(Response::new(Body::from(response.to_string())))
+    }
+
+    async fn run_source(config: &ElasticSearchConfig, status: EventStatus) -> Vec<Event> {
+        let (tx, rx) = Pipeline::new_test_finalize(status);
+        let mut context = SourceContext::new_test(tx);
+        context.acknowledgements = true;
+        let source = tokio::spawn(config.build(context).await.unwrap());
+
+        // Events are finalized as they are received, which the source waits
+        // for before reading the next page.
+        let events = rx.collect::<Vec<_>>().await;
+        let _ = source.await.unwrap();
+        events
+    }
+
+    #[tokio::test]
+    async fn builds_search_body() {
+        let source = ElasticSearchSource {
+            client: ElasticSearchClient::new(
+                &config("http://localhost:9200".into(), temp_dir()),
+                &SourceContext::new_test(Pipeline::new_test().0),
+            )
+            .unwrap(),
+            index: "logs".into(),
+            query: json!({ "match_all": {} }),
+            sort: default_sort(),
+            resumable: false,
+            page_size: 10,
+            keep_alive: "60s".into(),
+        };
+
+        assert_eq!(
+            source.search_body("pit", Some(&vec![json!(5)])),
+            json!({
+                "size": 10,
+                "query": { "match_all": {} },
+                "pit": { "id": "pit", "keep_alive": "60s" },
+                "sort": [{ "_shard_doc": "asc" }],
+                "track_total_hits": false,
+                "search_after": [5],
+            })
+        );
+    }
+
+    #[test]
+    fn converts_hit_to_event() {
+        let event = hit_to_event(Hit {
+            index: "logs".into(),
+            id: "1".into(),
+            source: json!({ "message": "hello", "host": { "name": "a" } }),
+            sort: vec![json!(1)],
+        });
+
+        let log = event.as_log();
+        assert_eq!(log["message"], "hello".into());
+        assert_eq!(log["host.name"], "a".into());
+        assert_eq!(log["_index"], "logs".into());
+        assert_eq!(log["_id"], "1".into());
+        assert_eq!(log[log_schema().source_type_key()], "elasticsearch".into());
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[tokio::test]
+    async fn checkpointer_round_trips() {
+        let checkpointer = Checkpointer::new(&temp_dir());
+        assert_eq!(checkpointer.get().await.unwrap(), None);
+
+        let checkpoint = Checkpoint {
+            pit_id: Some("pit".into()),
+            search_after: Some(vec![json!(1), json!("a")]),
+            done: false,
+        };
+        checkpointer.set(&checkpoint).await.unwrap();
+        assert_eq!(checkpointer.get().await.unwrap(), Some(checkpoint));
+    }
+
+    #[tokio::test]
+    async fn pages_through_query() {
+        let addr = next_addr();
+        spawn_server(addr, 0);
+        wait_for_tcp(addr).await;
+
+        let data_dir = temp_dir();
+        let config = config(format!("http://{}", addr), data_dir.clone());
+
+        let events = run_source(&config, EventStatus::Delivered).await;
+        let ids = events
+            .iter()
+            .map(|event| event.as_log()["_id"].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+
+        let checkpoint = Checkpointer::new(&data_dir.join("default"))
+            .get()
+            .await
+            .unwrap();
+        assert!(checkpoint.unwrap().done);
+
+        // The query isn't read again once it's done.
+        assert!(run_source(&config, EventStatus::Delivered).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn resumes_from_checkpoint() {
+        let addr = next_addr();
+        spawn_server(addr, 0);
+        wait_for_tcp(addr).await;
+
+        let data_dir = temp_dir();
+        let checkpoint_dir = data_dir.join("default");
+        std::fs::create_dir_all(&checkpoint_dir).unwrap();
+        Checkpointer::new(&checkpoint_dir)
+            .set(&Checkpoint {
+                pit_id: Some("expired".into()),
+                search_after: Some(vec![json!(1)]),
+                done: false,
+            })
+            .await
+            .unwrap();
+
+        let config = ElasticSearchConfig {
+            sort: Some(json!([{ "number": "asc" }, { "_id": "asc" }])),
+            ..config(format!("http://{}", addr), data_dir)
+        };
+        let events = run_source(&config, EventStatus::Delivered).await;
+        let ids = events
+            .iter()
+            .map(|event| event.as_log()["_id"].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn drops_position_of_expired_default_sort() {
+        let addr = next_addr();
+        spawn_server(addr, 0);
+        wait_for_tcp(addr).await;
+
+        let data_dir = temp_dir();
+        let checkpoint_dir = data_dir.join("default");
+        std::fs::create_dir_all(&checkpoint_dir).unwrap();
+        let checkpointer = Checkpointer::new(&checkpoint_dir);
+        checkpointer
+            .set(&Checkpoint {
+                pit_id: Some("expired".into()),
+                search_after: Some(vec![json!(1)]),
+                done: false,
+            })
+            .await
+            .unwrap();
+
+        // The query is read again from the start.
+        let config = config(format!("http://{}", addr), data_dir);
+        let events = run_source(&config, EventStatus::Delivered).await;
+        assert_eq!(events.len(), 3);
+        assert!(checkpointer.get().await.unwrap().unwrap().done);
+    }
+
+    #[tokio::test]
+    async fn retries_unavailable_cluster() {
+        let addr = next_addr();
+        spawn_server(addr, 2);
+        wait_for_tcp(addr).await;
+
+        let config = config(format!("http://{}", addr), temp_dir());
+        let events = run_source(&config, EventStatus::Delivered).await;
+        assert_eq!(events.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_on_rejected_request() {
+        let addr = next_addr();
+        spawn_server(addr, 0);
+        wait_for_tcp(addr).await;
+
+        // Only the `logs` index exists, so opening a point in time fails.
+        let config = ElasticSearchConfig {
+            index: "other".into(),
+            ..config(format!("http://{}", addr), temp_dir())
+        };
+        assert!(run_source(&config, EventStatus::Delivered).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_unstable_sorts() {
+        for sort in &[
+            json!([{ "@timestamp": "asc" }]),
+            json!([{ "@timestamp": "asc" }, { "_shard_doc": "asc" }]),
+        ] {
+            let config = ElasticSearchConfig {
+                sort: Some(sort.clone()),
+                ..config("http://localhost:9200".into(), temp_dir())
+            };
+            let context = SourceContext::new_test(Pipeline::new_test().0);
+            assert!(config.build(context).await.is_err());
+        }
+    }
+
+    #[test]
+    fn keeps_non_object_source() {
+        let event = hit_to_event(Hit {
+            index: "logs".into(),
+            id: "1".into(),
+            source: json!("hello"),
+            sort: vec![json!(1)],
+        });
+        assert_eq!(event.as_log()[log_schema().message_key()], "hello".into());
+    }
+
+    #[tokio::test]
+    async fn keeps_position_on_delivery_failure() {
+        let addr = next_addr();
+        spawn_server(addr, 0);
+        wait_for_tcp(addr).await;
+
+        let data_dir = temp_dir();
+        let config = config(format!("http://{}", addr), data_dir.clone());
+
+        let events = run_source(&config, EventStatus::Failed).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            Checkpointer::new(&data_dir.join("default"))
+                .get()
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
+
+#[cfg(feature = "es-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::test_util::{collect_ready, random_string, temp_dir};
+
+    #[tokio::test]
+    async fn reads_index() {
+        let index = format!("test-{}", random_string(10).to_lowercase());
+        let endpoint = "http://localhost:9200";
+
+        let client = reqwest::Client::new();
+        for n in 0..5 {
+            client
+                .put(format!("{}/{}/_doc/{}?refresh=true", endpoint, index, n))
+                .json(&json!({ "message": format!("log {}", n) }))
+                .send()
+                .await
+                .unwrap()
+                .error_for_status()
+                .unwrap();
+        }
+
+        let config = ElasticSearchConfig {
+            endpoint: endpoint.into(),
+            index: index.clone(),
+            query: None,
+            sort: None,
+            page_size: 2,
+            keep_alive_secs: default_keep_alive_secs(),
+            data_dir: Some(temp_dir()),
+            auth: None,
+            aws: None,
+            tls: None,
+        };
+
+        let (tx, rx) = Pipeline::new_test();
+        config
+            .build(SourceContext::new_test(tx))
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+
+        let mut ids = collect_ready(rx)
+            .await
+            .into_iter()
+            .map(|event| {
+                let log = event.as_log();
+                assert_eq!(log["_index"], index.clone().into());
+                log["_id"].to_string_lossy()
+            })
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec!["0", "1", "2", "3", "4"]);
+    }
+}
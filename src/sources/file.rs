@@ -315,7 +315,7 @@ pub fn file_source(
     let shutdown = shutdown.shared();
     let finalizer = acknowledgements.then(|| {
         let checkpoints = checkpointer.view();
-        OrderedFinalizer::new(shutdown.clone(), move |entry: FinalizerEntry| {
+        OrderedFinalizer::new(shutdown.clone(), move |_status, entry: FinalizerEntry| {
             checkpoints.update(entry.file_id, entry.offset)
         })
     });
@@ -0,0 +1,534 @@
+use crate::udp;
+use crate::{
+    config::{self, GenerateConfig, Resource, SourceConfig, SourceContext, SourceDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event,
+    },
+    internal_events::{
+        GraphiteEventsReceived, GraphiteInvalidFrame, GraphiteInvalidRecord, GraphiteSocketError,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::{SocketListenAddr, TcpSource},
+    tcp::TcpKeepaliveConfig,
+    tls::{MaybeTlsSettings, TlsConfig},
+    Pipeline,
+};
+use bytes::{Bytes, BytesMut};
+use codec::BytesDelimitedCodec;
+use futures::{stream, SinkExt, StreamExt, TryFutureExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    io,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
+};
+use tokio::net::UdpSocket;
+use tokio_util::{
+    codec::{BytesCodec, Decoder, LengthDelimitedCodec},
+    udp::UdpFramed,
+};
+
+pub mod parser;
+pub mod pickle;
+mod template;
+
+use parser::{parse_line, split_tagged_path, Datapoint};
+use template::Templates;
+
+/// Carbon refuses the pickle frames longer than 1MiB.
+const MAX_PICKLE_FRAME_LENGTH: usize = 1024 * 1024;
+
+/// Plaintext lines longer than this are discarded, so that a connection that
+/// never sends a newline can't grow its buffer without bound.
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+// TODO: add back when https://github.com/serde-rs/serde/issues/1358 is addressed
+// #[serde(deny_unknown_fields)]
+pub struct GraphiteConfig {
+    #[serde(flatten)]
+    mode: Mode,
+    #[serde(default)]
+    templates: Vec<String>,
+    #[serde(default = "default_separator")]
+    separator: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+enum Mode {
+    Tcp(TcpConfig),
+    Udp(UdpConfig),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct TcpConfig {
+    address: SocketListenAddr,
+    keepalive: Option<TcpKeepaliveConfig>,
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+    #[serde(default = "default_shutdown_timeout_secs")]
+    shutdown_timeout_secs: u64,
+    receive_buffer_bytes: Option<usize>,
+    #[serde(default)]
+    framing: Framing,
+}
+
+impl TcpConfig {
+    #[cfg(test)]
+    fn from_address(address: SocketListenAddr, framing: Framing) -> Self {
+        Self {
+            address,
+            keepalive: None,
+            tls: None,
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+            receive_buffer_bytes: None,
+            framing,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct UdpConfig {
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Framing {
+    Plaintext,
+    Pickle,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self::Plaintext
+    }
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
+fn default_separator() -> String {
+    ".".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<GraphiteConfig>("graphite")
+}
+
+impl GenerateConfig for GraphiteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            mode: Mode::Tcp(TcpConfig {
+                address: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 2003)).into(),
+                keepalive: None,
+                tls: None,
+                shutdown_timeout_secs: default_shutdown_timeout_secs(),
+                receive_buffer_bytes: None,
+                framing: Framing::Plaintext,
+            }),
+            templates: Vec::new(),
+            separator: default_separator(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "graphite")]
+impl SourceConfig for GraphiteConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let templates = Arc::new(Templates::new(&self.templates, &self.separator)?);
+
+        match &self.mode {
+            Mode::Tcp(config) => {
+                let tls = MaybeTlsSettings::from_config(&config.tls, true)?;
+                GraphiteTcpSource {
+                    framing: config.framing,
+                    templates,
+                }
+                .run(
+                    config.address,
+                    config.keepalive,
+                    config.shutdown_timeout_secs,
+                    tls,
+                    config.receive_buffer_bytes,
+                    cx.shutdown,
+                    cx.out,
+                )
+            }
+            Mode::Udp(config) => Ok(Box::pin(graphite_udp(
+                config.clone(),
+                templates,
+                cx.shutdown,
+                cx.out,
+            ))),
+        }
+    }
+
+    fn output_type(&self) -> config::DataType {
+        config::DataType::Metric
+    }
+
+    fn source_type(&self) -> &'static str {
+        "graphite"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        match &self.mode {
+            Mode::Tcp(tcp) => vec![tcp.address.into()],
+            Mode::Udp(udp) => vec![Resource::udp(udp.address)],
+        }
+    }
+}
+
+/// Build the gauge of a datapoint, with the name and tags given by the
+/// templates, to which the tags of a tagged series are added.
+fn build_metric(datapoint: Datapoint, templates: &Templates) -> Metric {
+    let (path, series_tags) = split_tagged_path(&datapoint.path);
+    let (name, mut tags) = templates.apply(path);
+    tags.extend(series_tags.map(|(name, value)| (name.to_owned(), value.to_owned())));
+
+    Metric::new(
+        name,
+        MetricKind::Absolute,
+        MetricValue::Gauge {
+            value: datapoint.value,
+        },
+    )
+    .with_tags(Some(tags).filter(|tags| !tags.is_empty()))
+    .with_timestamp(datapoint.timestamp)
+}
+
+fn parse_datapoint(line: &[u8]) -> Option<Datapoint> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    match parse_line(line) {
+        Ok(datapoint) => {
+            emit!(GraphiteEventsReceived {
+                count: 1,
+                byte_size: line.len(),
+            });
+            Some(datapoint)
+        }
+        Err(error) => {
+            emit!(GraphiteInvalidRecord { error, text: line });
+            None
+        }
+    }
+}
+
+fn decode_frame(frame: &[u8]) -> Vec<Datapoint> {
+    match pickle::decode(frame) {
+        Ok(datapoints) => {
+            emit!(GraphiteEventsReceived {
+                count: datapoints.len(),
+                byte_size: frame.len(),
+            });
+            datapoints
+        }
+        Err(error) => {
+            emit!(GraphiteInvalidFrame {
+                error,
+                byte_size: frame.len(),
+            });
+            Vec::new()
+        }
+    }
+}
+
+/// Decodes the datapoints of the lines, or of the pickle frames made of a
+/// 4-byte big-endian length followed by the pickled datapoints.
+struct GraphiteDecoder {
+    framing: Framing,
+    lines: BytesDelimitedCodec,
+    frames: LengthDelimitedCodec,
+    /// The datapoints of the last frame not returned yet.
+    pending: VecDeque<Datapoint>,
+}
+
+impl GraphiteDecoder {
+    fn new(framing: Framing) -> Self {
+        Self {
+            framing,
+            lines: BytesDelimitedCodec::new_with_max_length(b'\n', MAX_LINE_LENGTH),
+            frames: LengthDelimitedCodec::builder()
+                .length_field_length(4)
+                .max_frame_length(MAX_PICKLE_FRAME_LENGTH)
+                .new_codec(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn decode_datapoint(
+        &mut self,
+        src: &mut BytesMut,
+        eof: bool,
+    ) -> Result<Option<Datapoint>, io::Error> {
+        loop {
+            if let Some(datapoint) = self.pending.pop_front() {
+                return Ok(Some(datapoint));
+            }
+
+            match self.framing {
+                Framing::Plaintext => {
+                    let line = if eof {
+                        self.lines.decode_eof(src)?
+                    } else {
+                        self.lines.decode(src)?
+                    };
+                    match line {
+                        Some(line) => {
+                            if let Some(datapoint) = parse_datapoint(&line) {
+                                return Ok(Some(datapoint));
+                            }
+                        }
+                        None => return Ok(None),
+                    }
+                }
+                Framing::Pickle => {
+                    let frame = if eof {
+                        self.frames.decode_eof(src)?
+                    } else {
+                        self.frames.decode(src)?
+                    };
+                    match frame {
+                        Some(frame) => self.pending.extend(decode_frame(&frame)),
+                        None => return Ok(None),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Decoder for GraphiteDecoder {
+    type Item = Datapoint;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Datapoint>, io::Error> {
+        self.decode_datapoint(src, false)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Datapoint>, io::Error> {
+        self.decode_datapoint(src, true)
+    }
+}
+
+#[derive(Clone)]
+struct GraphiteTcpSource {
+    framing: Framing,
+    templates: Arc<Templates>,
+}
+
+impl TcpSource for GraphiteTcpSource {
+    type Error = io::Error;
+    type Decoder = GraphiteDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        GraphiteDecoder::new(self.framing)
+    }
+
+    fn build_event(&self, datapoint: Datapoint, _host: Bytes) -> Option<Event> {
+        Some(build_metric(datapoint, &self.templates).into())
+    }
+}
+
+/// Receives the plaintext protocol, one or more lines per datagram.
+async fn graphite_udp(
+    config: UdpConfig,
+    templates: Arc<Templates>,
+    shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    let socket = UdpSocket::bind(&config.address)
+        .map_err(|error| emit!(GraphiteSocketError::bind(error)))
+        .await?;
+
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(
+        message = "Listening.",
+        addr = %config.address,
+        r#type = "udp"
+    );
+
+    let mut stream = UdpFramed::new(socket, BytesCodec::new()).take_until(shutdown);
+    while let Some(frame) = stream.next().await {
+        match frame {
+            Ok((bytes, _sock)) => {
+                let metrics = bytes
+                    .split(|&byte| byte == b'\n')
+                    .filter_map(parse_datapoint)
+                    .map(|datapoint| Ok(Event::Metric(build_metric(datapoint, &templates))))
+                    .collect::<Vec<_>>();
+
+                if let Err(error) = out.send_all(&mut stream::iter(metrics)).await {
+                    error!(message = "Error sending metric.", %error);
+                    break;
+                }
+            }
+            Err(error) => {
+                emit!(GraphiteSocketError::read(error));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{collect_ready, next_addr};
+    use chrono::{TimeZone, Utc};
+    use shared::btreemap;
+    use tokio::{
+        io::AsyncWriteExt,
+        time::{sleep, Duration},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GraphiteConfig>();
+    }
+
+    #[test]
+    fn discards_long_lines() {
+        let mut decoder = GraphiteDecoder::new(Framing::Plaintext);
+        let mut src = BytesMut::from(
+            format!(
+                "{}\nservers.host1.cpu 12.5 1622548800\n",
+                "a".repeat(MAX_LINE_LENGTH + 1)
+            )
+            .as_str(),
+        );
+
+        assert_eq!(decoder.decode(&mut src).unwrap(), None);
+        let datapoint = decoder.decode(&mut src).unwrap().unwrap();
+        assert_eq!(datapoint.path, "servers.host1.cpu");
+        assert!(src.is_empty());
+    }
+
+    fn gauge(name: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+            .with_timestamp(Some(Utc.timestamp(1622548800, 0)))
+    }
+
+    async fn run_source(
+        mode: Mode,
+        templates: Vec<String>,
+        send: impl std::future::Future<Output = ()>,
+    ) -> Vec<Event> {
+        let (tx, rx) = Pipeline::new_test();
+        let config = GraphiteConfig {
+            mode,
+            templates,
+            separator: "_".into(),
+        };
+        let source = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_millis(100)).await;
+
+        send.await;
+        sleep(Duration::from_millis(200)).await;
+        collect_ready(rx).await
+    }
+
+    #[tokio::test]
+    async fn receives_plaintext_over_tcp() {
+        let address = next_addr();
+        let events = run_source(
+            Mode::Tcp(TcpConfig::from_address(address.into(), Framing::Plaintext)),
+            vec!["servers.* .host.measurement*".into()],
+            async move {
+                let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+                stream
+                    .write_all(
+                        b"servers.web01.cpu.load 0.5 1622548800\n\
+                          invalid\n\
+                          disk.used;datacenter=dc1 42 1622548800",
+                    )
+                    .await
+                    .unwrap();
+            },
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![
+                gauge("cpu_load", 0.5)
+                    .with_tags(Some(btreemap! {"host" => "web01"}))
+                    .into(),
+                gauge("disk.used", 42.0)
+                    .with_tags(Some(btreemap! {"datacenter" => "dc1"}))
+                    .into(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_pickle_over_tcp() {
+        // pickle.dumps([("servers.web01.cpu", (1622548800, 12.5))], protocol=2)
+        const FRAME: &[u8] = b"\x80\x02]q\x00X\x11\x00\x00\x00servers.web01.cpuq\x01J@!\xb6`G@)\x00\x00\x00\x00\x00\x00\x86q\x02\x86q\x03a.";
+
+        let address = next_addr();
+        let events = run_source(
+            Mode::Tcp(TcpConfig::from_address(address.into(), Framing::Pickle)),
+            vec![],
+            async move {
+                let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+                for _ in 0..2 {
+                    stream
+                        .write_all(&(FRAME.len() as u32).to_be_bytes())
+                        .await
+                        .unwrap();
+                    stream.write_all(FRAME).await.unwrap();
+                }
+            },
+        )
+        .await;
+
+        let metric = Event::from(gauge("servers.web01.cpu", 12.5));
+        assert_eq!(events, vec![metric.clone(), metric]);
+    }
+
+    #[tokio::test]
+    async fn receives_plaintext_over_udp() {
+        let address = next_addr();
+        let events = run_source(
+            Mode::Udp(UdpConfig {
+                address,
+                receive_buffer_bytes: None,
+            }),
+            vec![],
+            async move {
+                let socket = UdpSocket::bind(next_addr()).await.unwrap();
+                socket
+                    .send_to(b"requests 1 1622548800\nerrors 2 1622548800\n", address)
+                    .await
+                    .unwrap();
+            },
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![gauge("requests", 1.0).into(), gauge("errors", 2.0).into()]
+        );
+    }
+}
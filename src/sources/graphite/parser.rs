@@ -0,0 +1,152 @@
+use chrono::{DateTime, TimeZone, Utc};
+use snafu::Snafu;
+use std::num::ParseFloatError;
+
+/// A single value sent to the source, before the templates are applied to its
+/// path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Datapoint {
+    pub path: String,
+    pub value: f64,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("Line should be made of a path, a value and a timestamp"))]
+    Malformed,
+    #[snafu(display("Invalid value: {}", source))]
+    InvalidValue { source: ParseFloatError },
+    #[snafu(display("Invalid timestamp: {}", source))]
+    InvalidTimestamp { source: ParseFloatError },
+    #[snafu(display("Timestamp out of range: {}", seconds))]
+    TimestampOutOfRange { seconds: f64 },
+}
+
+/// Parse a line of the plaintext protocol: `<path> <value> [<timestamp>]`.
+pub fn parse_line(line: &str) -> Result<Datapoint, ParseError> {
+    let mut parts = line.split_whitespace();
+    let (path, value) = match (parts.next(), parts.next()) {
+        (Some(path), Some(value)) => (path, value),
+        _ => return Err(ParseError::Malformed),
+    };
+    let timestamp = parts.next();
+    if parts.next().is_some() {
+        return Err(ParseError::Malformed);
+    }
+
+    let value = value
+        .parse()
+        .map_err(|source| ParseError::InvalidValue { source })?;
+    let timestamp = match timestamp {
+        Some(timestamp) => parse_timestamp(
+            timestamp
+                .parse()
+                .map_err(|source| ParseError::InvalidTimestamp { source })?,
+        )?,
+        None => None,
+    };
+
+    Ok(Datapoint {
+        path: path.into(),
+        value,
+        timestamp,
+    })
+}
+
+/// Convert a timestamp in seconds, where `-1` stands for the time the value
+/// is received at, as does any negative value.
+pub fn parse_timestamp(seconds: f64) -> Result<Option<DateTime<Utc>>, ParseError> {
+    if !seconds.is_finite() {
+        return Err(ParseError::TimestampOutOfRange { seconds });
+    }
+    if seconds < 0.0 {
+        return Ok(None);
+    }
+    let nanoseconds = ((seconds.fract() * 1e9).round() as u32).min(999_999_999);
+    // Too large values saturate, and are then out of range as well.
+    Utc.timestamp_opt(seconds.trunc() as i64, nanoseconds)
+        .single()
+        .map(Some)
+        .ok_or(ParseError::TimestampOutOfRange { seconds })
+}
+
+/// Split a tagged series path, `name;tag1=value1;tag2=value2`, into its name
+/// and tags. Tags without a value are ignored.
+pub fn split_tagged_path(path: &str) -> (&str, impl Iterator<Item = (&str, &str)>) {
+    let mut parts = path.split(';');
+    let name = parts.next().unwrap_or_default();
+    let tags = parts.filter_map(|tag| {
+        let mut parts = tag.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(name), Some(value)) if !name.is_empty() && !value.is_empty() => {
+                Some((name, value))
+            }
+            _ => None,
+        }
+    });
+    (name, tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines() {
+        assert_eq!(
+            parse_line("servers.host1.cpu.user 12.5 1622548800"),
+            Ok(Datapoint {
+                path: "servers.host1.cpu.user".into(),
+                value: 12.5,
+                timestamp: Some(Utc.ymd(2021, 6, 1).and_hms(12, 0, 0)),
+            })
+        );
+        assert_eq!(
+            parse_line("  requests   42  1622548800.25 "),
+            Ok(Datapoint {
+                path: "requests".into(),
+                value: 42.0,
+                timestamp: Some(Utc.ymd(2021, 6, 1).and_hms_milli(12, 0, 0, 250)),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_lines_without_timestamp() {
+        assert_eq!(parse_line("requests 42 -1").unwrap().timestamp, None);
+        assert_eq!(parse_line("requests 42").unwrap().timestamp, None);
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert_eq!(parse_line("requests"), Err(ParseError::Malformed));
+        assert_eq!(parse_line("requests 1 2 3"), Err(ParseError::Malformed));
+        assert!(matches!(
+            parse_line("requests many 1622548800"),
+            Err(ParseError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_line("requests 1 now"),
+            Err(ParseError::InvalidTimestamp { .. })
+        ));
+        assert!(matches!(
+            parse_line("requests 1 inf"),
+            Err(ParseError::TimestampOutOfRange { .. })
+        ));
+        assert!(matches!(
+            parse_line("requests 1 1e20"),
+            Err(ParseError::TimestampOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn splits_tagged_paths() {
+        let (name, tags) = split_tagged_path("disk.used;datacenter=dc1;rack=;server=web01");
+        assert_eq!(name, "disk.used");
+        assert_eq!(
+            tags.collect::<Vec<_>>(),
+            vec![("datacenter", "dc1"), ("server", "web01")]
+        );
+    }
+}
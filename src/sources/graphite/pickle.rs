@@ -0,0 +1,491 @@
+//! A minimal decoder for the frames of the pickle protocol, made of a list of
+//! `(path, (timestamp, value))` tuples pickled by Python.
+//!
+//! Only the opcodes needed to build lists, tuples, numbers and strings are
+//! supported, which covers what carbon clients send with any pickle protocol.
+
+use super::parser::{parse_timestamp, Datapoint};
+use snafu::Snafu;
+use std::{collections::HashMap, convert::TryInto};
+
+/// How much can be copied by `DUP` and memo lookups, counting every value and
+/// every byte of strings. Copies can be copied again, so a small frame could
+/// otherwise decode into exponentially many values.
+const MAX_COPIED_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum PickleError {
+    #[snafu(display("Unexpected end of frame"))]
+    UnexpectedEnd,
+    #[snafu(display("Unsupported opcode {:#04x}", opcode))]
+    UnsupportedOpcode { opcode: u8 },
+    #[snafu(display("Invalid {} argument", opcode))]
+    InvalidArgument { opcode: &'static str },
+    #[snafu(display("Stack underflow"))]
+    StackUnderflow,
+    #[snafu(display("Unknown memo key {}", key))]
+    UnknownMemoKey { key: u64 },
+    #[snafu(display("Frame should contain a list of (path, (timestamp, value)) tuples"))]
+    InvalidDatapoint,
+    #[snafu(display("Frame decodes into too many values"))]
+    TooLarge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    List(Vec<Value>),
+    Tuple(Vec<Value>),
+}
+
+impl Value {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            Value::Int(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            // Carbon converts the values with `float()`, which parses strings.
+            Value::String(value) => value.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The number of values this is made of, plus the length of strings.
+    fn size(&self) -> usize {
+        match self {
+            Value::String(value) => 1 + value.len(),
+            Value::List(items) | Value::Tuple(items) => {
+                1 + items.iter().map(Value::size).sum::<usize>()
+            }
+            _ => 1,
+        }
+    }
+
+    fn into_items(self) -> Option<Vec<Value>> {
+        match self {
+            Value::List(items) | Value::Tuple(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Decode the datapoints of a frame, without its length prefix.
+pub fn decode(frame: &[u8]) -> Result<Vec<Datapoint>, PickleError> {
+    Unpickler::new(frame)
+        .load()?
+        .into_items()
+        .ok_or(PickleError::InvalidDatapoint)?
+        .into_iter()
+        .map(datapoint)
+        .collect()
+}
+
+fn datapoint(item: Value) -> Result<Datapoint, PickleError> {
+    let mut item = item
+        .into_items()
+        .filter(|items| items.len() == 2)
+        .ok_or(PickleError::InvalidDatapoint)?;
+    let point = item
+        .pop()
+        .and_then(Value::into_items)
+        .ok_or(PickleError::InvalidDatapoint)?;
+    let path = match item.pop() {
+        Some(Value::String(path)) => path,
+        _ => return Err(PickleError::InvalidDatapoint),
+    };
+
+    match point.as_slice() {
+        [timestamp, value] => Ok(Datapoint {
+            path,
+            value: value.as_f64().ok_or(PickleError::InvalidDatapoint)?,
+            timestamp: parse_timestamp(timestamp.as_f64().ok_or(PickleError::InvalidDatapoint)?)
+                .map_err(|_| PickleError::InvalidDatapoint)?,
+        }),
+        _ => Err(PickleError::InvalidDatapoint),
+    }
+}
+
+struct Unpickler<'a> {
+    input: &'a [u8],
+    stack: Vec<Value>,
+    /// The length of the stack at each mark.
+    marks: Vec<usize>,
+    memo: HashMap<u64, Value>,
+    /// The size of the values copied so far.
+    copied: usize,
+}
+
+impl<'a> Unpickler<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            stack: Vec::new(),
+            marks: Vec::new(),
+            memo: HashMap::new(),
+            copied: 0,
+        }
+    }
+
+    fn load(mut self) -> Result<Value, PickleError> {
+        loop {
+            let opcode = self.take(1)?[0];
+            match opcode {
+                // PROTO
+                0x80 => {
+                    self.take(1)?;
+                }
+                // FRAME
+                0x95 => {
+                    self.take(8)?;
+                }
+                // STOP
+                b'.' => return self.pop(),
+                // MARK
+                b'(' => self.marks.push(self.stack.len()),
+                // POP
+                b'0' => {
+                    self.pop()?;
+                }
+                // POP_MARK
+                b'1' => {
+                    self.pop_mark()?;
+                }
+                // DUP
+                b'2' => {
+                    let top = self.stack.last().ok_or(PickleError::StackUnderflow)?;
+                    let top = copy(&mut self.copied, top)?;
+                    self.stack.push(top);
+                }
+                // NONE, NEWTRUE, NEWFALSE
+                b'N' => self.stack.push(Value::None),
+                0x88 => self.stack.push(Value::Bool(true)),
+                0x89 => self.stack.push(Value::Bool(false)),
+                // INT, with `01` and `00` standing for booleans.
+                b'I' => {
+                    let line = self.line("INT")?;
+                    let value = match line {
+                        "01" => Value::Bool(true),
+                        "00" => Value::Bool(false),
+                        _ => Value::Int(parse_argument(line, "INT")?),
+                    };
+                    self.stack.push(value);
+                }
+                // BININT, BININT1, BININT2
+                b'J' => {
+                    let bytes = self.take(4)?;
+                    let value = i32::from_le_bytes(bytes.try_into().unwrap());
+                    self.stack.push(Value::Int(value.into()));
+                }
+                b'K' => {
+                    let value = self.take(1)?[0];
+                    self.stack.push(Value::Int(value.into()));
+                }
+                b'M' => {
+                    let bytes = self.take(2)?;
+                    let value = u16::from_le_bytes(bytes.try_into().unwrap());
+                    self.stack.push(Value::Int(value.into()));
+                }
+                // LONG, LONG1
+                b'L' => {
+                    let line = self.line("LONG")?;
+                    let value = parse_argument(line.trim_end_matches('L'), "LONG")?;
+                    self.stack.push(Value::Int(value));
+                }
+                0x8a => {
+                    let length = self.take(1)?[0] as usize;
+                    let value = decode_long(self.take(length)?)?;
+                    self.stack.push(Value::Int(value));
+                }
+                // FLOAT, BINFLOAT
+                b'F' => {
+                    let value = parse_argument(self.line("FLOAT")?, "FLOAT")?;
+                    self.stack.push(Value::Float(value));
+                }
+                b'G' => {
+                    let bytes = self.take(8)?;
+                    let value = f64::from_be_bytes(bytes.try_into().unwrap());
+                    self.stack.push(Value::Float(value));
+                }
+                // STRING
+                b'S' => {
+                    let line = self.line("STRING")?;
+                    let value =
+                        unquote(line).ok_or(PickleError::InvalidArgument { opcode: "STRING" })?;
+                    self.stack.push(Value::String(value));
+                }
+                // UNICODE
+                b'V' => {
+                    let value = self.line("UNICODE")?.to_owned();
+                    self.stack.push(Value::String(value));
+                }
+                // SHORT_BINSTRING, SHORT_BINBYTES, SHORT_BINUNICODE
+                b'U' | b'C' | 0x8c => {
+                    let length = self.take(1)?[0] as usize;
+                    self.push_string(length)?;
+                }
+                // BINSTRING, BINBYTES, BINUNICODE
+                b'T' | b'B' | b'X' => {
+                    let bytes = self.take(4)?;
+                    let length = u32::from_le_bytes(bytes.try_into().unwrap()) as usize;
+                    self.push_string(length)?;
+                }
+                // BINUNICODE8, BINBYTES8
+                0x8d | 0x8e => {
+                    let bytes = self.take(8)?;
+                    let length = u64::from_le_bytes(bytes.try_into().unwrap()) as usize;
+                    self.push_string(length)?;
+                }
+                // EMPTY_LIST, LIST, APPEND, APPENDS
+                b']' => self.stack.push(Value::List(Vec::new())),
+                b'l' => {
+                    let items = self.pop_mark()?;
+                    self.stack.push(Value::List(items));
+                }
+                b'a' => {
+                    let item = self.pop()?;
+                    self.list()?.push(item);
+                }
+                b'e' => {
+                    let items = self.pop_mark()?;
+                    self.list()?.extend(items);
+                }
+                // EMPTY_TUPLE, TUPLE, TUPLE1, TUPLE2, TUPLE3
+                b')' => self.stack.push(Value::Tuple(Vec::new())),
+                b't' => {
+                    let items = self.pop_mark()?;
+                    self.stack.push(Value::Tuple(items));
+                }
+                0x85..=0x87 => {
+                    let length = (opcode - 0x84) as usize;
+                    let start = self
+                        .stack
+                        .len()
+                        .checked_sub(length)
+                        .ok_or(PickleError::StackUnderflow)?;
+                    let items = self.stack.split_off(start);
+                    self.stack.push(Value::Tuple(items));
+                }
+                // PUT, BINPUT, LONG_BINPUT, MEMOIZE
+                b'p' => {
+                    let key = parse_argument(self.line("PUT")?, "PUT")?;
+                    self.put(key)?;
+                }
+                b'q' => {
+                    let key = self.take(1)?[0];
+                    self.put(key.into())?;
+                }
+                b'r' => {
+                    let bytes = self.take(4)?;
+                    self.put(u32::from_le_bytes(bytes.try_into().unwrap()).into())?;
+                }
+                0x94 => {
+                    let key = self.memo.len() as u64;
+                    self.put(key)?;
+                }
+                // GET, BINGET, LONG_BINGET
+                b'g' => {
+                    let key = parse_argument(self.line("GET")?, "GET")?;
+                    self.get(key)?;
+                }
+                b'h' => {
+                    let key = self.take(1)?[0];
+                    self.get(key.into())?;
+                }
+                b'j' => {
+                    let bytes = self.take(4)?;
+                    self.get(u32::from_le_bytes(bytes.try_into().unwrap()).into())?;
+                }
+                opcode => return Err(PickleError::UnsupportedOpcode { opcode }),
+            }
+        }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], PickleError> {
+        if self.input.len() < length {
+            return Err(PickleError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.input.split_at(length);
+        self.input = rest;
+        Ok(taken)
+    }
+
+    /// Read the text argument of an opcode, ending with a newline.
+    fn line(&mut self, opcode: &'static str) -> Result<&'a str, PickleError> {
+        let end = self
+            .input
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or(PickleError::UnexpectedEnd)?;
+        let line = self.take(end + 1)?;
+        std::str::from_utf8(&line[..end]).map_err(|_| PickleError::InvalidArgument { opcode })
+    }
+
+    fn push_string(&mut self, length: usize) -> Result<(), PickleError> {
+        let bytes = self.take(length)?;
+        let value = String::from_utf8_lossy(bytes).into_owned();
+        self.stack.push(Value::String(value));
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Value, PickleError> {
+        self.stack.pop().ok_or(PickleError::StackUnderflow)
+    }
+
+    fn pop_mark(&mut self) -> Result<Vec<Value>, PickleError> {
+        let mark = self.marks.pop().ok_or(PickleError::StackUnderflow)?;
+        if mark > self.stack.len() {
+            return Err(PickleError::StackUnderflow);
+        }
+        Ok(self.stack.split_off(mark))
+    }
+
+    fn list(&mut self) -> Result<&mut Vec<Value>, PickleError> {
+        match self.stack.last_mut() {
+            Some(Value::List(items)) => Ok(items),
+            Some(_) => Err(PickleError::InvalidDatapoint),
+            None => Err(PickleError::StackUnderflow),
+        }
+    }
+
+    fn put(&mut self, key: u64) -> Result<(), PickleError> {
+        let top = self.stack.last().ok_or(PickleError::StackUnderflow)?;
+        let top = copy(&mut self.copied, top)?;
+        self.memo.insert(key, top);
+        Ok(())
+    }
+
+    fn get(&mut self, key: u64) -> Result<(), PickleError> {
+        let value = self
+            .memo
+            .get(&key)
+            .ok_or(PickleError::UnknownMemoKey { key })?;
+        let value = copy(&mut self.copied, value)?;
+        self.stack.push(value);
+        Ok(())
+    }
+}
+
+/// Copies a value, adding its size to what has been copied so far.
+fn copy(copied: &mut usize, value: &Value) -> Result<Value, PickleError> {
+    *copied += value.size();
+    if *copied > MAX_COPIED_SIZE {
+        return Err(PickleError::TooLarge);
+    }
+    Ok(value.clone())
+}
+
+fn parse_argument<T: std::str::FromStr>(
+    argument: &str,
+    opcode: &'static str,
+) -> Result<T, PickleError> {
+    argument
+        .parse()
+        .map_err(|_| PickleError::InvalidArgument { opcode })
+}
+
+/// Decode a little-endian two's complement integer, as written by `LONG1`.
+fn decode_long(bytes: &[u8]) -> Result<i64, PickleError> {
+    if bytes.len() > 8 {
+        return Err(PickleError::InvalidArgument { opcode: "LONG1" });
+    }
+    let negative = bytes.last().map_or(false, |&byte| byte & 0x80 != 0);
+    let mut buffer = if negative { [0xff; 8] } else { [0; 8] };
+    buffer[..bytes.len()].copy_from_slice(bytes);
+    Ok(i64::from_le_bytes(buffer))
+}
+
+/// Remove the quotes around the argument of `STRING`, which is the `repr` of
+/// a Python 2 string. Only the common escape sequences are supported.
+fn unquote(argument: &str) -> Option<String> {
+    let quote = argument.chars().next().filter(|&c| c == '\'' || c == '"')?;
+    let inner = argument.strip_prefix(quote)?.strip_suffix(quote)?;
+
+    let mut output = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => output.push('\n'),
+            't' => output.push('\t'),
+            'r' => output.push('\r'),
+            c @ '\\' | c @ '\'' | c @ '"' => output.push(c),
+            _ => return None,
+        }
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn datapoint(path: &str, value: f64, seconds: i64) -> Datapoint {
+        Datapoint {
+            path: path.into(),
+            value,
+            timestamp: Some(Utc.timestamp(seconds, 0)),
+        }
+    }
+
+    // pickle.dumps([("servers.host1.cpu", (1622548800, 12.5)), ("servers.host2.cpu", (1622548800, 3))], protocol=0)
+    // with Python 2, which writes `str` values with `STRING`.
+    const PROTOCOL_0: &[u8] = b"(lp0\n(S'servers.host1.cpu'\np1\n(I1622548800\nF12.5\ntp2\ntp3\na(S'servers.host2.cpu'\np4\n(I1622548800\nI3\ntp5\ntp6\na.";
+
+    // The same list, pickled with `protocol=2`.
+    const PROTOCOL_2: &[u8] = b"\x80\x02]q\x00(X\x11\x00\x00\x00servers.host1.cpuq\x01J@!\xb6`G@)\x00\x00\x00\x00\x00\x00\x86q\x02\x86q\x03X\x11\x00\x00\x00servers.host2.cpuq\x04J@!\xb6`K\x03\x86q\x05\x86q\x06e.";
+
+    // The same list, pickled with `protocol=4`.
+    const PROTOCOL_4: &[u8] = b"\x80\x04\x95J\x00\x00\x00\x00\x00\x00\x00]\x94(\x8c\x11servers.host1.cpu\x94J@!\xb6`G@)\x00\x00\x00\x00\x00\x00\x86\x94\x86\x94\x8c\x11servers.host2.cpu\x94J@!\xb6`K\x03\x86\x94\x86\x94e.";
+
+    #[test]
+    fn decodes_frames() {
+        let expected = vec![
+            datapoint("servers.host1.cpu", 12.5, 1622548800),
+            datapoint("servers.host2.cpu", 3.0, 1622548800),
+        ];
+        for frame in &[PROTOCOL_0, PROTOCOL_2, PROTOCOL_4] {
+            assert_eq!(decode(frame), Ok(expected.clone()));
+        }
+    }
+
+    #[test]
+    fn decodes_longs() {
+        assert_eq!(decode_long(b""), Ok(0));
+        assert_eq!(decode_long(b"\xff\x00"), Ok(255));
+        assert_eq!(decode_long(b"\x00\xff"), Ok(-256));
+        assert_eq!(decode_long(b"\x00\x82\xb6\x60\x01"), Ok(5917540864));
+    }
+
+    #[test]
+    fn rejects_invalid_frames() {
+        assert_eq!(decode(b"]q\x00"), Err(PickleError::UnexpectedEnd));
+        assert_eq!(
+            decode(b"}q\x00."),
+            Err(PickleError::UnsupportedOpcode { opcode: b'}' })
+        );
+        // pickle.dumps([("servers.host1.cpu", 12.5)], protocol=2)
+        assert_eq!(
+            decode(b"\x80\x02]q\x00X\x11\x00\x00\x00servers.host1.cpuq\x01G@)\x00\x00\x00\x00\x00\x00\x86q\x02a."),
+            Err(PickleError::InvalidDatapoint)
+        );
+    }
+
+    #[test]
+    fn rejects_exponential_frames() {
+        // A list made of two copies of the previous one, over and over.
+        let mut frame = b"]q\x00".to_vec();
+        for _ in 0..64 {
+            frame.extend_from_slice(b"(h\x00h\x00lq\x00");
+        }
+        frame.push(b'.');
+        assert_eq!(decode(&frame), Err(PickleError::TooLarge));
+    }
+}
@@ -0,0 +1,275 @@
+//! Templates converting dotted paths into a metric name and tags, using the
+//! syntax of the InfluxDB and Telegraf Graphite parsers:
+//! `[filter] template [tag=value,...]`.
+//!
+//! Each part of the template names what the matching part of the path is:
+//! `measurement` adds it to the metric name, `measurement*` adds it and all
+//! the parts following it, an empty part skips it, and any other part turns it
+//! into the value of the tag named after the part.
+
+use crate::event::metric::MetricTags;
+use snafu::Snafu;
+
+const MEASUREMENT: &str = "measurement";
+const MEASUREMENT_REST: &str = "measurement*";
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum TemplateError {
+    #[snafu(display(
+        "Template {:?} should be made of a filter, a template and tags",
+        template
+    ))]
+    Malformed { template: String },
+    #[snafu(display("Invalid tag {:?} in template {:?}", tag, template))]
+    InvalidTag { tag: String, template: String },
+    #[snafu(display("`measurement*` must be the last part of template {:?}", template))]
+    MisplacedWildcard { template: String },
+    #[snafu(display("Only one template can be without filter"))]
+    MultipleDefaults,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Skip,
+    Measurement,
+    MeasurementRest,
+    Tag(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Template {
+    /// The parts of the paths the template applies to, where `*` matches any
+    /// part. The paths can be longer than the filter.
+    filter: Option<Vec<String>>,
+    parts: Vec<Part>,
+    tags: MetricTags,
+}
+
+impl Template {
+    fn parse(template: &str) -> Result<Self, TemplateError> {
+        let malformed = || TemplateError::Malformed {
+            template: template.into(),
+        };
+        let (filter, parts, tags) = match template.split_whitespace().collect::<Vec<_>>()[..] {
+            [parts] => (None, parts, None),
+            [parts, tags] if tags.contains('=') => (None, parts, Some(tags)),
+            [filter, parts] => (Some(filter), parts, None),
+            [filter, parts, tags] => (Some(filter), parts, Some(tags)),
+            _ => return Err(malformed()),
+        };
+
+        let filter = filter.map(|filter| filter.split('.').map(Into::into).collect());
+
+        let parts = parts
+            .split('.')
+            .map(|part| match part {
+                "" => Part::Skip,
+                MEASUREMENT => Part::Measurement,
+                MEASUREMENT_REST => Part::MeasurementRest,
+                tag => Part::Tag(tag.into()),
+            })
+            .collect::<Vec<_>>();
+        if parts[..parts.len() - 1].contains(&Part::MeasurementRest) {
+            return Err(TemplateError::MisplacedWildcard {
+                template: template.into(),
+            });
+        }
+
+        let tags = tags
+            .map(|tags| {
+                tags.split(',')
+                    .map(|tag| {
+                        let mut parts = tag.splitn(2, '=');
+                        match (parts.next(), parts.next()) {
+                            (Some(name), Some(value)) if !name.is_empty() => {
+                                Ok((name.into(), value.into()))
+                            }
+                            _ => Err(TemplateError::InvalidTag {
+                                tag: tag.into(),
+                                template: template.into(),
+                            }),
+                        }
+                    })
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(Self {
+            filter,
+            parts,
+            tags,
+        })
+    }
+
+    fn matches(&self, segments: &[&str]) -> bool {
+        match &self.filter {
+            Some(filter) => {
+                filter.len() <= segments.len()
+                    && filter
+                        .iter()
+                        .zip(segments)
+                        .all(|(filter, segment)| filter == "*" || filter == segment)
+            }
+            None => true,
+        }
+    }
+
+    fn apply(&self, segments: &[&str], separator: &str) -> (Option<String>, MetricTags) {
+        let mut name = Vec::new();
+        let mut tags = self.tags.clone();
+        for (index, (part, segment)) in self.parts.iter().zip(segments).enumerate() {
+            match part {
+                Part::Skip => (),
+                Part::Measurement => name.push(*segment),
+                Part::MeasurementRest => {
+                    name.extend(&segments[index..]);
+                    break;
+                }
+                Part::Tag(tag) => {
+                    // Like Telegraf, the parts matching the same tag are joined.
+                    tags.entry(tag.clone())
+                        .and_modify(|value| {
+                            value.push_str(separator);
+                            value.push_str(segment);
+                        })
+                        .or_insert_with(|| (*segment).into());
+                }
+            }
+        }
+        let name = Some(name.join(separator)).filter(|name| !name.is_empty());
+        (name, tags)
+    }
+}
+
+/// The templates of the source. The first template whose filter matches a path
+/// is applied, or the template without filter when there is none.
+#[derive(Clone, Debug, Default)]
+pub struct Templates {
+    templates: Vec<Template>,
+    default: Option<Template>,
+    separator: String,
+}
+
+impl Templates {
+    pub fn new(templates: &[String], separator: &str) -> Result<Self, TemplateError> {
+        let mut filtered = Vec::new();
+        let mut default = None;
+        for template in templates {
+            let template = Template::parse(template)?;
+            if template.filter.is_some() {
+                filtered.push(template);
+            } else if default.replace(template).is_some() {
+                return Err(TemplateError::MultipleDefaults);
+            }
+        }
+
+        Ok(Self {
+            templates: filtered,
+            default,
+            separator: separator.into(),
+        })
+    }
+
+    /// Convert a path into a metric name and tags. The path is used as the name
+    /// when no template applies, or when the template doesn't name the metric.
+    pub fn apply(&self, path: &str) -> (String, MetricTags) {
+        let segments = path.split('.').collect::<Vec<_>>();
+        let template = self
+            .templates
+            .iter()
+            .find(|template| template.matches(&segments))
+            .or(self.default.as_ref());
+
+        match template {
+            Some(template) => {
+                let (name, tags) = template.apply(&segments, &self.separator);
+                (name.unwrap_or_else(|| path.into()), tags)
+            }
+            None => (path.into(), MetricTags::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    fn templates(templates: &[&str]) -> Templates {
+        let templates = templates.iter().map(|&t| t.into()).collect::<Vec<_>>();
+        Templates::new(&templates, "_").unwrap()
+    }
+
+    #[test]
+    fn applies_matching_template() {
+        let templates = templates(&[
+            "servers.* .host.measurement* datacenter=dc1",
+            "stats.*.requests .service.measurement.status",
+            "measurement.measurement.host",
+        ]);
+
+        assert_eq!(
+            templates.apply("servers.web01.cpu.load.shortterm"),
+            (
+                "cpu_load_shortterm".into(),
+                btreemap! {"host" => "web01", "datacenter" => "dc1"}
+            )
+        );
+        assert_eq!(
+            templates.apply("stats.api.requests.200"),
+            (
+                "requests".into(),
+                btreemap! {"service" => "api", "status" => "200"}
+            )
+        );
+        assert_eq!(
+            templates.apply("nginx.connections.web02.active"),
+            ("nginx_connections".into(), btreemap! {"host" => "web02"})
+        );
+    }
+
+    #[test]
+    fn joins_repeated_tags() {
+        let templates = templates(&["measurement.host.host"]);
+        assert_eq!(
+            templates.apply("cpu.web01.example"),
+            ("cpu".into(), btreemap! {"host" => "web01_example"})
+        );
+    }
+
+    #[test]
+    fn keeps_path_without_template() {
+        let templates = templates(&["servers.* .host.measurement*"]);
+        assert_eq!(
+            templates.apply("clients.web01.cpu"),
+            ("clients.web01.cpu".into(), MetricTags::new())
+        );
+        assert_eq!(
+            templates.apply("servers.web01"),
+            ("servers.web01".into(), btreemap! {"host" => "web01"})
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        let new = |templates: &[&str]| {
+            let templates = templates.iter().map(|&t| t.into()).collect::<Vec<_>>();
+            Templates::new(&templates, ".").unwrap_err()
+        };
+
+        assert!(matches!(new(&["a b c d"]), TemplateError::Malformed { .. }));
+        assert!(matches!(
+            new(&["servers.* host.measurement region"]),
+            TemplateError::InvalidTag { .. }
+        ));
+        assert!(matches!(
+            new(&["measurement*.host"]),
+            TemplateError::MisplacedWildcard { .. }
+        ));
+        assert_eq!(
+            new(&["measurement", "host.measurement"]),
+            TemplateError::MultipleDefaults
+        );
+    }
+}
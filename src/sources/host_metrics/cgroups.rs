@@ -0,0 +1,395 @@
+//! Control group statistics, read from the cgroup v2 unified hierarchy or from
+//! the `cpuacct`, `memory` and `blkio` hierarchies of cgroup v1.
+
+use super::{process::USER_HZ, CgroupsConfig};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct CgroupStats {
+    /// The path of the group from the root of its hierarchy, like
+    /// `/system.slice/nginx.service`.
+    pub name: String,
+    pub cpu: Option<CpuStats>,
+    pub memory: Option<MemoryStats>,
+    pub io: Vec<IoStats>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct CpuStats {
+    pub usage_seconds: Option<f64>,
+    pub user_seconds: Option<f64>,
+    pub system_seconds: Option<f64>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct MemoryStats {
+    /// The root group has no memory usage in cgroup v2.
+    pub current_bytes: Option<u64>,
+    pub anon_bytes: Option<u64>,
+    pub file_bytes: Option<u64>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct IoStats {
+    /// The `major:minor` number of the device.
+    pub device: String,
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+    pub reads: u64,
+    pub writes: u64,
+}
+
+type Reader = fn(&Path, &mut CgroupStats);
+
+/// Read the statistics of the groups of the hierarchies mounted at `base`,
+/// whose name matches the filter of the configuration.
+pub(super) fn collect(base: &Path, config: &CgroupsConfig) -> io::Result<Vec<CgroupStats>> {
+    let mut cgroups = BTreeMap::new();
+    if base.join("cgroup.controllers").exists() {
+        walk(base, base, 0, config, read_v2, &mut cgroups)?;
+    } else {
+        let hierarchies: [(&str, Reader); 3] = [
+            ("cpuacct", read_v1_cpu),
+            ("memory", read_v1_memory),
+            ("blkio", read_v1_io),
+        ];
+        for (hierarchy, read) in &hierarchies {
+            let root = base.join(hierarchy);
+            // The hierarchies of the controllers that aren't mounted are skipped.
+            if root.is_dir() {
+                walk(&root, &root, 0, config, *read, &mut cgroups)?;
+            }
+        }
+    }
+    Ok(cgroups.into_iter().map(|(_, cgroup)| cgroup).collect())
+}
+
+fn walk(
+    root: &Path,
+    path: &Path,
+    level: usize,
+    config: &CgroupsConfig,
+    read: Reader,
+    cgroups: &mut BTreeMap<String, CgroupStats>,
+) -> io::Result<()> {
+    let name = match path.strip_prefix(root) {
+        Ok(relative) => format!("/{}", relative.to_string_lossy()),
+        Err(_) => return Ok(()),
+    };
+    if config.groups.contains_str(Some(&name)) {
+        let cgroup = cgroups.entry(name.clone()).or_insert_with(|| CgroupStats {
+            name,
+            ..Default::default()
+        });
+        read(path, cgroup);
+    }
+
+    if level < config.levels {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                // The groups removed while being read are skipped.
+                let _ = walk(root, &entry.path(), level + 1, config, read, cgroups);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_v2(path: &Path, cgroup: &mut CgroupStats) {
+    if let Some(stat) = read_keyed(&path.join("cpu.stat")) {
+        let seconds = |key| stat.get(key).map(|&usec| usec as f64 / 1_000_000.0);
+        cgroup.cpu = Some(CpuStats {
+            usage_seconds: seconds("usage_usec"),
+            user_seconds: seconds("user_usec"),
+            system_seconds: seconds("system_usec"),
+        });
+    }
+
+    let current_bytes = read_value(&path.join("memory.current"));
+    if let Some(stat) = read_keyed(&path.join("memory.stat")) {
+        cgroup.memory = Some(MemoryStats {
+            current_bytes,
+            anon_bytes: stat.get("anon").copied(),
+            file_bytes: stat.get("file").copied(),
+        });
+    }
+
+    // Each line is made of a device and its `key=value` statistics.
+    if let Ok(stat) = fs::read_to_string(path.join("io.stat")) {
+        for line in stat.lines() {
+            let mut fields = line.split_whitespace();
+            let mut io = match fields.next() {
+                Some(device) => IoStats {
+                    device: device.into(),
+                    ..Default::default()
+                },
+                None => continue,
+            };
+            for (key, value) in fields.filter_map(|field| field.split_once('=')) {
+                let value = value.parse().unwrap_or_default();
+                match key {
+                    "rbytes" => io.read_bytes = value,
+                    "wbytes" => io.written_bytes = value,
+                    "rios" => io.reads = value,
+                    "wios" => io.writes = value,
+                    _ => (),
+                }
+            }
+            cgroup.io.push(io);
+        }
+    }
+}
+
+fn read_v1_cpu(path: &Path, cgroup: &mut CgroupStats) {
+    let usage_seconds =
+        read_value(&path.join("cpuacct.usage")).map(|nanos| nanos as f64 / 1_000_000_000.0);
+    let stat = read_keyed(&path.join("cpuacct.stat"));
+    let seconds = |key| {
+        stat.as_ref()
+            .and_then(|stat| stat.get(key))
+            .map(|&ticks| ticks as f64 / USER_HZ)
+    };
+    if usage_seconds.is_some() || stat.is_some() {
+        cgroup.cpu = Some(CpuStats {
+            usage_seconds,
+            user_seconds: seconds("user"),
+            system_seconds: seconds("system"),
+        });
+    }
+}
+
+fn read_v1_memory(path: &Path, cgroup: &mut CgroupStats) {
+    let current_bytes = read_value(&path.join("memory.usage_in_bytes"));
+    if let Some(stat) = read_keyed(&path.join("memory.stat")) {
+        cgroup.memory = Some(MemoryStats {
+            current_bytes,
+            anon_bytes: stat.get("rss").copied(),
+            file_bytes: stat.get("cache").copied(),
+        });
+    }
+}
+
+fn read_v1_io(path: &Path, cgroup: &mut CgroupStats) {
+    // Each line is made of a device, an operation and a value, and the last
+    // line of the total of all the devices.
+    let mut devices = BTreeMap::<String, IoStats>::new();
+    let files = [
+        "blkio.throttle.io_service_bytes",
+        "blkio.throttle.io_serviced",
+    ];
+    for (index, file) in files.iter().enumerate() {
+        let stat = match fs::read_to_string(path.join(file)) {
+            Ok(stat) => stat,
+            Err(_) => continue,
+        };
+        for line in stat.lines() {
+            let (device, operation, value) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [device, operation, value] => (device, operation, value),
+                _ => continue,
+            };
+            let io = devices.entry(device.into()).or_insert_with(|| IoStats {
+                device: device.into(),
+                ..Default::default()
+            });
+            let value = value.parse().unwrap_or_default();
+            match (index, operation) {
+                (0, "Read") => io.read_bytes = value,
+                (0, "Write") => io.written_bytes = value,
+                (1, "Read") => io.reads = value,
+                (1, "Write") => io.writes = value,
+                _ => (),
+            }
+        }
+    }
+    cgroup.io.extend(devices.into_iter().map(|(_, io)| io));
+}
+
+fn read_value(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Read the flat keyed files made of `key value` lines.
+fn read_keyed(path: &Path) -> Option<BTreeMap<String, u64>> {
+    let stat = fs::read_to_string(path).ok()?;
+    Some(
+        stat.lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                Some((key.into(), value.trim().parse().ok()?))
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::host_metrics::{FilterList, PatternWrapper};
+
+    fn write_files(path: &Path, files: &[(&str, &str)]) {
+        fs::create_dir_all(path).unwrap();
+        for (file, content) in files {
+            fs::write(path.join(file), content).unwrap();
+        }
+    }
+
+    #[test]
+    fn collects_v2_groups() {
+        let base = tempfile::tempdir().unwrap();
+        write_files(
+            base.path(),
+            &[
+                ("cgroup.controllers", "cpu io memory"),
+                (
+                    "cpu.stat",
+                    "usage_usec 3000000\nuser_usec 2000000\nsystem_usec 1000000\n",
+                ),
+                ("memory.stat", "anon 4096\nfile 8192\n"),
+            ],
+        );
+        write_files(
+            &base.path().join("system.slice").join("nginx.service"),
+            &[
+                (
+                    "cpu.stat",
+                    "usage_usec 1500000\nuser_usec 1000000\nsystem_usec 500000\n",
+                ),
+                ("memory.current", "16384\n"),
+                ("memory.stat", "anon 1024\nfile 2048\n"),
+                (
+                    "io.stat",
+                    "8:0 rbytes=4096 wbytes=512 rios=2 wios=1 dbytes=0 dios=0\n",
+                ),
+            ],
+        );
+
+        let config = CgroupsConfig {
+            groups: FilterList {
+                includes: None,
+                excludes: Some(vec![PatternWrapper::new("/system.slice").unwrap()]),
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            collect(base.path(), &config).unwrap(),
+            vec![
+                CgroupStats {
+                    name: "/".into(),
+                    cpu: Some(CpuStats {
+                        usage_seconds: Some(3.0),
+                        user_seconds: Some(2.0),
+                        system_seconds: Some(1.0),
+                    }),
+                    memory: Some(MemoryStats {
+                        current_bytes: None,
+                        anon_bytes: Some(4096),
+                        file_bytes: Some(8192),
+                    }),
+                    io: vec![],
+                },
+                CgroupStats {
+                    name: "/system.slice/nginx.service".into(),
+                    cpu: Some(CpuStats {
+                        usage_seconds: Some(1.5),
+                        user_seconds: Some(1.0),
+                        system_seconds: Some(0.5),
+                    }),
+                    memory: Some(MemoryStats {
+                        current_bytes: Some(16384),
+                        anon_bytes: Some(1024),
+                        file_bytes: Some(2048),
+                    }),
+                    io: vec![IoStats {
+                        device: "8:0".into(),
+                        read_bytes: 4096,
+                        written_bytes: 512,
+                        reads: 2,
+                        writes: 1,
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_v1_groups() {
+        let base = tempfile::tempdir().unwrap();
+        write_files(
+            &base.path().join("cpuacct").join("docker"),
+            &[
+                ("cpuacct.usage", "2500000000\n"),
+                ("cpuacct.stat", "user 150\nsystem 50\n"),
+            ],
+        );
+        write_files(
+            &base.path().join("memory").join("docker"),
+            &[
+                ("memory.usage_in_bytes", "16384\n"),
+                ("memory.stat", "cache 2048\nrss 1024\n"),
+            ],
+        );
+        write_files(
+            &base.path().join("blkio").join("docker"),
+            &[
+                (
+                    "blkio.throttle.io_service_bytes",
+                    "8:0 Read 4096\n8:0 Write 512\n8:0 Sync 0\nTotal 4608\n",
+                ),
+                (
+                    "blkio.throttle.io_serviced",
+                    "8:0 Read 2\n8:0 Write 1\nTotal 3\n",
+                ),
+            ],
+        );
+
+        let config = CgroupsConfig {
+            groups: FilterList {
+                includes: Some(vec![PatternWrapper::new("/docker*").unwrap()]),
+                excludes: None,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            collect(base.path(), &config).unwrap(),
+            vec![CgroupStats {
+                name: "/docker".into(),
+                cpu: Some(CpuStats {
+                    usage_seconds: Some(2.5),
+                    user_seconds: Some(1.5),
+                    system_seconds: Some(0.5),
+                }),
+                memory: Some(MemoryStats {
+                    current_bytes: Some(16384),
+                    anon_bytes: Some(1024),
+                    file_bytes: Some(2048),
+                }),
+                io: vec![IoStats {
+                    device: "8:0".into(),
+                    read_bytes: 4096,
+                    written_bytes: 512,
+                    reads: 2,
+                    writes: 1,
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn limits_levels() {
+        let base = tempfile::tempdir().unwrap();
+        write_files(base.path(), &[("cgroup.controllers", "")]);
+        fs::create_dir_all(base.path().join("a").join("b")).unwrap();
+
+        let config = CgroupsConfig {
+            levels: 1,
+            ..Default::default()
+        };
+        let names = collect(base.path(), &config)
+            .unwrap()
+            .into_iter()
+            .map(|cgroup| cgroup.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["/", "/a"]);
+    }
+}
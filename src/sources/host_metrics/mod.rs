@@ -34,13 +34,19 @@ use serde::{
 use shared::btreemap;
 use std::collections::BTreeMap;
 use std::fmt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::time;
 use tokio_stream::wrappers::IntervalStream;
 
+#[cfg(target_os = "linux")]
+mod cgroups;
+#[cfg(target_os = "linux")]
+mod process;
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 enum Collector {
+    Cgroups,
     Cpu,
     Disk,
     Filesystem,
@@ -48,6 +54,7 @@ enum Collector {
     Host,
     Memory,
     Network,
+    Process,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -78,6 +85,39 @@ struct NetworkConfig {
     devices: FilterList,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct ProcessConfig {
+    #[serde(default)]
+    names: FilterList,
+    #[serde(default)]
+    cmdlines: FilterList,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct CgroupsConfig {
+    /// The mount point of the cgroup hierarchies, `$SYSFS_ROOT/fs/cgroup` by
+    /// default.
+    base: Option<PathBuf>,
+    #[serde(default = "default_cgroups_levels")]
+    levels: usize,
+    #[serde(default)]
+    groups: FilterList,
+}
+
+impl Default for CgroupsConfig {
+    fn default() -> Self {
+        Self {
+            base: None,
+            levels: default_cgroups_levels(),
+            groups: FilterList::default(),
+        }
+    }
+}
+
+const fn default_cgroups_levels() -> usize {
+    100
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 struct Namespace(Option<String>);
 
@@ -103,6 +143,10 @@ pub struct HostMetricsConfig {
     filesystem: FilesystemConfig,
     #[serde(default)]
     network: NetworkConfig,
+    #[serde(default)]
+    process: ProcessConfig,
+    #[serde(default)]
+    cgroups: CgroupsConfig,
 }
 
 const fn default_scrape_interval() -> u64 {
@@ -153,7 +197,9 @@ impl HostMetricsConfig {
 
     fn has_collector(&self, collector: Collector) -> bool {
         match &self.collectors {
-            None => true,
+            // The process and cgroups collectors are opt-in, as they generate
+            // metrics for each process and control group.
+            None => !matches!(collector, Collector::Process | Collector::Cgroups),
             Some(collectors) => collectors.iter().any(|&c| c == collector),
         }
     }
@@ -183,6 +229,12 @@ impl HostMetricsConfig {
         if self.has_collector(Collector::Network) {
             metrics.extend(add_collector("network", self.network_metrics().await));
         }
+        if self.has_collector(Collector::Process) {
+            metrics.extend(add_collector("process", self.process_metrics().await));
+        }
+        if self.has_collector(Collector::Cgroups) {
+            metrics.extend(add_collector("cgroups", self.cgroups_metrics().await));
+        }
         if let Ok(hostname) = &hostname {
             for metric in &mut metrics {
                 metric.insert_tag("host".into(), hostname.into());
@@ -683,6 +735,155 @@ impl HostMetricsConfig {
         }
     }
 
+    pub async fn process_metrics(&self) -> Vec<Metric> {
+        #[cfg(target_os = "linux")]
+        let result = {
+            let procfs = root_path("PROCFS_ROOT", "/proc");
+            let config = self.process.clone();
+            match run_blocking(move || process::collect(&procfs, &config)).await {
+                Ok(processes) => processes
+                    .into_iter()
+                    .flat_map(|process| {
+                        let timestamp = Utc::now();
+                        let tags = btreemap! {
+                            "pid" => process.pid.to_string(),
+                            "name" => process.name,
+                        };
+                        let mut metrics = vec![
+                            self.counter(
+                                "process_cpu_seconds_total",
+                                timestamp,
+                                process.user_seconds,
+                                with_tag(&tags, "mode", "user"),
+                            ),
+                            self.counter(
+                                "process_cpu_seconds_total",
+                                timestamp,
+                                process.system_seconds,
+                                with_tag(&tags, "mode", "system"),
+                            ),
+                            self.gauge(
+                                "process_threads",
+                                timestamp,
+                                process.threads as f64,
+                                tags.clone(),
+                            ),
+                        ];
+                        let gauges = vec![
+                            ("process_memory_rss_bytes", process.rss_bytes),
+                            ("process_memory_virtual_bytes", process.virtual_bytes),
+                            ("process_open_fds", process.open_fds),
+                        ];
+                        for (name, value) in gauges {
+                            if let Some(value) = value {
+                                metrics.push(self.gauge(
+                                    name,
+                                    timestamp,
+                                    value as f64,
+                                    tags.clone(),
+                                ));
+                            }
+                        }
+                        metrics
+                    })
+                    .collect(),
+                Err(error) => {
+                    error!(message = "Failed to load processes info.", %error, internal_log_rate_secs = 60);
+                    vec![]
+                }
+            }
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = vec![];
+
+        result
+    }
+
+    pub async fn cgroups_metrics(&self) -> Vec<Metric> {
+        #[cfg(target_os = "linux")]
+        let result = {
+            let base = self
+                .cgroups
+                .base
+                .clone()
+                .unwrap_or_else(|| root_path("SYSFS_ROOT", "/sys").join("fs/cgroup"));
+            let config = self.cgroups.clone();
+            match run_blocking(move || cgroups::collect(&base, &config)).await {
+                Ok(cgroups) => cgroups
+                    .into_iter()
+                    .flat_map(|cgroup| {
+                        let timestamp = Utc::now();
+                        let tags = btreemap! { "cgroup" => cgroup.name };
+                        let mut metrics = Vec::new();
+                        if let Some(cpu) = cgroup.cpu {
+                            let counters = vec![
+                                ("cgroup_cpu_usage_seconds_total", cpu.usage_seconds, None),
+                                ("cgroup_cpu_seconds_total", cpu.user_seconds, Some("user")),
+                                (
+                                    "cgroup_cpu_seconds_total",
+                                    cpu.system_seconds,
+                                    Some("system"),
+                                ),
+                            ];
+                            for (name, value, mode) in counters {
+                                if let Some(value) = value {
+                                    let tags = match mode {
+                                        Some(mode) => with_tag(&tags, "mode", mode),
+                                        None => tags.clone(),
+                                    };
+                                    metrics.push(self.counter(name, timestamp, value, tags));
+                                }
+                            }
+                        }
+                        if let Some(memory) = cgroup.memory {
+                            let gauges = vec![
+                                ("cgroup_memory_current_bytes", memory.current_bytes),
+                                ("cgroup_memory_anon_bytes", memory.anon_bytes),
+                                ("cgroup_memory_file_bytes", memory.file_bytes),
+                            ];
+                            for (name, value) in gauges {
+                                if let Some(value) = value {
+                                    metrics.push(self.gauge(
+                                        name,
+                                        timestamp,
+                                        value as f64,
+                                        tags.clone(),
+                                    ));
+                                }
+                            }
+                        }
+                        for io in cgroup.io {
+                            let tags = with_tag(&tags, "device", &io.device);
+                            let counters = vec![
+                                ("cgroup_io_read_bytes_total", io.read_bytes),
+                                ("cgroup_io_written_bytes_total", io.written_bytes),
+                                ("cgroup_io_reads_completed_total", io.reads),
+                                ("cgroup_io_writes_completed_total", io.writes),
+                            ];
+                            for (name, value) in counters {
+                                metrics.push(self.counter(
+                                    name,
+                                    timestamp,
+                                    value as f64,
+                                    tags.clone(),
+                                ));
+                            }
+                        }
+                        metrics
+                    })
+                    .collect(),
+                Err(error) => {
+                    error!(message = "Failed to load cgroups info.", %error, internal_log_rate_secs = 60);
+                    vec![]
+                }
+            }
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = vec![];
+
+        result
+    }
+
     fn counter(
         &self,
         name: &str,
@@ -716,6 +917,35 @@ async fn filter_result<T>(result: Result<T, Error>, message: &'static str) -> Op
         .ok()
 }
 
+#[cfg(target_os = "linux")]
+fn with_tag(tags: &BTreeMap<String, String>, name: &str, value: &str) -> BTreeMap<String, String> {
+    let mut tags = tags.clone();
+    tags.insert(name.into(), value.into());
+    tags
+}
+
+/// Read procfs or cgroupfs on the blocking threads, as the reads are
+/// synchronous.
+#[cfg(target_os = "linux")]
+async fn run_blocking<T, F>(read: F) -> std::io::Result<T>
+where
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(read)
+        .await
+        .unwrap_or_else(|error| Err(std::io::Error::new(std::io::ErrorKind::Other, error)))
+}
+
+/// The root of procfs or sysfs, set like heim's by the `PROCFS_ROOT` and
+/// `SYSFS_ROOT` environment variables.
+#[cfg(target_os = "linux")]
+fn root_path(variable: &str, default: &str) -> PathBuf {
+    std::env::var_os(variable)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default.into())
+}
+
 fn add_collector(collector: &str, mut metrics: Vec<Metric>) -> Vec<Metric> {
     for metric in &mut metrics {
         metric.insert_tag("collector".into(), collector.into());
@@ -1186,6 +1416,42 @@ mod tests {
         assert!(all_gauges(&metrics));
     }
 
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn generates_process_metrics() {
+        let metrics = HostMetricsConfig::default().process_metrics().await;
+        assert!(!metrics.is_empty());
+
+        // All metrics are named process_*
+        assert!(!metrics
+            .iter()
+            .any(|metric| !metric.name().starts_with("process_")));
+
+        // They should all have "pid" and "name" tags
+        assert_eq!(count_tag(&metrics, "pid"), metrics.len());
+        assert_eq!(count_tag(&metrics, "name"), metrics.len());
+
+        // The process running the tests is included
+        let pid = std::process::id().to_string();
+        assert!(collect_tag_values(&metrics, "pid").contains(&pid));
+    }
+
+    #[tokio::test]
+    async fn process_collector_is_opt_in() {
+        let metrics = HostMetricsConfig::default().capture_metrics().await;
+        assert!(!metrics
+            .map(Event::into_metric)
+            .any(|metric| metric.name().starts_with("process_")));
+    }
+
+    #[tokio::test]
+    async fn cgroups_collector_is_opt_in() {
+        let metrics = HostMetricsConfig::default().capture_metrics().await;
+        assert!(!metrics
+            .map(Event::into_metric)
+            .any(|metric| metric.tag_value("collector").as_deref() == Some("cgroups")));
+    }
+
     fn all_counters(metrics: &[Metric]) -> bool {
         !metrics
             .iter()
@@ -0,0 +1,211 @@
+//! Per-process statistics, read from the `/proc/<pid>` directories of procfs.
+
+use super::ProcessConfig;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The kernel reports the CPU times in `USER_HZ`, which is 100 on all the
+/// architectures Vector supports.
+pub(super) const USER_HZ: f64 = 100.0;
+
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct ProcessStats {
+    pub pid: u32,
+    pub name: String,
+    pub user_seconds: f64,
+    pub system_seconds: f64,
+    /// The memory statistics of the kernel threads are left out.
+    pub rss_bytes: Option<u64>,
+    pub virtual_bytes: Option<u64>,
+    pub threads: u64,
+    /// The file descriptors of the processes of other users are only readable
+    /// with the `CAP_SYS_PTRACE` capability.
+    pub open_fds: Option<u64>,
+}
+
+/// Read the statistics of the processes whose name and command line match the
+/// filters of the configuration.
+pub(super) fn collect(procfs: &Path, config: &ProcessConfig) -> io::Result<Vec<ProcessStats>> {
+    let mut processes = Vec::new();
+    for entry in fs::read_dir(procfs)? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(_) => continue,
+        };
+        let pid = match path
+            .file_name()
+            .and_then(|name| name.to_str()?.parse().ok())
+        {
+            Some(pid) => pid,
+            None => continue,
+        };
+        // The processes exiting while being read are skipped.
+        if let Ok(Some(process)) = read_process(path, pid, config) {
+            processes.push(process);
+        }
+    }
+    Ok(processes)
+}
+
+fn read_process(
+    path: PathBuf,
+    pid: u32,
+    config: &ProcessConfig,
+) -> io::Result<Option<ProcessStats>> {
+    let mut process = parse_status(&fs::read_to_string(path.join("status"))?);
+    process.pid = pid;
+
+    // The arguments are separated, and terminated, by NUL characters.
+    let cmdline = fs::read(path.join("cmdline"))?;
+    let cmdline = String::from_utf8_lossy(&cmdline)
+        .split_terminator('\0')
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !config.names.contains_str(Some(&process.name))
+        || !config.cmdlines.contains_str(Some(&cmdline))
+    {
+        return Ok(None);
+    }
+
+    let stat = fs::read_to_string(path.join("stat"))?;
+    let (user_seconds, system_seconds) = parse_cpu_times(&stat)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid process stat file."))?;
+    process.user_seconds = user_seconds;
+    process.system_seconds = system_seconds;
+    process.open_fds = fs::read_dir(path.join("fd"))
+        .ok()
+        .map(|fds| fds.count() as u64);
+
+    Ok(Some(process))
+}
+
+/// Parse the name, memory and threads of `/proc/<pid>/status`.
+fn parse_status(status: &str) -> ProcessStats {
+    let mut process = ProcessStats::default();
+    for line in status.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key, value.trim()),
+            None => continue,
+        };
+        match key {
+            "Name" => process.name = value.into(),
+            "VmRSS" => process.rss_bytes = parse_kilobytes(value),
+            "VmSize" => process.virtual_bytes = parse_kilobytes(value),
+            "Threads" => process.threads = value.parse().unwrap_or_default(),
+            _ => (),
+        }
+    }
+    process
+}
+
+fn parse_kilobytes(value: &str) -> Option<u64> {
+    let kilobytes = value.strip_suffix("kB")?.trim_end().parse::<u64>().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Parse the user and system CPU times of `/proc/<pid>/stat`, the 14th and
+/// 15th fields. The second field is the name of the process in parentheses,
+/// which can contain spaces and parentheses.
+fn parse_cpu_times(stat: &str) -> Option<(f64, f64)> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let mut fields = fields.split_whitespace().skip(11);
+    let user = fields.next()?.parse::<f64>().ok()?;
+    let system = fields.next()?.parse::<f64>().ok()?;
+    Some((user / USER_HZ, system / USER_HZ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::host_metrics::{FilterList, PatternWrapper};
+
+    const STATUS: &str = "Name:\tnginx\nUmask:\t0022\nState:\tS (sleeping)\nPid:\t42\n\
+                          VmSize:\t   10240 kB\nVmRSS:\t    2048 kB\nThreads:\t4\n";
+    const STAT: &str = "42 (nginx: worker (1)) S 1 42 42 0 -1 4194624 1 0 0 0 250 125 0 0 20 0 4 0";
+
+    fn write_process(procfs: &Path, pid: u32, status: &str, cmdline: &[u8]) {
+        let path = procfs.join(pid.to_string());
+        fs::create_dir_all(path.join("fd")).unwrap();
+        fs::write(path.join("status"), status).unwrap();
+        fs::write(path.join("stat"), STAT).unwrap();
+        fs::write(path.join("cmdline"), cmdline).unwrap();
+        fs::write(path.join("fd").join("0"), "").unwrap();
+        fs::write(path.join("fd").join("1"), "").unwrap();
+    }
+
+    #[test]
+    fn parses_process_files() {
+        assert_eq!(parse_cpu_times(STAT), Some((2.5, 1.25)));
+        assert_eq!(parse_cpu_times("42 (nginx) S 1"), None);
+        assert_eq!(
+            parse_status(STATUS),
+            ProcessStats {
+                name: "nginx".into(),
+                rss_bytes: Some(2 * 1024 * 1024),
+                virtual_bytes: Some(10 * 1024 * 1024),
+                threads: 4,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn collects_filtered_processes() {
+        let procfs = tempfile::tempdir().unwrap();
+        write_process(procfs.path(), 42, STATUS, b"nginx: worker process\0");
+        write_process(procfs.path(), 43, "Name:\tkthreadd\nThreads:\t1\n", b"");
+        fs::create_dir(procfs.path().join("self")).unwrap();
+
+        let mut processes = collect(procfs.path(), &ProcessConfig::default()).unwrap();
+        processes.sort_by_key(|process| process.pid);
+        assert_eq!(
+            processes,
+            vec![
+                ProcessStats {
+                    pid: 42,
+                    name: "nginx".into(),
+                    user_seconds: 2.5,
+                    system_seconds: 1.25,
+                    rss_bytes: Some(2 * 1024 * 1024),
+                    virtual_bytes: Some(10 * 1024 * 1024),
+                    threads: 4,
+                    open_fds: Some(2),
+                },
+                ProcessStats {
+                    pid: 43,
+                    name: "kthreadd".into(),
+                    user_seconds: 2.5,
+                    system_seconds: 1.25,
+                    rss_bytes: None,
+                    virtual_bytes: None,
+                    threads: 1,
+                    open_fds: Some(2),
+                },
+            ]
+        );
+
+        let config = ProcessConfig {
+            cmdlines: FilterList {
+                includes: Some(vec![PatternWrapper::new("*worker*").unwrap()]),
+                excludes: None,
+            },
+            ..Default::default()
+        };
+        let processes = collect(procfs.path(), &config).unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].pid, 42);
+
+        let config = ProcessConfig {
+            names: FilterList {
+                includes: None,
+                excludes: Some(vec![PatternWrapper::new("nginx").unwrap()]),
+            },
+            ..Default::default()
+        };
+        let processes = collect(procfs.path(), &config).unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].pid, 43);
+    }
+}
@@ -0,0 +1,668 @@
+//! InfluxDB source
+//!
+//! Receives the InfluxDB line protocol on the `/write` endpoint of InfluxDB
+//! 1.x and the `/api/v2/write` endpoint of InfluxDB 2.x, or as raw lines over
+//! TCP and UDP like the Telegraf `socket_listener` input.
+//!
+//! https://docs.influxdata.com/influxdb/v2.0/reference/syntax/line-protocol/
+
+use crate::udp;
+use crate::{
+    config::{
+        self, log_schema, GenerateConfig, Resource, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent, Value,
+    },
+    internal_events::{InfluxDbEventsReceived, InfluxDbInvalidLine, InfluxDbSocketError},
+    shutdown::ShutdownSignal,
+    sources::util::{ErrorMessage, HttpSource, HttpSourceAuthConfig, SocketListenAddr, TcpSource},
+    tcp::TcpKeepaliveConfig,
+    tls::{MaybeTlsSettings, TlsConfig},
+    Pipeline,
+};
+use bytes::{Bytes, BytesMut};
+use chrono::Utc;
+use codec::BytesDelimitedCodec;
+use futures::{stream, SinkExt, StreamExt, TryFutureExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::TryFrom,
+    io,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+};
+use tokio::net::UdpSocket;
+use tokio_util::{
+    codec::{BytesCodec, Decoder},
+    udp::UdpFramed,
+};
+use warp::http::{HeaderMap, StatusCode};
+
+pub mod parser;
+
+use parser::{parse_line, FieldValue, Line, ParseError, Precision};
+
+const SOURCE_NAME: &str = "influxdb";
+const MEASUREMENT_KEY: &str = "measurement";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+// TODO: add back when https://github.com/serde-rs/serde/issues/1358 is addressed
+// #[serde(deny_unknown_fields)]
+pub struct InfluxDbConfig {
+    #[serde(flatten)]
+    mode: Mode,
+    #[serde(default)]
+    output: Output,
+    #[serde(default)]
+    precision: Precision,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+enum Mode {
+    Http(HttpConfig),
+    Tcp(TcpConfig),
+    Udp(UdpConfig),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct HttpConfig {
+    address: SocketAddr,
+    tls: Option<TlsConfig>,
+    auth: Option<HttpSourceAuthConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct TcpConfig {
+    address: SocketListenAddr,
+    keepalive: Option<TcpKeepaliveConfig>,
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+    #[serde(default = "default_shutdown_timeout_secs")]
+    shutdown_timeout_secs: u64,
+    receive_buffer_bytes: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct UdpConfig {
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+}
+
+/// The events the points are turned into.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Output {
+    /// A gauge per numeric or boolean field, named after the field in the
+    /// namespace of the measurement. The string fields are left out.
+    Metrics,
+    /// A log event per point, with the measurement, tags and fields as
+    /// top-level fields.
+    Logs,
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self::Metrics
+    }
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
+inventory::submit! {
+    SourceDescription::new::<InfluxDbConfig>(SOURCE_NAME)
+}
+
+impl GenerateConfig for InfluxDbConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            mode: Mode::Http(HttpConfig {
+                address: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 8086)),
+                tls: None,
+                auth: None,
+            }),
+            output: Output::default(),
+            precision: Precision::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "influxdb")]
+impl SourceConfig for InfluxDbConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        match &self.mode {
+            Mode::Http(config) => InfluxDbHttpSource {
+                output: self.output,
+                precision: self.precision,
+            }
+            .run(config.address, "", false, &config.tls, &config.auth, cx),
+            Mode::Tcp(config) => {
+                let tls = MaybeTlsSettings::from_config(&config.tls, true)?;
+                InfluxDbTcpSource {
+                    output: self.output,
+                    precision: self.precision,
+                }
+                .run(
+                    config.address,
+                    config.keepalive,
+                    config.shutdown_timeout_secs,
+                    tls,
+                    config.receive_buffer_bytes,
+                    cx.shutdown,
+                    cx.out,
+                )
+            }
+            Mode::Udp(config) => Ok(Box::pin(influxdb_udp(
+                config.clone(),
+                self.output,
+                self.precision,
+                cx.shutdown,
+                cx.out,
+            ))),
+        }
+    }
+
+    fn output_type(&self) -> config::DataType {
+        match self.output {
+            Output::Metrics => config::DataType::Metric,
+            Output::Logs => config::DataType::Log,
+        }
+    }
+
+    fn source_type(&self) -> &'static str {
+        SOURCE_NAME
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        match &self.mode {
+            Mode::Http(http) => vec![Resource::tcp(http.address)],
+            Mode::Tcp(tcp) => vec![tcp.address.into()],
+            Mode::Udp(udp) => vec![Resource::udp(udp.address)],
+        }
+    }
+}
+
+/// Build the events of a point, with its timestamp in the given precision.
+fn build_events(line: Line, output: Output, precision: Precision) -> Vec<Event> {
+    let timestamp = line
+        .timestamp
+        .map(|timestamp| precision.timestamp(timestamp));
+
+    match output {
+        Output::Metrics => {
+            let tags = Some(line.tags).filter(|tags| !tags.is_empty());
+            let namespace = line.measurement;
+            line.fields
+                .into_iter()
+                .filter_map(|(name, value)| {
+                    let value = match value {
+                        FieldValue::Float(value) => value,
+                        FieldValue::Integer(value) => value as f64,
+                        FieldValue::UnsignedInteger(value) => value as f64,
+                        FieldValue::Boolean(value) => f64::from(u8::from(value)),
+                        FieldValue::String(_) => return None,
+                    };
+                    let metric =
+                        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+                            .with_namespace(Some(namespace.clone()))
+                            .with_tags(tags.clone())
+                            .with_timestamp(timestamp);
+                    Some(metric.into())
+                })
+                .collect()
+        }
+        Output::Logs => {
+            let mut log = LogEvent::default();
+            // Tags and fields are flat fields, the fields take precedence over
+            // the tags and the fields set by the source over both.
+            for (name, value) in line.tags {
+                log.insert_flat(name, value);
+            }
+            for (name, value) in line.fields {
+                let value = match value {
+                    FieldValue::Float(value) => Value::Float(value),
+                    FieldValue::Integer(value) => Value::Integer(value),
+                    FieldValue::UnsignedInteger(value) => i64::try_from(value)
+                        .map(Value::Integer)
+                        .unwrap_or(Value::Float(value as f64)),
+                    FieldValue::String(value) => Value::from(value),
+                    FieldValue::Boolean(value) => Value::Boolean(value),
+                };
+                log.insert_flat(name, value);
+            }
+            log.insert_flat(MEASUREMENT_KEY, line.measurement);
+            log.insert(
+                log_schema().timestamp_key(),
+                timestamp.unwrap_or_else(Utc::now),
+            );
+            log.insert(log_schema().source_type_key(), Bytes::from(SOURCE_NAME));
+            vec![log.into()]
+        }
+    }
+}
+
+/// Parse a line, skipping the empty lines and the comments.
+fn parse(line: &str) -> Option<Result<Line, ParseError>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        None
+    } else {
+        Some(parse_line(line))
+    }
+}
+
+/// Parse the lines received over TCP and UDP, discarding the invalid ones.
+fn parse_events(bytes: &[u8], output: Output, precision: Precision) -> Vec<Event> {
+    let text = String::from_utf8_lossy(bytes);
+    text.lines()
+        .filter_map(|line| match parse(line)? {
+            Ok(line) => Some(build_events(line, output, precision)),
+            Err(error) => {
+                emit!(InfluxDbInvalidLine {
+                    error: &error,
+                    text: line,
+                });
+                None
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+#[derive(Clone)]
+struct InfluxDbHttpSource {
+    output: Output,
+    precision: Precision,
+}
+
+impl HttpSource for InfluxDbHttpSource {
+    fn build_events(
+        &self,
+        body: Bytes,
+        _header_map: HeaderMap,
+        query_parameters: HashMap<String, String>,
+        path: &str,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        match path.trim_end_matches('/') {
+            "/write" | "/api/v2/write" => (),
+            _ => {
+                return Err(ErrorMessage::new(
+                    StatusCode::NOT_FOUND,
+                    "Not found".to_string(),
+                ))
+            }
+        }
+
+        let precision = match query_parameters.get("precision") {
+            Some(precision) => Precision::from_query(precision).ok_or_else(|| {
+                ErrorMessage::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid precision {:?}", precision),
+                )
+            })?,
+            None => self.precision,
+        };
+
+        let body = std::str::from_utf8(&body).map_err(|error| {
+            ErrorMessage::new(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid UTF-8 body: {}", error),
+            )
+        })?;
+
+        // Like InfluxDB, a request with an invalid line is rejected, but
+        // none of its points are written.
+        let mut events = Vec::new();
+        for (index, line) in body.lines().enumerate() {
+            match parse(line) {
+                Some(Ok(line)) => events.extend(build_events(line, self.output, precision)),
+                Some(Err(error)) => {
+                    emit!(InfluxDbInvalidLine {
+                        error: &error,
+                        text: line,
+                    });
+                    return Err(ErrorMessage::new(
+                        StatusCode::BAD_REQUEST,
+                        format!("Unable to parse line {}: {}", index + 1, error),
+                    ));
+                }
+                None => (),
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Decodes the events of the lines received over TCP.
+struct InfluxDbDecoder {
+    lines: BytesDelimitedCodec,
+    output: Output,
+    precision: Precision,
+    /// The events of the last line not returned yet.
+    pending: VecDeque<Event>,
+}
+
+impl InfluxDbDecoder {
+    fn decode_event(&mut self, src: &mut BytesMut, eof: bool) -> Result<Option<Event>, io::Error> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let line = if eof {
+                self.lines.decode_eof(src)?
+            } else {
+                self.lines.decode(src)?
+            };
+            match line {
+                Some(line) => {
+                    let events = parse_events(&line, self.output, self.precision);
+                    if !events.is_empty() {
+                        emit!(InfluxDbEventsReceived {
+                            count: events.len(),
+                            byte_size: line.len(),
+                        });
+                    }
+                    self.pending.extend(events);
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+impl Decoder for InfluxDbDecoder {
+    type Item = Event;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Event>, io::Error> {
+        self.decode_event(src, false)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Event>, io::Error> {
+        self.decode_event(src, true)
+    }
+}
+
+#[derive(Clone)]
+struct InfluxDbTcpSource {
+    output: Output,
+    precision: Precision,
+}
+
+impl TcpSource for InfluxDbTcpSource {
+    type Error = io::Error;
+    type Decoder = InfluxDbDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        InfluxDbDecoder {
+            lines: BytesDelimitedCodec::new(b'\n'),
+            output: self.output,
+            precision: self.precision,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn build_event(&self, event: Event, _host: Bytes) -> Option<Event> {
+        Some(event)
+    }
+}
+
+/// Receives the line protocol, one or more lines per datagram.
+async fn influxdb_udp(
+    config: UdpConfig,
+    output: Output,
+    precision: Precision,
+    shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    let socket = UdpSocket::bind(&config.address)
+        .map_err(|error| emit!(InfluxDbSocketError::bind(error)))
+        .await?;
+
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(
+        message = "Listening.",
+        addr = %config.address,
+        r#type = "udp"
+    );
+
+    let mut stream = UdpFramed::new(socket, BytesCodec::new()).take_until(shutdown);
+    while let Some(frame) = stream.next().await {
+        match frame {
+            Ok((bytes, _sock)) => {
+                let events = parse_events(&bytes, output, precision);
+                emit!(InfluxDbEventsReceived {
+                    count: events.len(),
+                    byte_size: bytes.len(),
+                });
+
+                let mut events = stream::iter(events.into_iter().map(Ok));
+                if let Err(error) = out.send_all(&mut events).await {
+                    error!(message = "Error sending events.", %error);
+                    break;
+                }
+            }
+            Err(error) => {
+                emit!(InfluxDbSocketError::read(error));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{collect_ready, next_addr, trace_init, wait_for_tcp};
+    use chrono::{DateTime, TimeZone};
+    use shared::btreemap;
+    use tokio::{
+        io::AsyncWriteExt,
+        time::{sleep, Duration},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<InfluxDbConfig>();
+    }
+
+    fn timestamp() -> DateTime<Utc> {
+        Utc.timestamp(1622548800, 0)
+    }
+
+    fn gauge(name: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+            .with_namespace(Some("cpu"))
+            .with_tags(Some(btreemap! {"host" => "web01"}))
+            .with_timestamp(Some(timestamp()))
+    }
+
+    async fn run_source(
+        mode: Mode,
+        output: Output,
+        send: impl std::future::Future<Output = ()>,
+    ) -> Vec<Event> {
+        trace_init();
+
+        let (tx, rx) = Pipeline::new_test();
+        let config = InfluxDbConfig {
+            mode,
+            output,
+            precision: Precision::Seconds,
+        };
+        let source = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_millis(100)).await;
+
+        send.await;
+        sleep(Duration::from_millis(200)).await;
+        collect_ready(rx).await
+    }
+
+    fn http_mode(address: SocketAddr) -> Mode {
+        Mode::Http(HttpConfig {
+            address,
+            tls: None,
+            auth: None,
+        })
+    }
+
+    async fn post(address: SocketAddr, path: &str, body: &'static str) -> u16 {
+        wait_for_tcp(address).await;
+        reqwest::Client::new()
+            .post(&format!("http://{}{}", address, path))
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn receives_v1_writes() {
+        let address = next_addr();
+        let events = run_source(http_mode(address), Output::Metrics, async move {
+            let body = "cpu,host=web01 usage=12.5,cores=8i,up=t,model=\"Xeon\" 1622548800000\n\n";
+            assert_eq!(
+                post(address, "/write?db=telegraf&precision=ms", body).await,
+                200
+            );
+        })
+        .await;
+
+        assert_eq!(
+            events,
+            vec![
+                gauge("usage", 12.5).into(),
+                gauge("cores", 8.0).into(),
+                gauge("up", 1.0).into(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_v2_writes() {
+        let address = next_addr();
+        let events = run_source(http_mode(address), Output::Metrics, async move {
+            let body = "cpu,host=web01 usage=12.5 1622548800";
+            let path = "/api/v2/write?org=acme&bucket=metrics";
+            assert_eq!(post(address, path, body).await, 200);
+        })
+        .await;
+
+        assert_eq!(events, vec![gauge("usage", 12.5).into()]);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_writes() {
+        let address = next_addr();
+        let events = run_source(http_mode(address), Output::Metrics, async move {
+            let body = "cpu,host=web01 usage=12.5 1622548800";
+            assert_eq!(post(address, "/query", body).await, 404);
+            assert_eq!(post(address, "/write?precision=d", body).await, 400);
+            let body = "cpu,host=web01 usage=12.5 1622548800\ncpu usage";
+            assert_eq!(post(address, "/write", body).await, 400);
+        })
+        .await;
+
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn receives_logs() {
+        let address = next_addr();
+        let events = run_source(http_mode(address), Output::Logs, async move {
+            let body =
+                "syslog,host=web01,appname=sshd message=\"Accepted\",host=\"web02\" 1622548800";
+            assert_eq!(post(address, "/write", body).await, 200);
+        })
+        .await;
+
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log[MEASUREMENT_KEY], "syslog".into());
+        assert_eq!(log["appname"], "sshd".into());
+        assert_eq!(log["host"], "web02".into());
+        assert_eq!(log["message"], "Accepted".into());
+        assert_eq!(log[log_schema().timestamp_key()], timestamp().into());
+        assert_eq!(log[log_schema().source_type_key()], SOURCE_NAME.into());
+    }
+
+    #[tokio::test]
+    async fn receives_lines_over_tcp() {
+        let address = next_addr();
+        let events = run_source(
+            Mode::Tcp(TcpConfig {
+                address: address.into(),
+                keepalive: None,
+                tls: None,
+                shutdown_timeout_secs: default_shutdown_timeout_secs(),
+                receive_buffer_bytes: None,
+            }),
+            Output::Metrics,
+            async move {
+                let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+                stream
+                    .write_all(
+                        b"cpu,host=web01 usage=12.5,cores=8i 1622548800\n\
+                          invalid\n\
+                          cpu,host=web01 usage=13 1622548800",
+                    )
+                    .await
+                    .unwrap();
+            },
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![
+                gauge("usage", 12.5).into(),
+                gauge("cores", 8.0).into(),
+                gauge("usage", 13.0).into(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_lines_over_udp() {
+        let address = next_addr();
+        let events = run_source(
+            Mode::Udp(UdpConfig {
+                address,
+                receive_buffer_bytes: None,
+            }),
+            Output::Metrics,
+            async move {
+                let socket = UdpSocket::bind(next_addr()).await.unwrap();
+                socket
+                    .send_to(
+                        b"cpu,host=web01 usage=12.5 1622548800\ncpu,host=web01 cores=8i 1622548800\n",
+                        address,
+                    )
+                    .await
+                    .unwrap();
+            },
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![gauge("usage", 12.5).into(), gauge("cores", 8.0).into()]
+        );
+    }
+}
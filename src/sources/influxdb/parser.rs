@@ -0,0 +1,381 @@
+//! Parser of the InfluxDB line protocol:
+//! `measurement[,tag=value...] field=value[,field=value...] [timestamp]`.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("Missing measurement"))]
+    MissingMeasurement,
+    #[snafu(display("Missing value of tag {:?}", tag))]
+    MissingTagValue { tag: String },
+    #[snafu(display("Missing fields"))]
+    MissingFields,
+    #[snafu(display("Missing value of field {:?}", field))]
+    MissingFieldValue { field: String },
+    #[snafu(display("Invalid value {:?} of field {:?}", value, field))]
+    InvalidFieldValue { field: String, value: String },
+    #[snafu(display("Unterminated string value of field {:?}", field))]
+    UnterminatedString { field: String },
+    #[snafu(display("Invalid timestamp {:?}", timestamp))]
+    InvalidTimestamp { timestamp: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Float(f64),
+    Integer(i64),
+    UnsignedInteger(u64),
+    String(String),
+    Boolean(bool),
+}
+
+/// A point of the line protocol, with its timestamp in the precision of the
+/// request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub measurement: String,
+    pub tags: BTreeMap<String, String>,
+    pub fields: Vec<(String, FieldValue)>,
+    pub timestamp: Option<i64>,
+}
+
+/// The unit of the timestamps.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum Precision {
+    #[serde(rename = "ns")]
+    Nanoseconds,
+    #[serde(rename = "us")]
+    Microseconds,
+    #[serde(rename = "ms")]
+    Milliseconds,
+    #[serde(rename = "s")]
+    Seconds,
+    #[serde(rename = "m")]
+    Minutes,
+    #[serde(rename = "h")]
+    Hours,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self::Nanoseconds
+    }
+}
+
+impl Precision {
+    /// Parse the `precision` query parameter, as accepted by the v1 and v2
+    /// write endpoints.
+    pub fn from_query(precision: &str) -> Option<Self> {
+        match precision {
+            "n" | "ns" => Some(Self::Nanoseconds),
+            "u" | "us" | "µ" | "µs" => Some(Self::Microseconds),
+            "ms" => Some(Self::Milliseconds),
+            "s" => Some(Self::Seconds),
+            "m" => Some(Self::Minutes),
+            "h" => Some(Self::Hours),
+            _ => None,
+        }
+    }
+
+    pub fn timestamp(self, timestamp: i64) -> DateTime<Utc> {
+        let nanoseconds = match self {
+            Self::Nanoseconds => return Utc.timestamp_nanos(timestamp),
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+            Self::Minutes => 60_000_000_000,
+            Self::Hours => 3_600_000_000_000,
+        };
+        Utc.timestamp_nanos(timestamp.saturating_mul(nanoseconds))
+    }
+}
+
+pub fn parse_line(line: &str) -> Result<Line, ParseError> {
+    let mut scanner = Scanner { input: line };
+
+    let (measurement, mut separator) = scanner.take_until(&[',', ' '], &[',', ' ']);
+    if measurement.is_empty() {
+        return Err(ParseError::MissingMeasurement);
+    }
+
+    let mut tags = BTreeMap::new();
+    while separator == Some(',') {
+        let (tag, next) = scanner.take_until(&['=', ',', ' '], &[',', '=', ' ']);
+        if next != Some('=') {
+            return Err(ParseError::MissingTagValue { tag });
+        }
+        let (value, next) = scanner.take_until(&[',', ' '], &[',', '=', ' ']);
+        if value.is_empty() {
+            return Err(ParseError::MissingTagValue { tag });
+        }
+        tags.insert(tag, value);
+        separator = next;
+    }
+
+    scanner.skip_spaces();
+    let mut fields = Vec::new();
+    loop {
+        let (field, next) = scanner.take_until(&['=', ',', ' '], &[',', '=', ' ']);
+        if field.is_empty() && next.is_none() && fields.is_empty() {
+            return Err(ParseError::MissingFields);
+        }
+        if next != Some('=') {
+            return Err(ParseError::MissingFieldValue { field });
+        }
+        let (value, next) = if scanner.input.starts_with('"') {
+            scanner.take_string(&field)?
+        } else {
+            let (value, next) = scanner.take_until(&[',', ' '], &[]);
+            (parse_field_value(&field, &value)?, next)
+        };
+        fields.push((field, value));
+        if next != Some(',') {
+            break;
+        }
+    }
+
+    scanner.skip_spaces();
+    let timestamp = match scanner.input.trim_end() {
+        "" => None,
+        timestamp => Some(
+            timestamp
+                .parse()
+                .map_err(|_| ParseError::InvalidTimestamp {
+                    timestamp: timestamp.into(),
+                })?,
+        ),
+    };
+
+    Ok(Line {
+        measurement,
+        tags,
+        fields,
+        timestamp,
+    })
+}
+
+fn parse_field_value(field: &str, value: &str) -> Result<FieldValue, ParseError> {
+    let invalid = || ParseError::InvalidFieldValue {
+        field: field.into(),
+        value: value.into(),
+    };
+    if value.is_empty() {
+        return Err(ParseError::MissingFieldValue {
+            field: field.into(),
+        });
+    }
+
+    match value {
+        "t" | "T" | "true" | "True" | "TRUE" => Ok(FieldValue::Boolean(true)),
+        "f" | "F" | "false" | "False" | "FALSE" => Ok(FieldValue::Boolean(false)),
+        _ => {
+            if let Some(integer) = value.strip_suffix('i') {
+                integer
+                    .parse()
+                    .map(FieldValue::Integer)
+                    .map_err(|_| invalid())
+            } else if let Some(integer) = value.strip_suffix('u') {
+                integer
+                    .parse()
+                    .map(FieldValue::UnsignedInteger)
+                    .map_err(|_| invalid())
+            } else {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .map(FieldValue::Float)
+                    .ok_or_else(invalid)
+            }
+        }
+    }
+}
+
+struct Scanner<'a> {
+    input: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    /// Take the input up to one of the `stops` characters, which is consumed
+    /// and returned. A backslash escapes the next character, and is only
+    /// removed before the `escaped` characters.
+    fn take_until(&mut self, stops: &[char], escaped: &[char]) -> (String, Option<char>) {
+        let mut output = String::new();
+        let mut chars = self.input.char_indices();
+        while let Some((index, c)) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some((_, next)) if escaped.contains(&next) => output.push(next),
+                    Some((_, next)) => {
+                        output.push(c);
+                        output.push(next);
+                    }
+                    None => output.push(c),
+                }
+            } else if stops.contains(&c) {
+                self.input = &self.input[index + c.len_utf8()..];
+                return (output, Some(c));
+            } else {
+                output.push(c);
+            }
+        }
+        self.input = "";
+        (output, None)
+    }
+
+    /// Take a string field value, in double quotes where double quotes and
+    /// backslashes are escaped, along with the separator following it.
+    fn take_string(&mut self, field: &str) -> Result<(FieldValue, Option<char>), ParseError> {
+        let unterminated = || ParseError::UnterminatedString {
+            field: field.into(),
+        };
+        let mut output = String::new();
+        let mut chars = self.input.char_indices().skip(1);
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, next @ '"')) | Some((_, next @ '\\')) => output.push(next),
+                    Some((_, next)) => {
+                        output.push(c);
+                        output.push(next);
+                    }
+                    None => return Err(unterminated()),
+                },
+                '"' => {
+                    let rest = &self.input[index + 1..];
+                    let next = rest.chars().next();
+                    if !matches!(next, None | Some(',') | Some(' ')) {
+                        return Err(ParseError::InvalidFieldValue {
+                            field: field.into(),
+                            value: self.input.into(),
+                        });
+                    }
+                    self.input = next.map_or(rest, |next| &rest[next.len_utf8()..]);
+                    return Ok((FieldValue::String(output), next));
+                }
+                c => output.push(c),
+            }
+        }
+        Err(unterminated())
+    }
+
+    fn skip_spaces(&mut self) {
+        self.input = self.input.trim_start_matches(' ');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    #[test]
+    fn parses_lines() {
+        assert_eq!(
+            parse_line(
+                r#"cpu,host=web01,region=us-west usage_user=12.5,cores=8i,up=t,model="Xeon \"E5\"" 1622548800000000000"#
+            ),
+            Ok(Line {
+                measurement: "cpu".into(),
+                tags: btreemap! {"host" => "web01", "region" => "us-west"},
+                fields: vec![
+                    ("usage_user".into(), FieldValue::Float(12.5)),
+                    ("cores".into(), FieldValue::Integer(8)),
+                    ("up".into(), FieldValue::Boolean(true)),
+                    ("model".into(), FieldValue::String(r#"Xeon "E5""#.into())),
+                ],
+                timestamp: Some(1622548800000000000),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_lines_without_tags_or_timestamp() {
+        assert_eq!(
+            parse_line("requests count=42u"),
+            Ok(Line {
+                measurement: "requests".into(),
+                tags: BTreeMap::new(),
+                fields: vec![("count".into(), FieldValue::UnsignedInteger(42))],
+                timestamp: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_escaped_characters() {
+        assert_eq!(
+            parse_line(r#"disk\ io,path=C:\\,mount\=point=/var\,log read\ bytes=1,msg="a,b c" 1"#),
+            Ok(Line {
+                measurement: "disk io".into(),
+                tags: btreemap! {"path" => r"C:\\", "mount=point" => "/var,log"},
+                fields: vec![
+                    ("read bytes".into(), FieldValue::Float(1.0)),
+                    ("msg".into(), FieldValue::String("a,b c".into())),
+                ],
+                timestamp: Some(1),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert_eq!(
+            parse_line(",host=a value=1"),
+            Err(ParseError::MissingMeasurement)
+        );
+        assert_eq!(
+            parse_line("cpu,host value=1"),
+            Err(ParseError::MissingTagValue { tag: "host".into() })
+        );
+        assert_eq!(parse_line("cpu"), Err(ParseError::MissingFields));
+        assert_eq!(
+            parse_line("cpu value"),
+            Err(ParseError::MissingFieldValue {
+                field: "value".into()
+            })
+        );
+        assert_eq!(
+            parse_line("cpu value=12x"),
+            Err(ParseError::InvalidFieldValue {
+                field: "value".into(),
+                value: "12x".into()
+            })
+        );
+        assert_eq!(
+            parse_line(r#"cpu model="Xeon"#),
+            Err(ParseError::UnterminatedString {
+                field: "model".into()
+            })
+        );
+        assert_eq!(
+            parse_line("cpu value=1 now"),
+            Err(ParseError::InvalidTimestamp {
+                timestamp: "now".into()
+            })
+        );
+    }
+
+    #[test]
+    fn converts_timestamps() {
+        let expected = Utc.timestamp(1622548800, 0);
+        assert_eq!(
+            Precision::Nanoseconds.timestamp(1622548800000000000),
+            expected
+        );
+        assert_eq!(
+            Precision::Microseconds.timestamp(1622548800000000),
+            expected
+        );
+        assert_eq!(Precision::Milliseconds.timestamp(1622548800000), expected);
+        assert_eq!(Precision::Seconds.timestamp(1622548800), expected);
+        assert_eq!(Precision::Minutes.timestamp(27042480), expected);
+        assert_eq!(Precision::Hours.timestamp(450708), expected);
+        assert_eq!(Precision::from_query("u"), Some(Precision::Microseconds));
+        assert_eq!(Precision::from_query("d"), None);
+    }
+}
@@ -19,7 +19,7 @@ use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use vector_core::event::{BatchNotifier, LogEvent, Value};
+use vector_core::event::{BatchNotifier, BatchStatus, LogEvent, Value};
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -252,8 +252,10 @@ impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
     }
 }
 
-fn mark_done(consumer: Arc<StreamConsumer<KafkaStatisticsContext>>) -> impl Fn(FinalizerEntry) {
-    move |entry| {
+fn mark_done(
+    consumer: Arc<StreamConsumer<KafkaStatisticsContext>>,
+) -> impl Fn(BatchStatus, FinalizerEntry) {
+    move |_status, entry| {
         // Would like to use `consumer.store_offset` here, but types don't allow it.
         let mut tpl = TopicPartitionList::new();
         tpl.add_partition(&entry.topic, entry.partition)
@@ -0,0 +1,521 @@
+//! Loki source
+//!
+//! Implements Loki's `/loki/api/v1/push` endpoint, so that Loki clients such
+//! as Promtail can push their logs to Vector.
+//!
+//! https://github.com/grafana/loki/blob/master/docs/api.md#post-lokiapiv1push
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::LokiPushParseError,
+    proto::loki as proto,
+    sources::util::{decode, ErrorMessage, HttpSource, HttpSourceAuthConfig},
+    tls::TlsConfig,
+};
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+};
+use warp::http::{HeaderMap, StatusCode};
+
+const SOURCE_NAME: &str = "loki";
+const TENANT_ID_KEY: &str = "tenant_id";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LokiConfig {
+    address: SocketAddr,
+    tls: Option<TlsConfig>,
+    auth: Option<HttpSourceAuthConfig>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<LokiConfig>(SOURCE_NAME)
+}
+
+impl GenerateConfig for LokiConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "0.0.0.0:3100".parse().unwrap(),
+            tls: None,
+            auth: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "loki")]
+impl SourceConfig for LokiConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let source = LokiSource;
+        source.run(
+            self.address,
+            "loki/api/v1/push",
+            true,
+            &self.tls,
+            &self.auth,
+            cx,
+        )
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        SOURCE_NAME
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum DecodeError {
+    #[snafu(display("Could not decode protobuf push request: {}", source))]
+    Protobuf { source: prost::DecodeError },
+    #[snafu(display("Could not decode JSON push request: {}", source))]
+    Json { source: serde_json::Error },
+    #[snafu(display("Invalid stream labels: {:?}", labels))]
+    InvalidLabels { labels: String },
+    #[snafu(display("Invalid entry timestamp: {:?}", timestamp))]
+    InvalidTimestamp { timestamp: String },
+}
+
+type Labels = Vec<(String, String)>;
+
+struct Stream {
+    labels: Labels,
+    entries: Vec<(DateTime<Utc>, String)>,
+}
+
+#[derive(Deserialize)]
+struct JsonPushRequest {
+    streams: Vec<JsonStream>,
+}
+
+#[derive(Deserialize)]
+struct JsonStream {
+    stream: BTreeMap<String, String>,
+    values: Vec<(String, String)>,
+}
+
+#[derive(Clone)]
+struct LokiSource;
+
+impl HttpSource for LokiSource {
+    fn build_events(
+        &self,
+        mut body: Bytes,
+        header_map: HeaderMap,
+        _query_parameters: HashMap<String, String>,
+        _full_path: &str,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        let is_json = header_map
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| value.starts_with("application/json"));
+
+        // Like Loki, anything that isn't JSON is a protobuf push request,
+        // which is snappy compressed without a `Content-Encoding` header.
+        let streams = if is_json {
+            decode_json(&body)
+        } else {
+            let is_decompressed = header_map
+                .get("Content-Encoding")
+                .and_then(|value| value.to_str().ok())
+                == Some("snappy");
+            if !is_decompressed {
+                body = decode(&Some("snappy".to_string()), body)?;
+            }
+            decode_protobuf(body)
+        }
+        .map_err(|error| {
+            emit!(LokiPushParseError { error: &error });
+            ErrorMessage::new(StatusCode::BAD_REQUEST, error.to_string())
+        })?;
+
+        let tenant_id = header_map
+            .get("X-Scope-OrgID")
+            .and_then(|value| value.to_str().ok());
+
+        Ok(to_events(streams, tenant_id))
+    }
+}
+
+fn decode_json(body: &[u8]) -> Result<Vec<Stream>, DecodeError> {
+    let request: JsonPushRequest = serde_json::from_slice(body).context(Json)?;
+
+    request
+        .streams
+        .into_iter()
+        .map(|stream| {
+            let entries = stream
+                .values
+                .into_iter()
+                .map(|(timestamp, line)| {
+                    let nanos = timestamp
+                        .parse::<i64>()
+                        .ok()
+                        .context(InvalidTimestamp { timestamp })?;
+                    Ok((Utc.timestamp_nanos(nanos), line))
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok(Stream {
+                labels: stream.stream.into_iter().collect(),
+                entries,
+            })
+        })
+        .collect()
+}
+
+fn decode_protobuf(body: Bytes) -> Result<Vec<Stream>, DecodeError> {
+    let request = proto::PushRequest::decode(body).context(Protobuf)?;
+
+    request
+        .streams
+        .into_iter()
+        .map(|stream| {
+            let labels = parse_labels(&stream.labels).with_context(|| InvalidLabels {
+                labels: stream.labels.clone(),
+            })?;
+            let entries = stream
+                .entries
+                .into_iter()
+                .map(|entry| {
+                    let timestamp = entry.timestamp.unwrap_or_default();
+                    let timestamp = Utc
+                        .timestamp_opt(timestamp.seconds, timestamp.nanos as u32)
+                        .single()
+                        .with_context(|| InvalidTimestamp {
+                            timestamp: format!("{:?}", timestamp),
+                        })?;
+                    Ok((timestamp, entry.line))
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok(Stream { labels, entries })
+        })
+        .collect()
+}
+
+/// Parses labels in the Prometheus exposition format: `{key="value", ...}`.
+fn parse_labels(labels: &str) -> Option<Labels> {
+    let mut rest = labels
+        .trim()
+        .strip_prefix('{')?
+        .strip_suffix('}')?
+        .trim_start();
+    let mut parsed = Vec::new();
+
+    while !rest.is_empty() {
+        let (name, tail) = rest.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let quoted = tail.trim_start().strip_prefix('"')?;
+        let mut chars = quoted.char_indices();
+        let mut value = String::new();
+        let end = loop {
+            match chars.next()? {
+                (index, '"') => break index,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    'r' => value.push('\r'),
+                    c => value.push(c),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        parsed.push((name.to_owned(), value));
+
+        rest = quoted[end + 1..].trim_start();
+        if let Some(tail) = rest.strip_prefix(',') {
+            rest = tail.trim_start();
+        } else if !rest.is_empty() {
+            return None;
+        }
+    }
+
+    Some(parsed)
+}
+
+fn to_events(streams: Vec<Stream>, tenant_id: Option<&str>) -> Vec<Event> {
+    streams
+        .into_iter()
+        .flat_map(|Stream { labels, entries }| {
+            entries.into_iter().map(move |(timestamp, line)| {
+                let mut log = LogEvent::default();
+                // Labels are flat fields, the fields set by the source take
+                // precedence over them.
+                for (name, value) in &labels {
+                    log.insert_flat(name.clone(), value.clone());
+                }
+                log.insert(log_schema().message_key(), line);
+                log.insert(log_schema().timestamp_key(), timestamp);
+                log.insert(log_schema().source_type_key(), Bytes::from(SOURCE_NAME));
+                if let Some(tenant_id) = tenant_id {
+                    log.insert(TENANT_ID_KEY, tenant_id.to_owned());
+                }
+                Event::from(log)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{next_addr, spawn_collect_n, trace_init, wait_for_tcp},
+        Pipeline,
+    };
+    use futures::Stream;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LokiConfig>();
+    }
+
+    #[test]
+    fn parses_labels() {
+        assert_eq!(parse_labels("{}"), Some(vec![]));
+        assert_eq!(
+            parse_labels(r#"{app="web", path="C:\\\"quoted\"\n",}"#),
+            Some(vec![
+                ("app".to_owned(), "web".to_owned()),
+                ("path".to_owned(), "C:\\\"quoted\"\n".to_owned()),
+            ])
+        );
+        assert_eq!(parse_labels(r#"app="web""#), None);
+        assert_eq!(parse_labels(r#"{app="web" pod="a"}"#), None);
+        assert_eq!(parse_labels(r#"{app="web}"#), None);
+        assert_eq!(parse_labels(r#"{="web"}"#), None);
+    }
+
+    async fn source(
+        status: EventStatus,
+        acknowledgements: bool,
+    ) -> (impl Stream<Item = Event>, SocketAddr) {
+        let (sender, recv) = Pipeline::new_test_finalize(status);
+        let address = next_addr();
+        let mut context = SourceContext::new_test(sender);
+        context.acknowledgements = acknowledgements;
+        tokio::spawn(async move {
+            LokiConfig {
+                address,
+                tls: None,
+                auth: None,
+            }
+            .build(context)
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    async fn send(address: SocketAddr, content_type: &str, body: Vec<u8>) -> u16 {
+        reqwest::Client::new()
+            .post(&format!("http://{}/loki/api/v1/push", address))
+            .header("Content-Type", content_type)
+            .header("X-Scope-OrgID", "tenant")
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    fn assert_event(event: &Event, labels: &[(&str, &str)], line: &str, timestamp: DateTime<Utc>) {
+        let log = event.as_log();
+        for (name, value) in labels {
+            assert_eq!(log[*name], (*value).into());
+        }
+        assert_eq!(log[log_schema().message_key()], line.into());
+        assert_eq!(log[log_schema().timestamp_key()], timestamp.into());
+        assert_eq!(log[log_schema().source_type_key()], "loki".into());
+        assert_eq!(log[TENANT_ID_KEY], "tenant".into());
+    }
+
+    #[tokio::test]
+    async fn receives_json_push() {
+        trace_init();
+        let (rx, address) = source(EventStatus::Delivered, true).await;
+
+        let body = serde_json::json!({
+            "streams": [
+                {
+                    "stream": {"app": "web", "pod": "a"},
+                    "values": [["1500000000", "first"], ["2000000000", "second"]]
+                },
+                {
+                    "stream": {"app": "api"},
+                    "values": [["3", "third"]]
+                }
+            ]
+        });
+
+        let events = spawn_collect_n(
+            async move {
+                assert_eq!(
+                    200,
+                    send(
+                        address,
+                        "application/json",
+                        serde_json::to_vec(&body).unwrap()
+                    )
+                    .await
+                );
+            },
+            rx,
+            3,
+        )
+        .await;
+
+        let labels = [("app", "web"), ("pod", "a")];
+        assert_event(&events[0], &labels, "first", Utc.timestamp(1, 500_000_000));
+        assert_event(&events[1], &labels, "second", Utc.timestamp(2, 0));
+        assert_event(&events[2], &[("app", "api")], "third", Utc.timestamp(0, 3));
+        assert!(!events[2].as_log().contains("pod"));
+    }
+
+    #[tokio::test]
+    async fn receives_protobuf_push() {
+        trace_init();
+        let (rx, address) = source(EventStatus::Delivered, true).await;
+
+        let request = proto::PushRequest {
+            streams: vec![proto::StreamAdapter {
+                labels: r#"{app="web", path="C:\\dir"}"#.into(),
+                entries: vec![proto::EntryAdapter {
+                    timestamp: Some(prost_types::Timestamp {
+                        seconds: 1,
+                        nanos: 500,
+                    }),
+                    line: "first".into(),
+                }],
+            }],
+        };
+        let mut body = Vec::new();
+        request.encode(&mut body).unwrap();
+        let body = snap::raw::Encoder::new().compress_vec(&body).unwrap();
+
+        let events = spawn_collect_n(
+            async move {
+                assert_eq!(200, send(address, "application/x-protobuf", body).await);
+            },
+            rx,
+            1,
+        )
+        .await;
+
+        assert_event(
+            &events[0],
+            &[("app", "web"), ("path", r"C:\dir")],
+            "first",
+            Utc.timestamp(1, 500),
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_push() {
+        trace_init();
+        let (_rx, address) = source(EventStatus::Delivered, true).await;
+
+        assert_eq!(
+            400,
+            send(address, "application/json", b"{\"streams\":1}".to_vec()).await
+        );
+        assert_eq!(
+            400,
+            send(
+                address,
+                "application/json",
+                br#"{"streams":[{"stream":{},"values":[["now","line"]]}]}"#.to_vec()
+            )
+            .await
+        );
+        assert_eq!(
+            400,
+            send(address, "application/x-protobuf", b"not snappy".to_vec()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_delivery_failure() {
+        trace_init();
+        let (rx, address) = source(EventStatus::Failed, true).await;
+
+        let body = br#"{"streams":[{"stream":{"app":"web"},"values":[["1","line"]]}]}"#;
+        spawn_collect_n(
+            async move {
+                assert_eq!(400, send(address, "application/json", body.to_vec()).await);
+            },
+            rx,
+            1,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "sinks-loki")]
+    #[tokio::test]
+    async fn receives_from_loki_sink() {
+        use crate::{
+            config::SinkConfig, sinks::loki::LokiConfig as LokiSinkConfig,
+            sinks::util::test::load_sink,
+        };
+        use futures::stream;
+
+        trace_init();
+        let (rx, address) = source(EventStatus::Delivered, true).await;
+
+        let (sink, cx) = load_sink::<LokiSinkConfig>(&format!(
+            r#"
+            endpoint = "http://{}/"
+            labels = {{app = "web"}}
+            encoding = "text"
+            tenant_id = "tenant"
+        "#,
+            address
+        ))
+        .unwrap();
+        let (sink, _) = sink.build(cx).await.unwrap();
+
+        let timestamp = Utc.timestamp(1_600_000_000, 123);
+        let mut event = Event::from("hello world");
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), timestamp);
+
+        let events = spawn_collect_n(
+            async move {
+                sink.run(stream::iter(vec![event])).await.unwrap();
+            },
+            rx,
+            1,
+        )
+        .await;
+
+        assert_event(&events[0], &[("app", "web")], "hello world", timestamp);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{MqttAckFailed, MqttConnectionError, MqttEventReceived},
+    mqtt::{MqttConfig, MqttQoS},
+    shutdown::ShutdownSignal,
+    sources::util::finalizer::OrderedFinalizer,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{FutureExt, SinkExt};
+use rumqttc::{AsyncClient, Event as MqttEvent, EventLoop, Packet, Publish, QoS};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MqttSourceConfig {
+    #[serde(flatten)]
+    connection: MqttConfig,
+    topics: Vec<String>,
+    #[serde(default)]
+    qos: MqttQoS,
+    #[serde(default = "default_topic_key")]
+    topic_key: String,
+}
+
+fn default_topic_key() -> String {
+    "topic".into()
+}
+
+impl Default for MqttSourceConfig {
+    fn default() -> Self {
+        Self {
+            connection: MqttConfig::default(),
+            topics: vec!["vector/#".into()],
+            qos: MqttQoS::default(),
+            topic_key: default_topic_key(),
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<MqttSourceConfig>("mqtt")
+}
+
+impl_generate_config_from_default!(MqttSourceConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SourceConfig for MqttSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let (client, eventloop) = if cx.acknowledgements {
+            self.connection.build_manual_ack_client()?
+        } else {
+            self.connection.build_client()?
+        };
+
+        Ok(Box::pin(mqtt_source(
+            self.clone(),
+            client,
+            eventloop,
+            cx.shutdown,
+            cx.out,
+            cx.acknowledgements,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "mqtt"
+    }
+}
+
+async fn mqtt_source(
+    config: MqttSourceConfig,
+    client: AsyncClient,
+    mut eventloop: EventLoop,
+    shutdown: ShutdownSignal,
+    mut out: Pipeline,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let shutdown = shutdown.shared();
+    let finalizer = acknowledgements
+        .then(|| OrderedFinalizer::new(shutdown.clone(), mark_done(client.clone())));
+
+    loop {
+        let event = tokio::select! {
+            _ = shutdown.clone() => break,
+            event = eventloop.poll() => event,
+        };
+
+        match event {
+            Ok(MqttEvent::Incoming(Packet::Publish(publish))) => {
+                emit!(MqttEventReceived {
+                    byte_size: publish.payload.len(),
+                });
+
+                match &finalizer {
+                    // Publishes with QoS 0 are never acknowledged.
+                    Some(finalizer) if publish.qos != QoS::AtMostOnce => {
+                        let (batch, receiver) = BatchNotifier::new_with_receiver();
+                        let event = to_event(&config, publish.clone()).with_batch_notifier(&batch);
+                        match out.send(event).await {
+                            // Left unacknowledged, the broker redelivers the
+                            // publish when the session is resumed.
+                            Err(error) => error!(message = "Error sending to sink.", %error),
+                            Ok(_) => finalizer.add(publish, receiver),
+                        }
+                    }
+                    _ => {
+                        if let Err(error) = out.send(to_event(&config, publish)).await {
+                            error!(message = "Error sending to sink.", %error);
+                        }
+                    }
+                }
+            }
+            // A broker that doesn't hold a session for us, either because
+            // `clean_session` is set or because it's our first connection,
+            // needs to be told what to subscribe to on every (re)connection.
+            Ok(MqttEvent::Incoming(Packet::ConnAck(ack))) if !ack.session_present => {
+                for topic in &config.topics {
+                    if let Err(error) = client.try_subscribe(topic, config.qos.into()) {
+                        error!(message = "Failed to subscribe to topic.", %topic, %error);
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(error) => {
+                emit!(MqttConnectionError { error });
+                // Polling again reconnects, so back off before doing so.
+                tokio::select! {
+                    _ = shutdown.clone() => break,
+                    _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+                }
+            }
+        }
+    }
+
+    let _ = client.try_disconnect();
+    Ok(())
+}
+
+/// Acknowledges publishes once their events have been delivered. MQTT has no
+/// negative acknowledgement, so publishes that errored are left for the broker
+/// to redeliver when the session is resumed, while those rejected by a sink
+/// are acknowledged and dropped.
+fn mark_done(client: AsyncClient) -> impl Fn(BatchStatus, Publish) {
+    move |status, publish| {
+        if status != BatchStatus::Errored {
+            if let Err(error) = client.try_ack(&publish) {
+                emit!(MqttAckFailed { error });
+            }
+        }
+    }
+}
+
+fn to_event(config: &MqttSourceConfig, publish: Publish) -> Event {
+    let mut event = Event::new_empty_log();
+    let log = event.as_mut_log();
+
+    log.insert(log_schema().message_key(), Value::from(publish.payload));
+    log.insert(log_schema().timestamp_key(), Utc::now());
+    log.insert(&config.topic_key, Value::from(publish.topic));
+
+    // Add source type
+    log.insert(log_schema().source_type_key(), Bytes::from("mqtt"));
+
+    event
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSourceConfig>();
+    }
+
+    #[test]
+    fn parse_config() {
+        let config: MqttSourceConfig = toml::from_str(
+            r#"
+            host = "broker.example.com"
+            port = 8883
+            user = "vector"
+            password = "secret"
+            client_id = "vector-factory"
+            clean_session = false
+            topics = ["factory/+/telemetry", "factory/alerts"]
+            qos = 2
+            tls.enabled = true
+            tls.ca_file = "/path/to/ca.crt"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.connection.port, 8883);
+        assert!(!config.connection.clean_session);
+        assert_eq!(config.qos, MqttQoS::ExactlyOnce);
+        assert_eq!(config.topic_key, "topic");
+        assert_eq!(config.topics.len(), 2);
+    }
+
+    #[test]
+    fn records_topic() {
+        let config = MqttSourceConfig::default();
+        let publish = Publish::new("factory/1/telemetry", rumqttc::QoS::AtLeastOnce, "hello");
+
+        let event = to_event(&config, publish);
+        let log = event.as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log["topic"], "factory/1/telemetry".into());
+        assert_eq!(log[log_schema().source_type_key()], "mqtt".into());
+    }
+}
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_test {
+    use super::*;
+    use crate::test_util::{collect_n, random_string, trace_init};
+    use rumqttc::QoS;
+
+    #[tokio::test]
+    async fn mqtt_happy() {
+        trace_init();
+
+        let topic = format!("test/{}", random_string(10));
+        let config = MqttSourceConfig {
+            connection: MqttConfig {
+                client_id: Some(format!("vector-source-{}", random_string(10))),
+                ..MqttConfig::default()
+            },
+            topics: vec![format!("{}/#", topic)],
+            ..MqttSourceConfig::default()
+        };
+
+        let (client, eventloop) = config.connection.build_client().unwrap();
+        let (tx, rx) = Pipeline::new_test();
+        tokio::spawn(mqtt_source(
+            config,
+            client,
+            eventloop,
+            ShutdownSignal::noop(),
+            tx,
+            false,
+        ));
+        // Wait for the subscription to be in place.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let publisher = MqttConfig {
+            client_id: Some(format!("vector-publisher-{}", random_string(10))),
+            ..MqttConfig::default()
+        };
+        let (client, mut eventloop) = publisher.build_client().unwrap();
+        tokio::spawn(async move { while eventloop.poll().await.is_ok() {} });
+        let device = format!("{}/device-1", topic);
+        client
+            .publish(&device, QoS::AtLeastOnce, false, "my message")
+            .await
+            .unwrap();
+
+        let events = collect_n(rx, 1).await;
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "my message".into()
+        );
+        assert_eq!(events[0].as_log()["topic"], device.into());
+    }
+}
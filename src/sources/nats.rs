@@ -1,17 +1,21 @@
+use super::util::finalizer::OrderedFinalizer;
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
     },
-    event::{Event, Value},
-    internal_events::NatsEventReceived,
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{NatsAckFailed, NatsEventReceived},
+    nats::{to_nats_options, NatsAuthConfig},
     shutdown::ShutdownSignal,
+    tls::TlsOptions,
     Pipeline,
 };
 use bytes::Bytes;
 use chrono::Utc;
-use futures::{pin_mut, stream, SinkExt, Stream, StreamExt};
+use futures::{pin_mut, stream, FutureExt, SinkExt, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
+use std::time::Duration;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -19,6 +23,8 @@ enum BuildError {
     NatsCreateError { source: std::io::Error },
     #[snafu(display("Could not subscribe to Nats topics: {}", source))]
     NatsSubscribeError { source: std::io::Error },
+    #[snafu(display("Could not create JetStream consumer: {}", description))]
+    JetStreamConsumerError { description: String },
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -29,6 +35,31 @@ pub struct NatsSourceConfig {
     connection_name: String,
     subject: String,
     queue: Option<String>,
+    tls: Option<TlsOptions>,
+    auth: Option<NatsAuthConfig>,
+    jetstream: Option<NatsJetStreamConfig>,
+}
+
+/// Consumes from a JetStream stream through a durable push consumer, so
+/// messages are redelivered until acknowledged. With a `queue`, it's the
+/// consumer's deliver group, sharing the messages between its subscribers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct NatsJetStreamConfig {
+    stream: String,
+    durable_name: String,
+    #[serde(default = "default_ack_wait_secs")]
+    ack_wait_secs: u64,
+    #[serde(default = "default_max_ack_pending")]
+    max_ack_pending: i64,
+}
+
+fn default_ack_wait_secs() -> u64 {
+    30
+}
+
+fn default_max_ack_pending() -> i64 {
+    1000
 }
 
 inventory::submit! {
@@ -56,8 +87,10 @@ impl SourceConfig for NatsSourceConfig {
         Ok(Box::pin(nats_source(
             connection,
             subscription,
+            self.jetstream.is_some(),
             cx.shutdown,
             cx.out,
+            cx.acknowledgements,
         )))
     }
 
@@ -71,30 +104,14 @@ impl SourceConfig for NatsSourceConfig {
 }
 
 impl NatsSourceConfig {
-    fn to_nats_options(&self) -> async_nats::Options {
-        // Set reconnect_buffer_size on the nats client to 0 bytes so that the
-        // client doesn't buffer internally (to avoid message loss).
-        async_nats::Options::new()
-            .with_name(&self.connection_name)
-            .reconnect_buffer_size(0)
-    }
-
     async fn connect(&self) -> crate::Result<async_nats::Connection> {
-        self.to_nats_options()
+        to_nats_options(&self.connection_name, self.auth.as_ref(), self.tls.as_ref())?
             .connect(&self.url)
             .await
             .map_err(|e| e.into())
     }
 }
 
-impl From<NatsSourceConfig> for async_nats::Options {
-    fn from(config: NatsSourceConfig) -> Self {
-        async_nats::Options::new()
-            .with_name(&config.connection_name)
-            .reconnect_buffer_size(0)
-    }
-}
-
 fn get_subscription_stream(
     subscription: async_nats::Subscription,
 ) -> impl Stream<Item = async_nats::Message> {
@@ -104,12 +121,16 @@ fn get_subscription_stream(
 }
 
 async fn nats_source(
-    // Take ownership of the connection so it doesn't get dropped.
-    _connection: async_nats::Connection,
+    connection: async_nats::Connection,
     subscription: async_nats::Subscription,
+    jetstream: bool,
     shutdown: ShutdownSignal,
     mut out: Pipeline,
+    acknowledgements: bool,
 ) -> Result<(), ()> {
+    let shutdown = shutdown.shared();
+    let finalizer = (jetstream && acknowledgements)
+        .then(|| OrderedFinalizer::new(shutdown.clone(), mark_done(connection.clone())));
     let stream = get_subscription_stream(subscription).take_until(shutdown);
     pin_mut!(stream);
     while let Some(msg) = stream.next().await {
@@ -130,21 +151,64 @@ async fn nats_source(
         // Add source type
         log.insert(log_schema().source_type_key(), Bytes::from("nats"));
 
-        if let Err(error) = out.send(event).await {
-            error!(message = "Error sending to sink.", %error)
+        // JetStream deliveries are acknowledged by replying to them.
+        let reply = msg.reply.filter(|_| jetstream);
+        match (&finalizer, reply) {
+            (Some(finalizer), Some(reply)) => {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                let event = event.with_batch_notifier(&batch);
+                match out.send(event).await {
+                    Err(error) => error!(message = "Error sending to sink.", %error),
+                    Ok(_) => finalizer.add(reply, receiver),
+                }
+            }
+            (_, reply) => {
+                if let Err(error) = out.send(event).await {
+                    error!(message = "Error sending to sink.", %error)
+                }
+                if let Some(reply) = reply {
+                    acknowledge(connection.clone(), reply, BatchStatus::Delivered);
+                }
+            }
         }
     }
     Ok(())
 }
 
+/// Acknowledges JetStream deliveries once their events have been delivered.
+/// Deliveries that errored are redelivered, while those rejected by a sink
+/// are terminated so they aren't redelivered.
+fn mark_done(connection: async_nats::Connection) -> impl Fn(BatchStatus, String) {
+    move |status, reply| acknowledge(connection.clone(), reply, status)
+}
+
+fn acknowledge(connection: async_nats::Connection, reply: String, status: BatchStatus) {
+    let ack = match status {
+        BatchStatus::Delivered => "+ACK",
+        BatchStatus::Errored => "-NAK",
+        BatchStatus::Failed => "+TERM",
+    };
+    tokio::spawn(async move {
+        if let Err(error) = connection.publish(&reply, ack).await {
+            emit!(NatsAckFailed { error });
+        }
+    });
+}
+
 async fn create_subscription(
     config: &NatsSourceConfig,
 ) -> crate::Result<(async_nats::Connection, async_nats::Subscription)> {
     let nc = config.connect().await?;
 
+    let subject = match &config.jetstream {
+        Some(jetstream) => {
+            create_consumer(&nc, &config.subject, config.queue.as_deref(), jetstream).await?
+        }
+        None => config.subject.clone(),
+    };
     let subscription = match &config.queue {
-        None => nc.subscribe(&config.subject).await,
-        Some(queue) => nc.queue_subscribe(&config.subject, queue).await,
+        None => nc.subscribe(&subject).await,
+        Some(queue) => nc.queue_subscribe(&subject, queue).await,
     };
 
     let subscription = subscription?;
@@ -152,6 +216,79 @@ async fn create_subscription(
     Ok((nc, subscription))
 }
 
+#[derive(Serialize)]
+struct CreateConsumerRequest<'a> {
+    stream_name: &'a str,
+    config: ConsumerConfig<'a>,
+}
+
+#[derive(Serialize)]
+struct ConsumerConfig<'a> {
+    durable_name: &'a str,
+    deliver_subject: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deliver_group: Option<&'a str>,
+    deliver_policy: &'static str,
+    ack_policy: &'static str,
+    /// In nanoseconds.
+    ack_wait: u64,
+    max_ack_pending: i64,
+    filter_subject: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    error: Option<ApiError>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    description: Option<String>,
+}
+
+/// Creates the durable push consumer, or binds to it if it already exists
+/// with the same configuration, returning the subject it delivers to.
+async fn create_consumer(
+    nc: &async_nats::Connection,
+    subject: &str,
+    deliver_group: Option<&str>,
+    config: &NatsJetStreamConfig,
+) -> crate::Result<String> {
+    let deliver_subject = format!("_VECTOR.{}.{}", config.stream, config.durable_name);
+    let request = CreateConsumerRequest {
+        stream_name: &config.stream,
+        config: ConsumerConfig {
+            durable_name: &config.durable_name,
+            deliver_subject: &deliver_subject,
+            deliver_group,
+            deliver_policy: "all",
+            ack_policy: "explicit",
+            ack_wait: Duration::from_secs(config.ack_wait_secs).as_nanos() as u64,
+            max_ack_pending: config.max_ack_pending,
+            filter_subject: subject,
+        },
+    };
+
+    let response = nc
+        .request(
+            &format!(
+                "$JS.API.CONSUMER.DURABLE.CREATE.{}.{}",
+                config.stream, config.durable_name
+            ),
+            serde_json::to_vec(&request)?,
+        )
+        .await?;
+    let response: ApiResponse = serde_json::from_slice(&response.data)?;
+    if let Some(error) = response.error {
+        return Err(BuildError::JetStreamConsumerError {
+            description: error.description.unwrap_or_else(|| "unknown error".into()),
+        }
+        .into());
+    }
+
+    Ok(deliver_subject)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,13 +297,56 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<NatsSourceConfig>();
     }
+
+    #[test]
+    fn parse_jetstream_config() {
+        let config: NatsSourceConfig = toml::from_str(
+            r#"
+            url = "nats://127.0.0.1:4222"
+            connection_name = "vector"
+            subject = "orders.>"
+            auth.strategy = "token"
+            auth.token = "secret"
+            tls.ca_file = "/path/to/ca.crt"
+            jetstream.stream = "ORDERS"
+            jetstream.durable_name = "vector"
+            "#,
+        )
+        .unwrap();
+
+        let jetstream = config.jetstream.unwrap();
+        assert_eq!(jetstream.stream, "ORDERS");
+        assert_eq!(jetstream.ack_wait_secs, 30);
+        assert_eq!(jetstream.max_ack_pending, 1000);
+    }
+
+    #[test]
+    fn consumer_request_includes_deliver_group() {
+        let request = |deliver_group| {
+            serde_json::to_value(&ConsumerConfig {
+                durable_name: "vector",
+                deliver_subject: "_VECTOR.ORDERS.vector",
+                deliver_group,
+                deliver_policy: "all",
+                ack_policy: "explicit",
+                ack_wait: 0,
+                max_ack_pending: 1000,
+                filter_subject: "orders.>",
+            })
+            .unwrap()
+        };
+
+        assert_eq!(request(Some("vector"))["deliver_group"], "vector");
+        assert!(request(None).get("deliver_group").is_none());
+    }
 }
 
 #[cfg(feature = "nats-integration-tests")]
 #[cfg(test)]
 mod integration_tests {
     use super::*;
-    use crate::test_util::{collect_n, random_string};
+    use crate::test_util::{collect_n, random_string, trace_init};
+    use vector_core::event::EventStatus;
 
     #[tokio::test]
     async fn nats_happy() {
@@ -176,14 +356,21 @@ mod integration_tests {
             connection_name: "".to_owned(),
             subject: subject.clone(),
             url: "nats://127.0.0.1:4222".to_owned(),
-            queue: None,
+            ..NatsSourceConfig::default()
         };
 
         let (nc, sub) = create_subscription(&conf).await.unwrap();
         let nc_pub = nc.clone();
 
         let (tx, rx) = Pipeline::new_test();
-        tokio::spawn(nats_source(nc, sub, ShutdownSignal::noop(), tx));
+        tokio::spawn(nats_source(
+            nc,
+            sub,
+            false,
+            ShutdownSignal::noop(),
+            tx,
+            false,
+        ));
         let msg = "my message";
         nc_pub.publish(&subject, msg).await.unwrap();
 
@@ -191,4 +378,49 @@ mod integration_tests {
         println!("Received event  {:?}", events[0].as_log());
         assert_eq!(events[0].as_log()[log_schema().message_key()], msg.into());
     }
+
+    #[tokio::test]
+    async fn nats_jetstream_redelivers_errored() {
+        trace_init();
+
+        let stream = format!("TEST{}", random_string(10));
+        let subject = format!("test-{}", random_string(10));
+        let conf = NatsSourceConfig {
+            connection_name: "".to_owned(),
+            subject: subject.clone(),
+            url: "nats://127.0.0.1:4222".to_owned(),
+            jetstream: Some(NatsJetStreamConfig {
+                stream: stream.clone(),
+                durable_name: "vector".to_owned(),
+                ack_wait_secs: default_ack_wait_secs(),
+                max_ack_pending: default_max_ack_pending(),
+            }),
+            ..NatsSourceConfig::default()
+        };
+
+        let nc_pub = conf.connect().await.unwrap();
+        let stream_config = serde_json::json!({ "name": stream, "subjects": [subject] });
+        nc_pub
+            .request(
+                &format!("$JS.API.STREAM.CREATE.{}", stream),
+                serde_json::to_vec(&stream_config).unwrap(),
+            )
+            .await
+            .unwrap();
+        nc_pub.publish(&subject, "my message").await.unwrap();
+
+        let (nc, sub) = create_subscription(&conf).await.unwrap();
+        let (tx, rx) = Pipeline::new_test_finalize(EventStatus::Errored);
+        tokio::spawn(nats_source(nc, sub, true, ShutdownSignal::noop(), tx, true));
+
+        // A negative acknowledgement makes the server redeliver the message
+        // straight away.
+        let events = collect_n(rx, 2).await;
+        for event in events {
+            assert_eq!(
+                event.as_log()[log_schema().message_key()],
+                "my message".into()
+            );
+        }
+    }
 }
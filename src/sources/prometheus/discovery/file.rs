@@ -0,0 +1,244 @@
+//! File based discovery, reading the targets from JSON or YAML files in the
+//! Prometheus `file_sd_configs` format.
+
+use super::{Discovery, Target, TargetsProvider};
+use crate::{event::metric::MetricTags, internal_events::PrometheusFileDiscoveryError};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::sync::watch;
+
+const SCHEME_LABEL: &str = "__scheme__";
+const METRICS_PATH_LABEL: &str = "__metrics_path__";
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FileSdConfig {
+    files: Vec<String>,
+    #[serde(default = "default_refresh_interval_secs")]
+    refresh_interval_secs: u64,
+}
+
+fn default_refresh_interval_secs() -> u64 {
+    30
+}
+
+/// A group of targets sharing the same labels, as found in the files.
+#[derive(Deserialize, Debug)]
+struct TargetGroup {
+    targets: Vec<String>,
+    #[serde(default)]
+    labels: MetricTags,
+}
+
+impl FileSdConfig {
+    pub(in crate::sources::prometheus) fn build(
+        &self,
+        scheme: &str,
+        metrics_path: &str,
+    ) -> crate::Result<Discovery> {
+        // Fail early on invalid patterns.
+        for pattern in &self.files {
+            glob::Pattern::new(pattern)?;
+        }
+
+        let mut files = Files {
+            patterns: self.files.clone(),
+            scheme: scheme.into(),
+            metrics_path: metrics_path.into(),
+            read: HashMap::new(),
+        };
+
+        let (sender, receiver) = watch::channel(files.targets());
+        let refresh_interval = Duration::from_secs(self.refresh_interval_secs);
+        let task = async move {
+            let mut interval = tokio::time::interval(refresh_interval);
+            // The first tick completes immediately, and the files were just read.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let targets = files.targets();
+                if *sender.borrow() != targets && sender.send(targets).is_err() {
+                    // The source is gone.
+                    break;
+                }
+            }
+        };
+
+        Ok(Discovery {
+            provider: Box::new(receiver),
+            task: task.boxed(),
+        })
+    }
+}
+
+impl TargetsProvider for watch::Receiver<Vec<Target>> {
+    fn targets(&self) -> Vec<Target> {
+        self.borrow().clone()
+    }
+}
+
+struct Files {
+    patterns: Vec<String>,
+    scheme: String,
+    metrics_path: String,
+    /// The targets of each file, with the modification time they were read at.
+    read: HashMap<PathBuf, (Option<SystemTime>, Vec<Target>)>,
+}
+
+impl Files {
+    /// The targets of all the matching files. Files are only read again once
+    /// modified, and the targets last read are kept when a file is invalid.
+    fn targets(&mut self) -> Vec<Target> {
+        let paths = self
+            .patterns
+            .iter()
+            .filter_map(|pattern| glob::glob(pattern).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+
+        // Forget the files that are gone.
+        self.read.retain(|path, _| paths.contains(path));
+
+        for path in paths {
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            if modified.is_some() && self.read.get(&path).map(|(read, _)| *read) == Some(modified) {
+                continue;
+            }
+
+            match read_target_groups(&path) {
+                Ok(groups) => {
+                    let targets = groups
+                        .into_iter()
+                        .flat_map(|group| self.group_targets(group))
+                        .collect();
+                    self.read.insert(path, (modified, targets));
+                }
+                Err(error) => emit!(PrometheusFileDiscoveryError { path: &path, error }),
+            }
+        }
+
+        let mut targets = self
+            .read
+            .values()
+            .flat_map(|(_, targets)| targets.iter().cloned())
+            .collect::<Vec<_>>();
+        // Keep the targets in a stable order to detect changes.
+        targets.sort_by(|a, b| a.url.to_string().cmp(&b.url.to_string()));
+        targets
+    }
+
+    fn group_targets(&self, group: TargetGroup) -> Vec<Target> {
+        let TargetGroup { targets, labels } = group;
+        let scheme = labels.get(SCHEME_LABEL).unwrap_or(&self.scheme);
+        let metrics_path = labels.get(METRICS_PATH_LABEL).unwrap_or(&self.metrics_path);
+        // Like Prometheus, labels starting with `__` are for internal use only.
+        let tags = labels
+            .iter()
+            .filter(|(name, _)| !name.starts_with("__"))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect::<MetricTags>();
+
+        targets
+            .iter()
+            .filter_map(|address| Target::new(scheme, address, metrics_path, tags.clone()))
+            .collect()
+    }
+}
+
+fn read_target_groups(path: &Path) -> crate::Result<Vec<TargetGroup>> {
+    let content = std::fs::read(path)?;
+    let groups = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("yml") | Some("yaml") => serde_yaml::from_slice(&content)?,
+        _ => serde_json::from_slice(&content)?,
+    };
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use shared::btreemap;
+
+    fn files(patterns: Vec<String>) -> Files {
+        Files {
+            patterns,
+            scheme: "http".into(),
+            metrics_path: "/metrics".into(),
+            read: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn reads_json_and_yaml_files() {
+        let dir = temp_dir();
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(
+            dir.join("api.json"),
+            r#"[{"targets": ["10.0.0.1:9090", "10.0.0.2:9090"], "labels": {"job": "api"}}]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("db.yml"),
+            r#"
+- targets: ["10.0.0.3:9187"]
+  labels:
+    job: db
+    __scheme__: https
+    __metrics_path__: /probe
+"#,
+        )
+        .unwrap();
+
+        let mut files = files(vec![
+            dir.join("*.json").to_str().unwrap().into(),
+            dir.join("*.yml").to_str().unwrap().into(),
+        ]);
+
+        assert_eq!(
+            files.targets(),
+            vec![
+                Target {
+                    url: "http://10.0.0.1:9090/metrics".parse().unwrap(),
+                    tags: btreemap! {"instance" => "10.0.0.1:9090", "job" => "api"},
+                },
+                Target {
+                    url: "http://10.0.0.2:9090/metrics".parse().unwrap(),
+                    tags: btreemap! {"instance" => "10.0.0.2:9090", "job" => "api"},
+                },
+                Target {
+                    url: "https://10.0.0.3:9187/probe".parse().unwrap(),
+                    tags: btreemap! {"instance" => "10.0.0.3:9187", "job" => "db"},
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_targets_of_invalid_files() {
+        let dir = temp_dir();
+        std::fs::create_dir(&dir).unwrap();
+        let path = dir.join("targets.json");
+        std::fs::write(&path, r#"[{"targets": ["10.0.0.1:9090"]}]"#).unwrap();
+
+        let mut files = files(vec![path.to_str().unwrap().into()]);
+        let targets = files.targets();
+        assert_eq!(targets.len(), 1);
+
+        std::fs::write(&path, "[{").unwrap();
+        // Make sure the file is read again.
+        files.read.get_mut(&path).unwrap().0 = None;
+        assert_eq!(files.targets(), targets);
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(files.targets(), vec![]);
+    }
+}
@@ -0,0 +1,514 @@
+//! Kubernetes discovery, watching the pods, services or endpoints through the
+//! Kubernetes API.
+
+use super::{sanitize_label_name, Discovery, Target, TargetsProvider};
+use crate::{
+    config::ProxyConfig,
+    event::metric::MetricTags,
+    kubernetes::{self as k8s, hash_value::HashKey, watch_request_builder::WatchRequestBuilder},
+};
+use evmap::ReadHandle;
+use futures::FutureExt;
+use k8s_openapi::{
+    api::core::v1::{Endpoints, Pod, Service},
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+    Metadata,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KubernetesSdConfig {
+    role: KubernetesRole,
+    #[serde(default)]
+    namespaces: Vec<String>,
+    label_selector: Option<String>,
+    field_selector: Option<String>,
+    kube_config_file: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+enum KubernetesRole {
+    Pod,
+    Service,
+    Endpoints,
+}
+
+impl KubernetesSdConfig {
+    pub(in crate::sources::prometheus) fn build(
+        &self,
+        scheme: &str,
+        metrics_path: &str,
+        proxy: &ProxyConfig,
+    ) -> crate::Result<Discovery> {
+        let config = match &self.kube_config_file {
+            Some(kube_config_file) => k8s::client::config::Config::kubeconfig(kube_config_file)?,
+            None => k8s::client::config::Config::in_cluster()?,
+        };
+        let client = k8s::client::Client::new(config, proxy)?;
+
+        Ok(match self.role {
+            KubernetesRole::Pod => self.discover(
+                client,
+                Pod::watch_pod_for_all_namespaces,
+                pod_targets,
+                scheme,
+                metrics_path,
+            ),
+            KubernetesRole::Service => self.discover(
+                client,
+                Service::watch_service_for_all_namespaces,
+                service_targets,
+                scheme,
+                metrics_path,
+            ),
+            KubernetesRole::Endpoints => self.discover(
+                client,
+                Endpoints::watch_endpoints_for_all_namespaces,
+                endpoints_targets,
+                scheme,
+                metrics_path,
+            ),
+        })
+    }
+
+    fn discover<B>(
+        &self,
+        client: k8s::client::Client,
+        request_builder: B,
+        targets: fn(&B::Object) -> Vec<(String, MetricTags)>,
+        scheme: &str,
+        metrics_path: &str,
+    ) -> Discovery
+    where
+        B: WatchRequestBuilder + Send + Sync + 'static,
+        B::Object: Metadata<Ty = ObjectMeta> + Send + Sync + Unpin + 'static,
+    {
+        let watcher = k8s::api_watcher::ApiWatcher::new(client, request_builder);
+        let watcher = k8s::instrumenting_watcher::InstrumentingWatcher::new(watcher);
+        let (state_reader, state_writer) = evmap::new();
+        let state_writer = k8s::state::evmap::Writer::new(
+            state_writer,
+            Some(Duration::from_millis(10)),
+            HashKey::Uid,
+        );
+        let state_writer = k8s::state::instrumenting::Writer::new(state_writer);
+
+        let mut reflector = k8s::reflector::Reflector::new(
+            watcher,
+            state_writer,
+            self.field_selector.clone(),
+            self.label_selector.clone(),
+            Duration::from_secs(1),
+        );
+        let task = async move {
+            if let Err(error) = reflector.run().await {
+                error!(message = "Kubernetes discovery exited with an error.", %error);
+            }
+        };
+
+        Discovery {
+            provider: Box::new(KubernetesTargets {
+                state_reader,
+                namespaces: self.namespaces.clone(),
+                scheme: scheme.into(),
+                metrics_path: metrics_path.into(),
+                targets,
+            }),
+            task: task.boxed(),
+        }
+    }
+}
+
+struct KubernetesTargets<T>
+where
+    T: Metadata<Ty = ObjectMeta>,
+{
+    state_reader: ReadHandle<String, k8s::state::evmap::Value<T>>,
+    namespaces: Vec<String>,
+    scheme: String,
+    metrics_path: String,
+    targets: fn(&T) -> Vec<(String, MetricTags)>,
+}
+
+impl<T> TargetsProvider for KubernetesTargets<T>
+where
+    T: Metadata<Ty = ObjectMeta> + Send + Sync,
+{
+    fn targets(&self) -> Vec<Target> {
+        let read_ref = match self.state_reader.read() {
+            Some(read_ref) => read_ref,
+            None => {
+                // The state is not initialized yet.
+                return Vec::new();
+            }
+        };
+
+        let mut targets = read_ref
+            .into_iter()
+            .filter_map(|(_, values)| values.get_one())
+            .map(|value| &***value)
+            .filter(|object| {
+                self.namespaces.is_empty()
+                    || object
+                        .metadata()
+                        .namespace
+                        .as_ref()
+                        .map_or(false, |namespace| self.namespaces.contains(namespace))
+            })
+            .flat_map(|object| (self.targets)(object))
+            .filter_map(|(address, tags)| {
+                Target::new(&self.scheme, &address, &self.metrics_path, tags)
+            })
+            .collect::<Vec<_>>();
+        // Keep the targets in a stable order.
+        targets.sort_by(|a, b| a.url.to_string().cmp(&b.url.to_string()));
+        targets
+    }
+}
+
+/// The tags shared by all the targets of an object.
+fn metadata_tags(metadata: &ObjectMeta, role: &str) -> MetricTags {
+    let mut tags = MetricTags::new();
+    if let Some(namespace) = &metadata.namespace {
+        tags.insert("kubernetes_namespace".into(), namespace.clone());
+    }
+    if let Some(name) = &metadata.name {
+        tags.insert(format!("kubernetes_{}_name", role), name.clone());
+    }
+    for (name, value) in metadata.labels.iter().flatten() {
+        tags.insert(
+            format!("kubernetes_{}_label_{}", role, sanitize_label_name(name)),
+            value.clone(),
+        );
+    }
+    tags
+}
+
+/// One target per TCP port declared by the containers of running pods.
+fn pod_targets(pod: &Pod) -> Vec<(String, MetricTags)> {
+    let status = match &pod.status {
+        Some(status) => status,
+        None => return Vec::new(),
+    };
+    let ip = match &status.pod_ip {
+        Some(ip) => ip,
+        None => return Vec::new(),
+    };
+    if matches!(status.phase.as_deref(), Some("Succeeded") | Some("Failed")) {
+        return Vec::new();
+    }
+
+    let mut tags = metadata_tags(&pod.metadata, "pod");
+    tags.insert("kubernetes_pod_ip".into(), ip.clone());
+    let spec = match &pod.spec {
+        Some(spec) => spec,
+        None => return Vec::new(),
+    };
+    if let Some(node_name) = &spec.node_name {
+        tags.insert("kubernetes_pod_node_name".into(), node_name.clone());
+    }
+
+    spec.containers
+        .iter()
+        .flat_map(|container| {
+            container
+                .ports
+                .iter()
+                .flatten()
+                .filter(|port| matches!(port.protocol.as_deref(), None | Some("TCP")))
+                .map(move |port| (container, port))
+        })
+        .map(|(container, port)| {
+            let mut tags = tags.clone();
+            tags.insert(
+                "kubernetes_pod_container_name".into(),
+                container.name.clone(),
+            );
+            tags.insert(
+                "kubernetes_pod_container_port_number".into(),
+                port.container_port.to_string(),
+            );
+            if let Some(name) = &port.name {
+                tags.insert("kubernetes_pod_container_port_name".into(), name.clone());
+            }
+            (host_port(ip, port.container_port), tags)
+        })
+        .collect()
+}
+
+/// The `host:port` of a pod or endpoint IP, with IPv6 addresses bracketed.
+fn host_port(ip: &str, port: i32) -> String {
+    match ip.parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::new(ip, port as u16).to_string(),
+        Err(_) => format!("{}:{}", ip, port),
+    }
+}
+
+/// One target per service port, at the DNS name of the service.
+fn service_targets(service: &Service) -> Vec<(String, MetricTags)> {
+    let (name, namespace) = match (&service.metadata.name, &service.metadata.namespace) {
+        (Some(name), Some(namespace)) => (name, namespace),
+        _ => return Vec::new(),
+    };
+    let tags = metadata_tags(&service.metadata, "service");
+
+    service
+        .spec
+        .iter()
+        .flat_map(|spec| spec.ports.iter().flatten())
+        .filter(|port| matches!(port.protocol.as_deref(), None | Some("TCP")))
+        .map(|port| {
+            let mut tags = tags.clone();
+            if let Some(port_name) = &port.name {
+                tags.insert("kubernetes_service_port_name".into(), port_name.clone());
+            }
+            (format!("{}.{}.svc:{}", name, namespace, port.port), tags)
+        })
+        .collect()
+}
+
+/// One target per port of each ready address of the endpoints.
+fn endpoints_targets(endpoints: &Endpoints) -> Vec<(String, MetricTags)> {
+    let tags = metadata_tags(&endpoints.metadata, "endpoints");
+
+    endpoints
+        .subsets
+        .iter()
+        .flatten()
+        .flat_map(|subset| {
+            let ports = subset.ports.iter().flatten();
+            subset
+                .addresses
+                .iter()
+                .flatten()
+                .flat_map(move |address| ports.clone().map(move |port| (address, port)))
+        })
+        .filter(|(_, port)| matches!(port.protocol.as_deref(), None | Some("TCP")))
+        .map(|(address, port)| {
+            let mut tags = tags.clone();
+            if let Some(port_name) = &port.name {
+                tags.insert("kubernetes_endpoint_port_name".into(), port_name.clone());
+            }
+            if let Some(node_name) = &address.node_name {
+                tags.insert("kubernetes_endpoint_node_name".into(), node_name.clone());
+            }
+            if let Some(hostname) = &address.hostname {
+                tags.insert("kubernetes_endpoint_hostname".into(), hostname.clone());
+            }
+            if let Some(target) = &address.target_ref {
+                if let (Some("Pod"), Some(pod_name)) = (target.kind.as_deref(), &target.name) {
+                    tags.insert("kubernetes_pod_name".into(), pod_name.clone());
+                }
+            }
+            (host_port(&address.ip, port.port), tags)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{
+        Container, ContainerPort, EndpointAddress, EndpointPort, EndpointSubset, ObjectReference,
+        PodSpec, PodStatus, ServicePort, ServiceSpec,
+    };
+    use shared::btreemap;
+
+    fn metadata(name: &str) -> ObjectMeta {
+        ObjectMeta {
+            name: Some(name.into()),
+            namespace: Some("default".into()),
+            labels: Some(btreemap! {"app.kubernetes.io/name" => "api"}),
+            ..ObjectMeta::default()
+        }
+    }
+
+    #[test]
+    fn pods() {
+        let pod = Pod {
+            metadata: metadata("api-1"),
+            spec: Some(PodSpec {
+                containers: vec![
+                    Container {
+                        name: "api".into(),
+                        ports: Some(vec![
+                            ContainerPort {
+                                container_port: 9090,
+                                name: Some("metrics".into()),
+                                ..ContainerPort::default()
+                            },
+                            ContainerPort {
+                                container_port: 53,
+                                protocol: Some("UDP".into()),
+                                ..ContainerPort::default()
+                            },
+                        ]),
+                        ..Container::default()
+                    },
+                    Container {
+                        name: "sidecar".into(),
+                        ..Container::default()
+                    },
+                ],
+                node_name: Some("node-1".into()),
+                ..PodSpec::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".into()),
+                pod_ip: Some("10.0.0.1".into()),
+                ..PodStatus::default()
+            }),
+        };
+
+        assert_eq!(
+            pod_targets(&pod),
+            vec![(
+                "10.0.0.1:9090".into(),
+                btreemap! {
+                    "kubernetes_namespace" => "default",
+                    "kubernetes_pod_container_name" => "api",
+                    "kubernetes_pod_container_port_name" => "metrics",
+                    "kubernetes_pod_container_port_number" => "9090",
+                    "kubernetes_pod_ip" => "10.0.0.1",
+                    "kubernetes_pod_label_app_kubernetes_io_name" => "api",
+                    "kubernetes_pod_name" => "api-1",
+                    "kubernetes_pod_node_name" => "node-1",
+                }
+            )]
+        );
+
+        let completed = Pod {
+            status: Some(PodStatus {
+                phase: Some("Succeeded".into()),
+                pod_ip: Some("10.0.0.1".into()),
+                ..PodStatus::default()
+            }),
+            ..pod
+        };
+        assert_eq!(pod_targets(&completed), vec![]);
+    }
+
+    #[test]
+    fn ipv6_addresses() {
+        let pod = Pod {
+            metadata: metadata("api-1"),
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "api".into(),
+                    ports: Some(vec![ContainerPort {
+                        container_port: 9090,
+                        ..ContainerPort::default()
+                    }]),
+                    ..Container::default()
+                }],
+                ..PodSpec::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".into()),
+                pod_ip: Some("fd00::1".into()),
+                ..PodStatus::default()
+            }),
+        };
+        let targets = pod_targets(&pod);
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].0, "[fd00::1]:9090");
+        assert_eq!(targets[0].1["kubernetes_pod_ip"], "fd00::1");
+
+        let endpoints = Endpoints {
+            metadata: metadata("api"),
+            subsets: Some(vec![EndpointSubset {
+                addresses: Some(vec![EndpointAddress {
+                    ip: "fd00::2".into(),
+                    ..EndpointAddress::default()
+                }]),
+                not_ready_addresses: None,
+                ports: Some(vec![EndpointPort {
+                    port: 9090,
+                    ..EndpointPort::default()
+                }]),
+            }]),
+        };
+        let targets = endpoints_targets(&endpoints);
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].0, "[fd00::2]:9090");
+    }
+
+    #[test]
+    fn services() {
+        let service = Service {
+            metadata: metadata("api"),
+            spec: Some(ServiceSpec {
+                ports: Some(vec![ServicePort {
+                    name: Some("metrics".into()),
+                    port: 9090,
+                    ..ServicePort::default()
+                }]),
+                ..ServiceSpec::default()
+            }),
+            status: None,
+        };
+
+        assert_eq!(
+            service_targets(&service),
+            vec![(
+                "api.default.svc:9090".into(),
+                btreemap! {
+                    "kubernetes_namespace" => "default",
+                    "kubernetes_service_label_app_kubernetes_io_name" => "api",
+                    "kubernetes_service_name" => "api",
+                    "kubernetes_service_port_name" => "metrics",
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn endpoints() {
+        let endpoints = Endpoints {
+            metadata: metadata("api"),
+            subsets: Some(vec![EndpointSubset {
+                addresses: Some(vec![EndpointAddress {
+                    ip: "10.0.0.1".into(),
+                    node_name: Some("node-1".into()),
+                    target_ref: Some(ObjectReference {
+                        kind: Some("Pod".into()),
+                        name: Some("api-1".into()),
+                        ..ObjectReference::default()
+                    }),
+                    ..EndpointAddress::default()
+                }]),
+                not_ready_addresses: Some(vec![EndpointAddress {
+                    ip: "10.0.0.2".into(),
+                    ..EndpointAddress::default()
+                }]),
+                ports: Some(vec![EndpointPort {
+                    name: Some("metrics".into()),
+                    port: 9090,
+                    ..EndpointPort::default()
+                }]),
+            }]),
+        };
+
+        assert_eq!(
+            endpoints_targets(&endpoints),
+            vec![(
+                "10.0.0.1:9090".into(),
+                btreemap! {
+                    "kubernetes_endpoint_node_name" => "node-1",
+                    "kubernetes_endpoint_port_name" => "metrics",
+                    "kubernetes_endpoints_label_app_kubernetes_io_name" => "api",
+                    "kubernetes_endpoints_name" => "api",
+                    "kubernetes_namespace" => "default",
+                    "kubernetes_pod_name" => "api-1",
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Discovery of the targets scraped by the `prometheus_scrape` source.
+//!
+//! Each discovery mechanism is made of a provider, returning the current
+//! targets when the source scrapes, and a task keeping the provider up to date
+//! in the background.
+
+use crate::event::metric::MetricTags;
+use futures::future::BoxFuture;
+
+mod file;
+mod kubernetes;
+
+pub(super) use file::FileSdConfig;
+pub(super) use kubernetes::KubernetesSdConfig;
+
+/// The tag holding the address of a discovered target.
+pub(super) const INSTANCE_TAG: &str = "instance";
+
+/// An endpoint to scrape, with the tags added to the metrics scraped from it.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Target {
+    pub url: http::Uri,
+    pub tags: MetricTags,
+}
+
+impl Target {
+    /// Build the target scraping `address` (a `host:port` pair), tagging the
+    /// metrics with the address as the `instance`.
+    fn new(scheme: &str, address: &str, metrics_path: &str, mut tags: MetricTags) -> Option<Self> {
+        let url = format!("{}://{}{}", scheme, address, metrics_path)
+            .parse()
+            .map_err(|error| warn!(message = "Invalid target discovered.", %address, %error))
+            .ok()?;
+        tags.entry(INSTANCE_TAG.into())
+            .or_insert_with(|| address.into());
+        Some(Self { url, tags })
+    }
+}
+
+/// Provides the targets to scrape.
+pub(super) trait TargetsProvider: Send {
+    fn targets(&self) -> Vec<Target>;
+}
+
+/// The statically configured targets.
+impl TargetsProvider for Vec<Target> {
+    fn targets(&self) -> Vec<Target> {
+        self.clone()
+    }
+}
+
+pub(super) struct Discovery {
+    pub provider: Box<dyn TargetsProvider>,
+    pub task: BoxFuture<'static, ()>,
+}
+
+/// Replace the characters not allowed in Prometheus label names.
+fn sanitize_label_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
@@ -1,3 +1,4 @@
+mod discovery;
 pub(crate) mod parser;
 mod remote_write;
 mod scrape;
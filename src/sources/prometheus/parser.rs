@@ -29,6 +29,11 @@ pub(super) fn parse_text(packet: &str) -> Result<Vec<Event>, ParserError> {
     prometheus_parser::parse_text(packet).map(reparse_groups)
 }
 
+/// Exemplars and the creation times of the `_created` samples are not kept.
+pub(super) fn parse_openmetrics(packet: &str) -> Result<Vec<Event>, ParserError> {
+    prometheus_parser::parse_openmetrics(packet).map(reparse_groups)
+}
+
 pub(super) fn parse_request(request: proto::WriteRequest) -> Result<Vec<Event>, ParserError> {
     prometheus_parser::parse_request(request).map(reparse_groups)
 }
@@ -153,6 +158,11 @@ mod test {
         super::parse_text(text).map(|events| events.into_iter().map(Event::into_metric).collect())
     }
 
+    fn parse_openmetrics(text: &str) -> Result<Vec<Metric>, ParserError> {
+        super::parse_openmetrics(text)
+            .map(|events| events.into_iter().map(Event::into_metric).collect())
+    }
+
     #[test]
     fn adds_timestamp_if_missing() {
         let now = Utc::now();
@@ -185,6 +195,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_openmetrics_counter() {
+        let exp = r##"
+            # HELP uptime A counter
+            # TYPE uptime counter
+            uptime_total 123.0 1612411506.789 # {trace_id="abc"} 1.0
+            uptime_created 1612411000.0
+            # EOF
+            "##;
+
+        assert_event_data_eq!(
+            parse_openmetrics(exp),
+            Ok(vec![Metric::new(
+                "uptime_total",
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 123.0 },
+            )
+            .with_timestamp(Some(*TIMESTAMP))]),
+        );
+    }
+
     #[test]
     fn test_counter_empty() {
         let exp = r##"
@@ -1,6 +1,15 @@
-use super::parser;
+use super::{
+    discovery::{
+        Discovery, FileSdConfig, KubernetesSdConfig, Target, TargetsProvider, INSTANCE_TAG,
+    },
+    parser,
+};
 use crate::{
     config::{self, GenerateConfig, ProxyConfig, SourceConfig, SourceContext, SourceDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricTags, MetricValue},
+        Event,
+    },
     http::Auth,
     http::HttpClient,
     internal_events::{
@@ -12,29 +21,50 @@ use crate::{
     tls::{TlsOptions, TlsSettings},
     Pipeline,
 };
-use futures::{stream, FutureExt, SinkExt, StreamExt, TryFutureExt};
+use chrono::Utc;
+use futures::{
+    future::{self, BoxFuture, Either},
+    stream, FutureExt, SinkExt, StreamExt, TryFutureExt,
+};
 use hyper::{Body, Request};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::{
-    future::ready,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 use tokio_stream::wrappers::IntervalStream;
 
 #[derive(Debug, Snafu)]
 enum ConfigError {
     #[snafu(display("Cannot set both `endpoints` and `hosts`"))]
     BothEndpointsAndHosts,
+    #[snafu(display(
+        "At least one of `endpoints`, `file_sd_configs` or `kubernetes_sd_configs` must be set"
+    ))]
+    NoTargets,
+    #[snafu(display("`scrape_timeout_secs` must be positive"))]
+    InvalidScrapeTimeout,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct PrometheusScrapeConfig {
     // Deprecated name
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
+    #[serde(default)]
+    file_sd_configs: Vec<FileSdConfig>,
+    #[serde(default)]
+    kubernetes_sd_configs: Vec<KubernetesSdConfig>,
+    #[serde(default = "default_scheme")]
+    scheme: String,
+    #[serde(default = "default_metrics_path")]
+    metrics_path: String,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
+    #[serde(default = "default_scrape_timeout_secs")]
+    scrape_timeout_secs: f64,
+    instance_tag: Option<String>,
+    endpoint_tag: Option<String>,
+    #[serde(default = "crate::serde::default_false")]
+    honor_labels: bool,
     tls: Option<TlsOptions>,
     auth: Option<Auth>,
 }
@@ -43,6 +73,27 @@ pub fn default_scrape_interval_secs() -> u64 {
     15
 }
 
+fn default_scrape_timeout_secs() -> f64 {
+    5.0
+}
+
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text";
+
+/// Prefer the OpenMetrics format, falling back to the Prometheus text format.
+const ACCEPT: &str =
+    "application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5,*/*;q=0.1";
+
+/// The number of targets scraped at once.
+const MAX_CONCURRENT_SCRAPES: usize = 64;
+
+fn default_scheme() -> String {
+    "http".into()
+}
+
+fn default_metrics_path() -> String {
+    "/metrics".into()
+}
+
 inventory::submit! {
     SourceDescription::new::<PrometheusScrapeConfig>("prometheus")
 }
@@ -55,7 +106,15 @@ impl GenerateConfig for PrometheusScrapeConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             endpoints: vec!["http://localhost:9090/metrics".to_string()],
+            file_sd_configs: Vec::new(),
+            kubernetes_sd_configs: Vec::new(),
+            scheme: default_scheme(),
+            metrics_path: default_metrics_path(),
             scrape_interval_secs: default_scrape_interval_secs(),
+            scrape_timeout_secs: default_scrape_timeout_secs(),
+            instance_tag: Some("instance".to_string()),
+            endpoint_tag: Some("endpoint".to_string()),
+            honor_labels: false,
             tls: None,
             auth: None,
         })
@@ -67,18 +126,62 @@ impl GenerateConfig for PrometheusScrapeConfig {
 #[typetag::serde(name = "prometheus_scrape")]
 impl SourceConfig for PrometheusScrapeConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
-        let urls = self
+        if self.endpoints.is_empty()
+            && self.file_sd_configs.is_empty()
+            && self.kubernetes_sd_configs.is_empty()
+        {
+            return Err(ConfigError::NoTargets.into());
+        }
+        if self.scrape_timeout_secs.is_nan() || self.scrape_timeout_secs <= 0.0 {
+            return Err(ConfigError::InvalidScrapeTimeout.into());
+        }
+
+        let targets = self
             .endpoints
             .iter()
-            .map(|s| s.parse::<http::Uri>().context(sources::UriParseError))
-            .collect::<Result<Vec<http::Uri>, sources::BuildError>>()?;
+            .map(|s| {
+                s.parse::<http::Uri>()
+                    .map(|url| Target {
+                        url,
+                        tags: MetricTags::new(),
+                    })
+                    .context(sources::UriParseError)
+            })
+            .collect::<Result<Vec<Target>, sources::BuildError>>()?;
+
+        let mut discoveries = self
+            .file_sd_configs
+            .iter()
+            .map(|config| config.build(&self.scheme, &self.metrics_path))
+            .collect::<crate::Result<Vec<_>>>()?;
+        for config in &self.kubernetes_sd_configs {
+            discoveries.push(config.build(&self.scheme, &self.metrics_path, &cx.proxy)?);
+        }
+        let (mut providers, tasks): (Vec<_>, Vec<_>) = discoveries
+            .into_iter()
+            .map(|Discovery { provider, task }| (provider, task))
+            .unzip();
+        providers.push(Box::new(targets));
+
         let tls = TlsSettings::from_options(&self.tls)?;
+        // A scrape never outlasts the interval.
+        let timeout = Duration::from_secs_f64(
+            self.scrape_timeout_secs
+                .min(self.scrape_interval_secs as f64),
+        );
         Ok(prometheus(
-            urls,
+            providers,
+            tasks,
+            TargetTags {
+                instance: self.instance_tag.clone(),
+                endpoint: self.endpoint_tag.clone(),
+                honor_labels: self.honor_labels,
+            },
             tls,
             self.auth.clone(),
             cx.proxy.clone(),
             self.scrape_interval_secs,
+            timeout,
             cx.shutdown,
             cx.out,
         ))
@@ -99,10 +202,24 @@ impl SourceConfig for PrometheusScrapeConfig {
 struct PrometheusCompatConfig {
     // Clone of PrometheusScrapeConfig to work around serde bug
     // https://github.com/serde-rs/serde/issues/1504
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
+    #[serde(default)]
+    file_sd_configs: Vec<FileSdConfig>,
+    #[serde(default)]
+    kubernetes_sd_configs: Vec<KubernetesSdConfig>,
+    #[serde(default = "default_scheme")]
+    scheme: String,
+    #[serde(default = "default_metrics_path")]
+    metrics_path: String,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
+    #[serde(default = "default_scrape_timeout_secs")]
+    scrape_timeout_secs: f64,
+    instance_tag: Option<String>,
+    endpoint_tag: Option<String>,
+    #[serde(default = "crate::serde::default_false")]
+    honor_labels: bool,
     tls: Option<TlsOptions>,
     auth: Option<Auth>,
 }
@@ -115,7 +232,15 @@ impl SourceConfig for PrometheusCompatConfig {
         // https://github.com/serde-rs/serde/issues/1504
         let config = PrometheusScrapeConfig {
             endpoints: self.endpoints.clone(),
+            file_sd_configs: self.file_sd_configs.clone(),
+            kubernetes_sd_configs: self.kubernetes_sd_configs.clone(),
+            scheme: self.scheme.clone(),
+            metrics_path: self.metrics_path.clone(),
             scrape_interval_secs: self.scrape_interval_secs,
+            scrape_timeout_secs: self.scrape_timeout_secs,
+            instance_tag: self.instance_tag.clone(),
+            endpoint_tag: self.endpoint_tag.clone(),
+            honor_labels: self.honor_labels,
             tls: self.tls.clone(),
             auth: self.auth.clone(),
         };
@@ -131,25 +256,83 @@ impl SourceConfig for PrometheusCompatConfig {
     }
 }
 
+/// How the metrics scraped from a target are tagged.
+#[derive(Clone, Debug)]
+struct TargetTags {
+    /// The tag holding the `host:port` of the target.
+    instance: Option<String>,
+    /// The tag holding the full URL of the target.
+    endpoint: Option<String>,
+    /// Keep the scraped tags conflicting with the target ones, instead of
+    /// renaming them.
+    honor_labels: bool,
+}
+
+impl TargetTags {
+    /// The tags added to the metrics scraped from the target.
+    fn tags(&self, target: &Target) -> MetricTags {
+        let mut tags = target.tags.clone();
+        if let (Some(tag), Some(authority)) = (&self.instance, target.url.authority()) {
+            tags.entry(tag.clone())
+                .or_insert_with(|| authority.to_string());
+        }
+        if let Some(tag) = &self.endpoint {
+            tags.insert(tag.clone(), target.url.to_string());
+        }
+        tags
+    }
+
+    /// Add the tags of the target to a scraped metric. On conflicts, the
+    /// scraped tags are kept when honoring the labels. Otherwise, like
+    /// Prometheus, they are kept as `exported_<name>`.
+    fn add_to(&self, event: Event, tags: &MetricTags) -> Event {
+        let mut metric = event.into_metric();
+        for (name, value) in tags {
+            if self.honor_labels {
+                metric
+                    .tag_entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            } else if let Some(exported) = metric.insert_tag(name.clone(), value.clone()) {
+                metric.insert_tag(format!("exported_{}", name), exported);
+            }
+        }
+        Event::Metric(metric)
+    }
+}
+
 fn prometheus(
-    urls: Vec<http::Uri>,
+    providers: Vec<Box<dyn TargetsProvider>>,
+    discovery_tasks: Vec<BoxFuture<'static, ()>>,
+    target_tags: TargetTags,
     tls: TlsSettings,
     auth: Option<Auth>,
     proxy: ProxyConfig,
     interval: u64,
+    timeout: Duration,
     shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> sources::Source {
     let out = out.sink_map_err(|error| error!(message = "Error sending metric.", %error));
 
-    Box::pin(IntervalStream::new(tokio::time::interval(Duration::from_secs(interval)))
+    let scrape = IntervalStream::new(tokio::time::interval(Duration::from_secs(interval)))
         .take_until(shutdown)
-        .map(move |_| stream::iter(urls.clone()))
+        .map(move |_| {
+            stream::iter(
+                providers
+                    .iter()
+                    .flat_map(|provider| provider.targets())
+                    .collect::<Vec<_>>(),
+            )
+        })
         .flatten()
-        .map(move |url| {
+        .map(move |target| {
             let client = HttpClient::new(tls.clone(), &proxy).expect("Building HTTP client failed");
+            let tags = target_tags.tags(&target);
+            let target_tags = target_tags.clone();
+            let url = target.url;
 
             let mut request = Request::get(&url)
+                .header(http::header::ACCEPT, ACCEPT)
                 .body(Body::empty())
                 .expect("error creating request");
             if let Some(auth) = &auth {
@@ -157,17 +340,18 @@ fn prometheus(
             }
 
             let start = Instant::now();
-            client
+            let response = client
                 .send(request)
                 .map_err(crate::Error::from)
                 .and_then(|response| async move {
                     let (header, body) = response.into_parts();
                     let body = hyper::body::to_bytes(body).await?;
                     Ok((header, body))
-                })
-                .into_stream()
-                .filter_map(move |response| {
-                    ready(match response {
+                });
+            tokio::time::timeout(timeout, response)
+                .map(|result| result.unwrap_or_else(|elapsed| Err(elapsed.into())))
+                .map(move |response| {
+                    let scraped = match response {
                         Ok((header, body)) if header.status == hyper::StatusCode::OK => {
                             emit!(PrometheusRequestCompleted {
                                 start,
@@ -176,15 +360,20 @@ fn prometheus(
 
                             let byte_size = body.len();
                             let body = String::from_utf8_lossy(&body);
+                            let parse = if is_openmetrics(&header.headers) {
+                                parser::parse_openmetrics
+                            } else {
+                                parser::parse_text
+                            };
 
-                            match parser::parse_text(&body) {
+                            match parse(&body) {
                                 Ok(metrics) => {
                                     emit!(PrometheusEventReceived {
                                         byte_size,
                                         count: metrics.len(),
                                         uri: url.clone()
                                     });
-                                    Some(stream::iter(metrics).map(Ok))
+                                    Some(metrics)
                                 }
                                 Err(error) => {
                                     if url.path() == "/" {
@@ -193,7 +382,7 @@ fn prometheus(
                                             message = "No path is set on the endpoint and we got a parse error, did you mean to use /metrics? This behavior changed in version 0.11.",
                                             endpoint = %url
                                         );
-                            }
+                                    }
                                     emit!(PrometheusParseError {
                                         error,
                                         url: url.clone(),
@@ -224,13 +413,77 @@ fn prometheus(
                             });
                             None
                         }
-                    })
+                    };
+
+                    let health = health_metrics(
+                        scraped.is_some(),
+                        start.elapsed(),
+                        &url,
+                        &tags,
+                        target_tags.instance.as_deref().unwrap_or(INSTANCE_TAG),
+                    );
+                    scraped
+                        .into_iter()
+                        .flatten()
+                        .map(|event| target_tags.add_to(event, &tags))
+                        .chain(health)
+                        .collect::<Vec<_>>()
                 })
-                .flatten()
         })
-        .flatten()
+        // Targets are scraped concurrently, so a round takes about as long as
+        // its slowest scrape rather than the sum of all of them.
+        .buffer_unordered(MAX_CONCURRENT_SCRAPES)
+        .flat_map(|events| stream::iter(events).map(Ok))
         .forward(out)
-        .inspect(|_| info!("Finished sending.")))
+        .inspect(|_| info!("Finished sending."));
+
+    // The discovery tasks run until the scraping stops.
+    let discovery = future::join_all(discovery_tasks).then(|_| future::pending::<()>());
+
+    Box::pin(
+        future::select(Box::pin(scrape), Box::pin(discovery)).map(|either| match either {
+            Either::Left((result, _)) => result,
+            Either::Right(((), _)) => Ok(()),
+        }),
+    )
+}
+
+/// Whether the scraped response is in the OpenMetrics text format.
+fn is_openmetrics(headers: &http::HeaderMap) -> bool {
+    headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map_or(false, |content_type| {
+            content_type.starts_with(OPENMETRICS_CONTENT_TYPE)
+        })
+}
+
+/// The `up` and `scrape_duration_seconds` metrics reporting the health of a
+/// scrape.
+fn health_metrics(
+    up: bool,
+    duration: Duration,
+    url: &http::Uri,
+    tags: &MetricTags,
+    instance_tag: &str,
+) -> Vec<Event> {
+    let mut tags = tags.clone();
+    if let Some(authority) = url.authority() {
+        tags.entry(instance_tag.into())
+            .or_insert_with(|| authority.to_string());
+    }
+    let timestamp = Some(Utc::now());
+    let gauge = |name: &str, value: f64| {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+            .with_tags(Some(tags.clone()))
+            .with_timestamp(timestamp)
+            .into()
+    };
+
+    vec![
+        gauge("up", if up { 1.0 } else { 0.0 }),
+        gauge("scrape_duration_seconds", duration.as_secs_f64()),
+    ]
 }
 
 #[cfg(all(test, feature = "sinks-prometheus"))]
@@ -239,14 +492,15 @@ mod test {
     use crate::{
         config,
         sinks::prometheus::exporter::PrometheusExporterConfig,
-        test_util::{next_addr, start_topology},
+        test_util::{collect_ready, next_addr, start_topology, temp_dir},
         Error,
     };
     use hyper::{
         service::{make_service_fn, service_fn},
-        {Body, Client, Response, Server},
+        {Body, Client, Request, Response, Server},
     };
     use pretty_assertions::assert_eq;
+    use shared::btreemap;
     use tokio::time::{sleep, Duration};
 
     #[test]
@@ -305,7 +559,15 @@ mod test {
             "in",
             PrometheusScrapeConfig {
                 endpoints: vec![format!("http://{}", in_addr)],
+                file_sd_configs: vec![],
+                kubernetes_sd_configs: vec![],
+                scheme: default_scheme(),
+                metrics_path: default_metrics_path(),
                 scrape_interval_secs: 1,
+                scrape_timeout_secs: default_scrape_timeout_secs(),
+                instance_tag: None,
+                endpoint_tag: None,
+                honor_labels: false,
                 tls: None,
                 auth: None,
             },
@@ -320,6 +582,7 @@ mod test {
                 buckets: vec![1.0, 2.0, 4.0],
                 quantiles: vec![],
                 flush_period_secs: 1,
+                ..Default::default()
             },
         );
 
@@ -333,10 +596,20 @@ mod test {
         assert!(response.status().is_success());
 
         let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-        let lines = std::str::from_utf8(&body)
+        let (health, lines): (Vec<_>, Vec<_>) = std::str::from_utf8(&body)
             .unwrap()
             .lines()
-            .collect::<Vec<_>>();
+            .partition(|line| {
+                line.contains("vector_up") || line.contains("vector_scrape_duration")
+            });
+
+        let up = format!("vector_up{{instance=\"{}\"}} 1 ", in_addr);
+        assert!(health.iter().any(|line| line.starts_with(&up)));
+        let duration = format!(
+            "vector_scrape_duration_seconds{{instance=\"{}\"}} ",
+            in_addr
+        );
+        assert!(health.iter().any(|line| line.starts_with(&duration)));
 
         assert_eq!(lines, vec![
             "# HELP vector_http_request_duration_seconds http_request_duration_seconds",
@@ -370,6 +643,289 @@ mod test {
 
         topology.stop().await;
     }
+
+    #[tokio::test]
+    async fn scrapes_file_discovered_targets() {
+        let in_addr = next_addr();
+
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|_| async {
+                Ok::<_, Error>(Response::new(Body::from(
+                    r#"
+                    # TYPE requests_total counter
+                    requests_total{job="scraped",code="200"} 100
+                    "#,
+                )))
+            }))
+        });
+
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&in_addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let dir = temp_dir();
+        std::fs::create_dir(&dir).unwrap();
+        let targets = dir.join("targets.json");
+        std::fs::write(
+            &targets,
+            format!(
+                r#"[{{"targets": ["{}"], "labels": {{"job": "api"}}}}]"#,
+                in_addr
+            ),
+        )
+        .unwrap();
+
+        let config = toml::from_str::<PrometheusScrapeConfig>(&format!(
+            r#"
+            scrape_interval_secs = 1
+
+            [[file_sd_configs]]
+            files = ["{}"]
+            "#,
+            targets.display()
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_secs(1)).await;
+
+        let metrics = collect_ready(rx)
+            .await
+            .into_iter()
+            .map(Event::into_metric)
+            .collect::<Vec<_>>();
+        let instance = in_addr.to_string();
+
+        let requests = metrics
+            .iter()
+            .find(|metric| metric.name() == "requests_total")
+            .unwrap();
+        assert_eq!(
+            requests.tags(),
+            Some(&btreemap! {
+                "code" => "200",
+                "exported_job" => "scraped",
+                "instance" => instance.clone(),
+                "job" => "api",
+            })
+        );
+
+        let up = metrics.iter().find(|metric| metric.name() == "up").unwrap();
+        assert_eq!(up.value(), &MetricValue::Gauge { value: 1.0 });
+        assert_eq!(
+            up.tags(),
+            Some(&btreemap! {
+                "instance" => instance,
+                "job" => "api",
+            })
+        );
+        assert!(metrics
+            .iter()
+            .any(|metric| metric.name() == "scrape_duration_seconds"));
+    }
+
+    #[test]
+    fn tags_scraped_metrics() {
+        let target = Target {
+            url: "http://10.0.0.1:9090/metrics".parse().unwrap(),
+            tags: btreemap! {"job" => "api"},
+        };
+        let scraped: Event = Metric::new(
+            "requests_total",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(btreemap! {"job" => "scraped", "code" => "200"}))
+        .into();
+
+        let mut target_tags = TargetTags {
+            instance: Some("instance".into()),
+            endpoint: Some("endpoint".into()),
+            honor_labels: false,
+        };
+        let tags = target_tags.tags(&target);
+        assert_eq!(
+            tags,
+            btreemap! {
+                "endpoint" => "http://10.0.0.1:9090/metrics",
+                "instance" => "10.0.0.1:9090",
+                "job" => "api",
+            }
+        );
+
+        let metric = target_tags.add_to(scraped.clone(), &tags).into_metric();
+        assert_eq!(
+            metric.tags(),
+            Some(&btreemap! {
+                "code" => "200",
+                "endpoint" => "http://10.0.0.1:9090/metrics",
+                "exported_job" => "scraped",
+                "instance" => "10.0.0.1:9090",
+                "job" => "api",
+            })
+        );
+
+        target_tags.honor_labels = true;
+        let metric = target_tags.add_to(scraped, &tags).into_metric();
+        assert_eq!(
+            metric.tags(),
+            Some(&btreemap! {
+                "code" => "200",
+                "endpoint" => "http://10.0.0.1:9090/metrics",
+                "instance" => "10.0.0.1:9090",
+                "job" => "scraped",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn scrapes_openmetrics() {
+        let in_addr = next_addr();
+
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|request: Request<Body>| async move {
+                let accept = request.headers()[http::header::ACCEPT].clone();
+                assert!(accept
+                    .to_str()
+                    .unwrap()
+                    .starts_with(OPENMETRICS_CONTENT_TYPE));
+                Ok::<_, Error>(
+                    Response::builder()
+                        .header(
+                            http::header::CONTENT_TYPE,
+                            "application/openmetrics-text; version=1.0.0; charset=utf-8",
+                        )
+                        .body(Body::from(
+                            r#"
+                            # TYPE requests counter
+                            requests_total{code="200"} 100 # {trace_id="abc"} 1
+                            requests_created{code="200"} 1612411000
+                            # TYPE build info
+                            build_info{version="1.0"} 1
+                            # EOF
+                            "#,
+                        ))
+                        .unwrap(),
+                )
+            }))
+        });
+
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&in_addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let config = toml::from_str::<PrometheusScrapeConfig>(&format!(
+            r#"
+            endpoints = ["http://{}/metrics"]
+            scrape_interval_secs = 1
+            endpoint_tag = "endpoint"
+            "#,
+            in_addr
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_secs(1)).await;
+
+        let metrics = collect_ready(rx)
+            .await
+            .into_iter()
+            .map(Event::into_metric)
+            .collect::<Vec<_>>();
+        let endpoint = format!("http://{}/metrics", in_addr);
+
+        let requests = metrics
+            .iter()
+            .find(|metric| metric.name() == "requests_total")
+            .unwrap();
+        assert_eq!(requests.value(), &MetricValue::Counter { value: 100.0 });
+        assert_eq!(
+            requests.tags(),
+            Some(&btreemap! {"code" => "200", "endpoint" => endpoint.clone()})
+        );
+
+        let build = metrics
+            .iter()
+            .find(|metric| metric.name() == "build_info")
+            .unwrap();
+        assert_eq!(build.value(), &MetricValue::Gauge { value: 1.0 });
+        assert_eq!(
+            build.tags(),
+            Some(&btreemap! {"version" => "1.0", "endpoint" => endpoint})
+        );
+    }
+
+    #[tokio::test]
+    async fn times_out_scrapes() {
+        let in_addr = next_addr();
+
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|_| async {
+                sleep(Duration::from_secs(2)).await;
+                Ok::<_, Error>(Response::new(Body::from("requests_total 100\n")))
+            }))
+        });
+
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&in_addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let config = toml::from_str::<PrometheusScrapeConfig>(&format!(
+            r#"
+            endpoints = ["http://{}/metrics"]
+            scrape_interval_secs = 1
+            scrape_timeout_secs = 0.2
+            "#,
+            in_addr
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_secs(1)).await;
+
+        let metrics = collect_ready(rx)
+            .await
+            .into_iter()
+            .map(Event::into_metric)
+            .collect::<Vec<_>>();
+        assert!(!metrics
+            .iter()
+            .any(|metric| metric.name() == "requests_total"));
+        let up = metrics.iter().find(|metric| metric.name() == "up").unwrap();
+        assert_eq!(up.value(), &MetricValue::Gauge { value: 0.0 });
+    }
+
+    #[tokio::test]
+    async fn requires_targets() {
+        let config = toml::from_str::<PrometheusScrapeConfig>("").unwrap();
+        let (tx, _rx) = Pipeline::new_test();
+        assert!(config.build(SourceContext::new_test(tx)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn requires_positive_timeout() {
+        let config = toml::from_str::<PrometheusScrapeConfig>(
+            r#"
+            endpoints = ["http://localhost:9090/metrics"]
+            scrape_timeout_secs = 0.0
+            "#,
+        )
+        .unwrap();
+        let (tx, _rx) = Pipeline::new_test();
+        assert!(config.build(SourceContext::new_test(tx)).await.is_err());
+    }
 }
 
 #[cfg(all(test, feature = "prometheus-integration-tests"))]
@@ -386,7 +942,15 @@ mod integration_tests {
     async fn scrapes_metrics() {
         let config = PrometheusScrapeConfig {
             endpoints: vec!["http://localhost:9090/metrics".into()],
+            file_sd_configs: vec![],
+            kubernetes_sd_configs: vec![],
+            scheme: default_scheme(),
+            metrics_path: default_metrics_path(),
             scrape_interval_secs: 1,
+            scrape_timeout_secs: default_scrape_timeout_secs(),
+            instance_tag: Some("instance".to_string()),
+            endpoint_tag: None,
+            honor_labels: false,
             auth: None,
             tls: None,
         };
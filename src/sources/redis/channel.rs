@@ -0,0 +1,57 @@
+use super::{send, ChannelOption, EventBuilder};
+use crate::{internal_events::RedisReceiveEventFailed, shutdown::ShutdownSignal, Pipeline};
+use futures::StreamExt;
+use std::time::Duration;
+
+pub(super) async fn run(
+    client: redis::Client,
+    events: EventBuilder,
+    option: ChannelOption,
+    mut shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    loop {
+        match subscribe(&client, &events.key, option).await {
+            Ok(mut pubsub) => {
+                let mut messages = pubsub.on_message();
+                loop {
+                    let message = tokio::select! {
+                        _ = &mut shutdown => return Ok(()),
+                        message = messages.next() => message,
+                    };
+                    // The stream only ends when the connection is lost.
+                    let message = match message {
+                        Some(message) => message,
+                        None => break,
+                    };
+
+                    let payload = message.get_payload_bytes().to_vec();
+                    let byte_size = payload.len();
+                    let event = events.message(message.get_channel_name(), payload);
+                    send(&mut out, event, byte_size).await?;
+                }
+                warn!(message = "Redis subscription ended, resubscribing.");
+            }
+            Err(error) => emit!(RedisReceiveEventFailed { error }),
+        }
+
+        tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+        }
+    }
+}
+
+async fn subscribe(
+    client: &redis::Client,
+    key: &str,
+    option: ChannelOption,
+) -> redis::RedisResult<redis::aio::PubSub> {
+    let mut pubsub = client.get_async_connection().await?.into_pubsub();
+    if option.pattern {
+        pubsub.psubscribe(key).await?;
+    } else {
+        pubsub.subscribe(key).await?;
+    }
+    Ok(pubsub)
+}
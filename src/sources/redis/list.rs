@@ -0,0 +1,39 @@
+use super::{send, EventBuilder, Method};
+use crate::{internal_events::RedisReceiveEventFailed, shutdown::ShutdownSignal, Pipeline};
+use futures::FutureExt;
+use redis::{aio::ConnectionManager, AsyncCommands};
+use std::time::Duration;
+
+/// How long each pop blocks for, which bounds how long shutdown can take.
+const POP_TIMEOUT_SECS: usize = 1;
+
+pub(super) async fn run(
+    mut conn: ConnectionManager,
+    events: EventBuilder,
+    method: Method,
+    mut shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    // Pops aren't cancelled on shutdown, as the popped value would be lost.
+    // They time out regularly instead, leaving a chance to check for shutdown.
+    while (&mut shutdown).now_or_never().is_none() {
+        let popped: redis::RedisResult<Option<(String, Vec<u8>)>> = match method {
+            Method::LPop => conn.blpop(&events.key, POP_TIMEOUT_SECS).await,
+            Method::RPop => conn.brpop(&events.key, POP_TIMEOUT_SECS).await,
+        };
+
+        match popped {
+            Ok(Some((key, payload))) => {
+                let byte_size = payload.len();
+                send(&mut out, events.message(&key, payload), byte_size).await?;
+            }
+            Ok(None) => {}
+            Err(error) => {
+                emit!(RedisReceiveEventFailed { error });
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,424 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::RedisEventReceived,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+mod channel;
+mod list;
+mod stream;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to create Redis client: {}", source))]
+    Client { source: redis::RedisError },
+    #[snafu(display("Failed to connect to Redis: {}", source))]
+    Connection { source: redis::RedisError },
+    #[snafu(display("Failed to create consumer group: {}", source))]
+    CreateGroup { source: redis::RedisError },
+    #[snafu(display("`key` cannot be empty."))]
+    EmptyKey,
+    #[snafu(display("`stream.group` must be set to read a stream."))]
+    MissingStreamGroup,
+}
+
+#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DataTypeConfig {
+    #[derivative(Default)]
+    List,
+    Channel,
+    Stream,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ListOption {
+    method: Method,
+}
+
+#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    #[derivative(Default)]
+    LPop,
+    RPop,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelOption {
+    /// Treats `key` as a glob-style pattern of channels to subscribe to.
+    #[serde(default)]
+    pattern: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    group: String,
+    #[serde(default = "default_consumer")]
+    consumer: String,
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    /// The id of the last entry a newly created group has seen, `$` for the
+    /// end of the stream or `0` to read it from the start.
+    #[serde(default = "default_start_id")]
+    start_id: String,
+}
+
+fn default_consumer() -> String {
+    "vector".into()
+}
+
+fn default_start_id() -> String {
+    "$".into()
+}
+
+fn default_batch_size() -> usize {
+    100
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedisSourceConfig {
+    #[serde(default)]
+    data_type: DataTypeConfig,
+    #[serde(alias = "list")]
+    list_option: Option<ListOption>,
+    #[serde(alias = "channel")]
+    channel_option: Option<ChannelOption>,
+    #[serde(alias = "stream")]
+    stream_option: Option<StreamOption>,
+    url: String,
+    key: String,
+    redis_key: Option<String>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<RedisSourceConfig>("redis")
+}
+
+impl GenerateConfig for RedisSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            data_type = "list"
+            list.method = "lpop"
+            redis_key = "redis_key"
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl SourceConfig for RedisSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.key.is_empty() {
+            return Err(BuildError::EmptyKey.into());
+        }
+
+        let client = redis::Client::open(self.url.as_str()).context(Client)?;
+        let events = EventBuilder {
+            key: self.key.clone(),
+            redis_key: self.redis_key.clone(),
+        };
+
+        Ok(match self.data_type {
+            DataTypeConfig::List => {
+                let conn = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(Connection)?;
+                let method = self.list_option.unwrap_or_default().method;
+                Box::pin(list::run(conn, events, method, cx.shutdown, cx.out))
+            }
+            DataTypeConfig::Channel => {
+                let option = self.channel_option.unwrap_or_default();
+                Box::pin(channel::run(client, events, option, cx.shutdown, cx.out))
+            }
+            DataTypeConfig::Stream => {
+                let option = self
+                    .stream_option
+                    .clone()
+                    .ok_or(BuildError::MissingStreamGroup)?;
+                // Reads block, so acknowledgements get their own connection.
+                let mut conn = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(Connection)?;
+                stream::create_group(&mut conn, &self.key, &option.group, &option.start_id)
+                    .await
+                    .context(CreateGroup)?;
+                let ack_conn = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(Connection)?;
+                Box::pin(stream::run(
+                    conn,
+                    ack_conn,
+                    events,
+                    option,
+                    cx.shutdown,
+                    cx.out,
+                    cx.acknowledgements,
+                ))
+            }
+        })
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "redis"
+    }
+}
+
+/// Creates the events for messages read from a key.
+#[derive(Clone, Debug)]
+struct EventBuilder {
+    key: String,
+    redis_key: Option<String>,
+}
+
+impl EventBuilder {
+    fn new_log(&self, key: &str) -> LogEvent {
+        let mut log = LogEvent::default();
+
+        log.insert(log_schema().timestamp_key(), Utc::now());
+        if let Some(redis_key) = &self.redis_key {
+            log.insert(redis_key.as_str(), key.to_owned());
+        }
+
+        // Add source type
+        log.insert(log_schema().source_type_key(), Bytes::from("redis"));
+
+        log
+    }
+
+    fn message(&self, key: &str, payload: Vec<u8>) -> Event {
+        let mut log = self.new_log(key);
+        log.insert(log_schema().message_key(), Bytes::from(payload));
+        log.into()
+    }
+}
+
+async fn send(out: &mut Pipeline, event: Event, byte_size: usize) -> Result<(), ()> {
+    emit!(RedisEventReceived { byte_size });
+    out.send(event).await.map_err(|error| {
+        error!(message = "Error sending event.", %error);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisSourceConfig>();
+    }
+
+    #[test]
+    fn parse_stream_config() {
+        let config: RedisSourceConfig = toml::from_str(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "orders"
+            data_type = "stream"
+            stream.group = "vector"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.data_type, DataTypeConfig::Stream);
+        assert_eq!(
+            config.stream_option,
+            Some(StreamOption {
+                group: "vector".into(),
+                consumer: "vector".into(),
+                batch_size: 100,
+                start_id: "$".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn builds_message_events() {
+        let events = EventBuilder {
+            key: "logs".into(),
+            redis_key: Some("redis_key".into()),
+        };
+
+        let event = events.message("logs", b"hello".to_vec());
+        let log = event.as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log["redis_key"], "logs".into());
+        assert_eq!(log[log_schema().source_type_key()], "redis".into());
+    }
+
+    #[tokio::test]
+    async fn stream_requires_group() {
+        let config: RedisSourceConfig = toml::from_str(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "orders"
+            data_type = "stream"
+            "#,
+        )
+        .unwrap();
+
+        let (tx, _rx) = Pipeline::new_test();
+        let error = config
+            .build(SourceContext::new_test(tx))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "`stream.group` must be set to read a stream."
+        );
+    }
+}
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        shutdown::ShutdownSignal,
+        test_util::{collect_n, random_string, trace_init},
+    };
+    use redis::AsyncCommands;
+    use vector_core::event::EventStatus;
+
+    const REDIS_SERVER: &str = "redis://127.0.0.1:6379/0";
+
+    async fn connect() -> redis::aio::ConnectionManager {
+        redis::Client::open(REDIS_SERVER)
+            .unwrap()
+            .get_tokio_connection_manager()
+            .await
+            .unwrap()
+    }
+
+    fn events(key: &str) -> EventBuilder {
+        EventBuilder {
+            key: key.to_owned(),
+            redis_key: Some("redis_key".to_owned()),
+        }
+    }
+
+    #[tokio::test]
+    async fn redis_source_list() {
+        trace_init();
+
+        let key = format!("test-{}", random_string(10));
+        let mut conn = connect().await;
+        let _: () = conn.rpush(&key, &["1", "2", "3"]).await.unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        tokio::spawn(list::run(
+            connect().await,
+            events(&key),
+            Method::LPop,
+            ShutdownSignal::noop(),
+            tx,
+        ));
+
+        let events = collect_n(rx, 3).await;
+        let messages: Vec<_> = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect();
+        assert_eq!(messages, vec!["1", "2", "3"]);
+        assert_eq!(events[0].as_log()["redis_key"], key.into());
+    }
+
+    #[tokio::test]
+    async fn redis_source_channel_pattern() {
+        trace_init();
+
+        let prefix = format!("test-{}", random_string(10));
+        let (tx, rx) = Pipeline::new_test();
+        tokio::spawn(channel::run(
+            redis::Client::open(REDIS_SERVER).unwrap(),
+            events(&format!("{}.*", prefix)),
+            ChannelOption { pattern: true },
+            ShutdownSignal::noop(),
+            tx,
+        ));
+        // Wait for the subscription to be in place.
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+        let channel = format!("{}.orders", prefix);
+        let mut conn = connect().await;
+        let _: () = conn.publish(&channel, "my message").await.unwrap();
+
+        let events = collect_n(rx, 1).await;
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "my message".into()
+        );
+        assert_eq!(events[0].as_log()["redis_key"], channel.into());
+    }
+
+    #[tokio::test]
+    async fn redis_source_stream_acks_delivered() {
+        trace_init();
+
+        let key = format!("test-{}", random_string(10));
+        let mut conn = connect().await;
+        let _: String = conn
+            .xadd(&key, "*", &[("message", "my message"), ("host", "a")])
+            .await
+            .unwrap();
+
+        let option = StreamOption {
+            group: "vector".to_owned(),
+            consumer: "vector".to_owned(),
+            batch_size: 10,
+            start_id: "0".to_owned(),
+        };
+        stream::create_group(&mut conn, &key, &option.group, &option.start_id)
+            .await
+            .unwrap();
+        let (tx, rx) = Pipeline::new_test_finalize(EventStatus::Delivered);
+        tokio::spawn(stream::run(
+            connect().await,
+            connect().await,
+            events(&key),
+            option,
+            ShutdownSignal::noop(),
+            tx,
+            true,
+        ));
+
+        let events = collect_n(rx, 1).await;
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "my message".into());
+        assert_eq!(log["host"], "a".into());
+
+        // Give the acknowledgement a moment to be sent.
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        let pending: redis::streams::StreamPendingReply =
+            conn.xpending(&key, "vector").await.unwrap();
+        assert_eq!(pending.count(), 0);
+    }
+}
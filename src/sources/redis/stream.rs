@@ -0,0 +1,238 @@
+use super::{send, EventBuilder, StreamOption};
+use crate::{
+    config::log_schema,
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{RedisAckFailed, RedisReceiveEventFailed},
+    shutdown::ShutdownSignal,
+    sources::util::finalizer::OrderedFinalizer,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::FutureExt;
+use redis::{
+    aio::ConnectionManager,
+    streams::{StreamId, StreamReadOptions, StreamReadReply},
+    AsyncCommands, RedisResult, Value,
+};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How long each read blocks for, which bounds how long shutdown can take.
+const READ_BLOCK_MILLIS: usize = 1000;
+
+/// How often entries left pending, such as those whose events errored, are
+/// read again.
+const PENDING_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Creates the consumer group, along with the stream if it doesn't exist yet.
+/// New groups see the entries added after `start_id`.
+pub(super) async fn create_group(
+    conn: &mut ConnectionManager,
+    key: &str,
+    group: &str,
+    start_id: &str,
+) -> RedisResult<()> {
+    match conn.xgroup_create_mkstream(key, group, start_id).await {
+        Err(error) if error.code() == Some("BUSYGROUP") => Ok(()),
+        result => result,
+    }
+}
+
+pub(super) async fn run(
+    mut conn: ConnectionManager,
+    ack_conn: ConnectionManager,
+    events: EventBuilder,
+    option: StreamOption,
+    shutdown: ShutdownSignal,
+    mut out: Pipeline,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let shutdown = shutdown.shared();
+    let ack = Acknowledger {
+        conn: ack_conn,
+        key: events.key.clone(),
+        group: option.group.clone(),
+        in_flight: Arc::default(),
+    };
+    let finalizer = acknowledgements.then(|| {
+        let ack = ack.clone();
+        OrderedFinalizer::new(shutdown.clone(), move |status, id| ack.finalize(status, id))
+    });
+
+    // Entries delivered to this consumer but never acknowledged, such as
+    // those in flight when Vector last stopped, are read before new ones, and
+    // again every `PENDING_RETRY_INTERVAL`.
+    let mut pending_from = Some("0".to_owned());
+    let mut pending_read_at = Instant::now();
+    let read_options = StreamReadOptions::default()
+        .group(&option.group, &option.consumer)
+        .count(option.batch_size)
+        .block(READ_BLOCK_MILLIS);
+
+    // Reads aren't cancelled on shutdown, they time out regularly instead,
+    // leaving a chance to check for shutdown.
+    while shutdown.clone().now_or_never().is_none() {
+        if pending_from.is_none() && pending_read_at.elapsed() >= PENDING_RETRY_INTERVAL {
+            pending_from = Some("0".to_owned());
+            pending_read_at = Instant::now();
+        }
+        let from = pending_from.as_deref().unwrap_or(">");
+        let reply: RedisResult<StreamReadReply> = conn
+            .xread_options(&[&events.key], &[from], &read_options)
+            .await;
+
+        let entries: Vec<StreamId> = match reply {
+            Ok(reply) => reply.keys.into_iter().flat_map(|key| key.ids).collect(),
+            Err(error) => {
+                emit!(RedisReceiveEventFailed { error });
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let reading_pending = pending_from.is_some();
+        if reading_pending {
+            pending_from = entries.last().map(|entry| entry.id.clone());
+        }
+
+        for entry in entries {
+            let id = entry.id.clone();
+            // Pending entries whose events are still on their way to the
+            // sinks are acknowledged once they get there.
+            if reading_pending && ack.is_in_flight(&id) {
+                continue;
+            }
+            // Pending entries that were trimmed from the stream since have no
+            // fields left, and nothing to deliver.
+            if entry.map.is_empty() {
+                ack.finalize(BatchStatus::Delivered, id);
+                continue;
+            }
+
+            let (event, byte_size) = to_event(&events, entry);
+            match &finalizer {
+                Some(finalizer) => {
+                    let (batch, receiver) = BatchNotifier::new_with_receiver();
+                    let event = event.with_batch_notifier(&batch);
+                    ack.track(id.clone());
+                    send(&mut out, event, byte_size).await?;
+                    finalizer.add(id, receiver);
+                }
+                None => {
+                    send(&mut out, event, byte_size).await?;
+                    ack.finalize(BatchStatus::Delivered, id);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Turns the fields of a stream entry into the fields of a log event,
+/// timestamped with the time the entry was added.
+fn to_event(events: &EventBuilder, entry: StreamId) -> (Event, usize) {
+    let mut log = events.new_log(&events.key);
+    let mut byte_size = 0;
+
+    // Entry ids are made of the millisecond timestamp they were added at and
+    // a sequence number.
+    if let Some(timestamp) = entry
+        .id
+        .split('-')
+        .next()
+        .and_then(|millis| millis.parse().ok())
+        .and_then(|millis| Utc.timestamp_millis_opt(millis).latest())
+    {
+        log.insert(log_schema().timestamp_key(), timestamp);
+    }
+
+    for (field, value) in entry.map {
+        match value {
+            Value::Data(bytes) => {
+                byte_size += bytes.len();
+                log.insert_flat(field, Bytes::from(bytes));
+            }
+            Value::Int(int) => log.insert_flat(field, int),
+            _ => {}
+        }
+    }
+
+    (log.into(), byte_size)
+}
+
+#[derive(Clone)]
+struct Acknowledger {
+    conn: ConnectionManager,
+    key: String,
+    group: String,
+    // Ids of the entries waiting on the delivery of their events.
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Acknowledger {
+    fn track(&self, id: String) {
+        self.in_flight.lock().unwrap().insert(id);
+    }
+
+    fn is_in_flight(&self, id: &str) -> bool {
+        self.in_flight.lock().unwrap().contains(id)
+    }
+
+    /// Acknowledges entries once their events have been delivered, or
+    /// rejected by a sink. Entries whose events errored are left pending and
+    /// are read again later.
+    fn finalize(&self, status: BatchStatus, id: String) {
+        self.in_flight.lock().unwrap().remove(&id);
+        if status == BatchStatus::Errored {
+            return;
+        }
+
+        let Self {
+            mut conn,
+            key,
+            group,
+            ..
+        } = self.clone();
+        tokio::spawn(async move {
+            let result: RedisResult<()> = conn.xack(key, group, &[id]).await;
+            if let Err(error) = result {
+                emit!(RedisAckFailed { error });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn entry_fields_become_log_fields() {
+        let events = EventBuilder {
+            key: "orders".into(),
+            redis_key: None,
+        };
+        let mut map = HashMap::new();
+        map.insert("message".to_owned(), Value::Data(b"my message".to_vec()));
+        map.insert("user.id".to_owned(), Value::Data(b"42".to_vec()));
+        let entry = StreamId {
+            id: "1526919030474-55".into(),
+            map,
+        };
+
+        let (event, byte_size) = to_event(&events, entry);
+        let log = event.as_log();
+        assert_eq!(byte_size, 12);
+        assert_eq!(log[log_schema().message_key()], "my message".into());
+        assert_eq!(log.get_flat("user.id"), Some(&"42".into()));
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp_millis(1526919030474).into()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::udp;
 use crate::{
     config::{self, GenerateConfig, Resource, SourceConfig, SourceContext, SourceDescription},
-    event::Event,
+    event::{metric, Event},
     internal_events::{StatsdEventReceived, StatsdInvalidRecord, StatsdSocketError},
     shutdown::ShutdownSignal,
     sources::util::{SocketListenAddr, TcpSource},
@@ -38,6 +38,7 @@ enum StatsdConfig {
 pub struct UdpConfig {
     address: SocketAddr,
     receive_buffer_bytes: Option<usize>,
+    sketch_relative_accuracy: Option<f64>,
 }
 
 impl UdpConfig {
@@ -45,6 +46,7 @@ impl UdpConfig {
         Self {
             address,
             receive_buffer_bytes: None,
+            sketch_relative_accuracy: None,
         }
     }
 }
@@ -58,6 +60,7 @@ struct TcpConfig {
     #[serde(default = "default_shutdown_timeout_secs")]
     shutdown_timeout_secs: u64,
     receive_buffer_bytes: Option<usize>,
+    sketch_relative_accuracy: Option<f64>,
 }
 
 impl TcpConfig {
@@ -69,6 +72,7 @@ impl TcpConfig {
             tls: None,
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             receive_buffer_bytes: None,
+            sketch_relative_accuracy: None,
         }
     }
 }
@@ -94,13 +98,22 @@ impl GenerateConfig for StatsdConfig {
 #[typetag::serde(name = "statsd")]
 impl SourceConfig for StatsdConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if let Some(relative_accuracy) = self.sketch_relative_accuracy() {
+            if !(relative_accuracy > 0.0 && relative_accuracy < 1.0) {
+                return Err("`sketch_relative_accuracy` must be in range (0.0, 1.0)".into());
+            }
+        }
+
         match self {
             StatsdConfig::Udp(config) => {
                 Ok(Box::pin(statsd_udp(config.clone(), cx.shutdown, cx.out)))
             }
             StatsdConfig::Tcp(config) => {
                 let tls = MaybeTlsSettings::from_config(&config.tls, true)?;
-                StatsdTcpSource.run(
+                StatsdTcpSource {
+                    sketch_relative_accuracy: config.sketch_relative_accuracy,
+                }
+                .run(
                     config.address,
                     config.keepalive,
                     config.shutdown_timeout_secs,
@@ -133,9 +146,24 @@ impl SourceConfig for StatsdConfig {
     }
 }
 
-pub(self) fn parse_event(line: &str) -> Option<Event> {
+impl StatsdConfig {
+    fn sketch_relative_accuracy(&self) -> Option<f64> {
+        match self {
+            Self::Tcp(tcp) => tcp.sketch_relative_accuracy,
+            Self::Udp(udp) => udp.sketch_relative_accuracy,
+            #[cfg(unix)]
+            Self::Unix(unix) => unix.sketch_relative_accuracy,
+        }
+    }
+}
+
+pub(self) fn parse_event(line: &str, sketch_relative_accuracy: Option<f64>) -> Option<Event> {
     match parse(line) {
         Ok(metric) => {
+            let metric = match sketch_relative_accuracy {
+                Some(relative_accuracy) => into_sketch(metric, relative_accuracy),
+                None => metric,
+            };
             emit!(StatsdEventReceived {
                 byte_size: line.len()
             });
@@ -148,6 +176,21 @@ pub(self) fn parse_event(line: &str) -> Option<Event> {
     }
 }
 
+/// Converts timers and histograms into sketches, which stay small and can be
+/// merged downstream without keeping every sample.
+fn into_sketch(metric: metric::Metric, relative_accuracy: f64) -> metric::Metric {
+    match metric.value() {
+        metric::MetricValue::Distribution { samples, statistic } => {
+            let value = metric::MetricValue::Sketch {
+                sketch: metric::DDSketch::from_samples(relative_accuracy, samples),
+                statistic: *statistic,
+            };
+            metric.with_value(value)
+        }
+        _ => metric,
+    }
+}
+
 async fn statsd_udp(
     config: UdpConfig,
     shutdown: ShutdownSignal,
@@ -174,7 +217,10 @@ async fn statsd_udp(
         match frame {
             Ok((bytes, _sock)) => {
                 let packet = String::from_utf8_lossy(bytes.as_ref());
-                let metrics = packet.lines().filter_map(parse_event).map(Ok);
+                let metrics = packet
+                    .lines()
+                    .filter_map(|line| parse_event(line, config.sketch_relative_accuracy))
+                    .map(Ok);
 
                 // Need `boxed` to resolve a lifetime issue
                 // https://github.com/rust-lang/rust/issues/64552#issuecomment-669728225
@@ -194,7 +240,9 @@ async fn statsd_udp(
 }
 
 #[derive(Clone)]
-struct StatsdTcpSource;
+struct StatsdTcpSource {
+    sketch_relative_accuracy: Option<f64>,
+}
 
 impl TcpSource for StatsdTcpSource {
     type Error = std::io::Error;
@@ -206,7 +254,7 @@ impl TcpSource for StatsdTcpSource {
 
     fn build_event(&self, line: Bytes, _host: Bytes) -> Option<Event> {
         let line = String::from_utf8_lossy(line.as_ref());
-        parse_event(&line)
+        parse_event(&line, self.sketch_relative_accuracy)
     }
 }
 
@@ -229,6 +277,24 @@ mod test {
         crate::test_util::test_generate_config::<StatsdConfig>();
     }
 
+    #[test]
+    fn parse_event_into_sketch() {
+        let event = parse_event("glork:320|ms|@0.1", Some(0.01)).unwrap();
+        assert_eq!(
+            event.as_metric().value(),
+            &metric::MetricValue::Sketch {
+                sketch: metric::DDSketch::from_samples(0.01, &vector_core::samples![0.32 => 10]),
+                statistic: metric::StatisticKind::Histogram,
+            }
+        );
+
+        let event = parse_event("foo:1|c", Some(0.01)).unwrap();
+        assert_eq!(
+            event.as_metric().value(),
+            &metric::MetricValue::Counter { value: 1.0 }
+        );
+    }
+
     fn parse_count(lines: &[&str], prefix: &str) -> usize {
         lines
             .iter()
@@ -279,6 +345,7 @@ mod test {
         let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
         let config = StatsdConfig::Unix(UnixConfig {
             path: in_path.clone(),
+            sketch_relative_accuracy: None,
         });
         let (sender, mut receiver) = mpsc::channel(200);
         tokio::spawn(async move {
@@ -314,6 +381,7 @@ mod test {
                 buckets: vec![1.0, 2.0, 4.0],
                 quantiles: vec![],
                 flush_period_secs: 1,
+                ..Default::default()
             },
         );
 
@@ -10,13 +10,15 @@ use tokio_util::codec::LinesCodec;
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct UnixConfig {
     pub path: PathBuf,
-}
-
-fn build_event(_: &str, _: Option<Bytes>, line: &str) -> Option<Event> {
-    super::parse_event(line)
+    pub sketch_relative_accuracy: Option<f64>,
 }
 
 pub fn statsd_unix(config: UnixConfig, shutdown: ShutdownSignal, out: Pipeline) -> Source {
+    let sketch_relative_accuracy = config.sketch_relative_accuracy;
+    let build_event = move |_: &str, _: Option<Bytes>, line: &str| -> Option<Event> {
+        super::parse_event(line, sketch_relative_accuracy)
+    };
+
     build_unix_stream_source(
         config.path,
         LinesCodec::new(),
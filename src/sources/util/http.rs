@@ -22,6 +22,9 @@ use warp::{
     Filter,
 };
 
+#[cfg(feature = "sources-websocket")]
+use crate::internal_events::{WsEventsReceived, WsReceiveError};
+
 #[cfg(any(feature = "sources-http", feature = "sources-heroku_logs"))]
 pub(crate) fn add_query_parameters(
     mut events: Vec<Event>,
@@ -336,3 +339,137 @@ async fn handle_batch_status(
         },
     }
 }
+
+/// A server accepting websocket connections, which turns each text or binary
+/// frame received into events.
+#[cfg(feature = "sources-websocket")]
+pub trait WebSocketSource: Clone + Send + Sync + 'static {
+    fn build_events(&self, frame: Bytes) -> Result<Vec<Event>, ErrorMessage>;
+
+    fn run(
+        self,
+        address: SocketAddr,
+        path: &str,
+        tls: &Option<TlsConfig>,
+        auth: &Option<HttpSourceAuthConfig>,
+        cx: SourceContext,
+    ) -> crate::Result<crate::sources::Source> {
+        let tls = MaybeTlsSettings::from_config(tls, true)?;
+        let auth = HttpSourceAuth::try_from(auth.as_ref())?;
+        let path = path.to_owned();
+        let out = cx.out;
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(async move {
+            let span = crate::trace::current_span();
+            let mut filter: BoxedFilter<()> = warp::any().boxed();
+            for s in path.split('/').filter(|&x| !x.is_empty()) {
+                filter = filter.and(warp::path(s.to_string())).boxed()
+            }
+            // Upgraded connections outlive the server's graceful shutdown, so
+            // each of them watches for shutdown itself.
+            let connection_shutdown = shutdown.clone();
+            let svc = filter
+                .and(warp::path::end())
+                .and(warp::header::optional::<String>("authorization"))
+                .and(warp::ws())
+                .and_then(move |auth_header, ws: warp::ws::Ws| {
+                    let upgrade = auth.is_valid(&auth_header).map(|()| {
+                        let source = self.clone();
+                        let out = out.clone();
+                        let shutdown = connection_shutdown.clone();
+                        ws.on_upgrade(move |socket| handle_socket(source, socket, out, shutdown))
+                    });
+
+                    async move {
+                        upgrade.map_err(|error| {
+                            emit!(HttpBadRequest {
+                                error_code: error.code,
+                                error_message: error.message.as_str(),
+                            });
+                            warp::reject::custom(error)
+                        })
+                    }
+                })
+                .with(warp::trace(move |_info| span.clone()));
+
+            let routes = svc.recover(|r: Rejection| async move {
+                if let Some(e_msg) = r.find::<ErrorMessage>() {
+                    let json = warp::reply::json(e_msg);
+                    Ok(warp::reply::with_status(
+                        json,
+                        StatusCode::from_u16(e_msg.code)
+                            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                    ))
+                } else {
+                    Err(r)
+                }
+            });
+
+            info!(message = "Building WebSocket server.", address = %address);
+
+            let listener = tls.bind(&address).await.unwrap();
+            warp::serve(routes)
+                .serve_incoming_with_graceful_shutdown(
+                    listener.accept_stream(),
+                    shutdown.map(|_| ()),
+                )
+                .await;
+            Ok(())
+        }))
+    }
+}
+
+#[cfg(feature = "sources-websocket")]
+async fn handle_socket(
+    source: impl WebSocketSource,
+    mut socket: warp::ws::WebSocket,
+    mut out: Pipeline,
+    mut shutdown: crate::shutdown::ShutdownSignal,
+) {
+    loop {
+        let message = tokio::select! {
+            _ = &mut shutdown => break,
+            message = socket.next() => message,
+        };
+        let message = match message {
+            Some(Ok(message)) => message,
+            Some(Err(error)) => {
+                emit!(WsReceiveError { error });
+                break;
+            }
+            None => break,
+        };
+
+        if message.is_close() {
+            break;
+        }
+        // Pings are answered by warp itself.
+        if !message.is_text() && !message.is_binary() {
+            continue;
+        }
+
+        let frame = Bytes::from(message.into_bytes());
+        let byte_size = frame.len();
+        match source.build_events(frame) {
+            Ok(events) => {
+                emit!(WsEventsReceived {
+                    count: events.len(),
+                    byte_size,
+                });
+                if let Err(error) = out
+                    .send_all(&mut futures::stream::iter(events).map(Ok))
+                    .await
+                {
+                    error!(message = "Failed to forward events, downstream is closed.", %error);
+                    break;
+                }
+            }
+            Err(error) => emit!(HttpBadRequest {
+                error_code: error.code,
+                error_message: error.message.as_str(),
+            }),
+        }
+    }
+
+    let _ = socket.close().await;
+}
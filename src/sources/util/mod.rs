@@ -1,7 +1,14 @@
-#[cfg(any(feature = "sources-http"))]
+#[cfg(any(feature = "sources-http", feature = "sources-websocket"))]
 mod body_decoding;
 mod encoding_config;
-#[cfg(any(feature = "sources-file", feature = "sources-kafka"))]
+#[cfg(any(
+    feature = "sources-amqp",
+    feature = "sources-file",
+    feature = "sources-kafka",
+    feature = "sources-mqtt",
+    feature = "sources-nats",
+    feature = "sources-redis",
+))]
 pub(crate) mod finalizer;
 #[cfg(all(unix, feature = "sources-dnstap"))]
 pub mod framestream;
@@ -15,12 +22,14 @@ mod unix_datagram;
 #[cfg(all(unix, feature = "sources-utils-unix"))]
 mod unix_stream;
 
-#[cfg(any(feature = "sources-http"))]
+#[cfg(any(feature = "sources-http", feature = "sources-websocket"))]
 pub(crate) use self::body_decoding::{decode_body, Encoding};
 #[cfg(any(feature = "sources-http", feature = "sources-heroku_logs"))]
 pub(crate) use self::http::add_query_parameters;
 #[cfg(feature = "sources-prometheus")]
 pub(crate) use self::http::decode;
+#[cfg(feature = "sources-websocket")]
+pub(crate) use self::http::WebSocketSource;
 #[cfg(feature = "sources-utils-http")]
 pub(crate) use self::http::{ErrorMessage, HttpSource, HttpSourceAuthConfig};
 pub use encoding_config::EncodingConfig;
@@ -0,0 +1,218 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::Event,
+    sources::util::{
+        decode_body, Encoding as BodyEncoding, ErrorMessage, HttpSourceAuthConfig, WebSocketSource,
+    },
+    tls::TlsConfig,
+};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketConfig {
+    address: SocketAddr,
+    #[serde(default = "default_path")]
+    path: String,
+    #[serde(default)]
+    encoding: Encoding,
+    tls: Option<TlsConfig>,
+    auth: Option<HttpSourceAuthConfig>,
+}
+
+/// How the payload of each frame is decoded.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative, Copy)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    /// The whole frame becomes the message of a single event.
+    #[derivative(Default)]
+    Text,
+    /// The frame holds a JSON object, or an array of them, each of which
+    /// becomes an event.
+    Json,
+}
+
+inventory::submit! {
+    SourceDescription::new::<WebSocketConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "0.0.0.0:8080".parse().unwrap(),
+            path: default_path(),
+            encoding: Encoding::default(),
+            tls: None,
+            auth: None,
+        })
+        .unwrap()
+    }
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+#[derive(Clone)]
+struct WebSocketFrameSource {
+    encoding: Encoding,
+}
+
+impl WebSocketSource for WebSocketFrameSource {
+    fn build_events(&self, frame: Bytes) -> Result<Vec<Event>, ErrorMessage> {
+        let encoding = match self.encoding {
+            Encoding::Text => BodyEncoding::Binary,
+            Encoding::Json => BodyEncoding::Json,
+        };
+
+        decode_body(frame, encoding).map(|mut events| {
+            // Add source type
+            let key = log_schema().source_type_key();
+            for event in &mut events {
+                event.as_mut_log().try_insert(key, Bytes::from("websocket"));
+            }
+            events
+        })
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SourceConfig for WebSocketConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let source = WebSocketFrameSource {
+            encoding: self.encoding,
+        };
+        source.run(self.address, &self.path, &self.tls, &self.auth, cx)
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "websocket"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{collect_n, next_addr, trace_init, wait_for_tcp},
+        Pipeline,
+    };
+    use futures::{channel::mpsc, SinkExt};
+    use tokio_tungstenite::tungstenite::{
+        client::IntoClientRequest, error::Error as WsError, http::StatusCode, protocol::Message,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketConfig>();
+    }
+
+    async fn source(
+        encoding: Encoding,
+        auth: Option<HttpSourceAuthConfig>,
+    ) -> (mpsc::Receiver<Event>, SocketAddr) {
+        let (sender, recv) = Pipeline::new_test();
+        let address = next_addr();
+        tokio::spawn(async move {
+            WebSocketConfig {
+                address,
+                path: "/events".into(),
+                encoding,
+                tls: None,
+                auth,
+            }
+            .build(SourceContext::new_test(sender))
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    #[tokio::test]
+    async fn websocket_text_frames() {
+        trace_init();
+
+        let (rx, address) = source(Encoding::Text, None).await;
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/events", address))
+            .await
+            .unwrap();
+        ws.send(Message::text("first\nline")).await.unwrap();
+        ws.send(Message::binary(b"second".to_vec())).await.unwrap();
+
+        let events = collect_n(rx, 2).await;
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "first\nline".into()
+        );
+        assert_eq!(
+            events[1].as_log()[log_schema().message_key()],
+            "second".into()
+        );
+        assert_eq!(
+            events[0].as_log()[log_schema().source_type_key()],
+            "websocket".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn websocket_json_frames() {
+        trace_init();
+
+        let (rx, address) = source(Encoding::Json, None).await;
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/events", address))
+            .await
+            .unwrap();
+        ws.send(Message::text(r#"[{"key":"a"},{"key":"b"}]"#))
+            .await
+            .unwrap();
+
+        let events = collect_n(rx, 2).await;
+        assert_eq!(events[0].as_log()["key"], "a".into());
+        assert_eq!(events[1].as_log()["key"], "b".into());
+    }
+
+    #[tokio::test]
+    async fn websocket_requires_auth() {
+        trace_init();
+
+        let auth = HttpSourceAuthConfig {
+            username: "user".into(),
+            password: "pass".into(),
+        };
+        let (_rx, address) = source(Encoding::Text, Some(auth)).await;
+
+        let request = format!("ws://{}/events", address)
+            .into_client_request()
+            .unwrap();
+        match tokio_tungstenite::connect_async(request).await {
+            Err(WsError::Http(response)) => assert_eq!(response.status(), StatusCode::UNAUTHORIZED),
+            _ => panic!("Connection without credentials was accepted."),
+        }
+
+        let mut request = format!("ws://{}/events", address)
+            .into_client_request()
+            .unwrap();
+        request
+            .headers_mut()
+            .insert("authorization", "Basic dXNlcjpwYXNz".parse().unwrap());
+        assert!(tokio_tungstenite::connect_async(request).await.is_ok());
+    }
+}
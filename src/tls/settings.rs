@@ -175,6 +175,33 @@ impl TlsSettings {
         Ok(())
     }
 
+    /// The identity as a DER encoded PKCS#12 archive and its password, for
+    /// clients that set up their TLS connections themselves.
+    #[cfg(any(
+        feature = "sources-amqp",
+        feature = "sinks-amqp",
+        feature = "sources-mqtt",
+        feature = "sinks-mqtt"
+    ))]
+    pub(crate) fn identity_pkcs12_der(&self) -> Option<(Vec<u8>, String)> {
+        self.identity
+            .as_ref()
+            .map(|identity| (identity.0.clone(), identity.1.clone()))
+    }
+
+    /// The configured certificate authorities, PEM encoded.
+    #[cfg(any(
+        feature = "sources-amqp",
+        feature = "sinks-amqp",
+        feature = "sources-mqtt",
+        feature = "sinks-mqtt"
+    ))]
+    pub(crate) fn authorities_pem(
+        &self,
+    ) -> impl Iterator<Item = std::result::Result<Vec<u8>, openssl::error::ErrorStack>> + '_ {
+        self.authorities.iter().map(|authority| authority.to_pem())
+    }
+
     pub fn apply_connect_configuration(&self, connection: &mut ConnectConfiguration) {
         connection.set_verify_hostname(self.verify_hostname);
     }
@@ -8,7 +8,8 @@ use async_stream::stream;
 use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
+    mem::discriminant,
     pin::Pin,
     time::Duration,
 };
@@ -19,6 +20,39 @@ pub struct AggregateConfig {
     /// The interval between flushes in milliseconds.
     #[serde(default = "default_interval_ms")]
     pub interval_ms: u64,
+    /// When set, distributions are converted to sketches with this relative
+    /// accuracy, so their size no longer grows with the number of samples.
+    pub sketch_relative_accuracy: Option<f64>,
+    /// Only keep these tags, aggregating the series that differ in the others.
+    pub group_by: Option<Vec<String>>,
+    /// Drop these tags, aggregating the series that differ only in them.
+    pub drop_tags: Option<Vec<String>>,
+    /// How the values of counters and gauges are combined.
+    pub aggregations: Aggregations,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct Aggregations {
+    pub counter: AggregationFunction,
+    pub gauge: AggregationFunction,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationFunction {
+    Sum,
+    Max,
+    Min,
+    Mean,
+    Last,
+    Count,
+}
+
+impl Default for AggregationFunction {
+    fn default() -> Self {
+        Self::Sum
+    }
 }
 
 fn default_interval_ms() -> u64 {
@@ -51,55 +85,315 @@ impl TransformConfig for AggregateConfig {
     }
 }
 
-type MetricEntry = (metric::MetricData, EventMetadata);
-
 //------------------------------------------------------------------------------
 
+/// Which tags are kept in the aggregated series.
+#[derive(Debug)]
+enum TagFilter {
+    All,
+    Keep(HashSet<String>),
+    Drop(HashSet<String>),
+}
+
+impl TagFilter {
+    fn keeps(&self, key: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Keep(tags) => tags.contains(key),
+            Self::Drop(tags) => !tags.contains(key),
+        }
+    }
+
+    fn apply(&self, series: &metric::MetricSeries) -> metric::MetricSeries {
+        if let Self::All = self {
+            return series.clone();
+        }
+
+        let tags = series
+            .tags
+            .iter()
+            .flatten()
+            .filter(|(key, _)| self.keeps(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<metric::MetricTags>();
+        metric::MetricSeries {
+            name: series.name.clone(),
+            tags: if tags.is_empty() { None } else { Some(tags) },
+        }
+    }
+}
+
+/// The running aggregate of the values of counters or gauges.
+#[derive(Clone, Copy, Debug)]
+struct Accumulator {
+    sum: f64,
+    min: f64,
+    max: f64,
+    last: f64,
+    count: u32,
+}
+
+impl Accumulator {
+    const fn new(value: f64) -> Self {
+        Self {
+            sum: value,
+            min: value,
+            max: value,
+            last: value,
+            count: 1,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.last = value;
+        self.count += 1;
+    }
+
+    fn result(&self, function: AggregationFunction) -> f64 {
+        match function {
+            AggregationFunction::Sum => self.sum,
+            AggregationFunction::Max => self.max,
+            AggregationFunction::Min => self.min,
+            AggregationFunction::Mean => self.sum / f64::from(self.count),
+            AggregationFunction::Last => self.last,
+            AggregationFunction::Count => f64::from(self.count),
+        }
+    }
+}
+
+fn scalar(value: &metric::MetricValue) -> Option<f64> {
+    match value {
+        metric::MetricValue::Counter { value } | metric::MetricValue::Gauge { value } => {
+            Some(*value)
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+enum GroupState {
+    /// Incremental metrics are combined as they arrive. The values of
+    /// counters and gauges are also accumulated, so they can be combined
+    /// with the configured function when flushed.
+    Incremental {
+        data: metric::MetricData,
+        values: Option<Accumulator>,
+    },
+    /// The latest absolute metric of each series in the group, with the
+    /// order it was received in. Series may differ in value type, only those
+    /// of the same type as the latest metric are merged.
+    Absolute(HashMap<metric::MetricSeries, (u64, metric::MetricData)>),
+}
+
+#[derive(Debug)]
+struct Group {
+    state: GroupState,
+    metadata: EventMetadata,
+}
+
+impl Group {
+    fn new(
+        series: metric::MetricSeries,
+        data: metric::MetricData,
+        metadata: EventMetadata,
+        sequence: u64,
+    ) -> Self {
+        let state = match data.kind {
+            metric::MetricKind::Incremental => GroupState::Incremental {
+                values: scalar(&data.value).map(Accumulator::new),
+                data,
+            },
+            metric::MetricKind::Absolute => {
+                let mut latest = HashMap::new();
+                latest.insert(series, (sequence, data));
+                GroupState::Absolute(latest)
+            }
+        };
+        Self { state, metadata }
+    }
+
+    /// Adds a metric to the group. The metric is handed back if its kind, or
+    /// for incremental metrics its value type, doesn't match the metrics
+    /// already in the group.
+    fn record(
+        &mut self,
+        series: metric::MetricSeries,
+        data: metric::MetricData,
+        sequence: u64,
+    ) -> Result<(), (metric::MetricSeries, metric::MetricData)> {
+        match &mut self.state {
+            GroupState::Incremental {
+                data: existing,
+                values,
+            } => {
+                let matches = data.kind == metric::MetricKind::Incremental
+                    && discriminant(&existing.value) == discriminant(&data.value)
+                    && existing.update(&data);
+                if !matches {
+                    return Err((series, data));
+                }
+                if let (Some(values), Some(value)) = (values, scalar(&data.value)) {
+                    values.push(value);
+                }
+            }
+            GroupState::Absolute(latest) => {
+                if data.kind != metric::MetricKind::Absolute {
+                    return Err((series, data));
+                }
+                // A series changing value type only replaces its own latest
+                // metric, the other series are left to be merged if they
+                // change to it too.
+                let mismatched = latest.values().any(|(_, existing)| {
+                    discriminant(&existing.value) != discriminant(&data.value)
+                });
+                if mismatched {
+                    emit!(AggregateUpdateFailed);
+                }
+                latest.insert(series, (sequence, data));
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self, aggregations: &Aggregations) -> (metric::MetricData, EventMetadata) {
+        let function = |value: &metric::MetricValue| match value {
+            metric::MetricValue::Counter { .. } => aggregations.counter,
+            _ => aggregations.gauge,
+        };
+
+        let data = match self.state {
+            GroupState::Incremental { mut data, values } => {
+                if let Some(values) = values {
+                    let result = values.result(function(&data.value));
+                    data.value = with_scalar(data.value, result);
+                }
+                data
+            }
+            GroupState::Absolute(latest) => {
+                let mut latest = latest
+                    .into_iter()
+                    .map(|(_, entry)| entry)
+                    .collect::<Vec<_>>();
+                latest.sort_by_key(|(sequence, _)| *sequence);
+                // Groups are only created with a first metric.
+                let value_type = latest
+                    .last()
+                    .map(|(_, data)| discriminant(&data.value))
+                    .expect("empty aggregate group");
+                let mut latest = latest
+                    .into_iter()
+                    .map(|(_, data)| data)
+                    .filter(|data| discriminant(&data.value) == value_type);
+                let mut merged = latest.next().expect("empty aggregate group");
+
+                if let Some(value) = scalar(&merged.value) {
+                    let mut values = Accumulator::new(value);
+                    for data in latest {
+                        values.push(scalar(&data.value).unwrap_or_default());
+                        merged.timestamp = merged.timestamp.max(data.timestamp);
+                    }
+                    let result = values.result(function(&merged.value));
+                    merged.value = with_scalar(merged.value, result);
+                } else {
+                    for data in latest {
+                        if !merged.update(&data) {
+                            emit!(AggregateUpdateFailed);
+                            merged = data;
+                        }
+                    }
+                }
+                merged
+            }
+        };
+        (data, self.metadata)
+    }
+}
+
+fn with_scalar(value: metric::MetricValue, scalar: f64) -> metric::MetricValue {
+    match value {
+        metric::MetricValue::Counter { .. } => metric::MetricValue::Counter { value: scalar },
+        metric::MetricValue::Gauge { .. } => metric::MetricValue::Gauge { value: scalar },
+        value => value,
+    }
+}
+
 #[derive(Debug)]
 pub struct Aggregate {
     interval: Duration,
-    map: HashMap<metric::MetricSeries, MetricEntry>,
+    sketch_relative_accuracy: Option<f64>,
+    tags: TagFilter,
+    aggregations: Aggregations,
+    map: HashMap<metric::MetricSeries, Group>,
+    sequence: u64,
 }
 
 impl Aggregate {
     pub fn new(config: &AggregateConfig) -> crate::Result<Self> {
+        if let Some(relative_accuracy) = config.sketch_relative_accuracy {
+            if !(relative_accuracy > 0.0 && relative_accuracy < 1.0) {
+                return Err("`sketch_relative_accuracy` must be in range (0.0, 1.0)".into());
+            }
+        }
+
+        let tags = match (&config.group_by, &config.drop_tags) {
+            (Some(_), Some(_)) => {
+                return Err("only one of `group_by` and `drop_tags` can be set".into());
+            }
+            (Some(group_by), None) => TagFilter::Keep(group_by.iter().cloned().collect()),
+            (None, Some(drop_tags)) => TagFilter::Drop(drop_tags.iter().cloned().collect()),
+            (None, None) => TagFilter::All,
+        };
+
         Ok(Self {
             interval: Duration::from_millis(config.interval_ms),
+            sketch_relative_accuracy: config.sketch_relative_accuracy,
+            tags,
+            aggregations: config.aggregations.clone(),
             map: HashMap::new(),
+            sequence: 0,
         })
     }
 
     fn record(&mut self, event: Event) {
-        let (series, data, metadata) = event.into_metric().into_parts();
-
-        match data.kind {
-            metric::MetricKind::Incremental => match self.map.entry(series) {
-                Entry::Occupied(mut entry) => {
-                    let existing = entry.get_mut();
-                    // In order to update (add) the new and old kind's must match
-                    if existing.0.kind == data.kind && existing.0.update(&data) {
-                        existing.1.merge(metadata);
-                    } else {
+        let (series, mut data, metadata) = event.into_metric().into_parts();
+
+        if let Some(relative_accuracy) = self.sketch_relative_accuracy {
+            if let metric::MetricValue::Distribution { samples, statistic } = data.value {
+                data.value = metric::MetricValue::Sketch {
+                    sketch: metric::DDSketch::from_samples(relative_accuracy, &samples),
+                    statistic,
+                };
+            }
+        }
+
+        self.sequence += 1;
+        match self.map.entry(self.tags.apply(&series)) {
+            Entry::Occupied(mut entry) => {
+                let group = entry.get_mut();
+                // In order to update (add) the new and old kind's must match
+                match group.record(series, data, self.sequence) {
+                    Ok(()) => group.metadata.merge(metadata),
+                    Err((series, data)) => {
                         emit!(AggregateUpdateFailed);
-                        *existing = (data, metadata);
+                        *group = Group::new(series, data, metadata, self.sequence);
                     }
                 }
-                Entry::Vacant(entry) => {
-                    entry.insert((data, metadata));
-                }
-            },
-            metric::MetricKind::Absolute => {
-                // Always replace/store
-                self.map.insert(series, (data, metadata));
             }
-        };
+            Entry::Vacant(entry) => {
+                entry.insert(Group::new(series, data, metadata, self.sequence));
+            }
+        }
 
         emit!(AggregateEventRecorded);
     }
 
     fn flush_into(&mut self, output: &mut Vec<Event>) {
-        for (series, entry) in self.map.drain() {
-            let metric = metric::Metric::from_parts(series, entry.0, entry.1);
+        for (series, group) in self.map.drain() {
+            let (data, metadata) = group.finish(&self.aggregations);
+            let metric = metric::Metric::from_parts(series, data, metadata);
             output.push(Event::Metric(metric));
         }
 
@@ -167,6 +461,7 @@ mod tests {
     fn incremental() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -237,6 +532,7 @@ mod tests {
     fn absolute() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -302,6 +598,7 @@ mod tests {
     fn conflicting_value_type() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -359,6 +656,7 @@ mod tests {
     fn conflicting_kinds() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -409,6 +707,333 @@ mod tests {
         assert_eq!(&summed, &out[0]);
     }
 
+    #[test]
+    fn distributions_to_sketches() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            sketch_relative_accuracy: Some(0.01),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let distribution_1 = make_metric(
+            "the-thing",
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 3, 2.0 => 1],
+                statistic: metric::StatisticKind::Histogram,
+            },
+        );
+        let distribution_2 = make_metric(
+            "the-thing",
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Distribution {
+                samples: vector_core::samples![2.0 => 1, 3.0 => 2],
+                statistic: metric::StatisticKind::Histogram,
+            },
+        );
+        let merged = make_metric(
+            "the-thing",
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Sketch {
+                sketch: metric::DDSketch::from_samples(
+                    0.01,
+                    &vector_core::samples![1.0 => 3, 2.0 => 2, 3.0 => 2],
+                ),
+                statistic: metric::StatisticKind::Histogram,
+            },
+        );
+
+        agg.record(distribution_1);
+        agg.record(distribution_2);
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(1, out.len());
+        assert_eq!(&merged, &out[0]);
+    }
+
+    #[test]
+    fn invalid_sketch_relative_accuracy() {
+        assert!(Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            sketch_relative_accuracy: Some(1.5),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    fn make_tagged_metric(
+        name: &'static str,
+        tags: &[(&str, &str)],
+        kind: metric::MetricKind,
+        value: metric::MetricValue,
+    ) -> Event {
+        let tags = tags
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        Event::Metric(Metric::new(name, kind, value).with_tags(Some(tags)))
+    }
+
+    fn flush_sorted(agg: &mut Aggregate) -> Vec<Event> {
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        out.sort_by_key(|event| format!("{:?}", event.as_metric().series()));
+        out
+    }
+
+    #[test]
+    fn group_by_tags() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            group_by: Some(vec!["region".into()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let counter = |host, region, value| {
+            make_tagged_metric(
+                "requests",
+                &[("host", host), ("region", region)],
+                metric::MetricKind::Incremental,
+                metric::MetricValue::Counter { value },
+            )
+        };
+        agg.record(counter("a", "eu", 1.0));
+        agg.record(counter("b", "eu", 2.0));
+        agg.record(counter("a", "eu", 3.0));
+        agg.record(counter("c", "us", 4.0));
+
+        let out = flush_sorted(&mut agg);
+        assert_eq!(
+            out,
+            vec![
+                make_tagged_metric(
+                    "requests",
+                    &[("region", "eu")],
+                    metric::MetricKind::Incremental,
+                    metric::MetricValue::Counter { value: 6.0 },
+                ),
+                make_tagged_metric(
+                    "requests",
+                    &[("region", "us")],
+                    metric::MetricKind::Incremental,
+                    metric::MetricValue::Counter { value: 4.0 },
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn drop_tags() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            drop_tags: Some(vec!["host".into()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        agg.record(make_tagged_metric(
+            "requests",
+            &[("host", "a"), ("region", "eu")],
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Counter { value: 1.0 },
+        ));
+        agg.record(make_tagged_metric(
+            "requests",
+            &[("host", "b")],
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Counter { value: 2.0 },
+        ));
+
+        let out = flush_sorted(&mut agg);
+        assert_eq!(
+            out,
+            vec![
+                make_metric(
+                    "requests",
+                    metric::MetricKind::Incremental,
+                    metric::MetricValue::Counter { value: 2.0 },
+                ),
+                make_tagged_metric(
+                    "requests",
+                    &[("region", "eu")],
+                    metric::MetricKind::Incremental,
+                    metric::MetricValue::Counter { value: 1.0 },
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn group_by_and_drop_tags_conflict() {
+        assert!(Aggregate::new(&AggregateConfig {
+            group_by: Some(vec!["region".into()]),
+            drop_tags: Some(vec!["host".into()]),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn incremental_aggregations() {
+        let aggregate = |function, values: &[f64]| {
+            let mut agg = Aggregate::new(&AggregateConfig {
+                interval_ms: 1000_u64,
+                drop_tags: Some(vec!["host".into()]),
+                aggregations: Aggregations {
+                    counter: AggregationFunction::Sum,
+                    gauge: function,
+                },
+                ..Default::default()
+            })
+            .unwrap();
+            for (host, value) in ["a", "b", "a"].iter().zip(values) {
+                agg.record(make_tagged_metric(
+                    "load",
+                    &[("host", *host)],
+                    metric::MetricKind::Incremental,
+                    metric::MetricValue::Gauge { value: *value },
+                ));
+            }
+            let out = flush_sorted(&mut agg);
+            assert_eq!(1, out.len());
+            assert_eq!(metric::MetricKind::Incremental, out[0].as_metric().kind());
+            match out[0].as_metric().value() {
+                metric::MetricValue::Gauge { value } => *value,
+                value => panic!("unexpected value {:?}", value),
+            }
+        };
+
+        let values = [3.0, 1.0, 2.0];
+        assert_eq!(aggregate(AggregationFunction::Sum, &values), 6.0);
+        assert_eq!(aggregate(AggregationFunction::Max, &values), 3.0);
+        assert_eq!(aggregate(AggregationFunction::Min, &values), 1.0);
+        assert_eq!(aggregate(AggregationFunction::Mean, &values), 2.0);
+        assert_eq!(aggregate(AggregationFunction::Last, &values), 2.0);
+        assert_eq!(aggregate(AggregationFunction::Count, &values), 3.0);
+    }
+
+    #[test]
+    fn absolute_aggregations() {
+        let aggregate = |function| {
+            let mut agg = Aggregate::new(&AggregateConfig {
+                interval_ms: 1000_u64,
+                group_by: Some(vec![]),
+                aggregations: Aggregations {
+                    counter: AggregationFunction::Sum,
+                    gauge: function,
+                },
+                ..Default::default()
+            })
+            .unwrap();
+            // The later value of host `a` replaces the earlier one
+            for (host, value) in &[("a", 10.0), ("b", 4.0), ("c", 6.0), ("a", 2.0)] {
+                agg.record(make_tagged_metric(
+                    "memory",
+                    &[("host", *host)],
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::Gauge { value: *value },
+                ));
+            }
+            let out = flush_sorted(&mut agg);
+            assert_eq!(1, out.len());
+            assert_eq!(metric::MetricKind::Absolute, out[0].as_metric().kind());
+            assert_eq!(None, out[0].as_metric().tags());
+            match out[0].as_metric().value() {
+                metric::MetricValue::Gauge { value } => *value,
+                value => panic!("unexpected value {:?}", value),
+            }
+        };
+
+        assert_eq!(aggregate(AggregationFunction::Sum), 12.0);
+        assert_eq!(aggregate(AggregationFunction::Max), 6.0);
+        assert_eq!(aggregate(AggregationFunction::Min), 2.0);
+        assert_eq!(aggregate(AggregationFunction::Mean), 4.0);
+        assert_eq!(aggregate(AggregationFunction::Last), 2.0);
+        assert_eq!(aggregate(AggregationFunction::Count), 3.0);
+    }
+
+    #[test]
+    fn absolute_conflicting_value_type_across_series() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            group_by: Some(vec![]),
+            aggregations: Aggregations {
+                counter: AggregationFunction::Sum,
+                gauge: AggregationFunction::Sum,
+            },
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Host `b` changing value type doesn't reset host `a`, and isn't
+        // merged while it doesn't match the latest metric
+        for (host, value) in &[
+            ("a", metric::MetricValue::Gauge { value: 10.0 }),
+            ("b", metric::MetricValue::Counter { value: 1.0 }),
+            ("c", metric::MetricValue::Counter { value: 2.0 }),
+            ("b", metric::MetricValue::Gauge { value: 4.0 }),
+        ] {
+            agg.record(make_tagged_metric(
+                "memory",
+                &[("host", *host)],
+                metric::MetricKind::Absolute,
+                value.clone(),
+            ));
+        }
+
+        let out = flush_sorted(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(
+            &metric::MetricValue::Gauge { value: 14.0 },
+            out[0].as_metric().value()
+        );
+    }
+
+    #[test]
+    fn absolute_distributions_across_series() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            drop_tags: Some(vec!["host".into()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        agg.record(make_tagged_metric(
+            "latency",
+            &[("host", "a")],
+            metric::MetricKind::Absolute,
+            metric::MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 1],
+                statistic: metric::StatisticKind::Histogram,
+            },
+        ));
+        agg.record(make_tagged_metric(
+            "latency",
+            &[("host", "b")],
+            metric::MetricKind::Absolute,
+            metric::MetricValue::Distribution {
+                samples: vector_core::samples![2.0 => 1],
+                statistic: metric::StatisticKind::Histogram,
+            },
+        ));
+
+        let out = flush_sorted(&mut agg);
+        assert_eq!(1, out.len());
+        match out[0].as_metric().value() {
+            metric::MetricValue::Distribution { samples, .. } => {
+                let mut values = samples
+                    .iter()
+                    .map(|sample| sample.value)
+                    .collect::<Vec<_>>();
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                assert_eq!(values, vec![1.0, 2.0]);
+            }
+            value => panic!("unexpected value {:?}", value),
+        }
+    }
+
     #[tokio::test]
     async fn transform_shutdown() {
         let agg = toml::from_str::<AggregateConfig>(
@@ -0,0 +1,561 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{metric::MetricSeries, Event, Metric},
+    internal_events::MetricRelabelEventDropped,
+    transforms::{FunctionTransform, Transform},
+};
+use md5::{Digest, Md5};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::convert::TryInto;
+
+/// The label standing for the metric name, as in Prometheus.
+const NAME_LABEL: &str = "__name__";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MetricRelabelConfig {
+    pub relabel_configs: Vec<RelabelConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelConfig {
+    #[serde(default)]
+    pub source_labels: Vec<String>,
+    #[serde(default = "default_separator")]
+    pub separator: String,
+    pub target_label: Option<String>,
+    #[serde(default = "default_regex")]
+    pub regex: String,
+    pub modulus: Option<u64>,
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+    #[serde(default)]
+    pub action: RelabelAction,
+}
+
+fn default_separator() -> String {
+    ";".into()
+}
+
+fn default_regex() -> String {
+    "(.*)".into()
+}
+
+fn default_replacement() -> String {
+    "$1".into()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    Replace,
+    Keep,
+    Drop,
+    HashMod,
+    LabelMap,
+    LabelDrop,
+    LabelKeep,
+}
+
+impl Default for RelabelAction {
+    fn default() -> Self {
+        Self::Replace
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid regular expression {:?}: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+    #[snafu(display("`target_label` is required by the {:?} action", action))]
+    MissingTargetLabel { action: RelabelAction },
+    #[snafu(display("`modulus` must be set to a positive number by the hashmod action"))]
+    MissingModulus,
+}
+
+inventory::submit! {
+    TransformDescription::new::<MetricRelabelConfig>("metric_relabel")
+}
+
+impl GenerateConfig for MetricRelabelConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            relabel_configs: Vec::new(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "metric_relabel")]
+impl TransformConfig for MetricRelabelConfig {
+    async fn build(&self, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        let rules = self
+            .relabel_configs
+            .iter()
+            .map(RelabelRule::new)
+            .collect::<Result<_, _>>()?;
+        Ok(Transform::function(MetricRelabel { rules }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "metric_relabel"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RelabelRule {
+    source_labels: Vec<String>,
+    separator: String,
+    target_label: String,
+    regex: Regex,
+    modulus: u64,
+    replacement: String,
+    action: RelabelAction,
+}
+
+impl RelabelRule {
+    fn new(config: &RelabelConfig) -> Result<Self, BuildError> {
+        // Like Prometheus, the regular expression has to match the whole value.
+        let regex = Regex::new(&format!("^(?:{})$", config.regex)).context(InvalidRegex {
+            regex: config.regex.clone(),
+        })?;
+
+        let target_label = match (config.action, &config.target_label) {
+            (RelabelAction::Replace, None) | (RelabelAction::HashMod, None) => {
+                return Err(BuildError::MissingTargetLabel {
+                    action: config.action,
+                });
+            }
+            (_, target_label) => target_label.clone().unwrap_or_default(),
+        };
+
+        let modulus = match (config.action, config.modulus) {
+            (RelabelAction::HashMod, None) | (RelabelAction::HashMod, Some(0)) => {
+                return Err(BuildError::MissingModulus);
+            }
+            (_, modulus) => modulus.unwrap_or_default(),
+        };
+
+        Ok(Self {
+            source_labels: config.source_labels.clone(),
+            separator: config.separator.clone(),
+            target_label,
+            regex,
+            modulus,
+            replacement: config.replacement.clone(),
+            action: config.action,
+        })
+    }
+
+    /// Applies the rule to the series, returning `false` if the metric is
+    /// to be dropped.
+    fn apply(&self, series: &mut MetricSeries) -> bool {
+        match self.action {
+            RelabelAction::Replace => {
+                let value = self.source_value(series);
+                if let Some(captures) = self.regex.captures(&value) {
+                    let mut target = String::new();
+                    captures.expand(&self.target_label, &mut target);
+                    let mut result = String::new();
+                    captures.expand(&self.replacement, &mut result);
+
+                    if !is_valid_label(&target) || result.is_empty() {
+                        remove_label(series, &self.target_label);
+                    } else {
+                        set_label(series, target, result);
+                    }
+                }
+                true
+            }
+            RelabelAction::Keep => self.regex.is_match(&self.source_value(series)),
+            RelabelAction::Drop => !self.regex.is_match(&self.source_value(series)),
+            RelabelAction::HashMod => {
+                let digest = Md5::digest(self.source_value(series).as_bytes());
+                // Prometheus uses the lower half of the digest, big-endian.
+                let hash = u64::from_be_bytes(digest[8..].try_into().expect("16 bytes digest"));
+                set_label(
+                    series,
+                    self.target_label.clone(),
+                    (hash % self.modulus).to_string(),
+                );
+                true
+            }
+            RelabelAction::LabelMap => {
+                let mapped = series
+                    .tags
+                    .iter()
+                    .flatten()
+                    .filter(|(name, _)| self.regex.is_match(name))
+                    .map(|(name, value)| {
+                        (
+                            self.regex
+                                .replace(name, self.replacement.as_str())
+                                .into_owned(),
+                            value.clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                for (name, value) in mapped {
+                    set_label(series, name, value);
+                }
+                true
+            }
+            RelabelAction::LabelDrop | RelabelAction::LabelKeep => {
+                let keep_matches = self.action == RelabelAction::LabelKeep;
+                if let Some(tags) = &mut series.tags {
+                    tags.retain(|name, _| self.regex.is_match(name) == keep_matches);
+                    if tags.is_empty() {
+                        series.tags = None;
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    /// The values of the source labels joined with the separator, missing
+    /// labels count as empty values.
+    fn source_value(&self, series: &MetricSeries) -> String {
+        self.source_labels
+            .iter()
+            .map(|label| label_value(series, label).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+}
+
+fn label_value<'a>(series: &'a MetricSeries, label: &str) -> Option<&'a str> {
+    if label == NAME_LABEL {
+        Some(&series.name.name)
+    } else {
+        series
+            .tags
+            .as_ref()
+            .and_then(|tags| tags.get(label))
+            .map(String::as_str)
+    }
+}
+
+fn set_label(series: &mut MetricSeries, label: String, value: String) {
+    if label == NAME_LABEL {
+        series.name.name = value;
+    } else {
+        series.insert_tag(label, value);
+    }
+}
+
+fn remove_label(series: &mut MetricSeries, label: &str) {
+    // Metrics always have a name.
+    if label != NAME_LABEL {
+        series.remove_tag(label);
+    }
+}
+
+/// Label names follow the Prometheus rules, `[a-zA-Z_][a-zA-Z0-9_]*`.
+fn is_valid_label(label: &str) -> bool {
+    let mut chars = label.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[derive(Clone, Debug)]
+pub struct MetricRelabel {
+    rules: Vec<RelabelRule>,
+}
+
+impl FunctionTransform for MetricRelabel {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        let (mut series, data, metadata) = event.into_metric().into_parts();
+
+        for rule in &self.rules {
+            if !rule.apply(&mut series) {
+                emit!(MetricRelabelEventDropped);
+                return;
+            }
+        }
+
+        output.push(Event::Metric(Metric::from_parts(series, data, metadata)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{MetricKind, MetricValue};
+    use crate::transforms::test::transform_one;
+    use shared::btreemap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MetricRelabelConfig>();
+    }
+
+    fn relabel(config: &str, metric: Metric) -> Option<Metric> {
+        let config = toml::from_str::<MetricRelabelConfig>(config).unwrap();
+        let rules = config
+            .relabel_configs
+            .iter()
+            .map(RelabelRule::new)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let mut transform = MetricRelabel { rules };
+        transform_one(&mut transform, metric.into()).map(Event::into_metric)
+    }
+
+    fn metric() -> Metric {
+        Metric::new(
+            "http_requests_total",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 10.0 },
+        )
+        .with_tags(Some(btreemap! {
+            "instance" => "10.0.0.1:9090",
+            "job" => "api",
+            "pod_name" => "api-1",
+        }))
+    }
+
+    #[test]
+    fn replace() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["job", "instance"]
+            regex = "(.*);(.*):.*"
+            target_label = "target"
+            replacement = "${1}@${2}"
+            "#,
+            metric(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            relabeled.tag_value("target"),
+            Some("api@10.0.0.1".to_owned())
+        );
+    }
+
+    #[test]
+    fn replace_not_matching() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["job"]
+            regex = "web"
+            target_label = "target"
+            replacement = "matched"
+            "#,
+            metric(),
+        )
+        .unwrap();
+
+        assert_eq!(relabeled, metric());
+    }
+
+    #[test]
+    fn replace_empty_removes_target() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["missing"]
+            target_label = "job"
+            "#,
+            metric(),
+        )
+        .unwrap();
+
+        assert_eq!(relabeled.tag_value("job"), None);
+    }
+
+    #[test]
+    fn replace_name() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["__name__"]
+            regex = "http_(.*)"
+            target_label = "__name__"
+            replacement = "api_$1"
+            "#,
+            metric(),
+        )
+        .unwrap();
+
+        assert_eq!(relabeled.name(), "api_requests_total");
+    }
+
+    #[test]
+    fn keep_and_drop() {
+        let keep = r#"
+            [[relabel_configs]]
+            source_labels = ["__name__"]
+            regex = "http_.*"
+            action = "keep"
+            "#;
+        assert!(relabel(keep, metric()).is_some());
+        assert!(relabel(keep, metric().with_name("go_goroutines")).is_none());
+
+        let drop = r#"
+            [[relabel_configs]]
+            source_labels = ["job", "pod_name"]
+            regex = "api;.*"
+            action = "drop"
+            "#;
+        assert!(relabel(drop, metric()).is_none());
+        assert!(relabel(drop, metric().with_tags(None)).is_some());
+    }
+
+    #[test]
+    fn hashmod() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["instance"]
+            target_label = "shard"
+            modulus = 8
+            action = "hashmod"
+            "#,
+            metric(),
+        )
+        .unwrap();
+
+        // md5("10.0.0.1:9090") = 47253175948c6581495a2c49cbaa7081
+        let expected = 0x495a_2c49_cbaa_7081_u64 % 8;
+        assert_eq!(relabeled.tag_value("shard"), Some(expected.to_string()));
+    }
+
+    #[test]
+    fn labelmap() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            regex = "pod_(.*)"
+            replacement = "kubernetes_$1"
+            action = "labelmap"
+            "#,
+            metric(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            relabeled.tags(),
+            Some(&btreemap! {
+                "instance" => "10.0.0.1:9090",
+                "job" => "api",
+                "kubernetes_name" => "api-1",
+                "pod_name" => "api-1",
+            })
+        );
+    }
+
+    #[test]
+    fn labeldrop_and_labelkeep() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            regex = "pod_.*|instance"
+            action = "labeldrop"
+            "#,
+            metric(),
+        )
+        .unwrap();
+        assert_eq!(relabeled.tags(), Some(&btreemap! {"job" => "api"}));
+
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            regex = "pod_.*|instance"
+            action = "labelkeep"
+            "#,
+            metric(),
+        )
+        .unwrap();
+        assert_eq!(
+            relabeled.tags(),
+            Some(&btreemap! {
+                "instance" => "10.0.0.1:9090",
+                "pod_name" => "api-1",
+            })
+        );
+
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            regex = "unknown"
+            action = "labelkeep"
+            "#,
+            metric(),
+        )
+        .unwrap();
+        assert_eq!(relabeled.tags(), None);
+    }
+
+    #[test]
+    fn rules_apply_in_order() {
+        let relabeled = relabel(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["pod_name"]
+            target_label = "pod"
+
+            [[relabel_configs]]
+            regex = "pod_name"
+            action = "labeldrop"
+            "#,
+            metric(),
+        )
+        .unwrap();
+
+        assert_eq!(relabeled.tag_value("pod"), Some("api-1".to_owned()));
+        assert_eq!(relabeled.tag_value("pod_name"), None);
+    }
+
+    #[test]
+    fn invalid_configs() {
+        let invalid = |config: &str| {
+            toml::from_str::<MetricRelabelConfig>(config)
+                .unwrap()
+                .relabel_configs
+                .iter()
+                .map(RelabelRule::new)
+                .collect::<Result<Vec<_>, _>>()
+                .is_err()
+        };
+
+        assert!(invalid(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["job"]
+            "#
+        ));
+        assert!(invalid(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["job"]
+            target_label = "shard"
+            action = "hashmod"
+            "#
+        ));
+        assert!(invalid(
+            r#"
+            [[relabel_configs]]
+            regex = "("
+            action = "labeldrop"
+            "#
+        ));
+    }
+}
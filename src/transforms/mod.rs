@@ -40,6 +40,8 @@ pub mod logfmt_parser;
 pub mod lua;
 #[cfg(feature = "transforms-merge")]
 pub mod merge;
+#[cfg(feature = "transforms-metric_relabel")]
+pub mod metric_relabel;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
 #[cfg(feature = "transforms-reduce")]
@@ -58,6 +60,8 @@ pub mod rename_fields;
 pub mod route;
 #[cfg(feature = "transforms-sample")]
 pub mod sample;
+#[cfg(feature = "transforms-slo")]
+pub mod slo;
 #[cfg(feature = "transforms-split")]
 pub mod split;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
@@ -0,0 +1,584 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, GlobalOptions, TransformConfig, TransformDescription},
+    event::{metric, Event},
+    internal_events::SloFlushed,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::Utc;
+use futures::{Stream, StreamExt};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, pin::Pin, time::Duration};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SloConfig {
+    /// The interval between emissions of the gauges in seconds.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// The windows the ratios are computed over in seconds. They are rounded
+    /// up to a whole number of intervals.
+    #[serde(default = "default_windows_secs")]
+    pub windows_secs: Vec<u64>,
+    pub namespace: Option<String>,
+    pub objectives: Vec<ObjectiveConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectiveConfig {
+    pub name: String,
+    /// The fraction of the events that are expected to be good.
+    pub target: f64,
+    /// The events counted by the objective, all of them when unset.
+    pub total: Option<AnyCondition>,
+    /// The events, amongst the counted ones, that are good.
+    pub good: AnyCondition,
+    pub tags: Option<IndexMap<String, String>>,
+}
+
+const fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_windows_secs() -> Vec<u64> {
+    vec![5 * 60, 60 * 60, 6 * 60 * 60]
+}
+
+inventory::submit! {
+    TransformDescription::new::<SloConfig>("slo")
+}
+
+impl GenerateConfig for SloConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            interval_secs: default_interval_secs(),
+            windows_secs: default_windows_secs(),
+            namespace: None,
+            objectives: vec![ObjectiveConfig {
+                name: "availability".into(),
+                target: 0.999,
+                total: None,
+                good: AnyCondition::String("(to_int(.status) ?? 500) < 500".into()),
+                tags: None,
+            }],
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "slo")]
+impl TransformConfig for SloConfig {
+    async fn build(&self, _globals: &GlobalOptions) -> crate::Result<Transform> {
+        Slo::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "slo"
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The number of events counted by an objective during an interval.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Counts {
+    good: u64,
+    total: u64,
+}
+
+impl Counts {
+    fn add(&mut self, other: &Self) {
+        self.good += other.good;
+        self.total += other.total;
+    }
+}
+
+#[derive(Debug)]
+struct Window {
+    /// The duration of the window, as used in the `window` tag.
+    name: String,
+    /// The number of intervals covered by the window.
+    buckets: usize,
+}
+
+impl Window {
+    fn new(window_secs: u64, interval_secs: u64) -> Self {
+        let name = match window_secs {
+            secs if secs % (24 * 60 * 60) == 0 => format!("{}d", secs / (24 * 60 * 60)),
+            secs if secs % (60 * 60) == 0 => format!("{}h", secs / (60 * 60)),
+            secs if secs % 60 == 0 => format!("{}m", secs / 60),
+            secs => format!("{}s", secs),
+        };
+        let buckets = ((window_secs + interval_secs - 1) / interval_secs) as usize;
+        Self { name, buckets }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Objective {
+    target: f64,
+    #[derivative(Debug = "ignore")]
+    total: Option<Box<dyn Condition>>,
+    #[derivative(Debug = "ignore")]
+    good: Box<dyn Condition>,
+    tags: metric::MetricTags,
+    current: Counts,
+    /// The counts of the past intervals, the most recent first.
+    buckets: VecDeque<Counts>,
+}
+
+impl Objective {
+    fn new(config: &ObjectiveConfig) -> crate::Result<Self> {
+        if !(config.target > 0.0 && config.target < 1.0) {
+            return Err(format!(
+                "the `target` of objective {:?} must be in range (0.0, 1.0)",
+                config.name
+            )
+            .into());
+        }
+
+        let mut tags = config
+            .tags
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<metric::MetricTags>();
+        tags.insert("slo".into(), config.name.clone());
+
+        Ok(Self {
+            target: config.target,
+            total: config.total.as_ref().map(AnyCondition::build).transpose()?,
+            good: config.good.build()?,
+            tags,
+            current: Counts::default(),
+            buckets: VecDeque::new(),
+        })
+    }
+
+    fn record(&mut self, event: &Event) {
+        if let Some(total) = &self.total {
+            if !total.check(event) {
+                return;
+            }
+        }
+        self.current.total += 1;
+        if self.good.check(event) {
+            self.current.good += 1;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Slo {
+    interval: Duration,
+    namespace: Option<String>,
+    windows: Vec<Window>,
+    objectives: Vec<Objective>,
+}
+
+impl Slo {
+    pub fn new(config: &SloConfig) -> crate::Result<Self> {
+        if config.interval_secs == 0 {
+            return Err("`interval_secs` must be greater than 0".into());
+        }
+        if config.windows_secs.is_empty() || config.windows_secs.contains(&0) {
+            return Err("`windows_secs` must contain durations greater than 0".into());
+        }
+        if config.objectives.is_empty() {
+            return Err("must specify at least one objective".into());
+        }
+
+        Ok(Self {
+            interval: Duration::from_secs(config.interval_secs),
+            namespace: config.namespace.clone(),
+            windows: config
+                .windows_secs
+                .iter()
+                .map(|window_secs| Window::new(*window_secs, config.interval_secs))
+                .collect(),
+            objectives: config
+                .objectives
+                .iter()
+                .map(Objective::new)
+                .collect::<crate::Result<_>>()?,
+        })
+    }
+
+    fn record(&mut self, event: &Event) {
+        for objective in &mut self.objectives {
+            objective.record(event);
+        }
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let timestamp = Utc::now();
+        let max_buckets = self.windows.iter().map(|window| window.buckets).max();
+
+        for objective in &mut self.objectives {
+            objective
+                .buckets
+                .push_front(std::mem::take(&mut objective.current));
+            objective.buckets.truncate(max_buckets.unwrap_or(1));
+
+            for window in &self.windows {
+                let mut counts = Counts::default();
+                for bucket in objective.buckets.iter().take(window.buckets) {
+                    counts.add(bucket);
+                }
+                // Without events the ratio is undefined, the objective is
+                // neither met nor missed.
+                if counts.total == 0 {
+                    continue;
+                }
+
+                let ratio = counts.good as f64 / counts.total as f64;
+                let burn_rate = (1.0 - ratio) / (1.0 - objective.target);
+
+                let mut tags = objective.tags.clone();
+                tags.insert("window".into(), window.name.clone());
+                for (name, value) in &[("slo_ratio", ratio), ("slo_burn_rate", burn_rate)] {
+                    let metric = metric::Metric::new(
+                        *name,
+                        metric::MetricKind::Absolute,
+                        metric::MetricValue::Gauge { value: *value },
+                    )
+                    .with_namespace(self.namespace.clone())
+                    .with_tags(Some(tags.clone()))
+                    .with_timestamp(Some(timestamp));
+                    output.push(Event::Metric(metric));
+                }
+            }
+        }
+
+        emit!(SloFlushed);
+    }
+}
+
+impl TaskTransform for Slo {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        // Skip the immediate first tick, so that every bucket covers a whole
+        // interval.
+        let start = tokio::time::Instant::now() + self.interval;
+        let mut flush_stream = tokio::time::interval_at(start, self.interval);
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = flush_stream.tick() => {
+                        self.flush_into(&mut output);
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_into(&mut output);
+                                done = true;
+                            }
+                            Some(event) => self.record(&event),
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SloConfig>();
+    }
+
+    fn make_slo(config: &str) -> Slo {
+        Slo::new(&toml::from_str::<SloConfig>(config).unwrap()).unwrap()
+    }
+
+    fn request(status: i64) -> Event {
+        let mut event = Event::from("GET /");
+        event.as_mut_log().insert("status", status);
+        event
+    }
+
+    fn gauges(output: &[Event]) -> Vec<(String, String, String, f64)> {
+        let mut gauges = output
+            .iter()
+            .map(|event| {
+                let metric = event.as_metric();
+                let tags = metric.tags().unwrap();
+                let value = match metric.value() {
+                    metric::MetricValue::Gauge { value } => *value,
+                    value => panic!("unexpected value {:?}", value),
+                };
+                (
+                    metric.name().to_string(),
+                    tags["slo"].clone(),
+                    tags["window"].clone(),
+                    value,
+                )
+            })
+            .collect::<Vec<_>>();
+        gauges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        gauges
+    }
+
+    const CONFIG: &str = r#"
+        interval_secs = 60
+        windows_secs = [60, 180]
+        namespace = "app"
+
+        [[objectives]]
+        name = "availability"
+        target = 0.9
+        good = "(to_int(.status) ?? 500) < 500"
+        tags.service = "api"
+    "#;
+
+    /// The burn rate of the objective of `CONFIG` at the given ratio.
+    fn burn_rate(ratio: f64) -> f64 {
+        (1.0 - ratio) / (1.0 - 0.9)
+    }
+
+    #[test]
+    fn computes_ratio_and_burn_rate() {
+        let mut slo = make_slo(CONFIG);
+
+        for status in &[200, 200, 200, 500] {
+            slo.record(&request(*status));
+        }
+        let mut output = Vec::new();
+        slo.flush_into(&mut output);
+
+        let metric = output[0].as_metric();
+        assert_eq!(metric.namespace(), Some("app"));
+        assert_eq!(metric.kind(), metric::MetricKind::Absolute);
+        assert_eq!(metric.tags().unwrap()["service"], "api");
+        assert!(metric.timestamp().is_some());
+
+        assert_eq!(
+            gauges(&output),
+            vec![
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "1m".into(),
+                    burn_rate(0.75)
+                ),
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "3m".into(),
+                    burn_rate(0.75)
+                ),
+                ("slo_ratio".into(), "availability".into(), "1m".into(), 0.75),
+                ("slo_ratio".into(), "availability".into(), "3m".into(), 0.75),
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_expire_old_intervals() {
+        let mut slo = make_slo(CONFIG);
+        let mut output = Vec::new();
+
+        slo.record(&request(500));
+        slo.flush_into(&mut output);
+
+        // The failure is out of the 1m window, but still in the 3m one.
+        output.clear();
+        slo.record(&request(200));
+        slo.flush_into(&mut output);
+        assert_eq!(
+            gauges(&output),
+            vec![
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "1m".into(),
+                    0.0
+                ),
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "3m".into(),
+                    burn_rate(0.5)
+                ),
+                ("slo_ratio".into(), "availability".into(), "1m".into(), 1.0),
+                ("slo_ratio".into(), "availability".into(), "3m".into(), 0.5),
+            ]
+        );
+
+        // Windows without any event are left out.
+        output.clear();
+        slo.flush_into(&mut output);
+        assert_eq!(
+            gauges(&output),
+            vec![
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "3m".into(),
+                    burn_rate(0.5)
+                ),
+                ("slo_ratio".into(), "availability".into(), "3m".into(), 0.5),
+            ]
+        );
+
+        // The failure is now out of both windows.
+        output.clear();
+        slo.flush_into(&mut output);
+        assert_eq!(
+            gauges(&output),
+            vec![
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "3m".into(),
+                    0.0
+                ),
+                ("slo_ratio".into(), "availability".into(), "3m".into(), 1.0),
+            ]
+        );
+
+        output.clear();
+        slo.flush_into(&mut output);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn total_condition_filters_events() {
+        let mut slo = make_slo(
+            r#"
+            windows_secs = [300]
+
+            [[objectives]]
+            name = "checkout"
+            target = 0.99
+            total = '.path == "/checkout"'
+            good = "(to_int(.status) ?? 500) < 500"
+
+            [[objectives]]
+            name = "all"
+            target = 0.5
+            good = "(to_int(.status) ?? 500) < 500"
+            "#,
+        );
+
+        let mut checkout = request(200);
+        checkout.as_mut_log().insert("path", "/checkout");
+        slo.record(&checkout);
+        slo.record(&request(500));
+
+        let mut output = Vec::new();
+        slo.flush_into(&mut output);
+        assert_eq!(
+            gauges(&output),
+            vec![
+                ("slo_burn_rate".into(), "all".into(), "5m".into(), 1.0),
+                ("slo_burn_rate".into(), "checkout".into(), "5m".into(), 0.0),
+                ("slo_ratio".into(), "all".into(), "5m".into(), 0.5),
+                ("slo_ratio".into(), "checkout".into(), "5m".into(), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let build = |config: &str| Slo::new(&toml::from_str::<SloConfig>(config).unwrap());
+
+        assert!(build("objectives = []").is_err());
+        assert!(build(
+            r#"
+            [[objectives]]
+            name = "availability"
+            target = 1.0
+            good = ".ok == true"
+            "#
+        )
+        .is_err());
+        assert!(build(
+            r#"
+            windows_secs = []
+            [[objectives]]
+            name = "availability"
+            target = 0.99
+            good = ".ok == true"
+            "#
+        )
+        .is_err());
+        assert!(build(
+            r#"
+            interval_secs = 0
+            [[objectives]]
+            name = "availability"
+            target = 0.99
+            good = ".ok == true"
+            "#
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn transform_shutdown() {
+        let slo = toml::from_str::<SloConfig>(CONFIG)
+            .unwrap()
+            .build(&GlobalOptions::default())
+            .await
+            .unwrap()
+            .into_task();
+
+        let inputs = vec![request(200), request(503)];
+        let mut out_stream = slo.transform(Box::pin(stream::iter(inputs)));
+
+        // The end of the input flushes the current interval.
+        let mut output = Vec::new();
+        while let Some(event) = out_stream.next().await {
+            output.push(event);
+        }
+        assert_eq!(
+            gauges(&output),
+            vec![
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "1m".into(),
+                    burn_rate(0.5)
+                ),
+                (
+                    "slo_burn_rate".into(),
+                    "availability".into(),
+                    "3m".into(),
+                    burn_rate(0.5)
+                ),
+                ("slo_ratio".into(), "availability".into(), "1m".into(), 0.5),
+                ("slo_ratio".into(), "availability".into(), "3m".into(), 0.5),
+            ]
+        );
+    }
+}
@@ -4,9 +4,11 @@ use crate::{
 };
 use colored::*;
 use exitcode::ExitCode;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::{fmt, fs::remove_dir_all, path::PathBuf};
 use structopt::StructOpt;
+use vrl::diagnostic::Format;
 
 const TEMPORARY_DIRECTORY: &str = "validate_tmp";
 
@@ -68,6 +70,10 @@ pub struct Opts {
         use_delimiter(true)
     )]
     pub config_dirs: Vec<PathBuf>,
+
+    /// Format the validation results in an encoding scheme.
+    #[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+    format: Format,
 }
 
 impl Opts {
@@ -90,13 +96,16 @@ impl Opts {
 
 /// Performs topology, component, and health checks.
 pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
-    let mut fmt = Formatter::new(color);
+    let mut fmt = Formatter::new(color, opts.format);
 
     let mut validated = true;
 
     let mut config = match validate_config(opts, &mut fmt) {
         Some(config) => config,
-        None => return exitcode::CONFIG,
+        None => {
+            fmt.finish(false);
+            return exitcode::CONFIG;
+        }
     };
 
     if !opts.no_environment {
@@ -108,8 +117,9 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
         }
     }
 
+    fmt.finish(validated);
+
     if validated {
-        fmt.validated();
         exitcode::OK
     } else {
         exitcode::CONFIG
@@ -270,7 +280,27 @@ fn remove_tmp_directory(path: PathBuf) {
     }
 }
 
+/// A single validation result, as emitted with `--format json`.
+#[derive(Serialize)]
+struct Message {
+    severity: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct Report {
+    validated: bool,
+    messages: Vec<Message>,
+}
+
 struct Formatter {
+    format: Format,
+    /// Messages collected for JSON output
+    messages: Vec<Message>,
+    /// Title the following sub messages belong to, until the next title
+    title: Option<String>,
     /// Width of largest printed line
     max_line_width: usize,
     /// Can empty line be printed
@@ -283,8 +313,12 @@ struct Formatter {
 }
 
 impl Formatter {
-    fn new(color: bool) -> Self {
+    fn new(color: bool, format: Format) -> Self {
+        let color = color && format == Format::Text;
         Self {
+            format,
+            messages: Vec::new(),
+            title: None,
             max_line_width: 0,
             print_space: false,
             error_intro: if color {
@@ -306,6 +340,22 @@ impl Formatter {
         }
     }
 
+    /// Concludes the validation process, printing the collected messages
+    /// when formatting as JSON.
+    fn finish(&mut self, validated: bool) {
+        match self.format {
+            Format::Text if validated => self.validated(),
+            Format::Text => {}
+            Format::Json => {
+                let report = Report {
+                    validated,
+                    messages: std::mem::take(&mut self.messages),
+                };
+                println!("{}", serde_json::to_string(&report).unwrap());
+            }
+        }
+    }
+
     /// Final confirmation that validation process was successful.
     fn validated(&self) {
         println!("{:-^width$}", "", width = self.max_line_width);
@@ -326,21 +376,34 @@ impl Formatter {
 
     /// Standalone line
     fn success(&mut self, msg: impl AsRef<str>) {
+        if self.record("success", None, msg.as_ref()) {
+            return;
+        }
         self.print(format!("{} {}\n", self.success_intro, msg.as_ref()))
     }
 
     /// Standalone line
     fn warning(&mut self, warning: impl AsRef<str>) {
+        if self.record("warning", None, warning.as_ref()) {
+            return;
+        }
         self.print(format!("{} {}\n", self.warning_intro, warning.as_ref()))
     }
 
     /// Standalone line
     fn error(&mut self, error: impl AsRef<str>) {
+        if self.record("error", None, error.as_ref()) {
+            return;
+        }
         self.print(format!("{} {}\n", self.error_intro, error.as_ref()))
     }
 
     /// Marks sub
     fn title(&mut self, title: impl AsRef<str>) {
+        if self.format == Format::Json {
+            self.title = Some(title.as_ref().to_owned());
+            return;
+        }
         self.space();
         self.print(format!(
             "{}\n{:-<width$}\n",
@@ -355,6 +418,13 @@ impl Formatter {
     where
         I::Item: fmt::Display,
     {
+        if self.format == Format::Json {
+            let title = self.title.clone();
+            for warning in warnings {
+                self.record("warning", title.clone(), &warning.to_string());
+            }
+            return;
+        }
         self.sub(self.warning_intro.clone(), warnings)
     }
 
@@ -363,6 +433,13 @@ impl Formatter {
     where
         I::Item: fmt::Display,
     {
+        if self.format == Format::Json {
+            let title = self.title.clone();
+            for error in errors {
+                self.record("error", title.clone(), &error.to_string());
+            }
+            return;
+        }
         self.sub(self.error_intro.clone(), errors)
     }
 
@@ -376,6 +453,20 @@ impl Formatter {
         self.space();
     }
 
+    /// Records the message for JSON output. Returns `false` if formatting as
+    /// text, in which case the message should be printed instead.
+    fn record(&mut self, severity: &'static str, title: Option<String>, msg: &str) -> bool {
+        if self.format != Format::Json {
+            return false;
+        }
+        self.messages.push(Message {
+            severity,
+            title,
+            message: msg.to_owned(),
+        });
+        true
+    }
+
     /// Prints empty space if necessary.
     fn space(&mut self) {
         if self.print_space {
@@ -133,6 +133,31 @@ fn validate_ignore_healthcheck() {
     );
 }
 
+#[test]
+fn validate_json_format() {
+    let dir = support::create_directory();
+    let config = support::create_file(FAILING_HEALTHCHECK);
+
+    let mut cmd = Command::cargo_bin("vector").unwrap();
+    cmd.arg("validate")
+        .arg("--format")
+        .arg("json")
+        .arg(config)
+        .env("VECTOR_DATA_DIR", dir);
+
+    let output = cmd.output().unwrap();
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Vector output isn't valid JSON");
+
+    assert_eq!(output.status.code(), Some(exitcode::CONFIG));
+    assert_eq!(report["validated"], false);
+    assert!(report["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|message| message["severity"] == "error"));
+}
+
 fn validate(config: &str) -> i32 {
     let dir = support::create_directory();
 
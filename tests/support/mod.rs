@@ -223,6 +223,14 @@ impl FunctionTransform for MockTransform {
                     }
                     MetricValue::AggregatedHistogram { .. } => None,
                     MetricValue::AggregatedSummary { .. } => None,
+                    MetricValue::Sketch { sketch, statistic } => {
+                        let mut increment = metric::DDSketch::new(sketch.relative_accuracy());
+                        increment.insert(self.increase, 1);
+                        Some(MetricValue::Sketch {
+                            sketch: increment,
+                            statistic: *statistic,
+                        })
+                    }
                     MetricValue::Set { .. } => {
                         let mut values = BTreeSet::new();
                         values.insert(self.suffix.clone());